@@ -0,0 +1,130 @@
+//! # Role-Based Access Control
+//!
+//! An `AccessControl` account maps roles to the keys holding them, so each
+//! admin instruction checks the one role it needs instead of a single
+//! `authority` that can do everything. A pauser key kept on an alerting
+//! bot can then stop a pool, but a leak of that key cannot change rates or
+//! take fees.
+//!
+//! Included via `pub mod access_control;`. Each program keeps one
+//! `AccessControl` per pool at `["access_control", pool]` and exposes
+//! `initialize_access_control`, `grant_role` and `revoke_role`; the checks
+//! and bookkeeping live here.
+//!
+//! ## Roles
+//! - `Admin`: grants and revokes roles, and passes every other role check
+//! - `Pauser`: pauses and unpauses the pool
+//! - `RateSetter`: changes reward rates
+//! - `FeeCollector`: withdraws accrued swap fees
+//!
+//! Errors start at 7500, above `logic::LogicError`.
+
+use anchor_lang::prelude::*;
+
+/// Most (role, key) grants one `AccessControl` holds
+pub const MAX_ROLE_GRANTS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    Admin,
+    Pauser,
+    RateSetter,
+    FeeCollector,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RoleGrant {
+    pub role: Role,
+    pub key: Pubkey,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AccessControl {
+    /// Account these roles govern (the pool)
+    pub scope: Pubkey,
+    #[max_len(MAX_ROLE_GRANTS)]
+    pub grants: Vec<RoleGrant>,
+    pub bump: u8,
+}
+
+impl AccessControl {
+    /// Whether `key` holds `role`, or `Admin`
+    ///
+    /// Admins pass every check: an admin could grant itself any role
+    /// anyway, so refusing it here would only add a transaction.
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.key == *key && (grant.role == role || grant.role == Role::Admin))
+    }
+
+    /// ✅ Fails with `MissingRole` unless `key` holds `role`
+    ///
+    /// Pair with `Signer<'info>`: holding a role proves nothing unless
+    /// that key signed.
+    pub fn require_role(&self, role: Role, key: &Pubkey) -> Result<()> {
+        require!(self.has_role(role, key), AccessControlError::MissingRole);
+        Ok(())
+    }
+
+    /// Add `role` for `key`
+    pub fn grant(&mut self, role: Role, key: Pubkey) -> Result<()> {
+        let grant = RoleGrant { role, key };
+        require!(!self.grants.contains(&grant), AccessControlError::RoleAlreadyGranted);
+        require!(self.grants.len() < MAX_ROLE_GRANTS, AccessControlError::AccessControlFull);
+
+        self.grants.push(grant);
+        Ok(())
+    }
+
+    /// Remove `role` from `key`
+    ///
+    /// ✅ The last `Admin` cannot be revoked, or nobody could ever grant
+    /// or revoke a role again.
+    pub fn revoke(&mut self, role: Role, key: Pubkey) -> Result<()> {
+        let index = self
+            .grants
+            .iter()
+            .position(|grant| *grant == RoleGrant { role, key })
+            .ok_or(AccessControlError::RoleNotGranted)?;
+
+        if role == Role::Admin {
+            let admins = self.grants.iter().filter(|grant| grant.role == Role::Admin).count();
+            require!(admins > 1, AccessControlError::LastAdmin);
+        }
+
+        self.grants.swap_remove(index);
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RoleGranted {
+    pub scope: Pubkey,
+    pub role: Role,
+    pub key: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub scope: Pubkey,
+    pub role: Role,
+    pub key: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[error_code(offset = 7500)]
+pub enum AccessControlError {
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    #[msg("Key already holds this role")]
+    RoleAlreadyGranted,
+    #[msg("Key does not hold this role")]
+    RoleNotGranted,
+    #[msg("Access control list is full")]
+    AccessControlFull,
+    #[msg("Cannot revoke the last admin")]
+    LastAdmin,
+}
@@ -0,0 +1,54 @@
+//! # Common Errors
+//!
+//! Error codes shared by every secure program, so the same failure has the
+//! same number and message everywhere, plus `assert_authority` for the
+//! handler-side authority check.
+//!
+//! Included via `pub mod common_errors;`. Program-specific errors stay in
+//! each program's own `ErrorCode`.
+//!
+//! ## Code Ranges
+//! - 6000+ : each program's own `ErrorCode`
+//! - 6500+ : `CommonError` (this file)
+//! - 7000+ : `logic::LogicError`
+//! - 7500+ : `access_control::AccessControlError`
+//!
+//! Only ever append variants. Reordering or removing one changes the codes
+//! clients and tests match on.
+
+use anchor_lang::prelude::*;
+
+#[error_code(offset = 6500)]
+pub enum CommonError {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Arithmetic underflow")]
+    Underflow,
+    #[msg("Invalid amount - must be greater than zero")]
+    InvalidAmount,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Invalid token account owner")]
+    InvalidOwner,
+    #[msg("Token mint mismatch")]
+    MintMismatch,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("State invariant violated")]
+    InvariantViolation,
+}
+
+/// ✅ Handler-side authority check: fails with `CommonError::Unauthorized`
+/// unless `got` is the stored `expected` authority
+///
+/// Same comparison as `require_keys_eq!`, with one error everywhere.
+/// Pubkey equality is not a timing side channel on-chain: account keys are
+/// public and compute units don't depend on where the bytes differ.
+/// Still pair this with `Signer<'info>`: matching a key proves nothing
+/// unless that key signed.
+pub fn assert_authority(expected: &Pubkey, got: &Pubkey) -> Result<()> {
+    require_keys_eq!(*got, *expected, CommonError::Unauthorized);
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! # Structured Logging
+//!
+//! `log_event!` writes one `msg!` line per handler in a fixed
+//! `evt=<name> key=value key=value` format, so monitoring tools can split
+//! on spaces and `=` instead of matching freeform sentences.
+//!
+//! Included via `pub mod logging;` and `use logging::log_event;`.
+//!
+//! ## Format
+//! - `evt` comes first and is the handler name (`withdraw`, `swap`, ...)
+//! - Keys are snake_case and named after the field or argument they log
+//! - Values use `Display`; wrap a `Debug`-only value in `format_args!("{:?}", x)`
+//! - Values are written as-is, so a value containing a space (a vault name)
+//!   ends at the first space for a naive parser
+//!
+//! Events (`emit!`) remain the source of truth for indexers; these lines
+//! are for humans and log-based alerting.
+
+/// `log_event!("withdraw", vault = vault.key(), amount = amount)` logs
+/// `evt=withdraw vault=<vault> amount=<amount>`
+macro_rules! log_event {
+    ($evt:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        ::anchor_lang::prelude::msg!(
+            concat!("evt=", $evt $(, " ", stringify!($key), "={}")*)
+            $(, $value)*
+        )
+    };
+}
+
+pub(crate) use log_event;
@@ -0,0 +1,699 @@
+//! # Pure Financial Logic
+//!
+//! Swap pricing, share minting, reward accrual, and compounding, extracted
+//! from the instruction handlers so they can be tested without a validator.
+//!
+//! Used by `secure_overflow`, `secure_cpi`, `secure_matching`, `secure_min_output`,
+//! `secure_zero_copy`, and `secure_compound_interest` via `mod logic;`.
+//! Nothing here reads accounts or sysvars: every input is a plain integer.
+//! The exceptions are `require_fresh`, a `Clock` wrapper around `check_fresh`,
+//! and `SolanaClock`, the on-chain `TimeSource`.
+
+use anchor_lang::prelude::*;
+
+/// Scale factor for fixed-point rates (6 decimals)
+pub const SCALE: u64 = 1_000_000;
+
+/// Seconds in a (non-leap) year, used to annualize reward rates
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Basis-point denominator (100% = 10_000 bps)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// `amount * bps / 10_000`, rounding down
+///
+/// Shared by fees, penalties and any other percentage math. The u128
+/// intermediate means `u64::MAX` at 9_999 bps is fine; `bps` above 100%
+/// is rejected, so the result never exceeds `amount`.
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64> {
+    require!(bps as u64 <= BPS_DENOMINATOR, LogicError::InvalidBps);
+
+    let result = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(LogicError::Overflow)?
+        / BPS_DENOMINATOR as u128;
+
+    Ok(result as u64)
+}
+
+/// `base^exp` in fixed point, where `scale` represents 1.0
+///
+/// For compound growth pass `base_scaled = scale + rate_scaled`, i.e.
+/// `(1 + r)^n`. Exponentiation by squaring, so `exp` up to `u32::MAX` takes
+/// at most 64 multiplications. Every product is rescaled by `scale` and
+/// rounds down, so the result never overstates growth. `exp = 0` is `scale`.
+pub fn checked_pow_fixed(base_scaled: u128, exp: u32, scale: u128) -> Result<u128> {
+    require!(scale > 0, LogicError::DivisionByZero);
+
+    let mut result = scale;
+    let mut base = base_scaled;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result
+                .checked_mul(base)
+                .ok_or(LogicError::Overflow)?
+                / scale;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base
+                .checked_mul(base)
+                .ok_or(LogicError::Overflow)?
+                / scale;
+        }
+    }
+
+    Ok(result)
+}
+
+/// What `normalize_amount_with` does when scaling down drops a nonzero fraction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// Fail with `PrecisionLoss`
+    Reject,
+    /// Round down and drop the fraction
+    RoundDown,
+}
+
+/// Convert `amount` between mints with `from_decimals` and `to_decimals`,
+/// rejecting any loss of precision
+///
+/// 1.5 USDC (6 decimals) is `1_500_000`; the same 1.5 in a 9-decimal mint is
+/// `1_500_000_000`. Comparing or adding raw amounts across such mints is off
+/// by the factor between them.
+pub fn normalize_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    normalize_amount_with(amount, from_decimals, to_decimals, Truncation::Reject)
+}
+
+/// `normalize_amount` with a choice of what to do about a truncated fraction
+///
+/// Scaling up fails with `Overflow` if the result exceeds u64. Scaling down
+/// by more than u128 can express (38+ decimals) also fails with `Overflow`.
+pub fn normalize_amount_with(
+    amount: u64,
+    from_decimals: u8,
+    to_decimals: u8,
+    truncation: Truncation,
+) -> Result<u64> {
+    let diff = from_decimals.abs_diff(to_decimals) as u32;
+    let factor = 10u128
+        .checked_pow(diff)
+        .ok_or(LogicError::Overflow)?;
+
+    if to_decimals >= from_decimals {
+        let scaled = (amount as u128)
+            .checked_mul(factor)
+            .ok_or(LogicError::Overflow)?;
+        require!(scaled <= u64::MAX as u128, LogicError::Overflow);
+        return Ok(scaled as u64);
+    }
+
+    let remainder = amount as u128 % factor;
+    require!(
+        remainder == 0 || truncation == Truncation::RoundDown,
+        LogicError::PrecisionLoss
+    );
+
+    Ok((amount as u128 / factor) as u64)
+}
+
+/// Constant-product swap output: `reserve_out * amount_in / (reserve_in + amount_in)`
+///
+/// Rounds down, so any truncation stays in the pool.
+pub fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    // x * y = k (constant product)
+    // (x + dx) * (y - dy) = k
+    // dy = y * dx / (x + dx)
+
+    let numerator = (amount_in as u128)
+        .checked_mul(reserve_out as u128)
+        .ok_or(LogicError::Overflow)?;
+
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(LogicError::Overflow)?;
+
+    let amount_out = numerator
+        .checked_div(denominator)
+        .ok_or(LogicError::DivisionByZero)?;
+
+    require!(
+        amount_out <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(amount_out as u64)
+}
+
+/// Constant-product input needed for `amount_out`:
+/// `reserve_in * amount_out / (reserve_out - amount_out)`, rounded up
+///
+/// The inverse of `swap_output`. Rounding up means
+/// `swap_output(swap_input(dy), ..) >= dy`, so the pool never sells
+/// `amount_out` for less than the curve asks. Draining `reserve_out`
+/// entirely would need infinite input and fails with `OutputTooLarge`.
+pub fn swap_input(amount_out: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    // (x + dx) * (y - dy) = x * y
+    // dx = x * dy / (y - dy)
+    require!(amount_out < reserve_out, LogicError::OutputTooLarge);
+
+    let numerator = (reserve_in as u128)
+        .checked_mul(amount_out as u128)
+        .ok_or(LogicError::Overflow)?;
+
+    let denominator = (reserve_out - amount_out) as u128;
+
+    let amount_in = numerator
+        .checked_add(denominator - 1)
+        .ok_or(LogicError::Overflow)?
+        / denominator;
+
+    require!(
+        amount_in <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(amount_in as u64)
+}
+
+/// Largest amplification coefficient `stable_swap_output` accepts
+pub const MAX_AMP: u64 = 10_000;
+
+/// Newton iterations before giving up on the StableSwap invariant
+const STABLE_SWAP_ITERATIONS: usize = 255;
+
+/// StableSwap (Curve-style, two coins) output for `amount_in`
+///
+/// Solves `4A(x + y) + D = 4AD + D³ / (4xy)` for the invariant `D` at the
+/// current reserves, then for the new `reserve_out` once `amount_in` is
+/// added. A large `amp` keeps the price close to 1:1 around balance; as
+/// `amp` → 0 the curve degrades to constant product. The result rounds
+/// down by one unit so the invariant never decreases.
+///
+/// All math is checked u128, which covers reserves up to roughly 10^16
+/// per side at `MAX_AMP`; beyond that it fails with `Overflow` rather than
+/// mispricing.
+pub fn stable_swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64, amp: u64) -> Result<u64> {
+    require!(amp > 0 && amp <= MAX_AMP, LogicError::InvalidAmp);
+    require!(reserve_in > 0 && reserve_out > 0, LogicError::DivisionByZero);
+    if amount_in == 0 {
+        return Ok(0);
+    }
+
+    let ann = amp as u128 * 4;
+    let d = stable_swap_invariant(reserve_in as u128, reserve_out as u128, ann)?;
+    let new_in = (reserve_in as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(LogicError::Overflow)?;
+    let new_out = stable_swap_y(new_in, d, ann)?;
+
+    let amount_out = (reserve_out as u128)
+        .saturating_sub(new_out)
+        .saturating_sub(1);
+
+    require!(
+        amount_out <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(amount_out as u64)
+}
+
+/// Invariant `D` for reserves `x`, `y` and `ann = 4 * amp` (Newton's method)
+fn stable_swap_invariant(x: u128, y: u128, ann: u128) -> Result<u128> {
+    let sum = x.checked_add(y).ok_or(LogicError::Overflow)?;
+    let mut d = sum;
+
+    for _ in 0..STABLE_SWAP_ITERATIONS {
+        // d_p = D³ / (4xy), built up one factor at a time
+        let d_p = d
+            .checked_mul(d).ok_or(LogicError::Overflow)?
+            / (x * 2);
+        let d_p = d_p
+            .checked_mul(d).ok_or(LogicError::Overflow)?
+            / (y * 2);
+
+        let previous = d;
+        // D = (ann·S + 2·d_p)·D / ((ann − 1)·D + 3·d_p)
+        let numerator = ann
+            .checked_mul(sum).ok_or(LogicError::Overflow)?
+            .checked_add(d_p * 2).ok_or(LogicError::Overflow)?
+            .checked_mul(d).ok_or(LogicError::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d).ok_or(LogicError::Overflow)?
+            .checked_add(d_p.checked_mul(3).ok_or(LogicError::Overflow)?)
+            .ok_or(LogicError::Overflow)?;
+        d = numerator / denominator;
+
+        if d.abs_diff(previous) <= 1 {
+            return Ok(d);
+        }
+    }
+
+    err!(LogicError::NoConvergence)
+}
+
+/// Reserve `y` that keeps invariant `d` when the other reserve is `x`
+fn stable_swap_y(x: u128, d: u128, ann: u128) -> Result<u128> {
+    // y² + (x + D/ann − D)·y = D³ / (4x·ann), solved as y = (y² + c) / (2y + b − D)
+    let c = d
+        .checked_mul(d).ok_or(LogicError::Overflow)?
+        / (x * 2);
+    let c = c
+        .checked_mul(d).ok_or(LogicError::Overflow)?
+        / (ann * 2);
+    let b = x + d / ann;
+    let mut y = d;
+
+    for _ in 0..STABLE_SWAP_ITERATIONS {
+        let previous = y;
+        let numerator = y
+            .checked_mul(y).ok_or(LogicError::Overflow)?
+            .checked_add(c).ok_or(LogicError::Overflow)?;
+        let denominator = (y * 2 + b)
+            .checked_sub(d)
+            .ok_or(LogicError::DivisionByZero)?;
+        y = numerator
+            .checked_div(denominator)
+            .ok_or(LogicError::DivisionByZero)?;
+
+        if y.abs_diff(previous) <= 1 {
+            return Ok(y);
+        }
+    }
+
+    err!(LogicError::NoConvergence)
+}
+
+/// Spot price `reserve_out / reserve_in` as unsigned Q64.64 fixed point
+///
+/// The integer part is the high 64 bits, so any pair of u64 reserves fits
+/// without overflow.
+pub fn spot_price_q64(reserve_in: u64, reserve_out: u64) -> Result<u128> {
+    require!(reserve_in > 0, LogicError::DivisionByZero);
+    Ok(((reserve_out as u128) << 64) / reserve_in as u128)
+}
+
+/// Advance a TWAP accumulator: `cumulative + price * elapsed`, wrapping
+///
+/// Like Uniswap V2's `price0CumulativeLast`, the accumulator is allowed to
+/// overflow. Only differences between two readings are meaningful, and
+/// `end.wrapping_sub(start)` is exact as long as the true sum over the
+/// window is below 2^128 — at a Q64.64 price of 1.0 that is 2^64 seconds.
+pub fn accumulate_price(cumulative: u128, price_q64: u128, elapsed: u64) -> u128 {
+    cumulative.wrapping_add(price_q64.wrapping_mul(elapsed as u128))
+}
+
+/// Time-weighted average Q64.64 price between two accumulator readings
+/// taken `elapsed` seconds apart
+pub fn twap_q64(cumulative_start: u128, cumulative_end: u128, elapsed: u64) -> Result<u128> {
+    require!(elapsed > 0, LogicError::DivisionByZero);
+    Ok(cumulative_end.wrapping_sub(cumulative_start) / elapsed as u128)
+}
+
+/// Shares minted for depositing `amount` into a pool that held
+/// `total_deposits` backing `total_shares` before the deposit
+///
+/// The first deposit mints 1:1. Later deposits mint pro rata, rounding down.
+pub fn shares_for_deposit(amount: u64, total_deposits: u64, total_shares: u64) -> Result<u64> {
+    if total_shares == 0 {
+        return Ok(amount);
+    }
+
+    let shares = (amount as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(LogicError::Overflow)?
+        .checked_div(total_deposits as u128)
+        .ok_or(LogicError::DivisionByZero)?;
+
+    require!(
+        shares <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(shares as u64)
+}
+
+/// LP shares minted for adding `amount_a` and `amount_b` to a two-sided pool
+///
+/// The first deposit (`supply == 0`) mints `sqrt(amount_a * amount_b)`, so
+/// the share count doesn't depend on which side is called `a`. Later
+/// deposits mint `min(amount_a * supply / reserve_a, amount_b * supply / reserve_b)`,
+/// rounding down: any excess on the richer side is donated to existing
+/// holders, never credited to the depositor.
+pub fn lp_shares_for_deposit(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    supply: u64,
+) -> Result<u64> {
+    if supply == 0 {
+        return Ok(isqrt(amount_a as u128 * amount_b as u128) as u64);
+    }
+
+    let shares_a = shares_for_deposit(amount_a, reserve_a, supply)?;
+    let shares_b = shares_for_deposit(amount_b, reserve_b, supply)?;
+
+    Ok(shares_a.min(shares_b))
+}
+
+/// Integer square root, rounding down
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method from an overestimate converges from above
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Tokens per share, scaled by `SCALE`: `total_deposits * SCALE / total_shares`
+///
+/// An empty pool (`total_shares == 0`) is priced at `SCALE` (1.0), the rate
+/// its first deposit mints at.
+pub fn share_price(total_deposits: u64, total_shares: u64) -> Result<u64> {
+    if total_shares == 0 {
+        return Ok(SCALE);
+    }
+
+    let price = (total_deposits as u128)
+        .checked_mul(SCALE as u128)
+        .ok_or(LogicError::Overflow)?
+        / total_shares as u128;
+
+    require!(
+        price <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(price as u64)
+}
+
+/// Direction to round a pro-rata conversion
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero: the remainder stays with the pool
+    Down,
+    /// Away from zero: the remainder is charged to the caller
+    Up,
+}
+
+/// Tokens backing `shares` in a pool holding `total_deposits` against `total_shares`
+///
+/// Returns `(amount, dust)`, where `dust` is the remainder of
+/// `shares * total_deposits / total_shares`. With `Rounding::Down` the exact
+/// entitlement is `amount + dust / total_shares` tokens.
+pub fn assets_for_shares(
+    shares: u64,
+    total_deposits: u64,
+    total_shares: u64,
+    rounding: Rounding,
+) -> Result<(u64, u64)> {
+    let numerator = (shares as u128)
+        .checked_mul(total_deposits as u128)
+        .ok_or(LogicError::Overflow)?;
+    let quotient = numerator
+        .checked_div(total_shares as u128)
+        .ok_or(LogicError::DivisionByZero)?;
+    let dust = numerator % total_shares as u128;
+
+    let amount = match rounding {
+        Rounding::Up if dust > 0 => quotient + 1,
+        _ => quotient,
+    };
+
+    require!(
+        amount <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok((amount as u64, dust as u64))
+}
+
+/// Result of a single reward accrual
+pub struct RewardAccrual {
+    /// Rewards earned over the period, before capping
+    pub rewards: u64,
+    /// Rewards actually credited (capped at the pool balance)
+    pub capped_rewards: u64,
+    /// Division remainder to carry into the next accrual
+    pub remainder: u128,
+    /// Seconds accrued over
+    pub time_staked: u64,
+}
+
+/// Rewards for `amount` staked at `rate` (scaled by `SCALE`, annual)
+/// between `accrual_start` and `now`
+///
+/// `remainder` is the value returned by the previous accrual; carrying it
+/// forward means many small accruals sum to exactly one large accrual.
+pub fn rewards(
+    amount: u64,
+    rate: u64,
+    accrual_start: i64,
+    now: i64,
+    remainder: u128,
+    pool_balance: u64,
+) -> Result<RewardAccrual> {
+    let time_staked = elapsed(accrual_start, now)?;
+
+    let rate_seconds = (rate as u128)
+        .checked_mul(time_staked as u128)
+        .ok_or(LogicError::Overflow)?;
+
+    accrue(amount, rate_seconds, time_staked, remainder, pool_balance)
+}
+
+/// Rewards for `amount` staked between `accrual_start` and `now` while the
+/// rate changed over time
+///
+/// `index_delta` is the growth of a cumulative reward index (Σ rate × seconds,
+/// rate scaled by `SCALE`, annual) over the same period. With a constant rate
+/// this equals `rewards(amount, rate, ..)`.
+pub fn rewards_for_index(
+    amount: u64,
+    index_delta: u128,
+    accrual_start: i64,
+    now: i64,
+    remainder: u128,
+    pool_balance: u64,
+) -> Result<RewardAccrual> {
+    let time_staked = elapsed(accrual_start, now)?;
+    accrue(amount, index_delta, time_staked, remainder, pool_balance)
+}
+
+/// Reject data last updated at `last_update` if it is older than `max_age`
+/// seconds at `now`, or if `now` is before `last_update`
+///
+/// A clock that appears to run backwards means either `last_update` came
+/// from somewhere other than this cluster's clock or it was set in the
+/// future; either way nothing computed from it can be trusted.
+pub fn check_fresh(last_update: i64, max_age: i64, now: i64) -> Result<()> {
+    require!(max_age >= 0, LogicError::InvalidTimestamp);
+    let age = now
+        .checked_sub(last_update)
+        .filter(|age| *age >= 0)
+        .ok_or(LogicError::InvalidTimestamp)?;
+    require!(age <= max_age, LogicError::StaleData);
+    Ok(())
+}
+
+/// Where time-dependent code gets "now"
+///
+/// Handlers pass `SolanaClock`; tests pass a `MockClock` and drive accrual
+/// across any timestamps they like without a validator.
+pub trait TimeSource {
+    fn now(&self) -> Result<i64>;
+}
+
+/// The `Clock` sysvar
+pub struct SolanaClock;
+
+impl TimeSource for SolanaClock {
+    fn now(&self) -> Result<i64> {
+        Ok(Clock::get()?.unix_timestamp)
+    }
+}
+
+/// A fixed `unix_timestamp`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MockClock(pub i64);
+
+impl TimeSource for MockClock {
+    fn now(&self) -> Result<i64> {
+        Ok(self.0)
+    }
+}
+
+/// `check_fresh` against the current `Clock` sysvar
+///
+/// Timelocks, reward checkpoints and oracle prices all go through this so
+/// "too old" and "from the future" mean the same thing everywhere.
+pub fn require_fresh(last_update: i64, max_age: i64) -> Result<()> {
+    check_fresh(last_update, max_age, SolanaClock.now()?)
+}
+
+/// Seconds from `start` to `now`, rejecting time going backwards
+fn elapsed(start: i64, now: i64) -> Result<u64> {
+    // ✅ Validate time hasn't gone backwards
+    let seconds = now
+        .checked_sub(start)
+        .filter(|d| *d >= 0)
+        .ok_or(LogicError::InvalidTimestamp)?;
+    Ok(seconds as u64)
+}
+
+/// Step of the reward formula that could not be computed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardOverflow {
+    /// `amount * rate * seconds + remainder` does not fit in u128
+    Multiply,
+    /// The result after dividing by `SCALE * SECONDS_PER_YEAR` does not fit in u64
+    Divide,
+}
+
+impl From<RewardOverflow> for LogicError {
+    fn from(step: RewardOverflow) -> Self {
+        match step {
+            RewardOverflow::Multiply => LogicError::Overflow,
+            RewardOverflow::Divide => LogicError::OutputTooLarge,
+        }
+    }
+}
+
+/// `(amount * rate_seconds + remainder) / (SCALE * SECONDS_PER_YEAR)` and
+/// the new remainder, or the step that overflowed
+///
+/// The exact math every accrual runs; `simulate_rewards` exposes it so the
+/// failing step can be reported instead of a bare overflow.
+pub fn reward_math(
+    amount: u64,
+    rate_seconds: u128,
+    remainder: u128,
+) -> core::result::Result<(u64, u128), RewardOverflow> {
+    // ✅ SECURE: u128 intermediate prevents overflow during multiplication
+    let numerator = (amount as u128)
+        .checked_mul(rate_seconds)
+        .and_then(|product| product.checked_add(remainder))
+        .ok_or(RewardOverflow::Multiply)?;
+
+    // Scale down and annualize in a single division
+    let denominator = (SCALE as u128) * (SECONDS_PER_YEAR as u128);
+    let rewards = numerator / denominator;
+
+    // ✅ SECURE: Verify result fits in u64
+    let rewards = u64::try_from(rewards).map_err(|_| RewardOverflow::Divide)?;
+
+    Ok((rewards, numerator % denominator))
+}
+
+/// `reward_math` for `amount` staked `seconds` at `rate`, with no carried
+/// remainder
+///
+/// `rate * seconds` is two u64s multiplied in u128, so it cannot overflow;
+/// only the two steps in `RewardOverflow` can fail.
+pub fn simulate_rewards(amount: u64, rate: u64, seconds: u64) -> core::result::Result<u64, RewardOverflow> {
+    let rate_seconds = rate as u128 * seconds as u128;
+    reward_math(amount, rate_seconds, 0).map(|(rewards, _)| rewards)
+}
+
+fn accrue(
+    amount: u64,
+    rate_seconds: u128,
+    time_staked: u64,
+    remainder: u128,
+    pool_balance: u64,
+) -> Result<RewardAccrual> {
+    let (rewards, remainder) = reward_math(amount, rate_seconds, remainder).map_err(LogicError::from)?;
+
+    Ok(RewardAccrual {
+        rewards,
+        // ✅ Cap rewards at available pool balance
+        capped_rewards: rewards.min(pool_balance),
+        remainder,
+        time_staked,
+    })
+}
+
+/// Fixed-point precision of `acc_reward_per_share`
+pub const ACC_PRECISION: u128 = 1_000_000_000_000;
+
+/// Global reward accumulator after `elapsed` seconds of emitting
+/// `reward_rate` tokens per second, shared across `total_staked`
+///
+/// `acc` is rewards per staked token, scaled by `ACC_PRECISION`. Nothing
+/// accrues while nothing is staked.
+pub fn acc_reward_per_share(acc: u128, reward_rate: u64, elapsed: u64, total_staked: u64) -> Result<u128> {
+    if total_staked == 0 {
+        return Ok(acc);
+    }
+
+    let increase = (reward_rate as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(LogicError::Overflow)?
+        .checked_mul(ACC_PRECISION)
+        .ok_or(LogicError::Overflow)?
+        / total_staked as u128;
+
+    let acc = acc.checked_add(increase).ok_or(LogicError::Overflow)?;
+    Ok(acc)
+}
+
+/// Checkpoint for a stake of `amount` at accumulator value `acc`:
+/// `amount * acc / ACC_PRECISION`
+pub fn reward_debt(amount: u64, acc: u128) -> Result<u128> {
+    let debt = (amount as u128)
+        .checked_mul(acc)
+        .ok_or(LogicError::Overflow)?
+        / ACC_PRECISION;
+    Ok(debt)
+}
+
+/// Rewards earned by `amount` since its `reward_debt` was last set:
+/// `amount * acc / ACC_PRECISION - reward_debt`
+pub fn pending_reward(amount: u64, acc: u128, reward_debt: u128) -> Result<u64> {
+    let pending = self::reward_debt(amount, acc)?
+        .checked_sub(reward_debt)
+        .ok_or(LogicError::Overflow)?;
+
+    require!(
+        pending <= u64::MAX as u128,
+        LogicError::OutputTooLarge
+    );
+
+    Ok(pending as u64)
+}
+
+/// Errors from the pure math helpers
+///
+/// Offset so they never collide with a program's own `ErrorCode` (6000+).
+#[error_code(offset = 7000)]
+pub enum LogicError {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Division by zero")]
+    DivisionByZero,
+    #[msg("Result does not fit in u64")]
+    OutputTooLarge,
+    #[msg("Invalid timestamp detected")]
+    InvalidTimestamp,
+    #[msg("Basis points exceed 10_000")]
+    InvalidBps,
+    #[msg("Decimal conversion would truncate a nonzero fraction")]
+    PrecisionLoss,
+    #[msg("Data is older than the allowed maximum age")]
+    StaleData,
+    #[msg("Amplification coefficient must be between 1 and MAX_AMP")]
+    InvalidAmp,
+    #[msg("StableSwap invariant did not converge")]
+    NoConvergence,
+}
@@ -0,0 +1,229 @@
+//! # Secure Compound Interest Example
+//!
+//! This program demonstrates accruing compound interest with checked
+//! fixed-point exponentiation instead of a loop or a float.
+//!
+//! ## Security Measures
+//! 1. `(1 + r)^n` via `logic::checked_pow_fixed`: O(log n) multiplications,
+//!    every one checked, so a long-idle account neither exhausts compute
+//!    nor wraps
+//! 2. Only whole periods accrue, and `last_accrual` advances by exactly
+//!    those periods, so the partial period is never lost or double counted
+//! 3. Every rescale rounds down: calling `accrue` more often can never earn
+//!    more than calling it once
+//! 4. `rate_per_period` is capped at `MAX_RATE_PER_PERIOD`
+//!
+//! ## Why This Works
+//! A per-period loop costs compute linear in idle time and eventually fails
+//! the transaction, which locks the account. Exponentiation by squaring caps
+//! the work at 64 multiplications for any `u32` period count. Checked u128
+//! products turn an absurd balance into an `Overflow` error instead of a
+//! wrapped, tiny one.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use common_errors::CommonError;
+use logic::SCALE;
+use logging::log_event;
+
+declare_id!("SecureFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+
+/// Length of one compounding period (one day)
+pub const PERIOD_SECONDS: i64 = 24 * 60 * 60;
+
+/// Highest interest rate per period, scaled by `SCALE` (1% per day)
+pub const MAX_RATE_PER_PERIOD: u64 = SCALE / 100;
+
+#[program]
+pub mod secure_compound_interest {
+    use super::*;
+
+    /// ✅ SECURE: Open an interest-bearing balance for the signer
+    pub fn open_account(ctx: Context<OpenAccount>, rate_per_period: u64) -> Result<()> {
+        require!(
+            rate_per_period <= MAX_RATE_PER_PERIOD,
+            ErrorCode::RateTooHigh
+        );
+
+        let account = &mut ctx.accounts.interest_account;
+        account.owner = ctx.accounts.owner.key();
+        account.balance = 0;
+        account.rate_per_period = rate_per_period;
+        account.last_accrual = Clock::get()?.unix_timestamp;
+        account.bump = ctx.bumps.interest_account;
+
+        log_event!("open_account", rate_per_period = rate_per_period, scale = SCALE);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Add to the balance, accruing what is owed first
+    ///
+    /// Without the accrual, the new amount would earn interest for the idle
+    /// time before it was deposited.
+    pub fn deposit(ctx: Context<UpdateAccount>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let account = &mut ctx.accounts.interest_account;
+        account.accrue(Clock::get()?.unix_timestamp)?;
+        account.balance = account.balance
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+
+        log_event!("deposit", amount = amount, balance = account.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Compound the balance up to the last whole period
+    pub fn accrue(ctx: Context<UpdateAccount>) -> Result<()> {
+        let account = &mut ctx.accounts.interest_account;
+        let interest = account.accrue(Clock::get()?.unix_timestamp)?;
+
+        emit!(InterestAccrued {
+            account: account.key(),
+            interest,
+            balance: account.balance,
+        });
+
+        log_event!("accrue", interest = interest, balance = account.balance);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OpenAccount<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + InterestAccount::INIT_SPACE,
+        seeds = [b"interest", owner.key().as_ref()],
+        bump
+    )]
+    pub interest_account: Account<'info, InterestAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAccount<'info> {
+    #[account(
+        mut,
+        seeds = [b"interest", owner.key().as_ref()],
+        bump = interest_account.bump,
+        has_one = owner @ CommonError::Unauthorized
+    )]
+    pub interest_account: Account<'info, InterestAccount>,
+
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct InterestAccount {
+    pub owner: Pubkey,
+    /// Principal plus all interest accrued so far
+    pub balance: u64,
+    /// Interest per `PERIOD_SECONDS`, scaled by `SCALE`
+    pub rate_per_period: u64,
+    /// Start of the first period not yet accrued
+    pub last_accrual: i64,
+    pub bump: u8,
+}
+
+impl InterestAccount {
+    /// Compound `balance` over every whole period between `last_accrual`
+    /// and `now`, returning the interest added
+    ///
+    /// `balance * (SCALE + rate)^periods / SCALE^periods`, rounding down.
+    /// A partial period is left for the next call.
+    pub fn accrue(&mut self, now: i64) -> Result<u64> {
+        let elapsed = now
+            .checked_sub(self.last_accrual)
+            .ok_or(CommonError::Underflow)?;
+        require!(elapsed >= 0, logic::LogicError::InvalidTimestamp);
+
+        let periods = elapsed / PERIOD_SECONDS;
+        if periods == 0 {
+            return Ok(0);
+        }
+
+        // ✅ SECURE: Whole periods only; the remainder keeps accruing
+        self.last_accrual = self.last_accrual
+            .checked_add(periods * PERIOD_SECONDS)
+            .ok_or(CommonError::Overflow)?;
+
+        let exp = u32::try_from(periods).map_err(|_| CommonError::Overflow)?;
+        let factor = logic::checked_pow_fixed(
+            SCALE as u128 + self.rate_per_period as u128,
+            exp,
+            SCALE as u128,
+        )?;
+
+        let new_balance = (self.balance as u128)
+            .checked_mul(factor)
+            .ok_or(CommonError::Overflow)?
+            / SCALE as u128;
+        require!(new_balance <= u64::MAX as u128, CommonError::Overflow);
+
+        let interest = new_balance as u64 - self.balance;
+        self.balance = new_balance as u64;
+        Ok(interest)
+    }
+}
+
+#[event]
+pub struct InterestAccrued {
+    pub account: Pubkey,
+    pub interest: u64,
+    pub balance: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Interest rate exceeds the maximum per period")]
+    RateTooHigh,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Common ways compound interest goes wrong, and how this program avoids them:
+//
+// COMPUTE EXHAUSTION BLOCKED:
+// ---------------------------
+// for _ in 0..periods { balance = balance * (SCALE + rate) / SCALE }
+// 1. An account idle for years needs thousands of iterations
+// 2. The transaction runs out of compute units and always fails
+// 3. The balance can never be updated again: funds are effectively frozen
+// checked_pow_fixed squares instead: ~2 * log2(periods) multiplications.
+//
+// SILENT WRAP BLOCKED:
+// --------------------
+// 1. (SCALE + rate)^n grows fast: 1.01^10000 is ~1.6e43, past u128
+// 2. Unchecked u128 math wraps to a small number and the balance shrinks
+// 3. Every product here is checked_mul → Overflow, and the final balance
+//    must fit in u64
+//
+// ACCRUAL GAMING BLOCKED:
+// -----------------------
+// 1. Attacker calls accrue every few seconds hoping partial periods or
+//    rounding add up in their favor
+// 2. Only whole periods accrue; last_accrual moves by exactly those periods
+// 3. Each rescale rounds down, so splitting accruals earns <= one big accrual
+//
+// DEPOSIT BACKDATING BLOCKED:
+// ---------------------------
+// 1. Account idle for 30 days, owner deposits 1_000_000
+// 2. If deposit didn't accrue first, the next accrue would compound the new
+//    1_000_000 over all 30 days
+// 3. deposit() accrues before adding, so new money is backdated by at most
+//    the current partial period, never by the whole idle time
@@ -7,6 +7,13 @@
 //! 2. Follow Checks-Effects-Interactions (CEI) pattern
 //! 3. Implement reentrancy guards when needed
 //! 4. Properly verify authorities and relationships
+//! 5. Verify off-chain permits through the instructions sysvar
+//! 6. Initialize a vault's recorded balance from its token account
+//! 7. Pay fees only to a `FeeCollector` role holder (`access_control`)
+//! 8. Reject vaults and pools not written at `CURRENT_VERSION` until
+//!    `migrate_vault` / `migrate_pool` rewrites them
+//! 9. Optionally cross-check a pool's reserves against its token balances
+//!    after a swap
 //! 
 //! ## Best Practices
 //! - Always verify program IDs for CPI targets
@@ -15,22 +22,187 @@
 //! - Validate all account relationships
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::{
+    ed25519_program,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_option::COption,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+pub mod access_control;
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use access_control::{AccessControl, Role, RoleGranted, RoleRevoked};
+use common_errors::CommonError;
+use logging::log_event;
 
 declare_id!("Secure5555555555555555555555555555555555555");
 
+/// Maximum number of programs a whitelist can hold
+pub const MAX_WHITELISTED_PROGRAMS: usize = 10;
+
+/// Highest swap fee a queued `AdminAction::SetFee` accepts (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Seconds between `queue_admin_action` and the earliest
+/// `execute_admin_action` (1 day)
+pub const ADMIN_ACTION_DELAY: i64 = 24 * 60 * 60;
+
+/// How far `add_liquidity` amounts may stray from the reserve ratio (0.5%)
+pub const LIQUIDITY_RATIO_TOLERANCE_BPS: u16 = 50;
+
+/// Number of recent `deposit` idempotency keys a vault remembers
+pub const RECENT_DEPOSIT_KEYS: usize = 8;
+
+/// Oldest snapshot `consult_twap` will average from (1 day); past that the
+/// window is mostly history, not the current price
+pub const MAX_TWAP_AGE: i64 = 24 * 60 * 60;
+
+/// Layout version `initialize_pool` and `initialize_token_vault` write;
+/// mutating handlers reject accounts at any other version
+pub const CURRENT_VERSION: u8 = 1;
+
+/// `freeze_vault` reason codes recorded for audit. Any nonzero code is
+/// accepted; 0 means "not frozen".
+pub const FREEZE_REASON_INCIDENT: u8 = 1;
+pub const FREEZE_REASON_COMPLIANCE: u8 = 2;
+pub const FREEZE_REASON_MAINTENANCE: u8 = 3;
+
+/// Bytes the vault authority signs off-chain to permit a deposit:
+/// `vault || user_tokens || amount || deadline || nonce`, integers little-endian
+pub fn permit_message(vault: &Pubkey, user_tokens: &Pubkey, amount: u64, deadline: i64, nonce: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 32 + 8 + 8 + 8);
+    message.extend_from_slice(vault.as_ref());
+    message.extend_from_slice(user_tokens.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&deadline.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// Size of the ed25519 program's per-signature offsets struct
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Header before the offsets: signature count + padding
+const ED25519_HEADER_LEN: usize = 2;
+
+/// ✅ SECURE: Verify that the instruction just before this one is an
+/// ed25519 program check of `signer` signing exactly `message` with
+/// `signature`
+///
+/// The ed25519 program rejects the whole transaction if the signature is
+/// bad, so reaching this point means it held. This proves it is the
+/// signature the permit needs: one signature, all data inline, expected
+/// key, message and signature bytes.
+fn verify_permit_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidPermit);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions)?;
+    
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidPermit);
+    require!(ix.accounts.is_empty(), ErrorCode::InvalidPermit);
+    
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        ErrorCode::InvalidPermit
+    );
+    
+    let offsets = &data[ED25519_HEADER_LEN..ED25519_HEADER_LEN + ED25519_OFFSETS_LEN];
+    let read_u16 = |i: usize| u16::from_le_bytes([offsets[i], offsets[i + 1]]);
+    let signature_offset = read_u16(0) as usize;
+    let public_key_offset = read_u16(4) as usize;
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+    
+    // ✅ Signature, key and message must all live in the ed25519
+    // instruction itself, not be pointed at some other instruction
+    for index_at in [2, 6, 12] {
+        require!(read_u16(index_at) == u16::MAX, ErrorCode::InvalidPermit);
+    }
+    
+    let signed_with = data
+        .get(signature_offset..signature_offset + 64)
+        .ok_or(ErrorCode::InvalidPermit)?;
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidPermit)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidPermit)?;
+    
+    require!(signed_with == signature.as_ref(), ErrorCode::InvalidPermit);
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidPermit);
+    require!(signed_message == message, ErrorCode::InvalidPermit);
+    Ok(())
+}
+
 #[program]
 pub mod secure_cpi {
     use super::*;
 
+    /// ✅ SECURE: Create the pool PDA for a `token_in_mint` → `token_out_mint` pair
+    ///
+    /// Stores the canonical bump used by every swap to re-derive the pool.
+    /// Reserves start at zero.
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.token_in_mint = ctx.accounts.token_in_mint.key();
+        pool.token_out_mint = ctx.accounts.token_out_mint.key();
+        pool.reserve_in = 0;
+        pool.reserve_out = 0;
+        pool.total_volume = 0;
+        pool.fee_bps = 0;
+        pool.fees_collected = 0;
+        pool.curve = CurveType::ConstantProduct;
+        pool.amp = 0;
+        pool.lp_supply = 0;
+        pool.price_cumulative = 0;
+        pool.last_twap_update = Clock::get()?.unix_timestamp;
+        pool.sequence = 0;
+        pool.pending_action = None;
+        pool.action_ready_at = 0;
+        pool.bump = ctx.bumps.pool;
+        pool.version = CURRENT_VERSION;
+        
+        emit!(PoolInitialized {
+            pool: pool.key(),
+            authority: pool.authority,
+            token_in_mint: pool.token_in_mint,
+            token_out_mint: pool.token_out_mint,
+            sequence: pool.sequence,
+        });
+        
+        log_event!("initialize_pool", pool = pool.key(), token_in_mint = pool.token_in_mint, token_out_mint = pool.token_out_mint);
+        Ok(())
+    }
+
     /// ✅ SECURE: CPI with verified program ID
+    ///
+    /// Passing `reserve_tolerance` turns on strict mode: after both
+    /// transfers the pool token accounts are reloaded and must match the
+    /// recorded reserves (plus uncollected fees on the input side) to within
+    /// that many tokens, else `ReserveMismatch`.
     pub fn swap_tokens(
         ctx: Context<SwapTokens>,
         amount_in: u64,
         min_amount_out: u64,
+        reserve_tolerance: Option<u64>,
     ) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
         // ✅ Validate inputs
-        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        require!(amount_in > 0, CommonError::InvalidAmount);
         require!(min_amount_out > 0, ErrorCode::InvalidMinOutput);
         
         let pool = &mut ctx.accounts.pool;
@@ -38,32 +210,38 @@ pub mod secure_cpi {
         // ✅ Validate user has sufficient balance
         require!(
             ctx.accounts.user_token_in.amount >= amount_in,
-            ErrorCode::InsufficientBalance
+            CommonError::InsufficientFunds
         );
         
         // ✅ Calculate output with checked arithmetic
-        let amount_out = calculate_swap_output(
-            amount_in,
-            pool.reserve_in,
-            pool.reserve_out,
-        )?;
+        let (net_in, fee) = pool.split_fee(amount_in)?;
+        let amount_out = pool.quote(amount_in)?;
         
         // ✅ Slippage protection
         require!(
             amount_out >= min_amount_out,
-            ErrorCode::SlippageExceeded
+            CommonError::SlippageExceeded
         );
         
         // ✅ CEI Pattern: Update state BEFORE CPI
+        // ✅ Accumulate the pre-swap price for the time it was in effect
+        pool.update_twap(Clock::get()?.unix_timestamp)?;
+        // ✅ The fee is booked outside the reserves, so collecting it
+        // later cannot move the price
         pool.reserve_in = pool.reserve_in
-            .checked_add(amount_in)
-            .ok_or(ErrorCode::Overflow)?;
+            .checked_add(net_in)
+            .ok_or(CommonError::Overflow)?;
+        pool.fees_collected = pool.fees_collected
+            .checked_add(fee)
+            .ok_or(CommonError::Overflow)?;
         pool.reserve_out = pool.reserve_out
             .checked_sub(amount_out)
-            .ok_or(ErrorCode::Underflow)?;
+            .ok_or(CommonError::Underflow)?;
         pool.total_volume = pool.total_volume
             .checked_add(amount_in)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
+        
+        assert_pool_invariants(pool)?;
         
         // ✅ SECURE: CPI with verified token program
         // Program<'info, Token> ensures this is the real SPL Token program
@@ -101,230 +279,1709 @@ pub mod secure_cpi {
         );
         token::transfer(cpi_ctx_out, amount_out)?;
         
+        // ✅ Strict mode: the balances the transfers left must be the ones
+        // the reserves describe
+        if let Some(tolerance) = reserve_tolerance {
+            ctx.accounts.pool_token_in.reload()?;
+            ctx.accounts.pool_token_out.reload()?;
+            pool.check_reserves(
+                ctx.accounts.pool_token_in.amount,
+                ctx.accounts.pool_token_out.amount,
+                tolerance,
+            )?;
+        }
+        
+        let sequence = pool.next_sequence()?;
+        
         emit!(SwapExecuted {
             pool: pool.key(),
             user: ctx.accounts.user.key(),
             amount_in,
             amount_out,
+            sequence,
         });
         
-        msg!("Swapped {} for {}", amount_in, amount_out);
+        log_event!("swap", amount_in = amount_in, amount_out = amount_out);
         Ok(())
     }
 
-    /// ✅ SECURE: Deposit with reentrancy protection
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        // ✅ Validate input
-        require!(amount > 0, ErrorCode::InvalidAmount);
+    /// ✅ SECURE: Buy exactly `amount_out`, paying at most `max_amount_in`
+    /// 
+    /// The exact-output counterpart of `swap_tokens`, on the same accounts.
+    /// The input is `Pool::quote_exact_out`, the least `amount_in` for which
+    /// `quote` gives at least `amount_out`, so both directions price a trade
+    /// identically. Constant-product pools only.
+    pub fn swap_exact_out(
+        ctx: Context<SwapTokens>,
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
         
-        let vault = &mut ctx.accounts.vault;
+        // ✅ Validate inputs
+        require!(amount_out > 0, CommonError::InvalidAmount);
         
-        // ✅ Reentrancy guard check
-        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        let pool = &mut ctx.accounts.pool;
         
-        // ✅ Set reentrancy guard
-        vault.locked = true;
+        // ✅ Fails with InsufficientLiquidity unless amount_out < reserve_out
+        let amount_in = pool.quote_exact_out(amount_out)?;
         
-        // ✅ CEI Pattern: Update state BEFORE CPI
-        vault.balance = vault.balance
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
-        vault.total_deposited = vault.total_deposited
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
-        vault.deposit_count = vault.deposit_count
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+        // ✅ Slippage protection, mirrored: cap the input, not the output
+        require!(
+            amount_in <= max_amount_in,
+            ErrorCode::ExcessiveInputAmount
+        );
+        require!(
+            ctx.accounts.user_token_in.amount >= amount_in,
+            CommonError::InsufficientFunds
+        );
         
-        // ✅ CPI with verified program
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.user_tokens.to_account_info(),
-            to: ctx.accounts.vault_tokens.to_account_info(),
+        // ✅ CEI Pattern: Update state BEFORE CPI, exactly as swap_tokens
+        // would for the same amount_in
+        let (net_in, fee) = pool.split_fee(amount_in)?;
+        pool.update_twap(Clock::get()?.unix_timestamp)?;
+        pool.reserve_in = pool.reserve_in
+            .checked_add(net_in)
+            .ok_or(CommonError::Overflow)?;
+        pool.fees_collected = pool.fees_collected
+            .checked_add(fee)
+            .ok_or(CommonError::Overflow)?;
+        pool.reserve_out = pool.reserve_out
+            .checked_sub(amount_out)
+            .ok_or(CommonError::Underflow)?;
+        pool.total_volume = pool.total_volume
+            .checked_add(amount_in)
+            .ok_or(CommonError::Overflow)?;
+        
+        assert_pool_invariants(pool)?;
+        
+        let cpi_accounts_in = Transfer {
+            from: ctx.accounts.user_token_in.to_account_info(),
+            to: ctx.accounts.pool_token_in.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
-        let cpi_ctx = CpiContext::new(
+        let cpi_ctx_in = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
+            cpi_accounts_in,
         );
-        token::transfer(cpi_ctx, amount)?;
+        token::transfer(cpi_ctx_in, amount_in)?;
         
-        // ✅ Release reentrancy guard
-        let vault = &mut ctx.accounts.vault;
-        vault.locked = false;
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
         
-        emit!(DepositMade {
-            vault: vault.key(),
+        let cpi_accounts_out = Transfer {
+            from: ctx.accounts.pool_token_out.to_account_info(),
+            to: ctx.accounts.user_token_out.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        let cpi_ctx_out = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts_out,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx_out, amount_out)?;
+        
+        let sequence = pool.next_sequence()?;
+        
+        emit!(SwapExecuted {
+            pool: pool.key(),
             user: ctx.accounts.user.key(),
-            amount,
-            new_balance: vault.balance,
+            amount_in,
+            amount_out,
+            sequence,
         });
         
-        msg!("Deposited {}. New balance: {}", amount, vault.balance);
+        log_event!("swap_exact_out", amount_in = amount_in, amount_out = amount_out);
         Ok(())
     }
 
-    /// ✅ SECURE: Withdraw with proper authority verification
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        // ✅ Validate input
-        require!(amount > 0, ErrorCode::InvalidAmount);
+    /// ✅ SECURE: Two-hop swap A → B → C through two verified pools
+    /// 
+    /// Intermediate amounts are threaded between hops and only the final
+    /// output is checked against `min_final_out`.
+    pub fn swap_route(
+        ctx: Context<SwapRoute>,
+        amount_in: u64,
+        min_final_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.pool1.check_version()?;
+        ctx.accounts.pool2.check_version()?;
         
-        let vault = &mut ctx.accounts.vault;
+        // ✅ Validate inputs
+        require!(amount_in > 0, CommonError::InvalidAmount);
+        require!(min_final_out > 0, ErrorCode::InvalidMinOutput);
         
-        // ✅ Check balance
         require!(
-            vault.balance >= amount,
-            ErrorCode::InsufficientBalance
+            ctx.accounts.user_token_in.amount >= amount_in,
+            CommonError::InsufficientFunds
         );
         
-        // ✅ Reentrancy guard
-        require!(!vault.locked, ErrorCode::ReentrancyDetected);
-        vault.locked = true;
+        let pool1 = &mut ctx.accounts.pool1;
+        let pool2 = &mut ctx.accounts.pool2;
         
-        // ✅ CEI: Update state first
-        vault.balance = vault.balance
-            .checked_sub(amount)
-            .ok_or(ErrorCode::Underflow)?;
-        vault.total_withdrawn = vault.total_withdrawn
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+        // ✅ Hop 1: A → B, Hop 2: B → C
+        let (net_in1, fee1) = pool1.split_fee(amount_in)?;
+        let intermediate_out = pool1.quote(amount_in)?;
+        let (net_in2, fee2) = pool2.split_fee(intermediate_out)?;
+        let final_out = pool2.quote(intermediate_out)?;
         
-        // ✅ CPI with PDA signer
-        let authority_key = ctx.accounts.authority.key();
-        let vault_seeds = &[
-            b"vault".as_ref(),
-            authority_key.as_ref(),
-            &[vault.bump],
+        // ✅ Single slippage check on the final output
+        require!(
+            final_out >= min_final_out,
+            CommonError::SlippageExceeded
+        );
+        
+        // ✅ CEI Pattern: Update both pools BEFORE CPI
+        let now = Clock::get()?.unix_timestamp;
+        pool1.update_twap(now)?;
+        pool2.update_twap(now)?;
+        pool1.reserve_in = pool1.reserve_in
+            .checked_add(net_in1)
+            .ok_or(CommonError::Overflow)?;
+        pool1.fees_collected = pool1.fees_collected
+            .checked_add(fee1)
+            .ok_or(CommonError::Overflow)?;
+        pool1.reserve_out = pool1.reserve_out
+            .checked_sub(intermediate_out)
+            .ok_or(CommonError::Underflow)?;
+        pool1.total_volume = pool1.total_volume
+            .checked_add(amount_in)
+            .ok_or(CommonError::Overflow)?;
+        
+        pool2.reserve_in = pool2.reserve_in
+            .checked_add(net_in2)
+            .ok_or(CommonError::Overflow)?;
+        pool2.fees_collected = pool2.fees_collected
+            .checked_add(fee2)
+            .ok_or(CommonError::Overflow)?;
+        pool2.reserve_out = pool2.reserve_out
+            .checked_sub(final_out)
+            .ok_or(CommonError::Underflow)?;
+        pool2.total_volume = pool2.total_volume
+            .checked_add(intermediate_out)
+            .ok_or(CommonError::Overflow)?;
+        
+        assert_pool_invariants(pool1)?;
+        assert_pool_invariants(pool2)?;
+        
+        // Transfer A from user to pool 1
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_token_in.to_account_info(),
+            to: ctx.accounts.pool1_token_in.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token::transfer(cpi_ctx, amount_in)?;
+        
+        // Transfer B from pool 1 to pool 2 (pool 1 PDA signs)
+        let pool1_seeds = &[
+            b"pool".as_ref(),
+            pool1.token_in_mint.as_ref(),
+            pool1.token_out_mint.as_ref(),
+            &[pool1.bump],
         ];
-        let signer_seeds = &[&vault_seeds[..]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool1_token_out.to_account_info(),
+            to: ctx.accounts.pool2_token_in.to_account_info(),
+            authority: pool1.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            &[&pool1_seeds[..]],
+        );
+        token::transfer(cpi_ctx, intermediate_out)?;
         
+        // Transfer C from pool 2 to user (pool 2 PDA signs)
+        let pool2_seeds = &[
+            b"pool".as_ref(),
+            pool2.token_in_mint.as_ref(),
+            pool2.token_out_mint.as_ref(),
+            &[pool2.bump],
+        ];
         let cpi_accounts = Transfer {
-            from: ctx.accounts.vault_tokens.to_account_info(),
-            to: ctx.accounts.user_tokens.to_account_info(),
-            authority: ctx.accounts.vault.to_account_info(),
+            from: ctx.accounts.pool2_token_out.to_account_info(),
+            to: ctx.accounts.user_token_out.to_account_info(),
+            authority: pool2.to_account_info(),
         };
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             cpi_accounts,
-            signer_seeds,
+            &[&pool2_seeds[..]],
         );
-        token::transfer(cpi_ctx, amount)?;
+        token::transfer(cpi_ctx, final_out)?;
         
-        // ✅ Release lock
-        let vault = &mut ctx.accounts.vault;
-        vault.locked = false;
+        let pool1_sequence = pool1.next_sequence()?;
+        let pool2_sequence = pool2.next_sequence()?;
         
-        emit!(WithdrawalMade {
-            vault: vault.key(),
-            authority: ctx.accounts.authority.key(),
-            amount,
-            remaining_balance: vault.balance,
+        emit!(RouteExecuted {
+            pool1: pool1.key(),
+            pool2: pool2.key(),
+            user: ctx.accounts.user.key(),
+            amount_in,
+            intermediate_out,
+            amount_out: final_out,
+            pool1_sequence,
+            pool2_sequence,
         });
         
+        log_event!("swap_route", amount_in = amount_in, intermediate_out = intermediate_out, amount_out = final_out);
         Ok(())
     }
-}
 
-/// Calculate swap output using constant product formula
-fn calculate_swap_output(
-    amount_in: u64,
-    reserve_in: u64,
-    reserve_out: u64,
-) -> Result<u64> {
-    // x * y = k (constant product)
-    // (x + dx) * (y - dy) = k
-    // dy = y * dx / (x + dx)
-    
-    let numerator = (amount_in as u128)
-        .checked_mul(reserve_out as u128)
-        .ok_or(ErrorCode::Overflow)?;
-    
-    let denominator = (reserve_in as u128)
-        .checked_add(amount_in as u128)
-        .ok_or(ErrorCode::Overflow)?;
+    /// ✅ SECURE: Queue a pool setting change (pool authority only)
+    ///
+    /// Nothing changes until `execute_admin_action` runs at least
+    /// `ADMIN_ACTION_DELAY` later, so traders see a fee change coming and
+    /// can leave first. Queuing again replaces the pending action and
+    /// restarts the delay.
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        // ✅ Validate now, so a queued action can always be executed
+        match action {
+            AdminAction::SetFee { fee_bps } => require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh),
+        }
+        
+        let pool = &mut ctx.accounts.pool;
+        let now = Clock::get()?.unix_timestamp;
+        pool.pending_action = Some(action);
+        pool.action_ready_at = now
+            .checked_add(ADMIN_ACTION_DELAY)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = pool.next_sequence()?;
+        
+        emit!(AdminActionQueued {
+            pool: pool.key(),
+            action,
+            ready_at: pool.action_ready_at,
+            sequence,
+        });
+        
+        log_event!("queue_admin_action", action = format_args!("{:?}", action), ready_at = pool.action_ready_at);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Apply the queued action once `ADMIN_ACTION_DELAY` has passed
+    ///
+    /// Anyone may submit it; the outcome was fixed by `queue_admin_action`.
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let action = pool.pending_action.ok_or(ErrorCode::NoQueuedAction)?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pool.action_ready_at, ErrorCode::ActionNotReady);
+        
+        pool.pending_action = None;
+        pool.action_ready_at = 0;
+        
+        match action {
+            AdminAction::SetFee { fee_bps } => {
+                let old_fee_bps = pool.fee_bps;
+                pool.fee_bps = fee_bps;
+                
+                let sequence = pool.next_sequence()?;
+                
+                emit!(FeeUpdated {
+                    pool: pool.key(),
+                    old_fee_bps,
+                    new_fee_bps: fee_bps,
+                    sequence,
+                });
+                
+                log_event!("execute_admin_action", action = "set_fee", old_fee_bps = old_fee_bps, fee_bps = fee_bps);
+            }
+        }
+        Ok(())
+    }
+
+    /// ✅ SECURE: Create the caller's LP position for `pool`
+    pub fn create_lp_position(ctx: Context<CreateLpPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.lp_position;
+        position.owner = ctx.accounts.user.key();
+        position.pool = ctx.accounts.pool.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.lp_position;
+        
+        log_event!("create_lp_position", owner = position.owner);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Add both sides of liquidity at the current reserve ratio
+    /// 
+    /// `amount_a` goes to the `token_in` side and `amount_b` to the
+    /// `token_out` side. The first deposit into an empty pool sets the price;
+    /// after that both amounts must match `reserve_in : reserve_out` within
+    /// `LIQUIDITY_RATIO_TOLERANCE_BPS`, or the depositor could move the price
+    /// for free. LP shares are credited to the caller's `LpPosition`.
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, amount_a: u64, amount_b: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        require!(amount_a > 0 && amount_b > 0, ErrorCode::ZeroLiquidity);
+        
+        let pool = &mut ctx.accounts.pool;
+        
+        if pool.lp_supply == 0 {
+            // ✅ Reserves nobody holds shares in would go to the first LP
+            require!(
+                pool.reserve_in == 0 && pool.reserve_out == 0,
+                CommonError::InvariantViolation
+            );
+        } else {
+            pool.check_deposit_ratio(amount_a, amount_b)?;
+        }
+        
+        let shares = logic::lp_shares_for_deposit(
+            amount_a,
+            amount_b,
+            pool.reserve_in,
+            pool.reserve_out,
+            pool.lp_supply,
+        )?;
+        require!(shares > 0, ErrorCode::ZeroLiquidity);
+        
+        // ✅ CEI Pattern: Update state BEFORE CPI
+        pool.update_twap(Clock::get()?.unix_timestamp)?;
+        pool.reserve_in = pool.reserve_in
+            .checked_add(amount_a)
+            .ok_or(CommonError::Overflow)?;
+        pool.reserve_out = pool.reserve_out
+            .checked_add(amount_b)
+            .ok_or(CommonError::Overflow)?;
+        pool.lp_supply = pool.lp_supply
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+        let position = &mut ctx.accounts.lp_position;
+        position.shares = position.shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+        
+        assert_pool_invariants(pool)?;
+        
+        for (from, to, amount) in [
+            (&ctx.accounts.user_token_in, &ctx.accounts.pool_token_in, amount_a),
+            (&ctx.accounts.user_token_out, &ctx.accounts.pool_token_out, amount_b),
+        ] {
+            let cpi_accounts = Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+            );
+            token::transfer(cpi_ctx, amount)?;
+        }
+        
+        let sequence = pool.next_sequence()?;
+        
+        emit!(LiquidityAdded {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_a,
+            amount_b,
+            shares,
+            sequence,
+        });
+        
+        log_event!("add_liquidity", amount_a = amount_a, amount_b = amount_b, shares = shares);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Start a TWAP window for the caller
+    /// 
+    /// Records the pool's accumulator as of now in a per-caller snapshot, so
+    /// nobody else can reset the caller's window.
+    pub fn snapshot_twap(ctx: Context<SnapshotTwap>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let snapshot = &mut ctx.accounts.snapshot;
+        snapshot.owner = ctx.accounts.owner.key();
+        snapshot.pool = ctx.accounts.pool.key();
+        snapshot.price_cumulative = ctx.accounts.pool.cumulative_price_at(now)?;
+        snapshot.timestamp = now;
+        snapshot.bump = ctx.bumps.snapshot;
+        
+        log_event!("snapshot_twap", timestamp = now);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Average price since the caller's snapshot
+    /// 
+    /// Fails with `TwapWindowTooShort` until at least `period` seconds have
+    /// passed, since a short window is cheap to move with one large swap,
+    /// and with `StaleData` once the snapshot is older than `MAX_TWAP_AGE`.
+    /// On success the snapshot rolls forward to now, and the average is
+    /// returned (Q64.64, `token_out` per `token_in`), logged and emitted.
+    pub fn consult_twap(ctx: Context<ConsultTwap>, period: i64) -> Result<u128> {
+        require!(period > 0, ErrorCode::TwapWindowTooShort);
+        
+        // ✅ Same staleness rule as every other time-based check
+        logic::require_fresh(ctx.accounts.snapshot.timestamp, MAX_TWAP_AGE)?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        let snapshot = &mut ctx.accounts.snapshot;
+        let elapsed = now
+            .checked_sub(snapshot.timestamp)
+            .ok_or(CommonError::Underflow)?;
+        require!(elapsed >= period, ErrorCode::TwapWindowTooShort);
+        
+        let cumulative = ctx.accounts.pool.cumulative_price_at(now)?;
+        let price = logic::twap_q64(snapshot.price_cumulative, cumulative, elapsed as u64)?;
+        
+        snapshot.price_cumulative = cumulative;
+        snapshot.timestamp = now;
+        
+        emit!(TwapConsulted {
+            pool: ctx.accounts.pool.key(),
+            owner: snapshot.owner,
+            price_q64: price,
+            window: elapsed,
+        });
+        
+        log_event!("consult_twap", window = elapsed, price_q64 = price);
+        Ok(price)
+    }
+
+    /// ✅ SECURE: Start the caller's TWAP window over from now
+    /// 
+    /// The way back from `StaleData`: the snapshot PDA already exists, so
+    /// `snapshot_twap` cannot run again.
+    pub fn restart_twap(ctx: Context<ConsultTwap>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let snapshot = &mut ctx.accounts.snapshot;
+        snapshot.price_cumulative = ctx.accounts.pool.cumulative_price_at(now)?;
+        snapshot.timestamp = now;
+        
+        log_event!("restart_twap", timestamp = now);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Switch the pricing curve (authority only)
+    /// 
+    /// `StableSwap` needs an amplification coefficient in `1..=MAX_AMP`;
+    /// `ConstantProduct` takes none, so `amp` must be 0. Anything else is
+    /// rejected with `UnsupportedCurve` before it can price a swap.
+    pub fn set_curve(ctx: Context<SetCurve>, curve: CurveType, amp: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.curve = curve;
+        pool.amp = amp;
+        pool.check_curve()?;
+        
+        let sequence = pool.next_sequence()?;
+        
+        emit!(CurveUpdated {
+            pool: pool.key(),
+            curve,
+            amp,
+            sequence,
+        });
+        
+        log_event!("set_curve", curve = format_args!("{:?}", curve), amp = amp);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Send accrued swap fees to a `FeeCollector`
+    /// 
+    /// Fees sit in `pool_token_in` next to `reserve_in` but are never part of
+    /// it, so paying them out leaves the constant-product reserves untouched.
+    pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        // ✅ Role check against the pool's AccessControl, not `pool.authority`
+        ctx.accounts
+            .access_control
+            .require_role(Role::FeeCollector, &ctx.accounts.admin.key())?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let amount = pool.fees_collected;
+        require!(amount > 0, ErrorCode::NoFeesToCollect);
+        
+        // ✅ CEI: Zero the fees before the transfer
+        pool.fees_collected = 0;
+        let sequence = pool.next_sequence()?;
+        
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_token_in.to_account_info(),
+            to: ctx.accounts.admin_tokens.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        emit!(FeesCollected {
+            pool: pool.key(),
+            admin: ctx.accounts.admin.key(),
+            amount,
+            sequence,
+        });
+        
+        log_event!("collect_fees", amount = amount);
+        Ok(())
+    }
+
+    /// Create the pool's `AccessControl` with the pool authority as the
+    /// first `Admin`
+    pub fn initialize_access_control(ctx: Context<InitializeAccessControl>) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.scope = ctx.accounts.pool.key();
+        access_control.grants = Vec::new();
+        access_control.bump = ctx.bumps.access_control;
+        access_control.grant(Role::Admin, ctx.accounts.authority.key())?;
+        
+        emit!(RoleGranted {
+            scope: access_control.scope,
+            role: Role::Admin,
+            key: ctx.accounts.authority.key(),
+            admin: ctx.accounts.authority.key(),
+        });
+        
+        log_event!("initialize_access_control", pool = access_control.scope, admin = ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// ✅ SECURE: Grant `role` to `key` (`Admin` only)
+    pub fn grant_role(ctx: Context<UpdateRoles>, role: Role, key: Pubkey) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.require_role(Role::Admin, &ctx.accounts.admin.key())?;
+        access_control.grant(role, key)?;
+        
+        emit!(RoleGranted {
+            scope: access_control.scope,
+            role,
+            key,
+            admin: ctx.accounts.admin.key(),
+        });
+        
+        log_event!("grant_role", pool = access_control.scope, role = format_args!("{:?}", role), key = key);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Revoke `role` from `key` (`Admin` only, never the last admin)
+    pub fn revoke_role(ctx: Context<UpdateRoles>, role: Role, key: Pubkey) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.require_role(Role::Admin, &ctx.accounts.admin.key())?;
+        access_control.revoke(role, key)?;
+        
+        emit!(RoleRevoked {
+            scope: access_control.scope,
+            role,
+            key,
+            admin: ctx.accounts.admin.key(),
+        });
+        
+        log_event!("revoke_role", pool = access_control.scope, role = format_args!("{:?}", role), key = key);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Create the vault PDA for `authority` over its token account
+    ///
+    /// `balance` starts at what `vault_tokens` actually holds, so the
+    /// reconcile invariant holds from the first instruction. Tokens already
+    /// in the account did not come through `deposit`; they are only booked
+    /// to `balance` when the authority passes `allow_prefunded`.
+    pub fn initialize_token_vault(ctx: Context<InitializeTokenVault>, allow_prefunded: bool) -> Result<()> {
+        let existing = ctx.accounts.vault_tokens.amount;
+        
+        // ✅ Pre-existing funds are opt-in, never silently credited
+        require!(existing == 0 || allow_prefunded, ErrorCode::PrefundedVault);
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        // ✅ Recorded balance comes from the token account, not a parameter
+        vault.balance = existing;
+        vault.total_deposited = 0;
+        vault.total_withdrawn = 0;
+        vault.deposit_count = 0;
+        vault.bump = ctx.bumps.vault;
+        vault.version = CURRENT_VERSION;
+        vault.locked = false;
+        vault.surplus = 0;
+        vault.sequence = 0;
+        vault.permit_nonce = 0;
+        vault.recent_deposit_keys = [[0; 32]; RECENT_DEPOSIT_KEYS];
+        vault.next_deposit_key = 0;
+        vault.frozen = false;
+        vault.freeze_reason = 0;
+        vault.frozen_allows_deposits = false;
+        
+        emit!(VaultInitialized {
+            vault: vault.key(),
+            authority: vault.authority,
+            vault_tokens: ctx.accounts.vault_tokens.key(),
+            balance: vault.balance,
+            sequence: vault.sequence,
+        });
+        
+        log_event!("initialize_token_vault", vault = vault.key(), balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Deposit with reentrancy protection
+    ///
+    /// Clients that retry can pass an `idempotency_key`. A key this
+    /// depositor used in one of the vault's last `RECENT_DEPOSIT_KEYS`
+    /// keyed deposits makes the call a logged no-op that still succeeds,
+    /// so a retried deposit is credited once. Keys are scoped to the
+    /// depositor, so nobody can pre-spend another user's key.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, idempotency_key: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        // ✅ Validate input
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        // ✅ Reentrancy guard check
+        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        
+        // ✅ A frozen vault takes deposits only if the freeze allows it
+        require!(vault.accepts_deposits(), ErrorCode::VaultFrozen);
+        
+        // ✅ Duplicate request: nothing moves, nothing is credited
+        let user_key = idempotency_key.map(|key| Vault::deposit_key(&ctx.accounts.user.key(), &key));
+        if let Some(user_key) = user_key {
+            if vault.recent_deposit_keys.contains(&user_key) {
+                log_event!("duplicate_request", vault = vault.key(), user = ctx.accounts.user.key(), amount = amount);
+                return Ok(());
+            }
+        }
+        
+        // ✅ Set reentrancy guard. Any error from here to the release
+        // fails the transaction and the runtime discards every write,
+        // the lock included, so no unlock is needed on the error path.
+        vault.locked = true;
+        
+        // ✅ CEI Pattern: Update state BEFORE CPI
+        vault.balance = vault.balance
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.total_deposited = vault.total_deposited
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.deposit_count = vault.deposit_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        if let Some(user_key) = user_key {
+            vault.remember_deposit_key(user_key);
+        }
+        
+        // ✅ CPI with verified program
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        // ✅ Release reentrancy guard
+        let vault = &mut ctx.accounts.vault;
+        vault.locked = false;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(DepositMade {
+            vault: vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            new_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("deposit", vault = vault.key(), amount = amount, balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Deposit on the vault authority's behalf with a signed permit
+    ///
+    /// Gasless deposit: the authority has approved the vault PDA as delegate
+    /// on `user_tokens` and signs `permit_message(...)` off-chain. Anyone
+    /// may submit the transaction, which must carry that signature in an
+    /// ed25519 program instruction immediately before this one. The vault
+    /// PDA then pulls `amount` as delegate.
+    ///
+    /// A submitter CANNOT:
+    /// - Change the amount, vault or source account (message would not match)
+    /// - Replay a permit (nonce must exceed `vault.permit_nonce`)
+    /// - Use a permit after `deadline`
+    /// - Pull more than the authority approved
+    pub fn deposit_with_permit(
+        ctx: Context<DepositWithPermit>,
+        amount: u64,
+        deadline: i64,
+        nonce: u64,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        // ✅ Permit window
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline, ErrorCode::PermitExpired);
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        // ✅ Each permit is usable once, in order
+        require!(nonce > vault.permit_nonce, ErrorCode::InvalidPermit);
+        
+        // ✅ The approval, not the permit, caps what can be pulled
+        require!(
+            ctx.accounts.user_tokens.delegated_amount >= amount,
+            ErrorCode::InvalidPermit
+        );
+        
+        // ✅ The token owner signed exactly this deposit
+        let message = permit_message(
+            &vault.key(),
+            &ctx.accounts.user_tokens.key(),
+            amount,
+            deadline,
+            nonce,
+        );
+        verify_permit_signature(
+            &ctx.accounts.instructions.to_account_info(),
+            &vault.authority,
+            &message,
+            &signature,
+        )?;
+        
+        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        require!(vault.accepts_deposits(), ErrorCode::VaultFrozen);
+        vault.locked = true;
+        
+        // ✅ CEI: consume the nonce and book the deposit before the CPI
+        vault.permit_nonce = nonce;
+        vault.balance = vault.balance
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.total_deposited = vault.total_deposited
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.deposit_count = vault.deposit_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        
+        // ✅ Vault PDA signs as the approved delegate
+        let authority_key = vault.authority;
+        let vault_seeds = &[
+            b"vault".as_ref(),
+            authority_key.as_ref(),
+            &[vault.bump],
+        ];
+        let signer_seeds = &[&vault_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.locked = false;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(DepositMade {
+            vault: vault.key(),
+            user: authority_key,
+            amount,
+            new_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("deposit_with_permit", vault = vault.key(), amount = amount, nonce = nonce, balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw with proper authority verification
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        // ✅ Validate input
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        // ✅ Nothing leaves a frozen vault
+        require!(!vault.frozen, ErrorCode::VaultFrozen);
+        
+        // ✅ Check balance
+        require!(
+            vault.balance >= amount,
+            CommonError::InsufficientFunds
+        );
+        
+        // ✅ Reentrancy guard
+        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        vault.locked = true;
+        
+        // ✅ CEI: Update state first
+        vault.balance = vault.balance
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        vault.total_withdrawn = vault.total_withdrawn
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        
+        // ✅ Write the lock and debit to the account before the CPI. Anchor
+        // only serializes `vault` when the instruction returns, so without
+        // this the token program would see it unlocked and undebited.
+        vault.exit(&crate::ID)?;
+        
+        // ✅ CPI with PDA signer
+        let authority_key = ctx.accounts.authority.key();
+        let vault_seeds = &[
+            b"vault".as_ref(),
+            authority_key.as_ref(),
+            &[vault.bump],
+        ];
+        let signer_seeds = &[&vault_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        // ✅ Release lock
+        let vault = &mut ctx.accounts.vault;
+        vault.locked = false;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("withdraw", vault = vault.key(), amount = amount, balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Compare the vault's token balance with its recorded balance
+    /// 
+    /// Tokens sent straight to `vault_tokens` never pass through `deposit`.
+    /// A surplus is a donation: with `credit_surplus` it is booked to
+    /// `vault.surplus` for the authority. A shortfall means tokens left
+    /// without the program's accounting and fails with `BalanceInvariantViolated`.
+    pub fn reconcile(ctx: Context<Reconcile>, credit_surplus: bool) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        
+        // Everything the program has accounted for
+        let recorded = vault.balance
+            .checked_add(vault.surplus)
+            .ok_or(CommonError::Overflow)?;
+        let actual = ctx.accounts.vault_tokens.amount;
+        
+        // ✅ Fewer tokens than recorded: the invariant is broken, stop here
+        require!(actual >= recorded, ErrorCode::BalanceInvariantViolated);
+        
+        if credit_surplus && actual > recorded {
+            vault.surplus = vault.surplus
+                .checked_add(actual - recorded)
+                .ok_or(CommonError::Overflow)?;
+        }
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(Reconciled {
+            vault: vault.key(),
+            recorded,
+            actual,
+            sequence,
+        });
+        
+        log_event!("reconcile", recorded = recorded, actual = actual);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Freeze one vault's withdrawals, recording why
+    ///
+    /// Scoped to this vault, so an incident in one vault does not halt the
+    /// others. `reason` is a nonzero `FREEZE_REASON_*` code (or any other
+    /// nonzero code off-chain tooling assigns). With `allow_deposits`,
+    /// deposits keep landing while withdrawals are blocked. Freezing a
+    /// frozen vault replaces its reason and deposit policy.
+    pub fn freeze_vault(ctx: Context<SetVaultFreeze>, reason: u8, allow_deposits: bool) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(reason != 0, ErrorCode::InvalidFreezeReason);
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.frozen = true;
+        vault.freeze_reason = reason;
+        vault.frozen_allows_deposits = allow_deposits;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(VaultFrozen {
+            vault: vault.key(),
+            reason,
+            allow_deposits,
+            sequence,
+        });
+        
+        log_event!("freeze_vault", vault = vault.key(), reason = reason, allow_deposits = allow_deposits);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Lift a freeze set by `freeze_vault`
+    pub fn unfreeze_vault(ctx: Context<SetVaultFreeze>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        require!(vault.frozen, ErrorCode::VaultNotFrozen);
+        
+        let reason = vault.freeze_reason;
+        vault.frozen = false;
+        vault.freeze_reason = 0;
+        vault.frozen_allows_deposits = false;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(VaultUnfrozen {
+            vault: vault.key(),
+            reason,
+            sequence,
+        });
+        
+        log_event!("unfreeze_vault", vault = vault.key(), reason = reason);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a vault left at the previous layout version
+    ///
+    /// The only way out of `VersionMismatch`: every other vault handler
+    /// rejects the account until this has run. The layout has not changed
+    /// since versioning began, so only the version is rewritten; a future
+    /// `CURRENT_VERSION` bump converts the old fields here.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let from_version = vault.version;
+        check_migratable(from_version)?;
+        
+        vault.version = CURRENT_VERSION;
+        let sequence = vault.next_sequence()?;
+        
+        emit!(AccountMigrated {
+            account: vault.key(),
+            from_version,
+            to_version: vault.version,
+            sequence,
+        });
+        
+        log_event!("migrate_vault", vault = vault.key(), from_version = from_version, to_version = vault.version);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a pool left at the previous layout version
+    ///
+    /// Same rules as `migrate_vault`, signed by `pool.authority`.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let from_version = pool.version;
+        check_migratable(from_version)?;
+        
+        pool.version = CURRENT_VERSION;
+        let sequence = pool.next_sequence()?;
+        
+        emit!(AccountMigrated {
+            account: pool.key(),
+            from_version,
+            to_version: pool.version,
+            sequence,
+        });
+        
+        log_event!("migrate_pool", pool = pool.key(), from_version = from_version, to_version = pool.version);
+        Ok(())
+    }
+
+    /// Create an empty CPI whitelist owned by `authority`
+    pub fn initialize_whitelist(ctx: Context<InitializeWhitelist>) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        whitelist.authority = ctx.accounts.authority.key();
+        whitelist.allowed = Vec::new();
+        whitelist.bump = ctx.bumps.whitelist;
+        
+        log_event!("initialize_whitelist", whitelist = whitelist.key(), authority = whitelist.authority);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Add a CPI target (whitelist authority only)
+    pub fn add_program(ctx: Context<UpdateWhitelist>, program_id: Pubkey) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        
+        require!(
+            !whitelist.allowed.contains(&program_id),
+            ErrorCode::ProgramAlreadyWhitelisted
+        );
+        require!(
+            whitelist.allowed.len() < MAX_WHITELISTED_PROGRAMS,
+            ErrorCode::WhitelistFull
+        );
+        
+        whitelist.allowed.push(program_id);
+        
+        emit!(WhitelistUpdated {
+            whitelist: whitelist.key(),
+            program_id,
+            allowed: true,
+        });
+        
+        log_event!("add_program", whitelist = whitelist.key(), program_id = program_id);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Remove a CPI target (whitelist authority only)
+    pub fn remove_program(ctx: Context<UpdateWhitelist>, program_id: Pubkey) -> Result<()> {
+        let whitelist = &mut ctx.accounts.whitelist;
+        
+        require!(
+            whitelist.allowed.contains(&program_id),
+            ErrorCode::ProgramNotWhitelisted
+        );
+        
+        whitelist.allowed.retain(|allowed| *allowed != program_id);
+        
+        emit!(WhitelistUpdated {
+            whitelist: whitelist.key(),
+            program_id,
+            allowed: false,
+        });
+        
+        log_event!("remove_program", whitelist = whitelist.key(), program_id = program_id);
+        Ok(())
+    }
+
+    /// ✅ SECURE: CPI into a configurable target, only if it is whitelisted
+    /// 
+    /// Accounts for the target instruction are passed as remaining accounts.
+    /// The CPI carries only the signatures of the outer transaction: this
+    /// program never signs with its own PDAs for an arbitrary target.
+    pub fn invoke_whitelisted(ctx: Context<InvokeWhitelisted>, data: Vec<u8>) -> Result<()> {
+        let target = &ctx.accounts.target_program;
+        
+        // ✅ SECURE: Target program ID must be on the whitelist
+        require!(
+            ctx.accounts.whitelist.allowed.contains(&target.key()),
+            ErrorCode::ProgramNotWhitelisted
+        );
+        
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+        let ix = Instruction {
+            program_id: target.key(),
+            accounts,
+            data,
+        };
+        
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(target.to_account_info());
+        invoke(&ix, &account_infos)?;
+        
+        log_event!("invoke_whitelisted", program_id = target.key());
+        Ok(())
+    }
+}
+
+/// ✅ Only the layout one version behind `CURRENT_VERSION` migrates
+///
+/// A current account fails with `AlreadyMigrated`. Anything older, or
+/// written by a newer deployment, fails with `VersionMismatch`: there is no
+/// conversion for it here.
+fn check_migratable(version: u8) -> Result<()> {
+    require!(version != CURRENT_VERSION, ErrorCode::AlreadyMigrated);
+    require!(
+        version.checked_add(1) == Some(CURRENT_VERSION),
+        ErrorCode::VersionMismatch
+    );
+    Ok(())
+}
+
+/// ✅ Post-condition for every reserve-mutating path
+/// 
+/// A pool with an empty side can't price a swap: `quote` returns 0 for every
+/// input and the next depositor sets an arbitrary price.
+pub fn assert_pool_invariants(pool: &Pool) -> Result<()> {
+    require!(
+        pool.reserve_in > 0 && pool.reserve_out > 0,
+        CommonError::InvariantViolation
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    // ✅ SECURE: One pool per mint pair, canonical bump stored on creation
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [
+            b"pool",
+            token_in_mint.key().as_ref(),
+            token_out_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub token_in_mint: Account<'info, Mint>,
+    
+    #[account(
+        constraint = token_out_mint.key() != token_in_mint.key() @ CommonError::MintMismatch
+    )]
+    pub token_out_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SwapTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ Verify token account ownership and mint
+    #[account(
+        mut,
+        constraint = user_token_in.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_in.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_out.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_out.mint == pool.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ Verify pool PDA and token accounts
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(
+        mut,
+        constraint = pool_token_in.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_in.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool_token_out.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_out.mint == pool.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Program<'info, Token> verifies this is SPL Token
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ User pays in pool 1's input mint
+    #[account(
+        mut,
+        constraint = user_token_in.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_in.mint == pool1.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_in: Account<'info, TokenAccount>,
+    
+    // ✅ User receives pool 2's output mint
+    #[account(
+        mut,
+        constraint = user_token_out.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_out.mint == pool2.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ Verify pool 1 PDA
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool1.token_in_mint.as_ref(),
+            pool1.token_out_mint.as_ref()
+        ],
+        bump = pool1.bump
+    )]
+    pub pool1: Account<'info, Pool>,
+    
+    #[account(
+        mut,
+        constraint = pool1_token_in.owner == pool1.key() @ CommonError::InvalidOwner,
+        constraint = pool1_token_in.mint == pool1.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub pool1_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool1_token_out.owner == pool1.key() @ CommonError::InvalidOwner,
+        constraint = pool1_token_out.mint == pool1.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub pool1_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ Verify pool 2 PDA, that it is a different pool,
+    // and that the route connects: pool 1 outputs what pool 2 takes in
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool2.token_in_mint.as_ref(),
+            pool2.token_out_mint.as_ref()
+        ],
+        bump = pool2.bump,
+        constraint = pool2.key() != pool1.key() @ ErrorCode::RouteMintMismatch,
+        constraint = pool1.token_out_mint == pool2.token_in_mint @ ErrorCode::RouteMintMismatch
+    )]
+    pub pool2: Account<'info, Pool>,
+    
+    #[account(
+        mut,
+        constraint = pool2_token_in.owner == pool2.key() @ CommonError::InvalidOwner,
+        constraint = pool2_token_in.mint == pool2.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub pool2_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool2_token_out.owner == pool2.key() @ CommonError::InvalidOwner,
+        constraint = pool2_token_out.mint == pool2.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub pool2_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Program<'info, Token> verifies this is SPL Token
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLpPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: One position per (pool, user), at a derivable address
+    #[account(
+        init,
+        payer = user,
+        space = 8 + LpPosition::INIT_SPACE,
+        seeds = [b"lp", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+    
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_in.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_in.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_token_out.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_token_out.mint == pool.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub user_token_out: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ Deposits must land in the pool's own token accounts
+    #[account(
+        mut,
+        constraint = pool_token_in.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_in.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool_token_out.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_out.mint == pool.token_out_mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_out: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Shares are credited to the depositor's own position
+    #[account(
+        mut,
+        seeds = [b"lp", pool.key().as_ref(), user.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Account<'info, LpPosition>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotTwap<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    // ✅ SECURE: One snapshot per (pool, owner); only the owner starts a window
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TwapSnapshot::INIT_SPACE,
+        seeds = [b"twap", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, TwapSnapshot>,
+    
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConsultTwap<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"twap", pool.key().as_ref(), owner.key().as_ref()],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, TwapSnapshot>,
+    
+    #[account(
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct SetCurve<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ Only a FeeCollector collects; seeds tie the role list to this pool
+    #[account(
+        seeds = [b"access_control", pool.key().as_ref()],
+        bump = access_control.bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    
+    #[account(
+        mut,
+        constraint = pool_token_in.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_in.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_in: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = admin_tokens.mint == pool.token_in_mint @ CommonError::MintMismatch
+    )]
+    pub admin_tokens: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAccessControl<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AccessControl::INIT_SPACE,
+        seeds = [b"access_control", pool.key().as_ref()],
+        bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    
+    // ✅ Only the pool authority can create its role list, and only once
+    #[account(has_one = authority @ CommonError::Unauthorized)]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    #[account(
+        mut,
+        seeds = [b"access_control", access_control.scope.as_ref()],
+        bump = access_control.bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenVault<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
     
-    let amount_out = numerator
-        .checked_div(denominator)
-        .ok_or(ErrorCode::Overflow)?;
+    // ✅ SECURE: Must already belong to the vault PDA being created
+    #[account(
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
     
-    require!(
-        amount_out <= u64::MAX as u128,
-        ErrorCode::OutputTooLarge
-    );
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
-    Ok(amount_out as u64)
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct SwapTokens<'info> {
+pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
-    // ✅ Verify token account ownership and mint
     #[account(
         mut,
-        constraint = user_token_in.owner == user.key() @ ErrorCode::InvalidOwner,
-        constraint = user_token_in.mint == pool.token_in_mint @ ErrorCode::MintMismatch
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner
     )]
-    pub user_token_in: Account<'info, TokenAccount>,
+    pub user_tokens: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = user_token_out.owner == user.key() @ ErrorCode::InvalidOwner,
-        constraint = user_token_out.mint == pool.token_out_mint @ ErrorCode::MintMismatch
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
     )]
-    pub user_token_out: Account<'info, TokenAccount>,
+    pub vault: Account<'info, Vault>,
     
-    // ✅ Verify pool PDA and token accounts
     #[account(
         mut,
-        seeds = [
-            b"pool",
-            pool.token_in_mint.as_ref(),
-            pool.token_out_mint.as_ref()
-        ],
-        bump = pool.bump
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner
     )]
-    pub pool: Account<'info, Pool>,
+    pub vault_tokens: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositWithPermit<'info> {
+    // The vault authority does not sign the transaction; its ed25519
+    // signature is checked through the instructions sysvar instead
+    pub submitter: Signer<'info>,
     
     #[account(
         mut,
-        constraint = pool_token_in.owner == pool.key() @ ErrorCode::InvalidOwner,
-        constraint = pool_token_in.mint == pool.token_in_mint @ ErrorCode::MintMismatch
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
     )]
-    pub pool_token_in: Account<'info, TokenAccount>,
+    pub vault: Account<'info, Vault>,
     
+    // ✅ SECURE: The permit signer's own account, delegated to this vault
     #[account(
         mut,
-        constraint = pool_token_out.owner == pool.key() @ ErrorCode::InvalidOwner,
-        constraint = pool_token_out.mint == pool.token_out_mint @ ErrorCode::MintMismatch
+        constraint = user_tokens.owner == vault.authority @ CommonError::InvalidOwner,
+        constraint = user_tokens.delegate == COption::Some(vault.key()) @ ErrorCode::InvalidPermit
     )]
-    pub pool_token_out: Account<'info, TokenAccount>,
+    pub user_tokens: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner,
+        constraint = vault_tokens.mint == user_tokens.mint @ CommonError::MintMismatch
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    
+    /// CHECK: Address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
     
-    // ✅ SECURE: Program<'info, Token> verifies this is SPL Token
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Deposit<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+pub struct Withdraw<'info> {
+    pub authority: Signer<'info>,
     
     #[account(
         mut,
-        constraint = user_tokens.owner == user.key() @ ErrorCode::InvalidOwner
+        constraint = user_tokens.owner == authority.key() @ CommonError::InvalidOwner
     )]
     pub user_tokens: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        seeds = [b"vault", vault.authority.as_ref()],
-        bump = vault.bump
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized
     )]
     pub vault: Account<'info, Vault>,
     
     #[account(
         mut,
-        constraint = vault_tokens.owner == vault.key() @ ErrorCode::InvalidOwner
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner
     )]
     pub vault_tokens: Account<'info, TokenAccount>,
     
@@ -332,30 +1989,112 @@ pub struct Deposit<'info> {
 }
 
 #[derive(Accounts)]
-pub struct Withdraw<'info> {
+pub struct Reconcile<'info> {
     pub authority: Signer<'info>,
     
     #[account(
         mut,
-        constraint = user_tokens.owner == authority.key() @ ErrorCode::InvalidOwner
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized
     )]
-    pub user_tokens: Account<'info, TokenAccount>,
+    pub vault: Account<'info, Vault>,
+    
+    #[account(
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetVaultFreeze<'info> {
+    pub authority: Signer<'info>,
     
+    // ✅ Only the vault's own authority can freeze or unfreeze it
     #[account(
         mut,
         seeds = [b"vault", authority.key().as_ref()],
         bump = vault.bump,
-        has_one = authority @ ErrorCode::Unauthorized
+        has_one = authority @ CommonError::Unauthorized
     )]
     pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    pub authority: Signer<'info>,
     
+    // ✅ Only the vault's own authority can migrate it
     #[account(
         mut,
-        constraint = vault_tokens.owner == vault.key() @ ErrorCode::InvalidOwner
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized
     )]
-    pub vault_tokens: Account<'info, TokenAccount>,
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pool",
+            pool.token_in_mint.as_ref(),
+            pool.token_out_mint.as_ref()
+        ],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
     
-    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWhitelist<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramWhitelist::INIT_SPACE,
+        seeds = [b"whitelist", authority.key().as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, ProgramWhitelist>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateWhitelist<'info> {
+    // ✅ Only the whitelist's admin can change it
+    #[account(
+        mut,
+        seeds = [b"whitelist", authority.key().as_ref()],
+        bump = whitelist.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub whitelist: Account<'info, ProgramWhitelist>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InvokeWhitelisted<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        seeds = [b"whitelist", whitelist.authority.as_ref()],
+        bump = whitelist.bump
+    )]
+    pub whitelist: Account<'info, ProgramWhitelist>,
+    
+    /// CHECK: Program ID is checked against `whitelist.allowed` in the handler
+    #[account(executable)]
+    pub target_program: UncheckedAccount<'info>,
 }
 
 #[account]
@@ -367,9 +2106,214 @@ pub struct Pool {
     pub reserve_in: u64,
     pub reserve_out: u64,
     pub total_volume: u64,
+    /// Swap fee taken from `amount_in`, in basis points
+    pub fee_bps: u16,
+    /// Fees held in `pool_token_in` but excluded from `reserve_in`
+    pub fees_collected: u64,
+    /// Pricing curve used by `quote`
+    pub curve: CurveType,
+    /// StableSwap amplification coefficient; 0 for `ConstantProduct`
+    pub amp: u64,
+    /// LP shares outstanding across all `LpPosition`s
+    pub lp_supply: u64,
+    /// Sum of Q64.64 spot price × seconds, wrapping (see `logic::accumulate_price`)
+    pub price_cumulative: u128,
+    /// When `price_cumulative` was last advanced
+    pub last_twap_update: i64,
+    pub bump: u8,
+    /// Bumped by every mutating instruction and emitted with its event,
+    /// so indexers get a total order per pool and can detect gaps
+    pub sequence: u64,
+    /// Setting change waiting for `action_ready_at`
+    pub pending_action: Option<AdminAction>,
+    /// Earliest time `execute_admin_action` may apply `pending_action`
+    pub action_ready_at: i64,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+/// Pool setting changes that wait `ADMIN_ACTION_DELAY` between
+/// `queue_admin_action` and `execute_admin_action`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    /// Swap fee in basis points, at most `MAX_FEE_BPS`
+    SetFee { fee_bps: u16 },
+}
+
+impl Pool {
+    /// ✅ Reject pools written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Advance `sequence` and return it for this instruction's event
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        Ok(self.sequence)
+    }
+
+    /// ✅ Compare the pool's token balances with what it has recorded
+    ///
+    /// `pool_token_in` holds `reserve_in` plus `fees_collected`;
+    /// `pool_token_out` holds `reserve_out`. A difference of more than
+    /// `tolerance` on either side means tokens moved without going through
+    /// the pool.
+    pub fn check_reserves(&self, balance_in: u64, balance_out: u64, tolerance: u64) -> Result<()> {
+        let expected_in = self.reserve_in
+            .checked_add(self.fees_collected)
+            .ok_or(CommonError::Overflow)?;
+        require!(
+            balance_in.abs_diff(expected_in) <= tolerance
+                && balance_out.abs_diff(self.reserve_out) <= tolerance,
+            ErrorCode::ReserveMismatch
+        );
+        Ok(())
+    }
+
+    /// Split `amount_in` into the part that enters the reserves and the fee
+    pub fn split_fee(&self, amount_in: u64) -> Result<(u64, u64)> {
+        let fee = logic::apply_bps(amount_in, self.fee_bps)?;
+        let net_in = amount_in
+            .checked_sub(fee)
+            .ok_or(CommonError::Underflow)?;
+        Ok((net_in, fee))
+    }
+
+    /// Expected output for `amount_in` at the current reserves, after the fee
+    /// 
+    /// Clients quote off-chain and derive `min_amount_out` with a small tolerance:
+    /// 
+    /// ```ignore
+    /// let expected = pool.quote(amount_in)?;
+    /// let min_amount_out = expected - expected / 100; // 1% slippage
+    /// ```
+    /// 
+    /// If a front-runner moves the reserves before the swap lands, the
+    /// on-chain output falls below `min_amount_out` and the swap reverts
+    /// with `SlippageExceeded` instead of filling at the worse price.
+    pub fn quote(&self, amount_in: u64) -> Result<u64> {
+        self.check_curve()?;
+        let (net_in, _) = self.split_fee(amount_in)?;
+        match self.curve {
+            CurveType::ConstantProduct => {
+                logic::swap_output(net_in, self.reserve_in, self.reserve_out)
+            }
+            CurveType::StableSwap => {
+                logic::stable_swap_output(net_in, self.reserve_in, self.reserve_out, self.amp)
+            }
+        }
+    }
+
+    /// Least `amount_in` for which `quote` returns at least `amount_out`
+    /// 
+    /// Inverts the curve with `logic::swap_input`, then grosses the result
+    /// up for the fee `split_fee` will take. Both steps round so that
+    /// `quote(n) >= amount_out` holds for `n = quote_exact_out(amount_out)`
+    /// and fails for `n - 1`. Constant product only: StableSwap has no
+    /// closed-form inverse.
+    pub fn quote_exact_out(&self, amount_out: u64) -> Result<u64> {
+        self.check_curve()?;
+        require!(self.curve == CurveType::ConstantProduct, ErrorCode::UnsupportedCurve);
+        require!(amount_out < self.reserve_out, ErrorCode::InsufficientLiquidity);
+        
+        let net_in = logic::swap_input(amount_out, self.reserve_in, self.reserve_out)?;
+        require!(net_in > 0, CommonError::InvariantViolation);
+        
+        // split_fee keeps a - floor(a * fee / D) = ceil(a * (D - fee) / D);
+        // the least a for which that reaches net_in
+        let keep_bps = logic::BPS_DENOMINATOR
+            .checked_sub(self.fee_bps as u64)
+            .ok_or(CommonError::Underflow)?;
+        let amount_in = ((net_in - 1) as u128 * logic::BPS_DENOMINATOR as u128)
+            .checked_div(keep_bps as u128)
+            .ok_or(logic::LogicError::DivisionByZero)?
+            + 1;
+        require!(amount_in <= u64::MAX as u128, CommonError::Overflow);
+        Ok(amount_in as u64)
+    }
+
+    /// `price_cumulative` as it would read at `now`, without writing it
+    /// 
+    /// The current spot price has been in effect since `last_twap_update`.
+    /// An empty pool has no price and adds nothing.
+    pub fn cumulative_price_at(&self, now: i64) -> Result<u128> {
+        let elapsed = now
+            .checked_sub(self.last_twap_update)
+            .ok_or(CommonError::Underflow)?;
+        require!(elapsed >= 0, logic::LogicError::InvalidTimestamp);
+        
+        if elapsed == 0 || self.reserve_in == 0 || self.reserve_out == 0 {
+            return Ok(self.price_cumulative);
+        }
+        let price = logic::spot_price_q64(self.reserve_in, self.reserve_out)?;
+        Ok(logic::accumulate_price(self.price_cumulative, price, elapsed as u64))
+    }
+
+    /// ✅ Advance the TWAP accumulator to `now`; call BEFORE reserves change
+    pub fn update_twap(&mut self, now: i64) -> Result<()> {
+        self.price_cumulative = self.cumulative_price_at(now)?;
+        self.last_twap_update = now;
+        Ok(())
+    }
+
+    /// ✅ Require `amount_a : amount_b` to match `reserve_in : reserve_out`
+    /// within `LIQUIDITY_RATIO_TOLERANCE_BPS`
+    pub fn check_deposit_ratio(&self, amount_a: u64, amount_b: u64) -> Result<()> {
+        // Cross-multiplied so no division rounds the comparison
+        let lhs = amount_a as u128 * self.reserve_out as u128;
+        let rhs = amount_b as u128 * self.reserve_in as u128;
+        let tolerance = lhs.max(rhs) * LIQUIDITY_RATIO_TOLERANCE_BPS as u128
+            / logic::BPS_DENOMINATOR as u128;
+        require!(lhs.abs_diff(rhs) <= tolerance, ErrorCode::RatioMismatch);
+        Ok(())
+    }
+
+    /// ✅ Reject curve/`amp` combinations that can't price a swap
+    pub fn check_curve(&self) -> Result<()> {
+        let valid = match self.curve {
+            CurveType::ConstantProduct => self.amp == 0,
+            CurveType::StableSwap => self.amp > 0 && self.amp <= logic::MAX_AMP,
+        };
+        require!(valid, ErrorCode::UnsupportedCurve);
+        Ok(())
+    }
+}
+
+/// A liquidity provider's share of one `Pool`
+#[account]
+#[derive(InitSpace)]
+pub struct LpPosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+/// Start of a caller's TWAP window over one `Pool`
+#[account]
+#[derive(InitSpace)]
+pub struct TwapSnapshot {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    /// `Pool::price_cumulative` as of `timestamp`
+    pub price_cumulative: u128,
+    pub timestamp: i64,
     pub bump: u8,
 }
 
+/// How a `Pool` prices swaps
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    /// `x * y = k`, for uncorrelated pairs
+    ConstantProduct,
+    /// Curve-style invariant with amplification `Pool::amp`, for pairs
+    /// expected to trade near 1:1 (stablecoins)
+    StableSwap,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
@@ -380,6 +2324,79 @@ pub struct Vault {
     pub deposit_count: u64,
     pub bump: u8,
     pub locked: bool,  // ✅ Reentrancy guard
+    /// Tokens donated directly to `vault_tokens`, credited by `reconcile`
+    pub surplus: u64,
+    /// Bumped by every mutating instruction and emitted with its event,
+    /// so indexers get a total order per vault and can detect gaps
+    pub sequence: u64,
+    /// Highest nonce consumed by `deposit_with_permit`
+    pub permit_nonce: u64,
+    /// Ring buffer of recent `deposit` keys, each hashed with its depositor;
+    /// all-zero slots are empty
+    pub recent_deposit_keys: [[u8; 32]; RECENT_DEPOSIT_KEYS],
+    /// Slot of `recent_deposit_keys` the next key overwrites
+    pub next_deposit_key: u8,
+    /// Set by `freeze_vault`: withdrawals fail with `VaultFrozen`
+    pub frozen: bool,
+    /// `FREEZE_REASON_*` code of the current freeze, 0 when not frozen
+    pub freeze_reason: u8,
+    /// Whether deposits are still accepted while `frozen`
+    pub frozen_allows_deposits: bool,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl Vault {
+    /// ✅ Reject vaults written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Advance `sequence` and return it for this instruction's event
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        Ok(self.sequence)
+    }
+    
+    /// `idempotency_key` as stored for `user`: two depositors sending the
+    /// same key never collide
+    pub fn deposit_key(user: &Pubkey, idempotency_key: &[u8; 32]) -> [u8; 32] {
+        hashv(&[user.as_ref(), idempotency_key]).to_bytes()
+    }
+    
+    /// Deposits are open unless frozen with `allow_deposits = false`
+    pub fn accepts_deposits(&self) -> bool {
+        !self.frozen || self.frozen_allows_deposits
+    }
+    
+    /// Record `key`, evicting the oldest once all slots are used
+    pub fn remember_deposit_key(&mut self, key: [u8; 32]) {
+        let slot = self.next_deposit_key as usize % RECENT_DEPOSIT_KEYS;
+        self.recent_deposit_keys[slot] = key;
+        self.next_deposit_key = ((slot + 1) % RECENT_DEPOSIT_KEYS) as u8;
+    }
+}
+
+/// Programs `invoke_whitelisted` is allowed to call
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramWhitelist {
+    pub authority: Pubkey,
+    #[max_len(MAX_WHITELISTED_PROGRAMS)]
+    pub allowed: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub token_in_mint: Pubkey,
+    pub token_out_mint: Pubkey,
+    pub sequence: u64,
 }
 
 #[event]
@@ -388,6 +2405,78 @@ pub struct SwapExecuted {
     pub user: Pubkey,
     pub amount_in: u64,
     pub amount_out: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct RouteExecuted {
+    pub pool1: Pubkey,
+    pub pool2: Pubkey,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub intermediate_out: u64,
+    pub amount_out: u64,
+    pub pool1_sequence: u64,
+    pub pool2_sequence: u64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub shares: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct TwapConsulted {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub price_q64: u128,
+    pub window: i64,
+}
+
+#[event]
+pub struct CurveUpdated {
+    pub pool: Pubkey,
+    pub curve: CurveType,
+    pub amp: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AdminActionQueued {
+    pub pool: Pubkey,
+    pub action: AdminAction,
+    pub ready_at: i64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct FeeUpdated {
+    pub pool: Pubkey,
+    pub old_fee_bps: u16,
+    pub new_fee_bps: u16,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct FeesCollected {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct VaultInitialized {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub vault_tokens: Pubkey,
+    pub balance: u64,
+    pub sequence: u64,
 }
 
 #[event]
@@ -396,6 +2485,7 @@ pub struct DepositMade {
     pub user: Pubkey,
     pub amount: u64,
     pub new_balance: u64,
+    pub sequence: u64,
 }
 
 #[event]
@@ -404,32 +2494,105 @@ pub struct WithdrawalMade {
     pub authority: Pubkey,
     pub amount: u64,
     pub remaining_balance: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct Reconciled {
+    pub vault: Pubkey,
+    pub recorded: u64,
+    pub actual: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct VaultFrozen {
+    pub vault: Pubkey,
+    pub reason: u8,
+    pub allow_deposits: bool,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct VaultUnfrozen {
+    pub vault: Pubkey,
+    /// Reason of the freeze being lifted
+    pub reason: u8,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    /// The vault or pool rewritten
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct WhitelistUpdated {
+    pub whitelist: Pubkey,
+    pub program_id: Pubkey,
+    pub allowed: bool,
 }
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Invalid amount")]
-    InvalidAmount,
     #[msg("Invalid minimum output")]
     InvalidMinOutput,
-    #[msg("Insufficient balance")]
-    InsufficientBalance,
-    #[msg("Slippage exceeded")]
-    SlippageExceeded,
-    #[msg("Arithmetic overflow")]
-    Overflow,
-    #[msg("Arithmetic underflow")]
-    Underflow,
     #[msg("Output too large")]
     OutputTooLarge,
-    #[msg("Invalid account owner")]
-    InvalidOwner,
-    #[msg("Token mint mismatch")]
-    MintMismatch,
-    #[msg("Unauthorized")]
-    Unauthorized,
     #[msg("Reentrancy detected")]
     ReentrancyDetected,
+    #[msg("Route pools do not connect")]
+    RouteMintMismatch,
+    #[msg("Target program is not whitelisted")]
+    ProgramNotWhitelisted,
+    #[msg("Program is already whitelisted")]
+    ProgramAlreadyWhitelisted,
+    #[msg("Whitelist is full")]
+    WhitelistFull,
+    #[msg("Vault token balance is below the recorded balance")]
+    BalanceInvariantViolated,
+    #[msg("Swap fee exceeds the maximum")]
+    FeeTooHigh,
+    #[msg("No fees to collect")]
+    NoFeesToCollect,
+    #[msg("Pool curve is misconfigured")]
+    UnsupportedCurve,
+    #[msg("Deposit amounts do not match the pool's reserve ratio")]
+    RatioMismatch,
+    #[msg("Liquidity deposit mints zero LP shares")]
+    ZeroLiquidity,
+    #[msg("TWAP window is shorter than the requested period")]
+    TwapWindowTooShort,
+    #[msg("Deposit permit has expired")]
+    PermitExpired,
+    #[msg("Missing, mismatched or already used deposit permit")]
+    InvalidPermit,
+    #[msg("Vault token account already holds tokens")]
+    PrefundedVault,
+    #[msg("Vault is frozen")]
+    VaultFrozen,
+    #[msg("Vault is not frozen")]
+    VaultNotFrozen,
+    #[msg("Freeze reason must be nonzero")]
+    InvalidFreezeReason,
+    #[msg("Queued admin action delay has not passed yet")]
+    ActionNotReady,
+    #[msg("No admin action is queued")]
+    NoQueuedAction,
+    #[msg("Account was written by a different program version")]
+    VersionMismatch,
+    #[msg("Pool token balances do not match the recorded reserves")]
+    ReserveMismatch,
+    #[msg("Required input exceeds max_amount_in")]
+    ExcessiveInputAmount,
+    #[msg("Pool cannot supply the requested output")]
+    InsufficientLiquidity,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
 }
 
 // ============================================================================
@@ -445,10 +2608,19 @@ pub enum ErrorCode {
 // 3. Attacker's fake program has different ID
 // 4. Transaction fails with "Invalid program id"
 //
+// When the target must be configurable, Program<'info, T> can't be used.
+// invoke_whitelisted instead checks the target against an admin-managed
+// ProgramWhitelist PDA:
+// 1. whitelist.allowed.contains(&target.key()) → else ProgramNotWhitelisted
+// 2. add_program / remove_program require has_one = authority
+// 3. No PDA signer seeds are passed, so a whitelisted program gains nothing
+//    beyond the signatures the caller already provided
+//
 // REENTRANCY ATTACK BLOCKED:
 // --------------------------
 // 1. Reentrancy guard: require!(!vault.locked)
-// 2. Lock set BEFORE any external calls
+// 2. Lock set BEFORE any external calls, and written to the account
+//    (withdraw calls vault.exit) so the callee reads it, not stale bytes
 // 3. If callback tries to re-enter:
 //    - vault.locked == true
 //    - require! fails
@@ -456,13 +2628,140 @@ pub enum ErrorCode {
 // 4. Lock released only after CPI completes
 //
 // Additionally, CEI pattern means:
-// - State updated BEFORE CPI
+// - State updated BEFORE CPI (persisted, in withdraw's case)
 // - Even without lock, reentrant call sees updated state
 // - No stale state to exploit
 //
+// PER-VAULT FREEZE:
+// -----------------
+// When one vault's authority key or token account is under investigation,
+// its authority freezes just that vault:
+// 1. freeze_vault(reason, allow_deposits) requires has_one = authority, so
+//    nobody else can freeze (grief) or unfreeze (escape) a vault
+// 2. withdraw fails with VaultFrozen while frozen, whatever the policy
+// 3. deposit / deposit_with_permit fail with VaultFrozen unless the freeze
+//    set allow_deposits: incoming funds are safe to accept, outgoing ones
+//    are what the freeze protects
+// 4. freeze_reason and the VaultFrozen / VaultUnfrozen events give the
+//    audit trail; reason 0 is rejected so it always means "not frozen"
+//
+// RETRIED DEPOSIT CREDITED ONCE:
+// -------------------------------
+// A client that times out and resends deposit cannot tell whether the
+// first attempt landed. Without a key, both land and the vault is
+// credited twice.
+// 1. deposit(amount, Some(key)) stores hash(user || key) in a ring buffer
+//    of the last RECENT_DEPOSIT_KEYS keyed deposits
+// 2. A resend with the same key finds it, logs duplicate_request and
+//    returns Ok before any state change or token transfer
+// 3. Hashing in the depositor means an observer who copies a pending key
+//    into their own deposit only uses up their own slot, never the victim's
+// 4. Dedup is best-effort: after RECENT_DEPOSIT_KEYS newer keyed deposits
+//    the key is evicted and would be credited again
+//
+// BALANCE DRIFT DETECTED:
+// -----------------------
+// Invariant: vault_tokens.amount == vault.balance + vault.surplus
+// 1. Direct token transfer to vault_tokens → actual > recorded
+//    reconcile(true) books the difference to vault.surplus
+// 2. Tokens leave without going through withdraw → actual < recorded
+//    reconcile fails with BalanceInvariantViolated
+// 3. initialize_token_vault sets balance = vault_tokens.amount, so the
+//    invariant holds before any deposit. A non-empty account fails with
+//    PrefundedVault unless allow_prefunded is set, and a caller-supplied
+//    starting balance is never trusted
+//
+// RESERVE DRIFT DETECTED (strict swaps):
+// --------------------------------------
+// Invariant: pool_token_in.amount  == reserve_in + fees_collected
+//            pool_token_out.amount == reserve_out
+// Prices come from the recorded reserves, so a direct transfer into a pool
+// token account cannot move them, but it does mean the accounts no longer
+// describe each other. swap_tokens(.., Some(tolerance)) reloads both token
+// accounts after its transfers and fails with ReserveMismatch when either
+// side is off by more than tolerance. Callers that want swaps to proceed
+// regardless pass None.
+//
+// SANDWICH / FRONT-RUN BLOCKED:
+// ------------------------------
+// 1. Victim quotes 10_000 in at reserves 1M/1M → expects 9_900 out
+// 2. Attacker sees the pending tx and swaps 500_000 in first
+// 3. Reserves are now 1.5M/~667K → victim would only get ~4_415 out
+// 4. With a loose min_amount_out (e.g. 1), the victim fills at the worse price
+// 5. With min_amount_out = quote - 1%, require! fails with SlippageExceeded
+// swap_exact_out is protected the same way from the other side: the input
+// is capped by max_amount_in, else ExcessiveInputAmount. An output the pool
+// cannot supply (amount_out >= reserve_out) fails with
+// InsufficientLiquidity before any pricing.
+//
 // AUTHORITY BYPASS BLOCKED:
 // -------------------------
 // 1. has_one = authority constraint
 // 2. PDA seeds include authority
 // 3. Attacker can't pass pool they don't own
 // 4. Transaction fails with "Unauthorized"
+//
+// MISCONFIGURED CURVE REJECTED:
+// -----------------------------
+// 1. StableSwap with amp = 0 has no invariant to solve; a huge amp pins the
+//    price at 1:1 even when the pool is badly imbalanced
+// 2. check_curve() runs in set_curve and again in every quote()
+// 3. Any curve/amp combination outside the table fails with UnsupportedCurve
+//    before a swap is priced
+//
+// RATIO MANIPULATION BLOCKED:
+// ---------------------------
+// 1. Attacker adds liquidity heavily skewed to one side
+// 2. Unchecked, that moves reserve_in / reserve_out (the price) at no cost
+//    and the next swapper trades against it
+// 3. check_deposit_ratio() rejects anything off by more than 0.5% with
+//    RatioMismatch, and shares are the MIN of both sides, so the excess
+//    that is tolerated goes to existing LPs, not the depositor
+//
+// SPOT PRICE MANIPULATION BLUNTED:
+// --------------------------------
+// 1. Attacker swaps 500_000 into a 1M/1M pool, reading the spot price in
+//    the same transaction moves it ~2.25x
+// 2. The accumulator only weights a price by how long it was in effect, and
+//    update_twap() runs BEFORE reserves change, so a price set and reverted
+//    within one slot contributes ~0 seconds
+// 3. consult_twap() refuses windows shorter than `period`; holding a skewed
+//    price for that long means arbitrageurs trade against the attacker
+//
+// PERMIT FORGERY AND REPLAY BLOCKED:
+// ----------------------------------
+// deposit_with_permit moves the authority's tokens without their signature
+// on the transaction, so the permit is the only authorization:
+// 1. Relayer raises the amount or swaps in another source account → the
+//    ed25519-verified message no longer matches → InvalidPermit
+// 2. Relayer points the ed25519 offsets at bytes in another instruction
+//    → every offset's instruction index must be "this one" → InvalidPermit
+// 3. Relayer resubmits a used permit → nonce <= permit_nonce → InvalidPermit
+// 4. Relayer holds a permit and submits it later → now > deadline
+//    → PermitExpired
+// 5. The SPL approval still caps the total: the vault PDA can never pull
+//    more than delegated_amount, whatever the permits say
+//
+// FEE COLLECTION BY ROLE:
+// -----------------------
+// collect_fees used to require pool.authority, the same key that sets
+// fees and curves. Now it requires FeeCollector in the pool's
+// AccessControl PDA (["access_control", pool]):
+// 1. A treasury key can collect without being able to reprice the pool
+// 2. Any other signer fails with MissingRole, and an AccessControl made
+//    for another pool fails ConstraintSeeds
+// 3. Roles change through grant_role / revoke_role, Admin only, and the
+//    last Admin cannot be revoked
+//
+// TIMELOCKED FEE CHANGES:
+// -----------------------
+// With a direct set_fee, the authority (or whoever steals its key) can
+// raise the fee to MAX_FEE_BPS in the same block as a large pending swap.
+// 1. queue_admin_action(SetFee) only records the change and
+//    action_ready_at = now + ADMIN_ACTION_DELAY, and emits it
+// 2. execute_admin_action fails with ActionNotReady before then, and
+//    NoQueuedAction when nothing is queued
+// 3. The fee is validated at queue time, so what traders see queued is
+//    exactly what will be applied
+// Traders and LPs get a full day's notice to leave before the new fee
+// applies. The authority checks are unchanged: only queuing needs one.
@@ -0,0 +1,237 @@
+//! # Secure Discriminator Example
+//!
+//! This program demonstrates how to verify account TYPE when reading raw `AccountInfo`.
+//!
+//! ## Security Measures
+//! 1. Check the owner: only this program can have written the bytes
+//! 2. Check the 8-byte discriminator with `check_discriminator::<T>` before reading fields
+//! 3. Reject the same account passed twice in one instruction
+//! 4. Checked addition when aggregating
+//!
+//! ## Why This Matters
+//! `Account<'info, T>` checks owner AND discriminator for you. Remaining accounts
+//! in a loop are plain `AccountInfo`, and reading a field at a fixed offset skips
+//! both. Two account types with the same layout are then indistinguishable.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::{assert_authority, CommonError};
+use logging::log_event;
+
+declare_id!("Secure9999999999999999999999999999999999999");
+
+/// Offset of `Pool::reserve` in account data: discriminator + authority
+const RESERVE_OFFSET: usize = 8 + 32;
+
+/// ✅ SECURE: Verify that `info` holds an account of type `T`
+///
+/// Compares the first 8 bytes of the account data with `T::DISCRIMINATOR`.
+/// This only proves the TYPE. Check the owner as well: any program can write
+/// these bytes into an account it owns.
+pub fn check_discriminator<T: Discriminator>(info: &AccountInfo) -> Result<()> {
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == T::DISCRIMINATOR[..],
+        ErrorCode::BadDiscriminator
+    );
+    Ok(())
+}
+
+#[program]
+pub mod secure_discriminator {
+    use super::*;
+
+    /// Initialize the reserve summary
+    pub fn initialize_summary(ctx: Context<InitializeSummary>) -> Result<()> {
+        let summary = &mut ctx.accounts.summary;
+        summary.authority = ctx.accounts.authority.key();
+        summary.total_reserves = 0;
+        Ok(())
+    }
+
+    /// Create a pool with an initial reserve
+    pub fn initialize_pool(ctx: Context<InitializePool>, reserve: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.reserve = reserve;
+
+        log_event!("initialize_pool", reserve = reserve);
+        Ok(())
+    }
+
+    /// Open a user position; the user picks `amount`
+    pub fn open_position(ctx: Context<OpenPosition>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.amount = amount;
+
+        log_event!("open_position", amount = amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Sum pool reserves passed as remaining accounts
+    ///
+    /// Each account must be owned by this program, carry the `Pool`
+    /// discriminator, and appear only once.
+    pub fn update_summary(ctx: Context<UpdateSummary>) -> Result<()> {
+        assert_authority(&ctx.accounts.summary.authority, &ctx.accounts.authority.key())?;
+
+        let mut total: u64 = 0;
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+
+        for info in ctx.remaining_accounts.iter() {
+            // ✅ Owner: the bytes were written by this program
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidAccountOwner);
+
+            // ✅ Type: the bytes are a Pool, not a same-shaped Position
+            check_discriminator::<Pool>(info)?;
+
+            // ✅ Each pool counted once
+            require!(!seen.contains(info.key), ErrorCode::DuplicatePool);
+            seen.push(info.key());
+
+            let data = info.try_borrow_data()?;
+            let reserve = data
+                .get(RESERVE_OFFSET..RESERVE_OFFSET + 8)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or(ErrorCode::InvalidAccountData)?;
+
+            total = total.checked_add(reserve).ok_or(CommonError::Overflow)?;
+        }
+
+        let summary = &mut ctx.accounts.summary;
+        summary.total_reserves = total;
+
+        emit!(SummaryUpdated {
+            summary: summary.key(),
+            pools: seen.len() as u32,
+            total_reserves: total,
+        });
+
+        log_event!("update_summary", pools = seen.len(), total_reserves = total);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeSummary<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Summary::INIT_SPACE
+    )]
+    pub summary: Account<'info, Summary>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSummary<'info> {
+    #[account(mut, has_one = authority @ CommonError::Unauthorized)]
+    pub summary: Account<'info, Summary>,
+    pub authority: Signer<'info>,
+    // Pools are passed as remaining accounts and checked in the handler
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Summary {
+    pub authority: Pubkey,
+    pub total_reserves: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub reserve: u64,
+}
+
+/// Same layout as `Pool`, different discriminator
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SummaryUpdated {
+    pub summary: Pubkey,
+    pub pools: u32,
+    pub total_reserves: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Account discriminator does not match the expected type")]
+    BadDiscriminator,
+    #[msg("Account is not owned by this program")]
+    InvalidAccountOwner,
+    #[msg("Account data too short")]
+    InvalidAccountData,
+    #[msg("Pool passed more than once")]
+    DuplicatePool,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_discriminator.rs FAILS here:
+//
+// TYPE CONFUSION BLOCKED:
+// -----------------------
+// 1. Attacker opens Position P with amount = 1_000_000_000_000
+// 2. Attacker calls update_summary([A, B, P])
+// 3. P passes the owner check (this program created it)
+// 4. check_discriminator::<Pool>(P): disc("Position") != disc("Pool")
+// 5. Transaction fails with BadDiscriminator
+//
+// DOUBLE COUNTING BLOCKED:
+// ------------------------
+// update_summary([B, B]) → second B fails with DuplicatePool
+//
+// Owner vs discriminator:
+// -----------------------
+// - Owner proves WHO wrote the bytes. Anyone can compute
+//   sha256("account:Pool")[..8] and write it into their own account.
+// - Discriminator proves WHICH of this program's types the bytes are.
+// Raw AccountInfo reads need both. Account<'info, T> does both.
@@ -0,0 +1,341 @@
+//! # Secure Donation Example
+//!
+//! This program demonstrates deriving a vault's economic state from an
+//! internally tracked balance, so tokens sent straight to its token account
+//! cannot move the share price.
+//!
+//! ## Security Measures
+//! 1. `Vault::total_assets` changes only in `deposit` and `withdraw`, by
+//!    exactly the amounts they transfer
+//! 2. Share math and `share_price` read `total_assets`, never
+//!    `vault_tokens.amount`
+//! 3. `vault_tokens.amount >= total_assets` is checked before paying out,
+//!    so a shortfall fails loudly with `InvariantViolation`
+//! 4. Deposits that would mint 0 shares fail with `ZeroShares`
+//!
+//! ## Why This Works
+//! A donation raises `vault_tokens.amount` but not `total_assets`. Every
+//! price the program computes or reports is the same before and after it;
+//! the donated tokens sit in the vault as surplus nobody can claim through
+//! shares.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMM");
+
+#[program]
+pub mod secure_donation {
+    use super::*;
+
+    /// Create a vault and its token account for `mint`
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.total_assets = 0;
+        vault.total_shares = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Open an empty share position in `vault`
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.vault = ctx.accounts.vault.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Deposit tokens for shares priced off `total_assets`
+    ///
+    /// An attacker CANNOT:
+    /// - Change the shares a deposit mints by transferring to `vault_tokens`
+    /// - Make a deposit succeed while minting nothing
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let vault = &ctx.accounts.vault;
+        // ✅ SECURE: Recorded assets, not the live balance
+        let shares = logic::shares_for_deposit(amount, vault.total_assets, vault.total_shares)?;
+        require!(shares > 0, ErrorCode::ZeroShares);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.total_assets = vault.total_assets
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.total_shares = vault.total_shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+        ctx.accounts.position.shares = ctx.accounts.position.shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+
+        emit!(DepositMade {
+            vault: vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+        });
+
+        log_event!("deposit", amount = amount, shares = shares);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Redeem `shares` for their part of `total_assets`
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        require!(shares > 0, CommonError::InvalidAmount);
+        require!(ctx.accounts.position.shares >= shares, ErrorCode::InsufficientShares);
+
+        let vault = &mut ctx.accounts.vault;
+        // ✅ SECURE: Surplus may exceed what is recorded; a shortfall may not
+        require!(
+            ctx.accounts.vault_tokens.amount >= vault.total_assets,
+            CommonError::InvariantViolation
+        );
+
+        let (amount, _) = logic::assets_for_shares(
+            shares,
+            vault.total_assets,
+            vault.total_shares,
+            logic::Rounding::Down,
+        )?;
+
+        // Update state BEFORE transfer (CEI pattern)
+        vault.total_assets = vault.total_assets
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        vault.total_shares = vault.total_shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+        ctx.accounts.position.shares = ctx.accounts.position.shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+
+        let mint = vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        log_event!("withdraw", shares = shares, amount = amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Tokens per share, scaled by `logic::SCALE`, for integrators
+    ///
+    /// Takes no token account: nothing a third party can transfer into is
+    /// an input.
+    pub fn share_price(ctx: Context<SharePrice>) -> Result<u64> {
+        let vault = &ctx.accounts.vault;
+        let price = logic::share_price(vault.total_assets, vault.total_shares)?;
+
+        log_event!("share_price", price = price, scale = logic::SCALE);
+        Ok(price)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", vault.key().as_ref(), user.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SharePrice<'info> {
+    #[account(seeds = [b"vault", vault.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub vault_tokens: Pubkey,
+    /// ✅ Tokens credited through `deposit`, less those paid by `withdraw`;
+    /// never read from `vault_tokens`
+    pub total_assets: u64,
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Not enough shares")]
+    InsufficientShares,
+    #[msg("Deposit would mint zero shares")]
+    ZeroShares,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_donation.rs FAILS here:
+//
+// Step                     vault_tokens  total_assets  total_shares  share_price
+// ----                     ------------  ------------  ------------  -----------
+// Honest deposits                10_000        10_000        10_000          1.0
+// Attacker donates 10_000        20_000        10_000        10_000          1.0
+// Victim deposit(10_000)         30_000        20_000        20_000          1.0
+//                          10_000 * 10_000 / 10_000 = 10_000 shares
+//
+// The donation changes one number, vault_tokens.amount, which no price
+// reads. The attacker has given away 10_000 tokens and moved nothing.
+//
+// Notes:
+// - The donated 10_000 stay in vault_tokens as surplus. A vault that wants
+//   to keep them needs an explicit, authorized path that credits them
+//   (secure_cpi::reconcile); an implicit one is the vulnerability
+// - Real yield (staking rewards, interest) must be booked into
+//   total_assets the same way, by the instruction that earns it
+// - The InvariantViolation check catches the opposite drift: tokens
+//   leaving vault_tokens without going through withdraw
+// - vulnerable_inflation/secure_inflation cover the first-depositor
+//   rounding attack built on the same live-balance read
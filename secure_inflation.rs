@@ -0,0 +1,595 @@
+//! # Secure Share Inflation Example
+//!
+//! This program demonstrates how to make a share-based vault resistant to the
+//! first-depositor ("donation") inflation attack.
+//!
+//! ## Security Measures
+//! 1. The first deposit must exceed `MINIMUM_LIQUIDITY`
+//! 2. `MINIMUM_LIQUIDITY` shares from the first deposit are minted to nobody
+//!    ("dead shares") and stay in `total_shares` forever
+//! 3. A deposit that would mint 0 shares is rejected instead of taking the tokens
+//! 4. Checked u128 share math
+//! 5. Shares can also be held as an SPL token (`share_mint`, minted by the
+//!    vault PDA); `redeem` burns them before paying out, behind a
+//!    reentrancy guard
+//!
+//! ## Why This Works
+//! The attack needs `total_shares` tiny so a donation can push the price of one
+//! share above the victim's deposit. With `MINIMUM_LIQUIDITY` dead shares the
+//! attacker owns at most a sliver of the supply: every donated token is shared
+//! pro rata with the dead shares, so rounding a victim down costs the attacker
+//! ~`MINIMUM_LIQUIDITY` times what the victim loses. Rejecting zero-share
+//! deposits turns the remaining rounding loss into a failed transaction.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+
+/// Shares locked out of the first deposit
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// ✅ SECURE: Shares credited to the depositor for depositing `amount` into a
+/// vault holding `total_assets` tokens against `total_shares`
+///
+/// On the first deposit (`total_shares == 0`) the depositor receives
+/// `amount - MINIMUM_LIQUIDITY`; the caller adds the dead shares to the supply.
+/// Later deposits mint pro rata, rounding down, and must mint at least one share.
+pub fn shares_for_deposit(amount: u64, total_assets: u64, total_shares: u64) -> Result<u64> {
+    if total_shares == 0 {
+        // ✅ SECURE: The first depositor cannot set a 1-token price
+        require!(amount > MINIMUM_LIQUIDITY, ErrorCode::MinimumLiquidity);
+        return Ok(amount - MINIMUM_LIQUIDITY);
+    }
+
+    let shares = (amount as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(CommonError::Overflow)?
+        .checked_div(total_assets as u128)
+        .ok_or(CommonError::InvariantViolation)?;
+
+    // ✅ SECURE: Never take tokens for nothing
+    require!(shares > 0, ErrorCode::ZeroShares);
+    require!(shares <= u64::MAX as u128, CommonError::Overflow);
+
+    Ok(shares as u64)
+}
+
+#[program]
+pub mod secure_inflation {
+    use super::*;
+
+    /// Create a vault and its token account for `mint`
+    ///
+    /// `share_mint` must be an empty mint whose `mint_authority` is the
+    /// vault PDA, so share tokens only ever come from `deposit_for_share_tokens`.
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.share_mint = ctx.accounts.share_mint.key();
+        vault.total_shares = 0;
+        vault.locked = false;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Open an empty share position in `vault`
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.vault = ctx.accounts.vault.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Deposit tokens for shares
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let shares = take_deposit(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.vault_tokens,
+            &ctx.accounts.user_tokens,
+            &ctx.accounts.user,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let vault = &ctx.accounts.vault;
+        let position = &mut ctx.accounts.position;
+        position.shares = position.shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+
+        emit!(DepositMade {
+            vault: vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+        });
+
+        log_event!("deposit", amount = amount, shares = shares);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Deposit tokens for shares paid out as `share_mint` tokens
+    ///
+    /// Same pricing as `deposit`; the shares are minted to the user by the
+    /// vault PDA instead of credited to a `Position`.
+    pub fn deposit_for_share_tokens(ctx: Context<DepositForShareTokens>, amount: u64) -> Result<()> {
+        let shares = take_deposit(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.vault_tokens,
+            &ctx.accounts.user_tokens,
+            &ctx.accounts.user,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let mint = ctx.accounts.vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.user_share_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            shares,
+        )?;
+
+        emit!(DepositMade {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+        });
+
+        log_event!("deposit_for_share_tokens", amount = amount, shares = shares);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Burn `shares` share tokens, then pay out their underlying
+    ///
+    /// An attacker CANNOT:
+    /// - Burn someone else's share tokens (the user must own and sign for them)
+    /// - Redeem tokens of another mint (address = vault.share_mint)
+    /// - Be paid before the burn, or re-enter between the two CPIs
+    pub fn redeem(ctx: Context<Redeem>, shares: u64) -> Result<()> {
+        require!(shares > 0, CommonError::InvalidAmount);
+        require!(
+            ctx.accounts.user_share_tokens.amount >= shares,
+            ErrorCode::InsufficientShareBalance
+        );
+
+        let vault = &mut ctx.accounts.vault;
+
+        // ✅ Reentrancy guard around both CPIs
+        require!(!vault.locked, ErrorCode::ReentrancyDetected);
+        vault.locked = true;
+
+        // Priced before any share leaves the supply
+        let amount = (shares as u128)
+            .checked_mul(ctx.accounts.vault_tokens.amount as u128)
+            .ok_or(CommonError::Overflow)?
+            .checked_div(vault.total_shares as u128)
+            .ok_or(CommonError::InvariantViolation)? as u64;
+
+        // ✅ CEI: state first, then burn, then pay
+        vault.total_shares = vault.total_shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            from: ctx.accounts.user_share_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::burn(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            shares,
+        )?;
+
+        let mint = ctx.accounts.vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.locked = false;
+
+        emit!(SharesRedeemed {
+            vault: vault.key(),
+            user: ctx.accounts.user.key(),
+            shares,
+            amount,
+        });
+
+        log_event!("redeem", shares = shares, amount = amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Redeem `shares` for their pro-rata part of the vault balance
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        require!(shares > 0, CommonError::InvalidAmount);
+        require!(ctx.accounts.position.shares >= shares, CommonError::InsufficientFunds);
+
+        let amount = (shares as u128)
+            .checked_mul(ctx.accounts.vault_tokens.amount as u128)
+            .ok_or(CommonError::Overflow)?
+            .checked_div(ctx.accounts.vault.total_shares as u128)
+            .ok_or(CommonError::InvariantViolation)? as u64;
+
+        let mint = ctx.accounts.vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        let position = &mut ctx.accounts.position;
+        position.shares = position.shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+        let vault = &mut ctx.accounts.vault;
+        vault.total_shares = vault.total_shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+
+        log_event!("withdraw", shares = shares, amount = amount);
+        Ok(())
+    }
+}
+
+/// Shared body of `deposit` and `deposit_for_share_tokens`: price the
+/// deposit, take the tokens and grow `total_shares`. Returns the shares
+/// owed to the depositor.
+fn take_deposit<'info>(
+    vault: &mut Account<'info, Vault>,
+    vault_tokens: &Account<'info, TokenAccount>,
+    user_tokens: &Account<'info, TokenAccount>,
+    user: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<u64> {
+    require!(amount > 0, CommonError::InvalidAmount);
+
+    let first_deposit = vault.total_shares == 0;
+    let shares = shares_for_deposit(amount, vault_tokens.amount, vault.total_shares)?;
+
+    let cpi_accounts = Transfer {
+        from: user_tokens.to_account_info(),
+        to: vault_tokens.to_account_info(),
+        authority: user.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new(token_program.to_account_info(), cpi_accounts),
+        amount,
+    )?;
+
+    // ✅ SECURE: Dead shares are counted in the supply but owned by nobody
+    let minted = if first_deposit {
+        shares.checked_add(MINIMUM_LIQUIDITY).ok_or(CommonError::Overflow)?
+    } else {
+        shares
+    };
+
+    vault.total_shares = vault.total_shares
+        .checked_add(minted)
+        .ok_or(CommonError::Overflow)?;
+
+    Ok(shares)
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    // ✅ SECURE: Only the vault PDA can issue share tokens, and none exist yet
+    #[account(
+        constraint = share_mint.mint_authority == COption::Some(vault.key()) @ ErrorCode::InvalidShareMint,
+        constraint = share_mint.supply == 0 @ ErrorCode::InvalidShareMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = position.owner == user.key() @ CommonError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositForShareTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, address = vault.share_mint @ CommonError::MintMismatch)]
+    pub share_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_share_tokens.mint == vault.share_mint @ CommonError::MintMismatch
+    )]
+    pub user_share_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    // ✅ SECURE: The vault's own share mint, so the burn lowers its supply
+    #[account(mut, address = vault.share_mint @ CommonError::MintMismatch)]
+    pub share_mint: Account<'info, Mint>,
+
+    // ✅ SECURE: The signer's own share tokens
+    #[account(
+        mut,
+        constraint = user_share_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_share_tokens.mint == vault.share_mint @ CommonError::MintMismatch
+    )]
+    pub user_share_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = position.owner == user.key() @ CommonError::Unauthorized
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.mint == vault.mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub vault_tokens: Pubkey,
+    /// Mint for shares held as tokens; its authority is this vault
+    pub share_mint: Pubkey,
+    /// Includes the `MINIMUM_LIQUIDITY` dead shares once seeded, and every
+    /// share token in circulation
+    pub total_shares: u64,
+    /// ✅ Reentrancy guard held across `redeem`'s CPIs
+    pub locked: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct SharesRedeemed {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("First deposit must exceed the minimum liquidity")]
+    MinimumLiquidity,
+    #[msg("Deposit too small to mint any shares")]
+    ZeroShares,
+    #[msg("Share mint must be empty with the vault as mint authority")]
+    InvalidShareMint,
+    #[msg("Not enough share tokens to redeem")]
+    InsufficientShareBalance,
+    #[msg("Reentrancy detected")]
+    ReentrancyDetected,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_inflation.rs FAILS here:
+//
+// 1-TOKEN SEED BLOCKED:
+// ---------------------
+// Attacker deposit(1) → MinimumLiquidity
+//
+// DONATION MADE UNPROFITABLE:
+// ---------------------------
+// Step                         vault_tokens   total_shares   shares credited
+// ----                         ------------   ------------   ---------------
+// Attacker deposit(1_001)             1_001          1_001     1 (+1_000 dead)
+// Attacker donates 10_000_000    10_001_001          1_001                 -
+// Victim deposit(10_000)         10_011_001          1_002                 1
+//                                     10_000 * 1_001 / 10_001_001 = 1
+//
+// The victim still receives a share (worth ~9_991). To round the victim
+// to 0 the attacker must donate > 10_000 * 1_001 tokens, ~99.9% of which
+// accrues to the dead shares and is lost. Even then the victim's deposit
+// fails with ZeroShares instead of silently vanishing.
+//
+// BURN-ON-REDEEM:
+// ---------------
+// total_shares = 2_000, vault_tokens = 4_000, user holds 500 share tokens
+//
+//   redeem(200)
+//     200 <= 500 share tokens              ✓ else InsufficientShareBalance
+//     amount = 200 * 4_000 / 2_000 = 400   priced before the burn
+//     locked = true, total_shares = 1_800
+//     burn 200 (user signs)                share supply 500 → 300
+//     transfer 400 (vault PDA signs)
+//     locked = false
+//
+// Burning first means the shares are gone before anything is paid: a
+// failed burn (wrong owner, frozen account) pays nothing, and no callback
+// between the CPIs can redeem the same shares twice.
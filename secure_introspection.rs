@@ -0,0 +1,200 @@
+//! # Secure Instruction Introspection Example
+//!
+//! This program demonstrates how to stop a sensitive instruction from being
+//! invoked through CPI by a program the user did not mean to authorize.
+//!
+//! ## Security Measures
+//! 1. `withdraw` takes the instructions sysvar, address-checked
+//! 2. `get_instruction_relative(0, ..)` loads the top-level instruction that
+//!    is currently executing
+//! 3. Its program id must be this program, else `MustBeTopLevel`
+//! 4. Usual `Signer` + `has_one` + seeds checks on the authority
+//!
+//! ## Why This Works
+//! The instructions sysvar only lists the transaction's top-level
+//! instructions. When `withdraw` runs directly, the current top-level
+//! instruction is ours. When another program CPIs into us, the current
+//! top-level instruction belongs to that program, so the check fails no
+//! matter which signer privileges it forwarded. The wallet prompt the user
+//! approved therefore names this program whenever funds leave the vault.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use anchor_lang::system_program::{self, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB");
+
+/// ✅ SECURE: Fail unless the executing top-level instruction is ours,
+/// i.e. we were not reached through another program's CPI
+fn assert_top_level(instructions: &AccountInfo) -> Result<()> {
+    let current = get_instruction_relative(0, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, ErrorCode::MustBeTopLevel);
+    Ok(())
+}
+
+#[program]
+pub mod secure_introspection {
+    use super::*;
+
+    /// Create the lamport vault PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Deposit lamports into the vault
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        log_event!("deposit", amount = amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw lamports to `destination`
+    ///
+    /// An attacker CANNOT:
+    /// - Wrap this call in their own program and reuse the authority's signature
+    /// - Pick `destination` without the authority calling us directly
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        // ✅ SECURE: Direct calls only
+        assert_top_level(&ctx.accounts.instructions.to_account_info())?;
+
+        let vault = ctx.accounts.vault.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(vault.data_len());
+        let remaining = vault.lamports()
+            .checked_sub(amount)
+            .ok_or(CommonError::InsufficientFunds)?;
+        require!(remaining >= rent_floor, CommonError::InsufficientFunds);
+
+        let destination = ctx.accounts.destination.to_account_info();
+        let credited = destination.lamports()
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        **vault.try_borrow_mut_lamports()? = remaining;
+        **destination.try_borrow_mut_lamports()? = credited;
+
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            destination: destination.key(),
+            amount,
+        });
+
+        log_event!("withdraw", amount = amount, destination = destination.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    /// CHECK: Address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[event]
+pub struct WithdrawalMade {
+    pub vault: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Instruction must be called directly, not through CPI")]
+    MustBeTopLevel,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_introspection.rs FAILS here:
+//
+//   [0] attacker_program::claim_airdrop
+//       └─ CPI secure_introspection::withdraw
+//            get_instruction_relative(0) → instruction [0]
+//            program_id = attacker_program ≠ secure_introspection
+//            → MustBeTopLevel
+//
+// Notes:
+// - The sysvar holds top-level instructions only; inner CPIs never appear
+//   in it, so "current top-level program == us" means "not a CPI" as long
+//   as this program never CPIs into itself
+// - get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT is an equivalent
+//   check that needs no extra account
+// - This blocks composability on purpose: aggregators and other programs
+//   cannot withdraw on a user's behalf. Apply it to instructions where that
+//   is the intent, not to everything
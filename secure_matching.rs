@@ -7,6 +7,9 @@
 //! 2. Verify mint relationships for all token operations
 //! 3. Use has_one for stored account references
 //! 4. Verify full relationship chains
+//! 5. Reject pools and staking accounts not written at `CURRENT_VERSION`
+//!    until `migrate_pool` / `migrate_staking_account` rewrites them
+//! 6. Time-box delegate approvals with a recorded `delegate_expiry`
 //! 
 //! ## Best Practices
 //! - Always verify token account ownership
@@ -15,24 +18,207 @@
 //! - Verify full relationship chains (user → account → pool)
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_lang::solana_program::program_option::COption;
+#[cfg(feature = "bench")]
+use anchor_lang::solana_program::log::sol_log_compute_units;
+use anchor_spl::token::{self, Approve, Mint, Revoke, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, TokenInterface, TransferChecked};
+
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
 
 declare_id!("Secure6666666666666666666666666666666666666");
 
+/// Number of lock tiers a pool can configure
+pub const MAX_LOCK_TIERS: usize = 4;
+
+/// Seconds between `queue_admin_action` and the earliest
+/// `execute_admin_action` (1 day)
+pub const ADMIN_ACTION_DELAY: i64 = 24 * 60 * 60;
+
+/// Layout version `initialize_pool` and `create_staking_account` write;
+/// mutating handlers reject accounts at any other version
+pub const CURRENT_VERSION: u8 = 1;
+
 #[program]
 pub mod secure_matching {
     use super::*;
 
+    /// ✅ SECURE: Create the pool PDA for `token_mint`
+    ///
+    /// The pool lives at `["pool", token_mint]` and stores its canonical bump,
+    /// which every other instruction re-derives the PDA with. The reward vault
+    /// must already be a `reward_mint` token account owned by the pool PDA.
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        min_stake_duration: i64,
+        early_exit_penalty_bps: u16,
+        reward_rate: u64,
+    ) -> Result<()> {
+        require!(min_stake_duration >= 0, CommonError::InvalidAmount);
+        require!(
+            early_exit_penalty_bps as u64 <= logic::BPS_DENOMINATOR,
+            ErrorCode::InvalidPenalty
+        );
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.token_mint = ctx.accounts.token_mint.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.reward_vault = ctx.accounts.reward_vault.key();
+        pool.total_deposits = 0;
+        pool.total_shares = 0;
+        pool.total_staked = 0;
+        pool.min_stake_duration = min_stake_duration;
+        pool.early_exit_penalty_bps = early_exit_penalty_bps;
+        pool.max_total_deposits = u64::MAX;
+        pool.per_user_cap = u64::MAX;
+        pool.total_rewards_funded = 0;
+        pool.reward_rate = reward_rate;
+        pool.acc_reward_per_share = 0;
+        pool.last_reward_time = Clock::get()?.unix_timestamp;
+        pool.lock_tiers = [LockTier::default(); MAX_LOCK_TIERS];
+        pool.bump = ctx.bumps.pool;
+        pool.version = CURRENT_VERSION;
+        pool.pending_action = None;
+        pool.action_ready_at = 0;
+        
+        emit!(PoolInitialized {
+            pool: pool.key(),
+            authority: pool.authority,
+            token_mint: pool.token_mint,
+            reward_mint: pool.reward_mint,
+        });
+        
+        log_event!("initialize_pool", pool = pool.key(), token_mint = pool.token_mint);
+        Ok(())
+    }
+
     /// ✅ SECURE: Transfer with full ownership verification
     pub fn transfer_tokens(
         ctx: Context<TransferTokens>,
         amount: u64,
     ) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        // Bench: everything before this line is account validation
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        
+        require!(amount > 0, CommonError::InvalidAmount);
         
         // All validations handled by constraints:
         // - from_account.owner == authority
         // - from_account.mint == to_account.mint
+        // - from_account != to_account, so TransferExecuted always
+        //   describes tokens that actually moved
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from_account.to_account_info(),
+            to: ctx.accounts.to_account.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        token::transfer(cpi_ctx, amount)?;
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        
+        emit!(TransferExecuted {
+            from: ctx.accounts.from_account.key(),
+            to: ctx.accounts.to_account.key(),
+            amount,
+            authority: ctx.accounts.authority.key(),
+        });
+        
+        log_event!("transfer_tokens", amount = amount);
+        
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        Ok(())
+    }
+
+    /// ✅ SECURE: Approve `delegate` for `amount` until `delegate_expiry`
+    ///
+    /// SPL `approve` has no expiry, so it is recorded next to the approval
+    /// in a `Delegation` PDA at `["delegation", token_account]`. One
+    /// delegation per token account; `revoke_delegate` before replacing it.
+    pub fn approve_delegate(
+        ctx: Context<ApproveDelegate>,
+        amount: u64,
+        delegate_expiry: i64,
+    ) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        // ✅ SECURE: An approval that is already expired is a mistake
+        let now = Clock::get()?.unix_timestamp;
+        require!(delegate_expiry > now, ErrorCode::DelegationExpired);
+        
+        let cpi_accounts = Approve {
+            to: ctx.accounts.token_account.to_account_info(),
+            delegate: ctx.accounts.delegate.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::approve(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+        
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.token_account = ctx.accounts.token_account.key();
+        delegation.delegate = ctx.accounts.delegate.key();
+        delegation.delegate_expiry = delegate_expiry;
+        delegation.bump = ctx.bumps.delegation;
+        
+        log_event!("approve_delegate", amount = amount, delegate_expiry = delegate_expiry);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Revoke the SPL approval and close its `Delegation`
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        let cpi_accounts = Revoke {
+            source: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        token::revoke(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts))?;
+        
+        log_event!("revoke_delegate", token_account = ctx.accounts.token_account.key());
+        Ok(())
+    }
+
+    /// ✅ SECURE: Transfer on the owner's behalf as an SPL delegate
+    ///
+    /// `transfer_tokens` requires `authority` to be the owner. Here the
+    /// owner has approved `authority` through `approve_delegate` for up to
+    /// `delegated_amount`, until `delegate_expiry`:
+    /// - from_account.delegate == authority (constraint)
+    /// - delegation.delegate == authority (constraint)
+    /// - now <= delegation.delegate_expiry (checked below)
+    /// - from_account.delegated_amount >= amount (checked below)
+    pub fn transfer_as_delegate(
+        ctx: Context<TransferAsDelegate>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        // ✅ SECURE: The approval is only good until its recorded expiry
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now <= ctx.accounts.delegation.delegate_expiry,
+            ErrorCode::DelegationExpired
+        );
+        
+        // ✅ SECURE: Fail clearly instead of inside the token CPI
+        require!(
+            ctx.accounts.from_account.delegated_amount >= amount,
+            ErrorCode::InsufficientDelegation
+        );
         
         let cpi_accounts = Transfer {
             from: ctx.accounts.from_account.to_account_info(),
@@ -52,7 +238,7 @@ pub mod secure_matching {
             authority: ctx.accounts.authority.key(),
         });
         
-        msg!("Transferred {} tokens", amount);
+        log_event!("transfer_as_delegate", amount = amount);
         Ok(())
     }
 
@@ -61,40 +247,375 @@ pub mod secure_matching {
         ctx: Context<DepositToPool>,
         amount: u64,
     ) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
         
-        let pool = &mut ctx.accounts.pool;
+        require!(amount > 0, CommonError::InvalidAmount);
         
         // All validations handled by constraints:
+        // - user_tokens.key() != pool_tokens.key()
         // - user_tokens.mint == pool.token_mint
         // - pool_tokens.mint == pool.token_mint
         // - pool_tokens.owner == pool.key()
         
+        // ✅ SECURE: Bounded state, not just checked math
+        let pool = &ctx.accounts.pool;
+        let new_total = pool.total_deposits
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        require!(
+            new_total <= pool.max_total_deposits,
+            ErrorCode::DepositCapExceeded
+        );
+        
+        // ✅ SECURE: Per-user cap, on the depositor's own PDA
+        let user_total = ctx.accounts.staking_account.total_deposited
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        require!(
+            user_total <= pool.per_user_cap,
+            ErrorCode::UserCapExceeded
+        );
+        
+        let balance_before = ctx.accounts.pool_tokens.amount;
+        
+        // Transfer tokens (SPL Token or Token-2022, no callback into this program)
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.pool_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        // ✅ SECURE: Credit what actually arrived, not what was requested.
+        // A Token-2022 transfer fee is withheld in pool_tokens, so the
+        // spendable delta can be less than `amount`
+        ctx.accounts.pool_tokens.reload()?;
+        let received = ctx.accounts.pool_tokens.amount
+            .checked_sub(balance_before)
+            .ok_or(CommonError::Underflow)?;
+        require!(received > 0, CommonError::InvalidAmount);
+        
+        let pool = &mut ctx.accounts.pool;
+        
+        // Calculate shares against the pre-deposit totals
+        let shares = logic::shares_for_deposit(
+            received,
+            pool.total_deposits,
+            pool.total_shares,
+        )?;
+        
         // Update pool state
         pool.total_deposits = pool.total_deposits
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+            .checked_add(received)
+            .ok_or(CommonError::Overflow)?;
+        pool.total_shares = pool.total_shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+        let position = &mut ctx.accounts.staking_account;
+        position.shares = position.shares
+            .checked_add(shares)
+            .ok_or(CommonError::Overflow)?;
+        position.total_deposited = position.total_deposited
+            .checked_add(received)
+            .ok_or(CommonError::Overflow)?;
+        
+        assert_pool_invariants(pool)?;
+        
+        emit!(DepositMade {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            amount: received,
+            shares,
+        });
+        
+        log_event!("deposit_to_pool", amount = received, shares = shares);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Redeem `shares` for their part of `total_deposits`
+    ///
+    /// The liquidity-removal counterpart of `deposit_to_pool`: burns shares
+    /// from the caller's position and pays out through the pool PDA signer.
+    ///
+    /// Pays `shares * total_deposits / total_shares` rounded down, so every
+    /// redemption leaves the remainder ("dust") behind for the holders still
+    /// in the pool. The last redeemer takes `total_deposits` outright, dust
+    /// included, so shares and deposits reach zero in the same instruction.
+    pub fn redeem_shares(ctx: Context<RedeemShares>, shares: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        require!(shares > 0, CommonError::InvalidAmount);
+        require!(
+            ctx.accounts.staking_account.shares >= shares,
+            ErrorCode::InsufficientShares
+        );
+        
+        let pool = &mut ctx.accounts.pool;
+        
+        let (pro_rata, dust) = logic::assets_for_shares(
+            shares,
+            pool.total_deposits,
+            pool.total_shares,
+            logic::Rounding::Down,
+        )?;
         
-        // Calculate shares (simplified - real implementation would be more complex)
-        let shares = if pool.total_shares == 0 {
-            amount
+        // ✅ SECURE: Final redeemer sweeps the pool; nothing is stranded
+        let amount = if shares == pool.total_shares {
+            pool.total_deposits
         } else {
-            (amount as u128)
-                .checked_mul(pool.total_shares as u128)
-                .ok_or(ErrorCode::Overflow)?
-                .checked_div(pool.total_deposits.saturating_sub(amount) as u128)
-                .ok_or(ErrorCode::Overflow)? as u64
+            pro_rata
         };
         
+        // ✅ SECURE: Burning shares for nothing is always a user error
+        require!(amount > 0, ErrorCode::RedeemRoundsToZero);
+        
+        // ✅ SECURE: Never pay out more than the pool holds
+        require!(
+            amount <= ctx.accounts.pool_tokens.amount,
+            CommonError::InsufficientFunds
+        );
+        
+        // Update state BEFORE transfer (CEI pattern)
+        pool.total_deposits = pool.total_deposits
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
         pool.total_shares = pool.total_shares
-            .checked_add(shares)
-            .ok_or(ErrorCode::Overflow)?;
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+        let position = &mut ctx.accounts.staking_account;
+        position.shares = position.shares
+            .checked_sub(shares)
+            .ok_or(CommonError::Underflow)?;
+        
+        assert_pool_invariants(pool)?;
+        
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.pool_tokens.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.token_mint.decimals)?;
+        
+        emit!(SharesRedeemed {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            shares,
+            amount,
+            dust,
+        });
+        
+        log_event!("redeem_shares", shares = shares, amount = amount, dust = dust);
+        Ok(())
+    }
+
+    /// Read-only: current price per share, scaled by `logic::SCALE`
+    ///
+    /// Mutates nothing. The price is the instruction's return data, and is
+    /// also logged and emitted as `SharePrice` for indexers that only read logs.
+    pub fn get_share_price(ctx: Context<GetSharePrice>) -> Result<u64> {
+        let pool = &ctx.accounts.pool;
+        let price = logic::share_price(pool.total_deposits, pool.total_shares)?;
+        
+        emit!(SharePrice {
+            pool: pool.key(),
+            price,
+        });
+        
+        log_event!("get_share_price", price = price, scale = logic::SCALE);
+        Ok(price)
+    }
+
+    /// ✅ SECURE: Queue a deposit cap change (pool authority only)
+    ///
+    /// Nothing changes until `execute_admin_action` runs at least
+    /// `ADMIN_ACTION_DELAY` later. Queuing again replaces the pending action
+    /// and restarts the delay.
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let now = Clock::get()?.unix_timestamp;
+        pool.pending_action = Some(action);
+        pool.action_ready_at = now
+            .checked_add(ADMIN_ACTION_DELAY)
+            .ok_or(CommonError::Overflow)?;
+        
+        emit!(AdminActionQueued {
+            pool: pool.key(),
+            action,
+            ready_at: pool.action_ready_at,
+        });
+        
+        log_event!("queue_admin_action", action = format_args!("{:?}", action), ready_at = pool.action_ready_at);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Apply the queued action once `ADMIN_ACTION_DELAY` has passed
+    ///
+    /// Anyone may submit it; the outcome was fixed by `queue_admin_action`.
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let action = pool.pending_action.ok_or(ErrorCode::NoQueuedAction)?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pool.action_ready_at, ErrorCode::ActionNotReady);
+        
+        pool.pending_action = None;
+        pool.action_ready_at = 0;
+        
+        match action {
+            AdminAction::SetCap { max_total_deposits } => {
+                let old_cap = pool.max_total_deposits;
+                pool.max_total_deposits = max_total_deposits;
+                
+                emit!(DepositCapUpdated {
+                    pool: pool.key(),
+                    old_cap,
+                    new_cap: max_total_deposits,
+                });
+                
+                log_event!("execute_admin_action", action = "set_cap", old_cap = old_cap, max_total_deposits = max_total_deposits);
+            }
+        }
+        Ok(())
+    }
+
+    /// ✅ SECURE: Update the per-user deposit cap (pool authority only)
+    ///
+    /// Applies to each depositor's cumulative `total_deposited`, on top of
+    /// `max_total_deposits`. Lowering it never touches existing deposits;
+    /// it only blocks further ones from users already over it.
+    pub fn set_user_cap(ctx: Context<SetCap>, per_user_cap: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let old_cap = pool.per_user_cap;
+        pool.per_user_cap = per_user_cap;
+        
+        emit!(UserCapUpdated {
+            pool: pool.key(),
+            old_cap,
+            new_cap: per_user_cap,
+        });
+        
+        log_event!("set_user_cap", old_cap = old_cap, per_user_cap = per_user_cap);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Replace the pool's lock tiers (pool authority only)
+    ///
+    /// Configured tiers come first, sorted by `min_duration`, with
+    /// multipliers that never decrease and never exceed 100%. Unused slots
+    /// are left at `LockTier::default()`. Existing stakes keep the
+    /// multiplier they locked in.
+    pub fn set_lock_tiers(ctx: Context<SetLockTiers>, lock_tiers: [LockTier; MAX_LOCK_TIERS]) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        Pool::validate_lock_tiers(&lock_tiers)?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.lock_tiers = lock_tiers;
+        
+        emit!(LockTiersUpdated {
+            pool: pool.key(),
+            lock_tiers,
+        });
+        
+        log_event!("set_lock_tiers");
+        Ok(())
+    }
+
+    /// ✅ SECURE: Create the user's staking account for `pool`
+    ///
+    /// The account is a PDA of `["staking", user, pool]`, so there is exactly
+    /// one per (user, pool) and every staking instruction can re-derive it.
+    pub fn create_staking_account(ctx: Context<CreateStakingAccount>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let staking = &mut ctx.accounts.staking_account;
+        staking.owner = ctx.accounts.user.key();
+        staking.pool = ctx.accounts.pool.key();
+        staking.amount = 0;
+        staking.pending_rewards = 0;
+        staking.total_claimed = 0;
+        staking.last_stake_time = 0;
+        staking.lock_duration = 0;
+        staking.multiplier_bps = logic::BPS_DENOMINATOR as u16;
+        staking.reward_debt = 0;
+        staking.shares = 0;
+        staking.total_deposited = 0;
+        staking.bump = ctx.bumps.staking_account;
+        staking.version = CURRENT_VERSION;
+        
+        emit!(StakingAccountCreated {
+            staking_account: staking.key(),
+            owner: staking.owner,
+            pool: staking.pool,
+        });
+        
+        log_event!("create_staking_account", staking_account = staking.key(), owner = staking.owner);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Close an empty staking account and refund its rent
+    ///
+    /// `unstake` and `claim_rewards` already close the account on a full
+    /// exit; this covers one that was created and never used. Fails with
+    /// `AccountNotEmpty` while anything is staked, pending or deposited.
+    pub fn close_staking_account(ctx: Context<CloseStakingAccount>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let staking = &ctx.accounts.staking_account;
+        
+        emit!(StakingAccountClosed {
+            staking_account: staking.key(),
+            owner: staking.owner,
+            pool: staking.pool,
+            rent: staking.to_account_info().lamports(),
+        });
+        
+        log_event!("close_staking_account", staking_account = staking.key(), owner = staking.owner);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Fund the pool's reward vault (pool authority only)
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        // All validations handled by constraints:
+        // - admin == pool.authority
+        // - reward_vault.key() == pool.reward_vault
+        // - admin_tokens.owner == admin
+        // - admin_tokens.mint == pool.reward_mint
         
-        // Transfer tokens
         let cpi_accounts = Transfer {
-            from: ctx.accounts.user_tokens.to_account_info(),
-            to: ctx.accounts.pool_tokens.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
+            from: ctx.accounts.admin_tokens.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.admin.to_account_info(),
         };
         let cpi_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -102,21 +623,35 @@ pub mod secure_matching {
         );
         token::transfer(cpi_ctx, amount)?;
         
-        emit!(DepositMade {
+        let pool = &mut ctx.accounts.pool;
+        pool.total_rewards_funded = pool.total_rewards_funded
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        
+        emit!(RewardsFunded {
             pool: pool.key(),
-            user: ctx.accounts.user.key(),
+            admin: ctx.accounts.admin.key(),
             amount,
-            shares,
+            total_rewards_funded: pool.total_rewards_funded,
         });
         
-        msg!("Deposited {} tokens, received {} shares", amount, shares);
+        log_event!("fund_rewards", amount = amount, total_rewards_funded = pool.total_rewards_funded);
         Ok(())
     }
 
     /// ✅ SECURE: Claim rewards with full relationship verification
+    ///
+    /// Closes the staking account and refunds its rent to `user` when the
+    /// claim leaves it empty (see `StakingAccount::is_empty`).
     pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
         let staking = &mut ctx.accounts.staking_account;
-        let pool = &ctx.accounts.pool;
+        let pool = &mut ctx.accounts.pool;
+        
+        pool.update_rewards(Clock::get()?.unix_timestamp)?;
+        staking.settle_rewards(pool)?;
         
         let rewards = staking.pending_rewards;
         require!(rewards > 0, ErrorCode::NoRewardsToClaim);
@@ -128,11 +663,18 @@ pub mod secure_matching {
         // - user_reward_account.owner == user
         // - user_reward_account.mint == pool.reward_mint
         
+        // ✅ SECURE: Fail with a clear error before touching state,
+        // rather than relying on the token program to reject the transfer
+        require!(
+            ctx.accounts.reward_vault.amount >= rewards,
+            ErrorCode::InsufficientRewardReserves
+        );
+        
         // Clear pending rewards BEFORE transfer (CEI pattern)
         staking.pending_rewards = 0;
         staking.total_claimed = staking.total_claimed
             .checked_add(rewards)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
         
         // Transfer rewards using pool PDA as signer
         let pool_seeds = &[
@@ -161,13 +703,24 @@ pub mod secure_matching {
             amount: rewards,
         });
         
-        msg!("Claimed {} rewards", rewards);
+        log_event!("claim_rewards", rewards = rewards);
+        
+        // ✅ Full exit: nothing staked, nothing left to claim
+        close_if_empty(&ctx.accounts.staking_account, &ctx.accounts.user)?;
         Ok(())
     }
 
     /// ✅ SECURE: Stake with pool relationship verification
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+    ///
+    /// `lock_duration` picks the reward multiplier from `pool.lock_tiers`
+    /// and applies to the whole position: nothing can be unstaked before
+    /// `last_stake_time + lock_duration`. A top-up may not end the lock
+    /// earlier than the lock already in place.
+    pub fn stake(ctx: Context<Stake>, amount: u64, lock_duration: i64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let staking = &mut ctx.accounts.staking_account;
         let pool = &mut ctx.accounts.pool;
@@ -178,16 +731,34 @@ pub mod secure_matching {
         // - user_tokens.owner == user
         // - user_tokens.mint == pool.token_mint
         
+        let now = Clock::get()?.unix_timestamp;
+        let multiplier_bps = pool.multiplier_for(lock_duration)?;
+        
+        // ✅ Topping up with a short lock must not release the existing stake
+        let new_lock_end = now
+            .checked_add(lock_duration)
+            .ok_or(CommonError::Overflow)?;
+        if staking.amount > 0 {
+            require!(new_lock_end >= staking.lock_end()?, ErrorCode::StillLocked);
+        }
+        
+        // ✅ Settle at the old stake and multiplier before either changes
+        pool.update_rewards(now)?;
+        staking.settle_rewards(pool)?;
+        
         // Update staking account
         staking.amount = staking.amount
             .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
-        staking.last_stake_time = Clock::get()?.unix_timestamp;
+            .ok_or(CommonError::Overflow)?;
+        staking.reward_debt = logic::reward_debt(staking.amount, pool.acc_reward_per_share)?;
+        staking.last_stake_time = now;
+        staking.lock_duration = lock_duration;
+        staking.multiplier_bps = multiplier_bps;
         
         // Update pool
         pool.total_staked = pool.total_staked
             .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
         
         // Transfer tokens to pool
         let cpi_accounts = Transfer {
@@ -206,53 +777,953 @@ pub mod secure_matching {
             user: ctx.accounts.user.key(),
             pool: pool.key(),
             amount,
+            lock_duration,
+            multiplier_bps,
         });
         
-        msg!("Staked {} tokens", amount);
+        log_event!("stake", amount = amount, lock_duration = lock_duration, multiplier_bps = multiplier_bps);
         Ok(())
     }
+
+    /// ✅ SECURE: Unstake with relationship verification and lock period
+    ///
+    /// Closes the staking account and refunds its rent to `user` when the
+    /// unstake leaves it empty (see `StakingAccount::is_empty`).
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let staking = &mut ctx.accounts.staking_account;
+        let pool = &mut ctx.accounts.pool;
+        
+        // All validations handled by constraints:
+        // - staking_account.owner == user
+        // - staking_account.pool == pool.key()
+        // - user_tokens.owner == user
+        // - user_tokens.mint == pool.token_mint
+        // - pool_tokens.owner == pool.key()
+        
+        require!(
+            staking.amount >= amount,
+            ErrorCode::InsufficientStake
+        );
+        
+        // ✅ Enforce minimum stake duration
+        let now = Clock::get()?.unix_timestamp;
+        let unlock_time = staking.last_stake_time
+            .checked_add(pool.min_stake_duration)
+            .ok_or(CommonError::Overflow)?;
+        require!(now >= unlock_time, ErrorCode::StakeLocked);
+        
+        // ✅ Enforce the lock the staker chose for their multiplier
+        require!(now >= staking.lock_end()?, ErrorCode::StillLocked);
+        
+        pool.update_rewards(now)?;
+        staking.settle_rewards(pool)?;
+        
+        // Update state BEFORE transfer (CEI pattern)
+        staking.amount = staking.amount
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        staking.reward_debt = logic::reward_debt(staking.amount, pool.acc_reward_per_share)?;
+        pool.total_staked = pool.total_staked
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        
+        // Transfer tokens back using pool PDA as signer
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        emit!(Unstaked {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: pool.key(),
+            amount,
+            remaining: staking.amount,
+        });
+        
+        log_event!("unstake", amount = amount, remaining = staking.amount);
+        
+        // ✅ Full exit with nothing pending: refund the account's rent
+        close_if_empty(&ctx.accounts.staking_account, &ctx.accounts.user)?;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Unstake early, paying a penalty into the reward vault
+    ///
+    /// If the stake is still inside `min_stake_duration`, `early_exit_penalty_bps`
+    /// of the amount is routed to the reward vault and the rest goes to the user.
+    /// After the lock period no penalty is applied. The penalty does not buy
+    /// out a tier lock: that still fails with `StillLocked`.
+    pub fn unstake_with_penalty(ctx: Context<UnstakeWithPenalty>, amount: u64) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let staking = &mut ctx.accounts.staking_account;
+        let pool = &mut ctx.accounts.pool;
+        
+        // All validations handled by constraints:
+        // - staking_account.owner == user
+        // - staking_account.pool == pool.key()
+        // - user_tokens.owner == user, user_tokens.mint == pool.token_mint
+        // - pool_tokens.owner == pool.key()
+        // - pool.reward_vault == reward_vault.key()
+        // - reward_vault.mint == pool.token_mint
+        
+        require!(
+            staking.amount >= amount,
+            ErrorCode::InsufficientStake
+        );
+        
+        // ✅ A boosted multiplier was paid for with the lock; no early exit
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= staking.lock_end()?, ErrorCode::StillLocked);
+        
+        // ✅ Penalty only applies inside the lock period
+        let unlock_time = staking.last_stake_time
+            .checked_add(pool.min_stake_duration)
+            .ok_or(CommonError::Overflow)?;
+        let penalty = if now < unlock_time {
+            logic::apply_bps(amount, pool.early_exit_penalty_bps)?
+        } else {
+            0
+        };
+        
+        // ✅ Penalty can never exceed the amount being unstaked
+        require!(penalty <= amount, ErrorCode::InvalidPenalty);
+        let payout = amount
+            .checked_sub(penalty)
+            .ok_or(CommonError::Underflow)?;
+        
+        pool.update_rewards(now)?;
+        staking.settle_rewards(pool)?;
+        
+        // Update state BEFORE transfers (CEI pattern)
+        staking.amount = staking.amount
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        staking.reward_debt = logic::reward_debt(staking.amount, pool.acc_reward_per_share)?;
+        pool.total_staked = pool.total_staked
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        // Route the penalty to the reward vault
+        if penalty > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.pool_tokens.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, penalty)?;
+            
+            emit!(PenaltyApplied {
+                staking_account: staking.key(),
+                user: ctx.accounts.user.key(),
+                amount,
+                penalty,
+            });
+        }
+        
+        // Return the remainder to the user
+        if payout > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.pool_tokens.to_account_info(),
+                to: ctx.accounts.user_tokens.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, payout)?;
+        }
+        
+        emit!(Unstaked {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: pool.key(),
+            amount: payout,
+            remaining: staking.amount,
+        });
+        
+        log_event!("unstake_with_penalty", amount = amount, penalty = penalty, remaining = staking.amount);
+        
+        // ✅ Full exit with nothing pending: refund the account's rent
+        close_if_empty(&ctx.accounts.staking_account, &ctx.accounts.user)?;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Return the full stake immediately, forfeiting all rewards
+    ///
+    /// Incident-response exit: no lock period, no penalty, no reward
+    /// settlement. Only principal moves, from `pool_tokens`; the reward vault
+    /// is not an account of this instruction. Rewards the staker had not yet
+    /// settled stay in the accumulator and go to the remaining stakers.
+    pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        let staking = &mut ctx.accounts.staking_account;
+        let pool = &mut ctx.accounts.pool;
+        
+        let amount = staking.amount;
+        require!(amount > 0, ErrorCode::InsufficientStake);
+        let rewards_forfeited = staking.pending_rewards;
+        
+        // Update state BEFORE transfer (CEI pattern)
+        staking.amount = 0;
+        staking.pending_rewards = 0;
+        staking.reward_debt = 0;
+        pool.total_staked = pool.total_staked
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.pool_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        
+        emit!(EmergencyUnstaked {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: pool.key(),
+            amount_returned: amount,
+            rewards_forfeited,
+        });
+        
+        log_event!("emergency_unstake", amount = amount, rewards_forfeited = rewards_forfeited);
+        
+        // ✅ Nothing left unless the account also holds pool shares
+        close_if_empty(&ctx.accounts.staking_account, &ctx.accounts.user)?;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Restake pending rewards without withdrawing them
+    ///
+    /// Only possible when rewards are paid in the staked token. The reward
+    /// tokens are moved from the reward vault into the pool's token account
+    /// so the increased stake stays fully backed.
+    pub fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking_account.check_version()?;
+        
+        let staking = &mut ctx.accounts.staking_account;
+        let pool = &mut ctx.accounts.pool;
+        
+        // ✅ Rewards can only become stake if they are the same token
+        require_keys_eq!(
+            pool.reward_mint,
+            pool.token_mint,
+            ErrorCode::CannotCompoundDifferentMint
+        );
+        
+        pool.update_rewards(Clock::get()?.unix_timestamp)?;
+        staking.settle_rewards(pool)?;
+        
+        let rewards = staking.pending_rewards;
+        require!(rewards > 0, ErrorCode::NoRewardsToClaim);
+        
+        // All validations handled by constraints:
+        // - staking_account.owner == user
+        // - staking_account.pool == pool.key()
+        // - pool.reward_vault == reward_vault.key()
+        // - pool_tokens.owner == pool.key()
+        
+        // Update state BEFORE transfer (CEI pattern)
+        // total_claimed is intentionally untouched: nothing left the pool
+        staking.pending_rewards = 0;
+        staking.amount = staking.amount
+            .checked_add(rewards)
+            .ok_or(CommonError::Overflow)?;
+        staking.reward_debt = logic::reward_debt(staking.amount, pool.acc_reward_per_share)?;
+        pool.total_staked = pool.total_staked
+            .checked_add(rewards)
+            .ok_or(CommonError::Overflow)?;
+        
+        // Move the backing tokens from the reward vault to the stake account
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.pool_tokens.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, rewards)?;
+        
+        emit!(RewardsCompounded {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: pool.key(),
+            amount: rewards,
+            new_stake: staking.amount,
+        });
+        
+        log_event!("compound_rewards", rewards = rewards, stake = staking.amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a pool left at the previous layout version
+    ///
+    /// The only way out of `VersionMismatch` for a pool (pool authority
+    /// only). The layout has not changed since versioning began, so only
+    /// the version is rewritten; a future `CURRENT_VERSION` bump converts
+    /// the old fields here.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let from_version = pool.version;
+        check_migratable(from_version)?;
+        
+        pool.version = CURRENT_VERSION;
+        
+        emit!(AccountMigrated {
+            account: pool.key(),
+            from_version,
+            to_version: pool.version,
+        });
+        
+        log_event!("migrate_pool", pool = pool.key(), from_version = from_version, to_version = pool.version);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a staking account left at the previous layout version
+    ///
+    /// Same rules as `migrate_pool`, signed by the stake's owner. It does
+    /// not need the pool, so a stake can be migrated, and then exited,
+    /// whatever version its pool is at.
+    pub fn migrate_staking_account(ctx: Context<MigrateStakingAccount>) -> Result<()> {
+        let staking = &mut ctx.accounts.staking_account;
+        let from_version = staking.version;
+        check_migratable(from_version)?;
+        
+        staking.version = CURRENT_VERSION;
+        
+        emit!(AccountMigrated {
+            account: staking.key(),
+            from_version,
+            to_version: staking.version,
+        });
+        
+        log_event!("migrate_staking_account", staking_account = staking.key(), from_version = from_version, to_version = staking.version);
+        Ok(())
+    }
+}
+
+/// ✅ Post-condition for every path that mutates deposits or shares
+/// 
+/// Shares without deposits are claims on nothing; deposits without shares
+/// are unclaimable. Either state breaks `shares_for_deposit`.
+pub fn assert_pool_invariants(pool: &Pool) -> Result<()> {
+    require!(
+        (pool.total_shares == 0) == (pool.total_deposits == 0),
+        CommonError::InvariantViolation
+    );
+    Ok(())
+}
+
+/// ✅ Close `staking_account` into `user` if it holds nothing, so a full
+/// exit does not leave rent stranded in an empty PDA
+///
+/// Returns whether the account was closed.
+fn close_if_empty<'info>(
+    staking_account: &Account<'info, StakingAccount>,
+    user: &Signer<'info>,
+) -> Result<bool> {
+    if !staking_account.is_empty() {
+        return Ok(false);
+    }
+    
+    let rent = staking_account.to_account_info().lamports();
+    staking_account.close(user.to_account_info())?;
+    
+    emit!(StakingAccountClosed {
+        staking_account: staking_account.key(),
+        owner: staking_account.owner,
+        pool: staking_account.pool,
+        rent,
+    });
+    
+    log_event!("close_staking_account", staking_account = staking_account.key(), rent = rent);
+    Ok(true)
+}
+
+/// ✅ Only an account one version behind can be migrated
+///
+/// A current account fails with `AlreadyMigrated`. Anything older, or
+/// written by a newer deployment, fails with `VersionMismatch`: there is no
+/// conversion for it here.
+fn check_migratable(version: u8) -> Result<()> {
+    require!(version != CURRENT_VERSION, ErrorCode::AlreadyMigrated);
+    require!(
+        version.checked_add(1) == Some(CURRENT_VERSION),
+        ErrorCode::VersionMismatch
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    // ✅ SECURE: One pool per mint, canonical bump stored on creation
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub token_mint: Account<'info, Mint>,
+    
+    pub reward_mint: Account<'info, Mint>,
+    
+    // ✅ SECURE: Rewards are paid from an account only the pool can sign for
+    #[account(
+        constraint = reward_vault.owner == pool.key() @ ErrorCode::InvalidRewardVault,
+        constraint = reward_vault.mint == reward_mint.key() @ CommonError::MintMismatch
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    // ✅ SECURE: Verify from_account is owned by authority
+    #[account(
+        mut,
+        constraint = from_account.owner == authority.key() @ CommonError::InvalidOwner,
+        constraint = from_account.mint == to_account.mint @ CommonError::MintMismatch,
+        // ✅ SECURE: A self-transfer is a no-op that would still emit an event
+        constraint = from_account.key() != to_account.key() @ ErrorCode::SelfTransfer,
+        // ✅ SECURE: Fail with a clear error instead of deep inside the token CPI
+        constraint = !from_account.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub from_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = !to_account.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub to_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAsDelegate<'info> {
+    // ✅ SECURE: Signer must be the approved delegate, not merely any signer
+    #[account(
+        mut,
+        constraint = from_account.delegate == COption::Some(authority.key()) @ ErrorCode::NotDelegate,
+        constraint = from_account.mint == to_account.mint @ CommonError::MintMismatch,
+        constraint = from_account.key() != to_account.key() @ ErrorCode::SelfTransfer,
+        constraint = !from_account.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub from_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = !to_account.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub to_account: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: The expiry recorded with this account's approval, for
+    // this delegate. An approval made with plain SPL `approve` has none.
+    #[account(
+        seeds = [b"delegation", from_account.key().as_ref()],
+        bump = delegation.bump,
+        constraint = delegation.delegate == authority.key() @ ErrorCode::NotDelegate
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    /// The delegate
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ CommonError::InvalidOwner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Only approved and recorded; it signs `transfer_as_delegate`
+    pub delegate: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ CommonError::InvalidOwner
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"delegation", token_account.key().as_ref()],
+        bump = delegation.bump,
+        close = owner
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToPool<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: Source and destination must be different accounts,
+    // and the source must match the pool's expected mint
+    #[account(
+        mut,
+        constraint = user_tokens.key() != pool_tokens.key() @ ErrorCode::DuplicateAccount,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch,
+        constraint = !user_tokens.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub user_tokens: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    // ✅ SECURE: Verify pool_tokens belongs to pool and has correct mint
+    #[account(
+        mut,
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch,
+        constraint = !pool_tokens.is_frozen() @ ErrorCode::TokenAccountFrozen
+    )]
+    pub pool_tokens: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    // ✅ SECURE: transfer_checked needs the mint; it must be the pool's
+    #[account(address = pool.token_mint @ CommonError::MintMismatch)]
+    pub token_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    // ✅ SECURE: Pool PDA verification
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ SECURE: Shares are credited to the depositor's own PDA
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    // ✅ SECURE: SPL Token or Token-2022, nothing else
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: Only the holder's own PDA can be debited
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    // ✅ SECURE: Deposits are paid from the pool's token account, never the reward vault
+    #[account(
+        mut,
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch,
+        constraint = pool_tokens.key() != pool.reward_vault @ ErrorCode::InvalidRewardVault
+    )]
+    pub pool_tokens: InterfaceAccount<'info, token_interface::TokenAccount>,
+    
+    #[account(address = pool.token_mint @ CommonError::MintMismatch)]
+    pub token_mint: InterfaceAccount<'info, token_interface::Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetSharePrice<'info> {
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct SetCap<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetLockTiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateStakingAccount<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: One staking account per (user, pool), at a derivable address
+    #[account(
+        init,
+        payer = user,
+        space = 8 + StakingAccount::INIT_SPACE,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStakingAccount<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: The user's own account, only when empty, rent to the user
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        constraint = staking_account.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = staking_account.is_empty() @ ErrorCode::AccountNotEmpty,
+        close = user
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    #[account(
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    // ✅ Only the pool's own authority can migrate it
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateStakingAccount<'info> {
+    pub user: Signer<'info>,
+    
+    // ✅ Only the stake's owner can migrate it
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), staking_account.pool.as_ref()],
+        bump = staking_account.bump,
+        constraint = staking_account.owner == user.key() @ CommonError::InvalidOwner
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub admin: Signer<'info>,
+    
+    // ✅ SECURE: Only the pool authority funds, only into the pool's vault
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.authority == admin.key() @ ErrorCode::UnauthorizedFunder,
+        has_one = reward_vault @ ErrorCode::InvalidRewardVault
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ SECURE: Verified through has_one on pool
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = admin_tokens.owner == admin.key() @ CommonError::InvalidOwner,
+        constraint = admin_tokens.mint == pool.reward_mint @ CommonError::MintMismatch
+    )]
+    pub admin_tokens: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    // Mutable: receives the staking account's rent if the claim empties it
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: Verify staking account belongs to user and pool
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
+        constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    // ✅ SECURE: Verify pool and its reward vault
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = reward_vault @ ErrorCode::InvalidRewardVault
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ SECURE: Verified through has_one on pool
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Verify user owns the reward account and mint matches
+    #[account(
+        mut,
+        constraint = user_reward_account.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_reward_account.mint == pool.reward_mint @ CommonError::MintMismatch
+    )]
+    pub user_reward_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Verified as staking_account.owner
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
+    pub owner: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    // ✅ SECURE: Verify from_account is owned by authority
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: Verify staking account ownership and pool relationship
     #[account(
         mut,
-        constraint = from_account.owner == authority.key() @ ErrorCode::InvalidOwner,
-        constraint = from_account.mint == to_account.mint @ ErrorCode::MintMismatch
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
+        constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
     )]
-    pub from_account: Account<'info, TokenAccount>,
+    pub staking_account: Account<'info, StakingAccount>,
     
-    #[account(mut)]
-    pub to_account: Account<'info, TokenAccount>,
+    // ✅ SECURE: Verify user token account
+    #[account(
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
     
-    pub authority: Signer<'info>,
+    // ✅ SECURE: Verify pool token account
+    #[account(
+        mut,
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch
+    )]
+    pub pool_tokens: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    /// CHECK: Verified as staking_account.owner
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
+    pub owner: AccountInfo<'info>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct DepositToPool<'info> {
+pub struct Unstake<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
-    // ✅ SECURE: Verify mint matches pool's expected mint
+    // ✅ SECURE: Verify staking account ownership and pool relationship
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
+        constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    // ✅ SECURE: Tokens can only be returned to the staker's own account
     #[account(
         mut,
-        constraint = user_tokens.owner == user.key() @ ErrorCode::InvalidOwner,
-        constraint = user_tokens.mint == pool.token_mint @ ErrorCode::MintMismatch
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch
     )]
     pub user_tokens: Account<'info, TokenAccount>,
     
-    // ✅ SECURE: Verify pool_tokens belongs to pool and has correct mint
+    // ✅ SECURE: Verify pool token account
     #[account(
         mut,
-        constraint = pool_tokens.owner == pool.key() @ ErrorCode::InvalidOwner,
-        constraint = pool_tokens.mint == pool.token_mint @ ErrorCode::MintMismatch
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch
     )]
     pub pool_tokens: Account<'info, TokenAccount>,
     
-    // ✅ SECURE: Pool PDA verification
     #[account(
         mut,
         seeds = [b"pool", pool.token_mint.as_ref()],
@@ -260,86 +1731,147 @@ pub struct DepositToPool<'info> {
     )]
     pub pool: Account<'info, Pool>,
     
+    /// CHECK: Verified as staking_account.owner
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
+    pub owner: AccountInfo<'info>,
+    
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
+pub struct EmergencyUnstake<'info> {
+    #[account(mut)]
     pub user: Signer<'info>,
     
-    // ✅ SECURE: Verify staking account belongs to user and pool
+    // ✅ SECURE: Verify staking account ownership and pool relationship
     #[account(
         mut,
-        has_one = owner @ ErrorCode::InvalidOwner,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
         constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
     )]
     pub staking_account: Account<'info, StakingAccount>,
     
-    // ✅ SECURE: Verify pool and its reward vault
+    // ✅ SECURE: Principal can only go to the staker's own account
     #[account(
-        seeds = [b"pool", pool.token_mint.as_ref()],
-        bump = pool.bump,
-        has_one = reward_vault @ ErrorCode::InvalidRewardVault
+        mut,
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch
     )]
-    pub pool: Account<'info, Pool>,
+    pub user_tokens: Account<'info, TokenAccount>,
     
-    // ✅ SECURE: Verified through has_one on pool
-    #[account(mut)]
-    pub reward_vault: Account<'info, TokenAccount>,
+    // ✅ SECURE: Principal comes from the pool's stake account, never the reward vault
+    #[account(
+        mut,
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch,
+        constraint = pool_tokens.key() != pool.reward_vault @ ErrorCode::InvalidRewardVault
+    )]
+    pub pool_tokens: Account<'info, TokenAccount>,
     
-    // ✅ SECURE: Verify user owns the reward account and mint matches
     #[account(
         mut,
-        constraint = user_reward_account.owner == user.key() @ ErrorCode::InvalidOwner,
-        constraint = user_reward_account.mint == pool.reward_mint @ ErrorCode::MintMismatch
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump
     )]
-    pub user_reward_account: Account<'info, TokenAccount>,
+    pub pool: Account<'info, Pool>,
     
     /// CHECK: Verified as staking_account.owner
-    #[account(constraint = owner.key() == user.key() @ ErrorCode::InvalidOwner)]
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
     pub owner: AccountInfo<'info>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct Stake<'info> {
+pub struct UnstakeWithPenalty<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
     // ✅ SECURE: Verify staking account ownership and pool relationship
     #[account(
         mut,
-        has_one = owner @ ErrorCode::InvalidOwner,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
         constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
     )]
     pub staking_account: Account<'info, StakingAccount>,
     
-    // ✅ SECURE: Verify user token account
     #[account(
         mut,
-        constraint = user_tokens.owner == user.key() @ ErrorCode::InvalidOwner,
-        constraint = user_tokens.mint == pool.token_mint @ ErrorCode::MintMismatch
+        constraint = user_tokens.owner == user.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == pool.token_mint @ CommonError::MintMismatch
     )]
     pub user_tokens: Account<'info, TokenAccount>,
     
-    // ✅ SECURE: Verify pool token account
     #[account(
         mut,
-        constraint = pool_tokens.owner == pool.key() @ ErrorCode::InvalidOwner,
-        constraint = pool_tokens.mint == pool.token_mint @ ErrorCode::MintMismatch
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch
     )]
     pub pool_tokens: Account<'info, TokenAccount>,
     
+    // ✅ SECURE: Penalty destination is the pool's own reward vault
     #[account(
         mut,
         seeds = [b"pool", pool.token_mint.as_ref()],
-        bump = pool.bump
+        bump = pool.bump,
+        has_one = reward_vault @ ErrorCode::InvalidRewardVault
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ SECURE: Penalty is paid in the staked token
+    #[account(
+        mut,
+        constraint = reward_vault.mint == pool.token_mint @ CommonError::MintMismatch
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Verified as staking_account.owner
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
+    pub owner: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    pub user: Signer<'info>,
+    
+    // ✅ SECURE: Verify staking account belongs to user and pool
+    #[account(
+        mut,
+        seeds = [b"staking", user.key().as_ref(), pool.key().as_ref()],
+        bump = staking_account.bump,
+        has_one = owner @ CommonError::InvalidOwner,
+        constraint = staking_account.pool == pool.key() @ ErrorCode::PoolMismatch
+    )]
+    pub staking_account: Account<'info, StakingAccount>,
+    
+    // ✅ SECURE: Verify pool and its reward vault
+    #[account(
+        mut,
+        seeds = [b"pool", pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = reward_vault @ ErrorCode::InvalidRewardVault
     )]
     pub pool: Account<'info, Pool>,
     
+    // ✅ SECURE: Verified through has_one on pool
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = pool_tokens.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_tokens.mint == pool.token_mint @ CommonError::MintMismatch
+    )]
+    pub pool_tokens: Account<'info, TokenAccount>,
+    
     /// CHECK: Verified as staking_account.owner
-    #[account(constraint = owner.key() == user.key() @ ErrorCode::InvalidOwner)]
+    #[account(constraint = owner.key() == user.key() @ CommonError::InvalidOwner)]
     pub owner: AccountInfo<'info>,
     
     pub token_program: Program<'info, Token>,
@@ -355,7 +1887,130 @@ pub struct Pool {
     pub total_deposits: u64,
     pub total_shares: u64,
     pub total_staked: u64,
+    /// Minimum seconds a stake must remain before it can be withdrawn
+    pub min_stake_duration: i64,
+    /// Penalty (in bps) for unstaking inside the lock period
+    pub early_exit_penalty_bps: u16,
+    /// Upper bound on `total_deposits`
+    pub max_total_deposits: u64,
+    /// Upper bound on each depositor's `StakingAccount::total_deposited`
+    pub per_user_cap: u64,
+    /// Cumulative amount paid into `reward_vault` through `fund_rewards`
+    pub total_rewards_funded: u64,
+    /// Reward tokens emitted per second, shared by all stakers
+    pub reward_rate: u64,
+    /// Rewards per staked token since creation, scaled by `logic::ACC_PRECISION`
+    pub acc_reward_per_share: u128,
+    /// When `acc_reward_per_share` was last brought up to date
+    pub last_reward_time: i64,
+    /// Reward multipliers by minimum lock, ascending; unused slots are default
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS],
     pub bump: u8,
+    /// Setting change waiting for `action_ready_at`
+    pub pending_action: Option<AdminAction>,
+    /// Earliest time `execute_admin_action` may apply `pending_action`
+    pub action_ready_at: i64,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+/// Pool setting changes that wait `ADMIN_ACTION_DELAY` between
+/// `queue_admin_action` and `execute_admin_action`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    /// New `max_total_deposits`
+    SetCap { max_total_deposits: u64 },
+}
+
+/// Stakes locked for at least `min_duration` seconds earn `multiplier_bps`
+/// of their share of the emission
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct LockTier {
+    pub min_duration: i64,
+    /// Out of `logic::BPS_DENOMINATOR`; 0 marks an unused slot
+    pub multiplier_bps: u16,
+}
+
+impl Pool {
+    /// ✅ Reject pools written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Bring `acc_reward_per_share` up to `now`
+    ///
+    /// Must run before any stake changes so the elapsed emission is split
+    /// across the stakes that were actually present.
+    pub fn update_rewards(&mut self, now: i64) -> Result<()> {
+        let elapsed = now
+            .checked_sub(self.last_reward_time)
+            .filter(|d| *d >= 0)
+            .ok_or(CommonError::InvariantViolation)?;
+        
+        self.acc_reward_per_share = logic::acc_reward_per_share(
+            self.acc_reward_per_share,
+            self.reward_rate,
+            elapsed as u64,
+            self.total_staked,
+        )?;
+        self.last_reward_time = now;
+        Ok(())
+    }
+    
+    /// Multiplier for a stake locked for `lock_duration` seconds
+    ///
+    /// The highest configured tier whose `min_duration` the lock meets. A
+    /// pool with no tiers pays every stake in full, but only unlocked.
+    pub fn multiplier_for(&self, lock_duration: i64) -> Result<u16> {
+        require!(lock_duration >= 0, ErrorCode::InvalidLockTier);
+        
+        let mut configured = self.lock_tiers
+            .iter()
+            .take_while(|tier| tier.multiplier_bps > 0)
+            .peekable();
+        if configured.peek().is_none() {
+            require!(lock_duration == 0, ErrorCode::InvalidLockTier);
+            return Ok(logic::BPS_DENOMINATOR as u16);
+        }
+        
+        configured
+            .filter(|tier| lock_duration >= tier.min_duration)
+            .last()
+            .map(|tier| tier.multiplier_bps)
+            .ok_or_else(|| ErrorCode::InvalidLockTier.into())
+    }
+    
+    /// Reject tiers `multiplier_for` could not read unambiguously
+    pub fn validate_lock_tiers(lock_tiers: &[LockTier; MAX_LOCK_TIERS]) -> Result<()> {
+        let configured = lock_tiers
+            .iter()
+            .take_while(|tier| tier.multiplier_bps > 0)
+            .count();
+        
+        // ✅ Unused slots only at the end, so none can hide a tier
+        require!(
+            lock_tiers[configured..].iter().all(|tier| *tier == LockTier::default()),
+            ErrorCode::InvalidLockTier
+        );
+        
+        for (i, tier) in lock_tiers[..configured].iter().enumerate() {
+            require!(tier.min_duration >= 0, ErrorCode::InvalidLockTier);
+            // ✅ Never more than the stake's share of the emission
+            require!(
+                tier.multiplier_bps as u64 <= logic::BPS_DENOMINATOR,
+                ErrorCode::InvalidLockTier
+            );
+            if i > 0 {
+                let prev = &lock_tiers[i - 1];
+                require!(
+                    tier.min_duration > prev.min_duration && tier.multiplier_bps >= prev.multiplier_bps,
+                    ErrorCode::InvalidLockTier
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 #[account]
@@ -367,8 +2022,126 @@ pub struct StakingAccount {
     pub pending_rewards: u64,
     pub total_claimed: u64,
     pub last_stake_time: i64,
+    /// Seconds after `last_stake_time` before any of `amount` can be unstaked
+    pub lock_duration: i64,
+    /// Share of earned rewards paid out, from the tier `lock_duration` met
+    pub multiplier_bps: u16,
+    /// `amount * acc_reward_per_share` at the last settlement
+    pub reward_debt: u128,
+    /// Pool shares minted by `deposit_to_pool`
+    pub shares: u64,
+    /// Cumulative amount credited by `deposit_to_pool`; redemptions do not
+    /// lower it, so `per_user_cap` bounds lifetime deposits
+    pub total_deposited: u64,
+    pub bump: u8,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl StakingAccount {
+    /// ✅ Reject staking accounts written by another program version until
+    /// migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Move everything earned since the last checkpoint, scaled by
+    /// `multiplier_bps`, into `pending_rewards` and re-checkpoint at the
+    /// current accumulator
+    ///
+    /// Call after `Pool::update_rewards` and before changing `amount` or
+    /// `multiplier_bps`; set `reward_debt` again once the new amount is
+    /// known. The unpaid remainder of a reduced multiplier stays in the
+    /// reward vault.
+    pub fn settle_rewards(&mut self, pool: &Pool) -> Result<()> {
+        let earned = logic::pending_reward(
+            self.amount,
+            pool.acc_reward_per_share,
+            self.reward_debt,
+        )?;
+        let earned = logic::apply_bps(earned, self.multiplier_bps)?;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(earned)
+            .ok_or(CommonError::Overflow)?;
+        self.reward_debt = logic::reward_debt(self.amount, pool.acc_reward_per_share)?;
+        Ok(())
+    }
+    
+    /// Whether the account holds nothing: no stake, no unclaimed rewards,
+    /// no pool shares
+    ///
+    /// `total_deposited` must be 0 too. It counts lifetime deposits
+    /// against `per_user_cap`, and closing then re-creating the account
+    /// would reset it.
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+            && self.pending_rewards == 0
+            && self.shares == 0
+            && self.total_deposited == 0
+    }
+    
+    /// First timestamp at which the stake can be unstaked
+    pub fn lock_end(&self) -> Result<i64> {
+        Ok(self.last_stake_time
+            .checked_add(self.lock_duration)
+            .ok_or(CommonError::Overflow)?)
+    }
+}
+
+/// Expiry of the SPL approval on `token_account`, which SPL Token cannot
+/// store itself
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub token_account: Pubkey,
+    pub delegate: Pubkey,
+    /// Last unix timestamp at which `transfer_as_delegate` is accepted
+    pub delegate_expiry: i64,
+    pub bump: u8,
 }
 
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub total_rewards_funded: u64,
+}
+
+#[event]
+pub struct StakingAccountCreated {
+    pub staking_account: Pubkey,
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+}
+
+#[event]
+pub struct StakingAccountClosed {
+    pub staking_account: Pubkey,
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    /// Lamports refunded to the owner
+    pub rent: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    /// The pool or staking account rewritten
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+/// Emitted only for a real movement: `from != to` is enforced
 #[event]
 pub struct TransferExecuted {
     pub from: Pubkey,
@@ -385,6 +2158,44 @@ pub struct DepositMade {
     pub shares: u64,
 }
 
+#[event]
+pub struct SharePrice {
+    pub pool: Pubkey,
+    /// Tokens per share, scaled by `logic::SCALE`
+    pub price: u64,
+}
+
+#[event]
+pub struct SharesRedeemed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64,
+    pub amount: u64,
+    /// Remainder of `shares * total_deposits / total_shares`, left in the pool
+    pub dust: u64,
+}
+
+#[event]
+pub struct AdminActionQueued {
+    pub pool: Pubkey,
+    pub action: AdminAction,
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct DepositCapUpdated {
+    pub pool: Pubkey,
+    pub old_cap: u64,
+    pub new_cap: u64,
+}
+
+#[event]
+pub struct UserCapUpdated {
+    pub pool: Pubkey,
+    pub old_cap: u64,
+    pub new_cap: u64,
+}
+
 #[event]
 pub struct RewardsClaimed {
     pub staking_account: Pubkey,
@@ -399,24 +2210,105 @@ pub struct Staked {
     pub user: Pubkey,
     pub pool: Pubkey,
     pub amount: u64,
+    pub lock_duration: i64,
+    pub multiplier_bps: u16,
+}
+
+#[event]
+pub struct LockTiersUpdated {
+    pub pool: Pubkey,
+    pub lock_tiers: [LockTier; MAX_LOCK_TIERS],
+}
+
+#[event]
+pub struct EmergencyUnstaked {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount_returned: u64,
+    pub rewards_forfeited: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct PenaltyApplied {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub penalty: u64,
+}
+
+#[event]
+pub struct RewardsCompounded {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub new_stake: u64,
 }
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Invalid account owner")]
-    InvalidOwner,
-    #[msg("Token mint mismatch")]
-    MintMismatch,
     #[msg("Pool mismatch")]
     PoolMismatch,
     #[msg("Invalid reward vault")]
     InvalidRewardVault,
-    #[msg("Invalid amount")]
-    InvalidAmount,
-    #[msg("Arithmetic overflow")]
-    Overflow,
     #[msg("No rewards to claim")]
     NoRewardsToClaim,
+    #[msg("Insufficient staked amount")]
+    InsufficientStake,
+    #[msg("Stake is still locked")]
+    StakeLocked,
+    #[msg("Penalty exceeds unstake amount")]
+    InvalidPenalty,
+    #[msg("Cannot compound rewards paid in a different mint")]
+    CannotCompoundDifferentMint,
+    #[msg("Source and destination token accounts are the same")]
+    DuplicateAccount,
+    #[msg("Deposit would exceed the pool's deposit cap")]
+    DepositCapExceeded,
+    #[msg("Only the pool authority can fund rewards")]
+    UnauthorizedFunder,
+    #[msg("Reward vault cannot cover the pending rewards")]
+    InsufficientRewardReserves,
+    #[msg("Redemption rounds down to zero tokens")]
+    RedeemRoundsToZero,
+    #[msg("Cannot transfer from a token account to itself")]
+    SelfTransfer,
+    #[msg("Token account is frozen by its mint's freeze authority")]
+    TokenAccountFrozen,
+    #[msg("Signer is not the token account's approved delegate")]
+    NotDelegate,
+    #[msg("Amount exceeds the delegate's remaining allowance")]
+    InsufficientDelegation,
+    #[msg("Position holds fewer shares than requested")]
+    InsufficientShares,
+    #[msg("Lock duration matches no tier, or the tiers are malformed")]
+    InvalidLockTier,
+    #[msg("Stake is inside the lock duration it was staked with")]
+    StillLocked,
+    #[msg("Deposit would exceed the per-user deposit cap")]
+    UserCapExceeded,
+    #[msg("Queued admin action delay has not passed yet")]
+    ActionNotReady,
+    #[msg("No admin action is queued")]
+    NoQueuedAction,
+    #[msg("Staking account still holds a stake, rewards or shares")]
+    AccountNotEmpty,
+    #[msg("Account was written by a different program version")]
+    VersionMismatch,
+    #[msg("Delegate approval has expired")]
+    DelegationExpired,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
 }
 
 // ============================================================================
@@ -442,6 +2334,86 @@ pub enum ErrorCode {
 // 3. Attacker's FAKE token has different mint
 // 4. Transaction fails with "Token mint mismatch"
 //
+// DEPOSIT CAP:
+// ------------
+// checked_add only stops wrap-around at u64::MAX. Values far below that
+// can still overflow later math (shares * price, reward accrual).
+// 1. total_deposits + amount <= max_total_deposits → else DepositCapExceeded
+// 2. Only the pool authority can move the cap (queue_admin_action,
+//    has_one = authority), and only ADMIN_ACTION_DELAY after queuing it, so
+//    depositors see a cap change coming
+//
+// A global cap alone lets one whale fill a fair launch in one deposit.
+// per_user_cap bounds each depositor too:
+// 3. staking_account.total_deposited + amount <= per_user_cap
+//    → else UserCapExceeded
+// 4. total_deposited lives on the ["staking", user, pool] PDA, so a user
+//    cannot pass someone else's (or a fresh) account to reset it. Splitting
+//    across wallets is still possible; per-user caps are per key
+// 5. Both caps are checked against the requested amount before the CPI;
+//    either one failing rejects the deposit
+//
+// SELF-TRANSFER DEPOSIT BLOCKED:
+// ------------------------------
+// Attacker passes the same token account as user_tokens AND pool_tokens:
+// 1. Constraint: user_tokens.key() != pool_tokens.key() → DuplicateAccount
+// 2. Even if bypassed, a self-transfer leaves the balance unchanged:
+//    pool_tokens is reloaded after the CPI and only the real delta (0)
+//    is credited, so received > 0 fails with "Invalid amount"
+//
+// FEE-ON-TRANSFER MINTS:
+// ----------------------
+// deposit_to_pool and redeem_shares take SPL Token or Token-2022. A
+// Token-2022 mint with a TransferFee extension withholds part of every
+// transfer in the destination account:
+// 1. Depositing 1_000 at 1% lands 990 spendable tokens in pool_tokens
+// 2. total_deposits += amount would record 1_000, so shares are backed by
+//    tokens the pool never received and the last redeemer is short 10
+// 3. Crediting the reloaded balance delta records 990, matching the vault
+//
+// transfer_tokens applies the same rule: from_account == to_account fails
+// with SelfTransfer before the CPI, so integrators indexing TransferExecuted
+// never see a "transfer" that moved nothing.
+//
+// DELEGATE TRANSFERS:
+// -------------------
+// transfer_as_delegate swaps the owner check for the SPL delegate fields:
+// 1. from_account.delegate == Some(authority) → else NotDelegate
+// 2. delegated_amount >= amount → else InsufficientDelegation
+// 3. now <= delegation.delegate_expiry → else DelegationExpired
+// The token program decrements delegated_amount on each transfer (and
+// clears the delegate at zero), so the owner's approval caps the total.
+// SPL approvals never expire on their own, so approve_delegate records
+// delegate_expiry in a Delegation PDA keyed by the token account. A
+// forgotten approval stops working at expiry instead of lasting until
+// revoked; one made with plain SPL approve has no Delegation and is
+// refused outright.
+//
+// FROZEN TOKEN ACCOUNTS:
+// ----------------------
+// A mint's freeze authority can freeze any holder's account. The token
+// program then rejects the transfer with its own AccountFrozen, surfaced
+// as a generic CPI failure. transfer_tokens and deposit_to_pool check
+// `state != Frozen` on both sides first and fail with TokenAccountFrozen.
+//
+// SHARED EMISSION (reward-per-share):
+// ------------------------------------
+// Per-account accrual needs one transaction per staker to keep up.
+// The pool instead emits reward_rate tokens/second split by stake:
+// 1. Every stake/unstake/claim/compound first calls update_rewards:
+//    acc_reward_per_share += reward_rate * dt * PRECISION / total_staked
+// 2. The staker is settled at their OLD amount:
+//    pending += amount * acc / PRECISION - reward_debt
+// 3. reward_debt is reset for the NEW amount, so a late staker can't
+//    collect emissions from before they joined
+//
+// REWARD FUNDING:
+// ---------------
+// fund_rewards only moves tokens INTO the pool's own reward vault:
+// 1. pool.authority == admin → else UnauthorizedFunder
+// 2. has_one = reward_vault → funds can't be routed to another account
+// 3. total_rewards_funded records every deposit for off-chain reconciliation
+//
 // REWARD THEFT BLOCKED:
 // ---------------------
 // Attacker tries to claim with fake staking account:
@@ -451,6 +2423,63 @@ pub enum ErrorCode {
 // 4. Fake staking account won't have correct pool reference
 // 5. Transaction fails with "Pool mismatch"
 //
+// Cross-pool claim (a real pool A staking account against pool B):
+// - pool = B, reward_vault = B's vault → staking_account.pool != pool
+//   → "Pool mismatch"
+// - pool = A, reward_vault = B's vault → has_one = reward_vault
+//   → InvalidRewardVault
+// Without the pool check the first pairing drains B. Without has_one the
+// second fails only because the token program rejects A's PDA as the
+// authority of B's vault, a generic CPI error instead of a named one.
+//
 // Even if attacker creates staking account pointing to real pool:
 // - They can't set pending_rewards (only program can)
 // - has_one = owner ensures they can only claim their own rewards
+// - seeds = ["staking", user, pool]: the staking account must be the one
+//   create_staking_account made for this signer and pool. Any other
+//   account, even one with matching fields, fails with ConstraintSeeds
+
+//
+// STAKE THEFT BLOCKED:
+// --------------------
+// Attacker tries to unstake from someone else's staking account:
+// 1. has_one = owner: staking_account.owner must match the signer
+// 2. user_tokens.owner == user: tokens only go to the staker's account
+// 3. Early unstake before last_stake_time + min_stake_duration fails
+//    with "Stake is still locked"
+//
+// LOCK TIER GAMING BLOCKED:
+// -------------------------
+// Longer locks earn a larger share of the emission, so the lock is what
+// the multiplier is paid for:
+// 1. Stake 1_000 with a 365-day lock, then top up 1 token with no lock to
+//    reset the position → new lock must end no earlier → StillLocked
+// 2. unstake / unstake_with_penalty before last_stake_time + lock_duration
+//    → StillLocked; the early-exit penalty only covers min_stake_duration
+// 3. Rewards are settled at the OLD multiplier before a stake changes it,
+//    so a new tier never applies retroactively
+// 4. Multipliers are capped at 100%: short locks forfeit part of their
+//    share (it stays in the vault) instead of long locks being paid more
+//    than was emitted
+// emergency_unstake still ignores the lock, but it forfeits every pending
+// reward, so the boosted multiplier earns nothing there.
+//
+// EMERGENCY EXIT:
+// ---------------
+// emergency_unstake skips the lock and penalty, so it must not become a
+// way to pull rewards early:
+// 1. Only staking_account.amount is returned; pending_rewards is zeroed
+// 2. No reward settlement runs, and the reward vault is not an account
+// 3. pool_tokens != pool.reward_vault, even when both hold the same mint
+//
+// STAKING ACCOUNT CLOSE:
+// ----------------------
+// A fully exited staking account keeps its rent locked in an empty PDA.
+// 1. unstake and claim_rewards close it into the user once amount,
+//    pending_rewards, shares and total_deposited are all 0
+// 2. close_staking_account does the same on demand, with close = user
+//    and AccountNotEmpty while anything remains
+// 3. Rent only ever goes to the signing owner: the seeds include
+//    user.key(), and owner == user is checked on every path
+// A partial unstake, or a full unstake with rewards still pending, leaves
+// the account open; the closing claim happens afterwards.
@@ -0,0 +1,231 @@
+//! # Secure Account Migration Example
+//!
+//! This program demonstrates upgrading accounts written by an earlier
+//! deployment to a new layout in place: `migrate_vault_v1_to_v2` reads a
+//! `VaultV1` (no stored bump, no timestamps), grows the account and
+//! rewrites it as a `Vault` (version 2).
+//!
+//! ## Security Measures
+//! 1. `version` is the first field of every layout, so it sits at byte 8
+//!    whatever the rest of the account looks like
+//! 2. Only version 1 migrates: version 2 fails with `AlreadyMigrated`,
+//!    anything else with `UnsupportedVersion`
+//! 3. `owner = crate::ID` plus the `Vault` discriminator before any byte is
+//!    trusted; the stored `authority` must be the signer
+//! 4. Vault address re-derived from `["vault", authority]`; the canonical
+//!    bump found there is what v2 stores
+//! 5. The rent difference is transferred in before `realloc`, as in
+//!    `secure_realloc`
+//!
+//! ## Why This Works
+//! The version byte is part of every layout, so the program never has to
+//! guess a layout from the account's length. Migration writes version 2
+//! in the same instruction that changes the layout, so a second call sees
+//! version 2 and stops; there is no window where a half-migrated account
+//! can be read as either layout.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLLL");
+
+/// Layout written by the previous deployment
+pub const VAULT_V1: u8 = 1;
+
+/// Layout written by this program; the only one its instructions accept
+pub const VAULT_V2: u8 = 2;
+
+#[program]
+pub mod secure_migration {
+    use super::*;
+
+    /// Create a vault PDA for `authority` directly in the current layout
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.version = VAULT_V2;
+        vault.authority = ctx.accounts.authority.key();
+        vault.balance = 0;
+        vault.bump = ctx.bumps.vault;
+        vault.migrated_at = 0;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a version 1 vault as version 2
+    ///
+    /// An attacker CANNOT:
+    /// - Migrate someone else's vault (seeds + stored authority)
+    /// - Migrate twice, or feed a v2 account through the v1 decoder
+    /// - Pass an account of another type or program as a "v1 vault"
+    pub fn migrate_vault_v1_to_v2(ctx: Context<MigrateVault>) -> Result<()> {
+        let info = ctx.accounts.vault.to_account_info();
+
+        // ✅ SECURE: Decode only what is known to be a v1 vault
+        let old = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() > 8 && data[..8] == Vault::DISCRIMINATOR[..],
+                ErrorCode::UnsupportedVersion
+            );
+            match data[8] {
+                VAULT_V1 => {}
+                VAULT_V2 => return err!(ErrorCode::AlreadyMigrated),
+                _ => return err!(ErrorCode::UnsupportedVersion),
+            }
+            VaultV1::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(old.authority, ctx.accounts.authority.key(), CommonError::Unauthorized);
+
+        // ✅ SECURE: Fund the new size before taking it
+        let new_len = 8 + Vault::INIT_SPACE;
+        let top_up = Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(info.lamports());
+        if top_up > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: info.clone(),
+            };
+            system_program::transfer(
+                CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+                top_up,
+            )?;
+        }
+        info.realloc(new_len, false)?;
+
+        let migrated_at = Clock::get()?.unix_timestamp;
+        let vault = Vault {
+            version: VAULT_V2,
+            authority: old.authority,
+            balance: old.balance,
+            bump: ctx.bumps.vault,
+            migrated_at,
+        };
+        // ✅ SECURE: Discriminator and version 2 written together
+        vault.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(VaultMigrated {
+            vault: info.key(),
+            authority: old.authority,
+            from_version: VAULT_V1,
+            to_version: VAULT_V2,
+            rent_paid: top_up,
+        });
+
+        log_event!("migrate_vault_v1_to_v2", vault = info.key(), new_len = new_len, rent_paid = top_up);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// CHECK: `Account<Vault>` would fail to decode a v1 layout; owner and
+    /// seeds are checked here, discriminator and version in the handler
+    #[account(
+        mut,
+        owner = crate::ID @ CommonError::InvalidOwner,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// Pays the rent for the larger layout
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Current vault layout
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    /// Always first, so it can be read before the rest of the layout is known
+    pub version: u8,
+    pub authority: Pubkey,
+    pub balance: u64,
+    /// Canonical bump, stored from version 2 on
+    pub bump: u8,
+    /// When `migrate_vault_v1_to_v2` ran; 0 for vaults created as version 2
+    pub migrated_at: i64,
+}
+
+/// Version 1 layout, after the shared `Vault` discriminator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultV1 {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub balance: u64,
+}
+
+impl VaultV1 {
+    /// Discriminator + version + authority + balance
+    pub const SPACE: usize = 8 + 1 + 32 + 8;
+}
+
+#[event]
+pub struct VaultMigrated {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub rent_paid: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Vault is already at the current version")]
+    AlreadyMigrated,
+    #[msg("Vault version is not one this program can migrate")]
+    UnsupportedVersion,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Migrating a vault written by the previous deployment:
+//
+//   before   [Vault disc][01][authority][balance]                 49 bytes
+//   migrate  version 1 ✓, authority == signer ✓
+//            top_up = rent(58) - rent(49), authority → vault
+//            realloc(58)
+//   after    [Vault disc][02][authority][balance][bump][migrated_at]  58 bytes
+//   migrate  version 2 → AlreadyMigrated
+//
+// Notes:
+// - Without the version byte, the only signal is the account length, and
+//   a v2 account reallocated or padded to another size would be decoded
+//   with the wrong layout. Put `version` first in every new `#[account]`
+// - The v1 layout keeps the `Vault` discriminator: it is derived from the
+//   type name, not the fields, so renaming the struct would have orphaned
+//   every existing account
+// - `UnsupportedVersion` covers version 0 (zeroed data) and versions from a
+//   future deployment this binary does not know how to read
+// - Migration is per account and paid by its authority; nothing forces
+//   it, so handlers that only understand v2 should reject v1 accounts
+//   rather than decode them
@@ -0,0 +1,211 @@
+//! # Secure Minimum Output Example
+//!
+//! This program demonstrates the fix for swaps that round the output down to ZERO.
+//!
+//! ## Security Measures
+//! 1. Reject any swap whose computed output is 0 (`OutputRoundsToZero`)
+//! 2. Enforce a configurable `min_trade_size` on the input side
+//! 3. Keep user slippage protection (`min_amount_out`) on top of both
+//! 4. Use u128 intermediate math from `logic::swap_output`
+//!
+//! ## Why This Matters
+//! `vulnerable_overflow::swap` computes `amount_in / rate`. Any input below
+//! `rate` truncates to 0, so the user pays and receives nothing. Integer
+//! division ALWAYS rounds down; the program must decide what happens when
+//! the rounded result is worthless.
+
+use anchor_lang::prelude::*;
+
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("Secure8888888888888888888888888888888888888");
+
+#[program]
+pub mod secure_min_output {
+    use super::*;
+
+    /// Initialize a pool with a minimum trade size
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        reserve_in: u64,
+        reserve_out: u64,
+        min_trade_size: u64,
+    ) -> Result<()> {
+        require!(min_trade_size > 0, CommonError::InvalidAmount);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.reserve_in = reserve_in;
+        pool.reserve_out = reserve_out;
+        pool.min_trade_size = min_trade_size;
+
+        log_event!("initialize_pool", min_trade_size = min_trade_size);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Update the minimum trade size (authority only)
+    pub fn set_min_trade_size(ctx: Context<SetMinTradeSize>, min_trade_size: u64) -> Result<()> {
+        require!(min_trade_size > 0, CommonError::InvalidAmount);
+
+        let pool = &mut ctx.accounts.pool;
+        let old_min_trade_size = pool.min_trade_size;
+        pool.min_trade_size = min_trade_size;
+
+        emit!(MinTradeSizeUpdated {
+            pool: pool.key(),
+            old_min_trade_size,
+            new_min_trade_size: min_trade_size,
+        });
+
+        log_event!("set_min_trade_size", old_min_trade_size = old_min_trade_size, min_trade_size = min_trade_size);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Swap that never accepts input for zero output
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+
+        // ✅ Dust inputs are rejected before any math
+        require!(
+            amount_in >= pool.min_trade_size,
+            ErrorCode::BelowMinTradeSize
+        );
+
+        let amount_out = logic::swap_output(
+            amount_in,
+            pool.reserve_in,
+            pool.reserve_out,
+        )?;
+
+        // ✅ SECURE: A swap that rounds to zero is a donation, not a trade
+        require!(amount_out > 0, ErrorCode::OutputRoundsToZero);
+
+        // ✅ User slippage protection still applies
+        require!(
+            amount_out >= min_amount_out,
+            CommonError::SlippageExceeded
+        );
+
+        pool.reserve_in = pool.reserve_in
+            .checked_add(amount_in)
+            .ok_or(CommonError::Overflow)?;
+        pool.reserve_out = pool.reserve_out
+            .checked_sub(amount_out)
+            .ok_or(CommonError::Underflow)?;
+
+        emit!(SwapExecuted {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_in,
+            amount_out,
+        });
+
+        log_event!("swap", amount_in = amount_in, amount_out = amount_out);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinTradeSize<'info> {
+    #[account(
+        mut,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    pub user: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+    /// Smallest `amount_in` the pool will accept
+    pub min_trade_size: u64,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct MinTradeSizeUpdated {
+    pub pool: Pubkey,
+    pub old_min_trade_size: u64,
+    pub new_min_trade_size: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Swap output rounds to zero")]
+    OutputRoundsToZero,
+    #[msg("Input is below the minimum trade size")]
+    BelowMinTradeSize,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION (against vulnerable_overflow::swap)
+// ============================================================================
+//
+// DUST DRAIN OF THE INPUT SIDE:
+// -----------------------------
+// Pool: rate = 1000 (1000 in-tokens per out-token)
+//
+// Victim (or a naive bot) calls swap(999):
+//   amount_out = 999 / 1000 = 0
+//   reserve_in  += 999   (victim paid)
+//   reserve_out -= 0     (victim received nothing)
+//
+// Repeated 1000 times, 999,000 tokens flow into the pool for nothing.
+// Whoever holds pool shares captures the victims' input for free.
+//
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack FAILS here:
+//
+// 1. amount_in < min_trade_size → BelowMinTradeSize, nothing is transferred
+// 2. Even above the minimum, a skewed pool can still round to 0:
+//    swap_output(1_000, 1_000_000_000, 100) = 0 → OutputRoundsToZero
+// 3. min_amount_out protects the user against any other unfavorable rounding
+//
+// Rounding boundary (reserve_in = 1_000_000, reserve_out = 1_000):
+//   amount_in = 1_000 → 1_000 * 1_000 / 1_001_000 = 0   → rejected
+//   amount_in = 1_002 → 1_002 * 1_000 / 1_001_002 = 1   → accepted
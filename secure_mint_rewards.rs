@@ -0,0 +1,362 @@
+//! # Secure Mint Rewards Example
+//!
+//! This program demonstrates verifying that the program's own PDA is a
+//! mint's `mint_authority` before minting rewards from it.
+//!
+//! ## Security Measures
+//! 1. `initialize_pool` requires `reward_mint.mint_authority` to be the pool
+//!    PDA, so the program is the token's sole issuer from the start
+//! 2. `claim_rewards` checks it again and binds `reward_mint` to the pool
+//! 3. `token::mint_to` is signed with the pool PDA's seeds; no external key
+//!    ever signs a mint
+//! 4. `user_tokens` must be the claimant's account for the reward mint
+//! 5. `total_minted` can never pass `reward_cap`; a claim near the cap is
+//!    clamped to what is left and the rest stays pending
+//!
+//! ## Why This Works
+//! Once the pool PDA is the mint authority, only this program can sign for
+//! it, and it only does so in `claim_rewards` against credited rewards.
+//! The token's supply then equals `total_minted` plus whatever existed when
+//! the pool was created, and no off-chain key can inflate it.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecurePPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPPP");
+
+#[program]
+pub mod secure_mint_rewards {
+    use super::*;
+
+    /// ✅ SECURE: Create a reward pool over a mint it controls
+    ///
+    /// The mint must already name the pool PDA (derivable from the mint's
+    /// address) as `mint_authority`. The pool will never mint more than
+    /// `reward_cap` in total.
+    pub fn initialize_pool(ctx: Context<InitializePool>, reward_cap: u64) -> Result<()> {
+        require!(reward_cap > 0, CommonError::InvalidAmount);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.total_minted = 0;
+        pool.reward_cap = reward_cap;
+        pool.bump = ctx.bumps.pool;
+
+        emit!(RewardPoolInitialized {
+            pool: pool.key(),
+            authority: pool.authority,
+            reward_mint: pool.reward_mint,
+            reward_cap,
+        });
+
+        log_event!("initialize_pool", pool = pool.key(), reward_mint = pool.reward_mint, reward_cap = reward_cap);
+        Ok(())
+    }
+
+    /// Create `owner`'s reward account in `pool`
+    pub fn open_reward_account(ctx: Context<OpenRewardAccount>) -> Result<()> {
+        let rewards = &mut ctx.accounts.rewards;
+        rewards.owner = ctx.accounts.owner.key();
+        rewards.pool = ctx.accounts.pool.key();
+        rewards.pending = 0;
+        rewards.bump = ctx.bumps.rewards;
+
+        log_event!("open_reward_account", rewards = rewards.key(), owner = rewards.owner);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Credit `amount` of rewards to a user (pool authority only)
+    pub fn credit_rewards(ctx: Context<CreditRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let rewards = &mut ctx.accounts.rewards;
+        rewards.pending = rewards.pending
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+
+        log_event!("credit_rewards", rewards = rewards.key(), amount = amount, pending = rewards.pending);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Mint pending rewards, signed by the pool PDA
+    ///
+    /// An attacker CANNOT:
+    /// - Claim from a pool whose mint someone else can also mint (NotMintAuthority)
+    /// - Substitute another mint (has_one = reward_mint)
+    /// - Mint into an account that isn't theirs or isn't for the reward mint
+    /// - Push `total_minted` past `reward_cap`
+    ///
+    /// Cap policy: a claim larger than the headroom left under the cap is
+    /// clamped. The headroom is minted, the rest stays in `pending`, and
+    /// once no headroom is left every claim fails with `RewardCapReached`.
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let pending = ctx.accounts.rewards.pending;
+        require!(pending > 0, ErrorCode::NothingToClaim);
+
+        let pool = &ctx.accounts.pool;
+
+        // ✅ Clamp to the headroom under the cap
+        let headroom = pool.reward_cap
+            .checked_sub(pool.total_minted)
+            .ok_or(ErrorCode::RewardCapReached)?;
+        let amount = pending.min(headroom);
+        require!(amount > 0, ErrorCode::RewardCapReached);
+
+        // ✅ Defense-in-depth: constraint already checked the mint authority
+        require!(
+            ctx.accounts.reward_mint.mint_authority == COption::Some(pool.key()),
+            ErrorCode::NotMintAuthority
+        );
+
+        // ✅ CEI: settle the claim before the CPI
+        ctx.accounts.rewards.pending = pending - amount;
+
+        let reward_mint = pool.reward_mint;
+        let seeds = &[b"reward_pool".as_ref(), reward_mint.as_ref(), &[pool.bump]];
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        // ✅ Checked add, then the cap itself as the invariant
+        let pool = &mut ctx.accounts.pool;
+        pool.total_minted = pool.total_minted
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        require!(pool.total_minted <= pool.reward_cap, ErrorCode::RewardCapReached);
+
+        let still_pending = ctx.accounts.rewards.pending;
+
+        emit!(RewardsMinted {
+            pool: pool.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+            total_minted: pool.total_minted,
+            still_pending,
+        });
+
+        log_event!(
+            "claim_rewards",
+            pool = pool.key(),
+            owner = ctx.accounts.owner.key(),
+            amount = amount,
+            total_minted = pool.total_minted,
+            still_pending = still_pending
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", reward_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    // ✅ SECURE: Only the pool PDA can mint this token
+    #[account(
+        constraint = reward_mint.mint_authority == COption::Some(pool.key()) @ ErrorCode::NotMintAuthority
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRewardAccount<'info> {
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RewardAccount::INIT_SPACE,
+        seeds = [b"rewards", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub rewards: Account<'info, RewardAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreditRewards<'info> {
+    #[account(has_one = authority @ CommonError::Unauthorized)]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"rewards", pool.key().as_ref(), rewards.owner.as_ref()],
+        bump = rewards.bump,
+        has_one = pool @ ErrorCode::PoolMismatch
+    )]
+    pub rewards: Account<'info, RewardAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = reward_mint @ CommonError::MintMismatch
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"rewards", pool.key().as_ref(), owner.key().as_ref()],
+        bump = rewards.bump,
+        has_one = pool @ ErrorCode::PoolMismatch,
+        has_one = owner @ CommonError::Unauthorized
+    )]
+    pub rewards: Account<'info, RewardAccount>,
+
+    // ✅ SECURE: The pool PDA must be the mint authority
+    #[account(
+        mut,
+        constraint = reward_mint.mint_authority == COption::Some(pool.key()) @ ErrorCode::NotMintAuthority
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_tokens.owner == owner.key() @ CommonError::InvalidOwner,
+        constraint = user_tokens.mint == reward_mint.key() @ CommonError::MintMismatch
+    )]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Rewards minted through `claim_rewards`
+    pub total_minted: u64,
+    /// `total_minted` never exceeds this
+    pub reward_cap: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardAccount {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    /// Credited and not yet minted
+    pub pending: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct RewardPoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_cap: u64,
+}
+
+#[event]
+pub struct RewardsMinted {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_minted: u64,
+    /// Left in `pending` because the claim was clamped at the cap
+    pub still_pending: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Pool PDA is not the reward mint's mint authority")]
+    NotMintAuthority,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+    #[msg("Reward account belongs to a different pool")]
+    PoolMismatch,
+    #[msg("Pool has minted its full reward cap")]
+    RewardCapReached,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_mint_rewards.rs FAILS here:
+//
+// OUTSIDE ISSUER BLOCKED:
+// -----------------------
+// reward_mint.mint_authority = admin key
+//
+//   initialize_pool()
+//     mint_authority == Some(pool PDA)     ✗ NotMintAuthority
+//   No pool can be built over a token someone else can mint.
+//
+// The admin instead creates the mint (or calls set_authority) with the pool
+// PDA as mint_authority, which they can derive from the mint's address
+// before the pool exists. From then on only claim_rewards can mint.
+//
+// WHY CHECK AGAIN AT CLAIM:
+// -------------------------
+// The pool PDA can't sign a set_authority this program never issues, so the
+// authority cannot change underneath an initialized pool. The check at
+// claim costs one comparison and makes that an enforced invariant rather
+// than a property of every future version of this program.
+//
+// FREEZE AUTHORITY:
+// -----------------
+// Not checked here. A freeze authority held elsewhere can still freeze
+// holders' accounts; require freeze_authority == None (or the pool PDA)
+// at initialize if that matters for the token.
+//
+// SUPPLY CAP:
+// -----------
+// Owning the mint bounds WHO can mint; reward_cap bounds HOW MUCH.
+// reward_cap = 250, total_minted = 200, pending = 100
+//
+//   claim_rewards()
+//     headroom = 250 - 200 = 50
+//     amount   = min(100, 50) = 50         mint 50, pending = 50
+//     total_minted = 250                   ✓ <= reward_cap
+//   claim_rewards()
+//     headroom = 0                         ✗ RewardCapReached
+//
+// Clamping rather than rejecting means the last claimant still gets
+// what is left. The remainder stays recorded as pending instead of being
+// silently dropped, so an off-chain top-up can honour it.
@@ -7,6 +7,11 @@
 //! 2. Validate inputs before operations
 //! 3. Use larger intermediate types (u128) for complex calculations
 //! 4. Add explicit bounds checks as defense-in-depth
+//! 5. Make saturation an explicit per-vault choice (`OverflowMode`) that
+//!    never applies to the balance bounds checks
+//! 6. Gate pool admin actions on roles (`access_control`), not one authority
+//! 7. Reject vaults, pools and staking accounts not written at `CURRENT_VERSION`
+//!    until `migrate_vault` / `migrate_pool` / `migrate_staking` rewrites them
 //! 
 //! ## Best Practices
 //! - Always use checked arithmetic in financial code
@@ -16,14 +21,32 @@
 
 use anchor_lang::prelude::*;
 
-declare_id!("Secure3333333333333333333333333333333333333");
+pub mod access_control;
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use access_control::{AccessControl, Role, RoleGranted, RoleRevoked};
+use common_errors::{assert_authority, CommonError};
+use logic::{RewardAccrual, RewardOverflow, SolanaClock, TimeSource, SCALE, SECONDS_PER_YEAR};
+use logging::log_event;
 
-/// Scale factor for fixed-point arithmetic (6 decimals)
-const SCALE: u64 = 1_000_000;
+declare_id!("Secure3333333333333333333333333333333333333");
 
 /// Maximum allowed balance to prevent overflow in calculations
 const MAX_BALANCE: u64 = u64::MAX / SCALE;
 
+/// Seconds between `queue_admin_action` and the earliest
+/// `execute_admin_action` (1 day)
+pub const ADMIN_ACTION_DELAY: i64 = 24 * 60 * 60;
+
+/// Most seconds a single reward accrual pays for (4 years)
+pub const MAX_STAKE_PERIOD: i64 = 4 * SECONDS_PER_YEAR as i64;
+
+/// Layout version of `Vault`, `Pool` and `StakingAccount`; handlers reject
+/// accounts at any other version
+pub const CURRENT_VERSION: u8 = 1;
+
 #[program]
 pub mod secure_overflow {
     use super::*;
@@ -35,19 +58,24 @@ pub mod secure_overflow {
         vault.balance = 0;
         vault.total_deposited = 0;
         vault.total_withdrawn = 0;
+        vault.overflow_mode = OverflowMode::Revert;
+        vault.version = CURRENT_VERSION;
         
         emit!(VaultInitialized {
             vault: vault.key(),
             authority: vault.authority,
         });
         
+        log_event!("initialize", vault = vault.key(), authority = vault.authority);
         Ok(())
     }
 
     /// ✅ SECURE: Deposit with checked addition and bounds validation
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
         // ✅ Validate input
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &mut ctx.accounts.vault;
         
@@ -57,98 +85,101 @@ pub mod secure_overflow {
             ErrorCode::BalanceExceedsMaximum
         );
         
-        // ✅ SECURE: checked_add returns None on overflow
-        vault.balance = vault.balance
-            .checked_add(amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        vault.total_deposited = vault.total_deposited
-            .checked_add(amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // ✅ SECURE: Revert fails on overflow, Saturate caps and reports it
+        let mode = vault.overflow_mode;
+        let (balance, balance_capped) = mode.add(vault.balance, amount)?;
+        let (total_deposited, total_capped) = mode.add(vault.total_deposited, amount)?;
+        vault.balance = balance;
+        vault.total_deposited = total_deposited;
+        let saturated = balance_capped || total_capped;
         
         emit!(DepositMade {
             vault: vault.key(),
             depositor: ctx.accounts.depositor.key(),
             amount,
             new_balance: vault.balance,
+            saturated,
         });
         
-        msg!("Deposited {}. New balance: {}", amount, vault.balance);
+        log_event!("deposit", vault = vault.key(), amount = amount, balance = vault.balance, saturated = saturated);
         Ok(())
     }
 
     /// ✅ SECURE: Withdraw with explicit balance check and checked subtraction
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
         // ✅ Validate input
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &mut ctx.accounts.vault;
         
+        // ✅ Defense-in-depth: has_one already matched the signer
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
+        
         // ✅ Explicit balance check FIRST
         require!(
             vault.balance >= amount,
-            ErrorCode::InsufficientBalance
+            CommonError::InsufficientFunds
         );
         
-        // ✅ SECURE: checked_sub for defense in depth
-        vault.balance = vault.balance
-            .checked_sub(amount)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
-        
-        vault.total_withdrawn = vault.total_withdrawn
-            .checked_add(amount)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        // ✅ SECURE: Mode-aware subtraction for defense in depth
+        let mode = vault.overflow_mode;
+        let (balance, balance_capped) = mode.sub(vault.balance, amount)?;
+        let (total_withdrawn, total_capped) = mode.add(vault.total_withdrawn, amount)?;
+        vault.balance = balance;
+        vault.total_withdrawn = total_withdrawn;
+        let saturated = balance_capped || total_capped;
         
         emit!(WithdrawalMade {
             vault: vault.key(),
             authority: ctx.accounts.authority.key(),
             amount,
             remaining_balance: vault.balance,
+            saturated,
         });
         
-        msg!("Withdrew {}. Remaining balance: {}", amount, vault.balance);
+        log_event!("withdraw", vault = vault.key(), amount = amount, balance = vault.balance, saturated = saturated);
         Ok(())
     }
 
-    /// ✅ SECURE: Reward calculation with u128 intermediate and bounds checking
-    pub fn calculate_rewards(ctx: Context<CalculateRewards>) -> Result<()> {
-        let staking = &mut ctx.accounts.staking;
-        let clock = Clock::get()?;
-        
-        // ✅ Validate time hasn't gone backwards (clock manipulation protection)
-        require!(
-            clock.unix_timestamp >= staking.start_time,
-            ErrorCode::InvalidTimestamp
-        );
+    /// ✅ SECURE: Choose how `deposit`/`withdraw` arithmetic handles overflow
+    /// (vault authority only)
+    ///
+    /// The `MAX_BALANCE` and `InsufficientFunds` checks run in both modes,
+    /// so `Saturate` only ever caps the lifetime totals, never a balance.
+    pub fn set_overflow_mode(ctx: Context<SetOverflowMode>, overflow_mode: OverflowMode) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
         
-        let time_staked = (clock.unix_timestamp - staking.start_time) as u64;
-        
-        // ✅ SECURE: Use u128 for intermediate calculations
-        // This prevents overflow during multiplication
-        let rewards_u128 = (staking.amount as u128)
-            .checked_mul(staking.rate as u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_mul(time_staked as u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_div(SCALE as u128)  // Scale down
-            .ok_or(ErrorCode::ArithmeticOverflow)?
-            .checked_div(365 * 24 * 60 * 60)  // Annualize
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        // ✅ SECURE: Verify result fits in u64
-        require!(
-            rewards_u128 <= u64::MAX as u128,
-            ErrorCode::RewardsTooLarge
-        );
+        let vault = &mut ctx.accounts.vault;
+        let old = vault.overflow_mode;
+        vault.overflow_mode = overflow_mode;
         
-        let rewards = rewards_u128 as u64;
+        emit!(OverflowModeChanged {
+            vault: vault.key(),
+            old,
+            new: overflow_mode,
+        });
         
-        // ✅ Cap rewards at available pool balance
-        let capped_rewards = rewards.min(staking.pool_balance);
+        log_event!("set_overflow_mode", vault = vault.key(), old = format_args!("{:?}", old), new = format_args!("{:?}", overflow_mode));
+        Ok(())
+    }
+
+    /// ✅ SECURE: Reward calculation with u128 intermediate and bounds checking
+    ///
+    /// Thin wrapper: the math lives in `StakingAccount::accrue_rewards`,
+    /// driven here by the `Clock` sysvar.
+    pub fn calculate_rewards(ctx: Context<CalculateRewards>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        ctx.accounts.staking.check_version()?;
         
-        staking.pending_rewards = staking.pending_rewards
-            .checked_add(capped_rewards)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        let staking = &mut ctx.accounts.staking;
+        let RewardAccrual {
+            rewards,
+            capped_rewards,
+            time_staked,
+            ..
+        } = staking.accrue_rewards(&ctx.accounts.pool, &SolanaClock)?;
         
         emit!(RewardsCalculated {
             staking_account: staking.key(),
@@ -157,7 +188,179 @@ pub mod secure_overflow {
             time_staked,
         });
         
-        msg!("Calculated rewards: {} (capped from {})", capped_rewards, rewards);
+        log_event!("calculate_rewards", staking_account = staking.key(), rewards = capped_rewards, uncapped = rewards);
+        Ok(())
+    }
+
+    /// Dry-run the reward formula for `amount` staked `seconds` at `rate`
+    ///
+    /// Reads and writes no accounts. Runs the same `logic::reward_math` as
+    /// `calculate_rewards` and returns the rewards, or fails naming the step
+    /// that overflowed, so auditors can probe limits before committing.
+    pub fn simulate_rewards(
+        _ctx: Context<SimulateRewards>,
+        amount: u64,
+        rate: u64,
+        seconds: u64,
+    ) -> Result<u64> {
+        match logic::simulate_rewards(amount, rate, seconds) {
+            Ok(rewards) => {
+                log_event!("simulate_rewards", amount = amount, rate = rate, seconds = seconds, rewards = rewards);
+                Ok(rewards)
+            }
+            Err(step) => {
+                log_event!("simulate_rewards", amount = amount, rate = rate, seconds = seconds, overflow = format_args!("{:?}", step));
+                Err(match step {
+                    RewardOverflow::Multiply => ErrorCode::RewardMultiplyOverflow,
+                    RewardOverflow::Divide => ErrorCode::RewardDivideOverflow,
+                }
+                .into())
+            }
+        }
+    }
+
+    /// ✅ SECURE: Queue a reward rate change (`RateSetter` role only)
+    ///
+    /// Nothing changes until `execute_admin_action` runs at least
+    /// `ADMIN_ACTION_DELAY` later, so stakers see a rate cut coming.
+    /// Queuing again replaces the pending action and restarts the delay.
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        // ✅ Role check, not `pool.authority`: a pauser cannot touch rates
+        ctx.accounts
+            .access_control
+            .require_role(Role::RateSetter, &ctx.accounts.authority.key())?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let now = Clock::get()?.unix_timestamp;
+        pool.pending_action = Some(action);
+        pool.action_ready_at = now
+            .checked_add(ADMIN_ACTION_DELAY)
+            .ok_or(CommonError::Overflow)?;
+        
+        emit!(AdminActionQueued {
+            pool: pool.key(),
+            action,
+            ready_at: pool.action_ready_at,
+        });
+        
+        log_event!("queue_admin_action", pool = pool.key(), action = format_args!("{:?}", action), ready_at = pool.action_ready_at);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Apply the queued action once `ADMIN_ACTION_DELAY` has passed
+    ///
+    /// Anyone may submit it; the outcome was fixed by `queue_admin_action`.
+    /// For a rate change, accrual up to now is checkpointed into
+    /// `reward_index` at the OLD rate before the new rate takes effect.
+    /// Stakers that have not called `calculate_rewards` since are still
+    /// paid the old rate for that period.
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        let pool = &mut ctx.accounts.pool;
+        let action = pool.pending_action.ok_or(ErrorCode::NoQueuedAction)?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pool.action_ready_at, ErrorCode::ActionNotReady);
+        
+        pool.pending_action = None;
+        pool.action_ready_at = 0;
+        
+        match action {
+            AdminAction::SetRewardRate { new_rate } => {
+                pool.reward_index = pool.reward_index_at(now)?;
+                pool.rate_updated_at = now;
+                
+                let old_rate = pool.reward_rate;
+                pool.reward_rate = new_rate;
+                
+                emit!(RewardRateChanged {
+                    pool: pool.key(),
+                    old: old_rate,
+                    new: new_rate,
+                    reward_index: pool.reward_index,
+                });
+                
+                log_event!("execute_admin_action", pool = pool.key(), action = "set_reward_rate", old_rate = old_rate, new_rate = new_rate);
+            }
+        }
+        Ok(())
+    }
+
+    /// ✅ SECURE: Pause or unpause swaps (`Pauser` role only)
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
+        ctx.accounts
+            .access_control
+            .require_role(Role::Pauser, &ctx.accounts.authority.key())?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.paused = paused;
+        
+        emit!(PoolPauseChanged {
+            pool: pool.key(),
+            paused,
+            by: ctx.accounts.authority.key(),
+        });
+        
+        log_event!("set_paused", pool = pool.key(), paused = paused);
+        Ok(())
+    }
+
+    /// Create the pool's `AccessControl` with the pool authority as the
+    /// first `Admin`
+    pub fn initialize_access_control(ctx: Context<InitializeAccessControl>) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.scope = ctx.accounts.pool.key();
+        access_control.grants = Vec::new();
+        access_control.bump = ctx.bumps.access_control;
+        access_control.grant(Role::Admin, ctx.accounts.authority.key())?;
+        
+        emit!(RoleGranted {
+            scope: access_control.scope,
+            role: Role::Admin,
+            key: ctx.accounts.authority.key(),
+            admin: ctx.accounts.authority.key(),
+        });
+        
+        log_event!("initialize_access_control", pool = access_control.scope, admin = ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// ✅ SECURE: Grant `role` to `key` (`Admin` only)
+    pub fn grant_role(ctx: Context<UpdateRoles>, role: Role, key: Pubkey) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.require_role(Role::Admin, &ctx.accounts.admin.key())?;
+        access_control.grant(role, key)?;
+        
+        emit!(RoleGranted {
+            scope: access_control.scope,
+            role,
+            key,
+            admin: ctx.accounts.admin.key(),
+        });
+        
+        log_event!("grant_role", pool = access_control.scope, role = format_args!("{:?}", role), key = key);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Revoke `role` from `key` (`Admin` only, never the last admin)
+    pub fn revoke_role(ctx: Context<UpdateRoles>, role: Role, key: Pubkey) -> Result<()> {
+        let access_control = &mut ctx.accounts.access_control;
+        access_control.require_role(Role::Admin, &ctx.accounts.admin.key())?;
+        access_control.revoke(role, key)?;
+        
+        emit!(RoleRevoked {
+            scope: access_control.scope,
+            role,
+            key,
+            admin: ctx.accounts.admin.key(),
+        });
+        
+        log_event!("revoke_role", pool = access_control.scope, role = format_args!("{:?}", role), key = key);
         Ok(())
     }
 
@@ -167,40 +370,27 @@ pub mod secure_overflow {
         amount_in: u64,
         min_amount_out: u64,  // Slippage protection
     ) -> Result<()> {
+        ctx.accounts.pool.check_version()?;
+        
         // ✅ Validate inputs
-        require!(amount_in > 0, ErrorCode::InvalidAmount);
+        require!(amount_in > 0, CommonError::InvalidAmount);
         require!(min_amount_out > 0, ErrorCode::InvalidMinOutput);
         
         let pool = &mut ctx.accounts.pool;
+        require!(!pool.paused, ErrorCode::PoolPaused);
         
-        // ✅ SECURE: Use u128 for price calculation to prevent overflow
-        // Formula: amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
-        // This is the constant product formula (x * y = k)
-        
-        let numerator = (amount_in as u128)
-            .checked_mul(pool.reserve_out as u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        let denominator = (pool.reserve_in as u128)
-            .checked_add(amount_in as u128)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        let amount_out_u128 = numerator
-            .checked_div(denominator)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-        
-        // ✅ Verify fits in u64
-        require!(
-            amount_out_u128 <= u64::MAX as u128,
-            ErrorCode::OutputTooLarge
-        );
-        
-        let amount_out = amount_out_u128 as u64;
+        // ✅ SECURE: Constant product formula (x * y = k) with u128 intermediate
+        // amount_out = (amount_in * reserve_out) / (reserve_in + amount_in)
+        let amount_out = logic::swap_output(
+            amount_in,
+            pool.reserve_in,
+            pool.reserve_out,
+        )?;
         
         // ✅ Slippage protection
         require!(
             amount_out >= min_amount_out,
-            ErrorCode::SlippageExceeded
+            CommonError::SlippageExceeded
         );
         
         // ✅ Verify pool has sufficient output reserves
@@ -212,11 +402,11 @@ pub mod secure_overflow {
         // ✅ Update reserves with checked arithmetic
         pool.reserve_in = pool.reserve_in
             .checked_add(amount_in)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+            .ok_or(CommonError::Overflow)?;
         
         pool.reserve_out = pool.reserve_out
             .checked_sub(amount_out)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+            .ok_or(CommonError::Underflow)?;
         
         emit!(SwapExecuted {
             pool: pool.key(),
@@ -225,11 +415,88 @@ pub mod secure_overflow {
             amount_out,
         });
         
-        msg!("Swapped {} for {}", amount_in, amount_out);
+        log_event!("swap", pool = pool.key(), amount_in = amount_in, amount_out = amount_out);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a vault left at the previous layout version
+    ///
+    /// The only way out of `VersionMismatch` for a vault (vault authority
+    /// only). The layout has not changed since versioning began, so only
+    /// the version is rewritten; a future `CURRENT_VERSION` bump converts
+    /// the old fields here.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let from_version = vault.version;
+        check_migratable(from_version)?;
+        
+        vault.version = CURRENT_VERSION;
+        
+        emit!(AccountMigrated {
+            account: vault.key(),
+            from_version,
+            to_version: vault.version,
+        });
+        
+        log_event!("migrate_vault", vault = vault.key(), from_version = from_version, to_version = vault.version);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a pool left at the previous layout version
+    ///
+    /// Same rules as `migrate_vault`, signed by `pool.authority`.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let from_version = pool.version;
+        check_migratable(from_version)?;
+        
+        pool.version = CURRENT_VERSION;
+        
+        emit!(AccountMigrated {
+            account: pool.key(),
+            from_version,
+            to_version: pool.version,
+        });
+        
+        log_event!("migrate_pool", pool = pool.key(), from_version = from_version, to_version = pool.version);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a staking account left at the previous layout version
+    ///
+    /// Same rules as `migrate_vault`, signed by `staking.owner`.
+    pub fn migrate_staking(ctx: Context<MigrateStaking>) -> Result<()> {
+        let staking = &mut ctx.accounts.staking;
+        let from_version = staking.version;
+        check_migratable(from_version)?;
+        
+        staking.version = CURRENT_VERSION;
+        
+        emit!(AccountMigrated {
+            account: staking.key(),
+            from_version,
+            to_version: staking.version,
+        });
+        
+        log_event!("migrate_staking", staking = staking.key(), from_version = from_version, to_version = staking.version);
         Ok(())
     }
 }
 
+/// ✅ Only an account one version behind can be migrated
+///
+/// A current account fails with `AlreadyMigrated`. Anything older, or
+/// written by a newer deployment, fails with `VersionMismatch`: there is no
+/// conversion for it here.
+fn check_migratable(version: u8) -> Result<()> {
+    require!(version != CURRENT_VERSION, ErrorCode::AlreadyMigrated);
+    require!(
+        version.checked_add(1) == Some(CURRENT_VERSION),
+        ErrorCode::VersionMismatch
+    );
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
@@ -256,7 +523,17 @@ pub struct Deposit<'info> {
 pub struct Withdraw<'info> {
     #[account(
         mut,
-        has_one = authority @ ErrorCode::Unauthorized
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOverflowMode<'info> {
+    #[account(
+        mut,
+        has_one = authority @ CommonError::Unauthorized
     )]
     pub vault: Account<'info, Vault>,
     pub authority: Signer<'info>,
@@ -266,12 +543,82 @@ pub struct Withdraw<'info> {
 pub struct CalculateRewards<'info> {
     #[account(
         mut,
-        has_one = owner @ ErrorCode::Unauthorized
+        has_one = owner @ CommonError::Unauthorized,
+        has_one = pool @ ErrorCode::PoolMismatch
     )]
     pub staking: Account<'info, StakingAccount>,
+    pub pool: Account<'info, Pool>,
     pub owner: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SimulateRewards {}
+
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    
+    // ✅ Seeds tie the role list to this pool; the handler checks the role
+    #[account(
+        seeds = [b"access_control", pool.key().as_ref()],
+        bump = access_control.bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(
+        seeds = [b"access_control", pool.key().as_ref()],
+        bump = access_control.bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAccessControl<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AccessControl::INIT_SPACE,
+        seeds = [b"access_control", pool.key().as_ref()],
+        bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    
+    // ✅ Only the pool authority can create its role list, and only once
+    #[account(has_one = authority @ CommonError::Unauthorized)]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    #[account(
+        mut,
+        seeds = [b"access_control", access_control.scope.as_ref()],
+        bump = access_control.bump
+    )]
+    pub access_control: Account<'info, AccessControl>,
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct Swap<'info> {
     #[account(mut)]
@@ -279,6 +626,36 @@ pub struct Swap<'info> {
     pub user: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(
+        mut,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(
+        mut,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: Account<'info, Pool>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateStaking<'info> {
+    #[account(
+        mut,
+        has_one = owner @ CommonError::Unauthorized
+    )]
+    pub staking: Account<'info, StakingAccount>,
+    pub owner: Signer<'info>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
@@ -286,17 +663,127 @@ pub struct Vault {
     pub balance: u64,
     pub total_deposited: u64,
     pub total_withdrawn: u64,
+    /// How `deposit`/`withdraw` arithmetic handles overflow
+    pub overflow_mode: OverflowMode,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl Vault {
+    /// ✅ Reject vaults written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+}
+
+/// What vault arithmetic does when a result does not fit in a `u64`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum OverflowMode {
+    /// `checked_*`: fail the instruction with `Overflow`/`Underflow`
+    Revert,
+    /// `saturating_*`: clamp to `u64::MAX`/`0` and carry on
+    Saturate,
+}
+
+impl OverflowMode {
+    /// `a + b` under this mode, and whether the result was capped
+    pub fn add(self, a: u64, b: u64) -> Result<(u64, bool)> {
+        match self {
+            OverflowMode::Revert => Ok((a.checked_add(b).ok_or(CommonError::Overflow)?, false)),
+            OverflowMode::Saturate => Ok((a.saturating_add(b), a.checked_add(b).is_none())),
+        }
+    }
+
+    /// `a - b` under this mode, and whether the result was capped
+    pub fn sub(self, a: u64, b: u64) -> Result<(u64, bool)> {
+        match self {
+            OverflowMode::Revert => Ok((a.checked_sub(b).ok_or(CommonError::Underflow)?, false)),
+            OverflowMode::Saturate => Ok((a.saturating_sub(b), a.checked_sub(b).is_none())),
+        }
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct StakingAccount {
     pub owner: Pubkey,
+    /// Pool whose reward rate this stake earns
+    pub pool: Pubkey,
     pub amount: u64,
-    pub rate: u64,
     pub start_time: i64,
     pub pending_rewards: u64,
     pub pool_balance: u64,
+    /// Timestamp of the last reward accrual
+    pub last_accrual_time: i64,
+    /// Division remainder carried into the next accrual (precision-loss guard)
+    pub accumulated_remainder: u128,
+    /// `pool.reward_index` at the last accrual
+    pub reward_index: u128,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl StakingAccount {
+    /// ✅ Reject staking accounts written by another program version until
+    /// migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Accrue rewards from `pool` up to `time.now()` into `pending_rewards`
+    /// and move the checkpoint forward
+    pub fn accrue_rewards(&mut self, pool: &Pool, time: &impl TimeSource) -> Result<RewardAccrual> {
+        let now = time.now()?;
+        
+        // ✅ Validate time hasn't gone backwards (clock manipulation protection).
+        // Accrued rewards never go stale, so only that half of the check applies.
+        logic::check_fresh(self.start_time, i64::MAX, now)?;
+        
+        // ✅ A stake reaching back to the Unix epoch was never started or
+        // was zeroed; paying for 50+ years of it would hit the cap every time
+        require!(self.start_time > 0, ErrorCode::ImplausibleDuration);
+        
+        // ✅ Accrue only since the last checkpoint so repeated calls don't double count
+        let accrual_start = self.last_accrual_time.max(self.start_time);
+        
+        // ✅ SECURE: Rate changes are folded into the pool's reward index,
+        // so each second is paid at the rate in effect during that second
+        let reward_index = pool.reward_index_at(now)?;
+        let mut index_delta = reward_index
+            .checked_sub(self.reward_index)
+            .ok_or(CommonError::Underflow)?;
+        
+        // ✅ Clamp the period to MAX_STAKE_PERIOD, paid at its average rate.
+        // Time beyond the clamp is forfeited, not carried forward.
+        let accrual_end = now.min(accrual_start.saturating_add(MAX_STAKE_PERIOD));
+        if accrual_end < now {
+            index_delta = index_delta
+                .checked_mul(MAX_STAKE_PERIOD as u128)
+                .ok_or(CommonError::Overflow)?
+                / (now - accrual_start) as u128;
+        }
+        
+        let accrual = logic::rewards_for_index(
+            self.amount,
+            index_delta,
+            accrual_start,
+            accrual_end,
+            self.accumulated_remainder,
+            self.pool_balance,
+        )?;
+        
+        self.accumulated_remainder = accrual.remainder;
+        self.last_accrual_time = now;
+        self.reward_index = reward_index;
+        
+        self.pending_rewards = self.pending_rewards
+            .checked_add(accrual.capped_rewards)
+            .ok_or(CommonError::Overflow)?;
+        
+        Ok(accrual)
+    }
 }
 
 #[account]
@@ -305,6 +792,55 @@ pub struct Pool {
     pub authority: Pubkey,
     pub reserve_in: u64,
     pub reserve_out: u64,
+    /// Annual reward rate, scaled by `SCALE`
+    pub reward_rate: u64,
+    /// When `reward_rate` last changed
+    pub rate_updated_at: i64,
+    /// Σ rate × seconds for every rate period before `rate_updated_at`
+    pub reward_index: u128,
+    /// Set by a `Pauser`; `swap` fails while true
+    pub paused: bool,
+    /// Setting change waiting for `action_ready_at`
+    pub pending_action: Option<AdminAction>,
+    /// Earliest time `execute_admin_action` may apply `pending_action`
+    pub action_ready_at: i64,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+/// Pool setting changes that wait `ADMIN_ACTION_DELAY` between
+/// `queue_admin_action` and `execute_admin_action`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    /// Annual reward rate, scaled by `SCALE`
+    SetRewardRate { new_rate: u64 },
+}
+
+impl Pool {
+    /// ✅ Reject pools written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Cumulative reward index at `now`: the checkpoint plus the
+    /// current rate since it was set
+    pub fn reward_index_at(&self, now: i64) -> Result<u128> {
+        let elapsed = now
+            .checked_sub(self.rate_updated_at)
+            .filter(|d| *d >= 0)
+            .ok_or(ErrorCode::InvalidTimestamp)?;
+        
+        let accrued = (self.reward_rate as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(CommonError::Overflow)?;
+        
+        let index = self.reward_index
+            .checked_add(accrued)
+            .ok_or(CommonError::Overflow)?;
+        
+        Ok(index)
+    }
 }
 
 #[event]
@@ -319,6 +855,8 @@ pub struct DepositMade {
     pub depositor: Pubkey,
     pub amount: u64,
     pub new_balance: u64,
+    /// A total was capped at `u64::MAX` instead of failing
+    pub saturated: bool,
 }
 
 #[event]
@@ -327,6 +865,15 @@ pub struct WithdrawalMade {
     pub authority: Pubkey,
     pub amount: u64,
     pub remaining_balance: u64,
+    /// A total was capped at `u64::MAX` instead of failing
+    pub saturated: bool,
+}
+
+#[event]
+pub struct OverflowModeChanged {
+    pub vault: Pubkey,
+    pub old: OverflowMode,
+    pub new: OverflowMode,
 }
 
 #[event]
@@ -337,6 +884,28 @@ pub struct RewardsCalculated {
     pub time_staked: u64,
 }
 
+#[event]
+pub struct AdminActionQueued {
+    pub pool: Pubkey,
+    pub action: AdminAction,
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct RewardRateChanged {
+    pub pool: Pubkey,
+    pub old: u64,
+    pub new: u64,
+    pub reward_index: u128,
+}
+
+#[event]
+pub struct PoolPauseChanged {
+    pub pool: Pubkey,
+    pub paused: bool,
+    pub by: Pubkey,
+}
+
 #[event]
 pub struct SwapExecuted {
     pub pool: Pubkey,
@@ -345,16 +914,16 @@ pub struct SwapExecuted {
     pub amount_out: u64,
 }
 
+#[event]
+pub struct AccountMigrated {
+    /// The vault, pool or staking account rewritten
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Arithmetic overflow occurred")]
-    ArithmeticOverflow,
-    #[msg("Arithmetic underflow occurred")]
-    ArithmeticUnderflow,
-    #[msg("Insufficient balance for operation")]
-    InsufficientBalance,
-    #[msg("Invalid amount - must be greater than zero")]
-    InvalidAmount,
     #[msg("Invalid minimum output amount")]
     InvalidMinOutput,
     #[msg("Balance would exceed maximum allowed")]
@@ -365,12 +934,26 @@ pub enum ErrorCode {
     RewardsTooLarge,
     #[msg("Output amount exceeds maximum")]
     OutputTooLarge,
-    #[msg("Slippage tolerance exceeded")]
-    SlippageExceeded,
     #[msg("Insufficient liquidity in pool")]
     InsufficientLiquidity,
-    #[msg("Unauthorized")]
-    Unauthorized,
+    #[msg("Staking account belongs to a different pool")]
+    PoolMismatch,
+    #[msg("Reward math overflowed at amount * rate * seconds")]
+    RewardMultiplyOverflow,
+    #[msg("Reward math result does not fit in u64 after scaling down")]
+    RewardDivideOverflow,
+    #[msg("Pool is paused")]
+    PoolPaused,
+    #[msg("Queued admin action delay has not passed yet")]
+    ActionNotReady,
+    #[msg("No admin action is queued")]
+    NoQueuedAction,
+    #[msg("Staking period is implausibly long")]
+    ImplausibleDuration,
+    #[msg("Account was written by a different program version")]
+    VersionMismatch,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
 }
 
 // ============================================================================
@@ -384,14 +967,14 @@ pub enum ErrorCode {
 // Attacker tries: withdraw(200) when balance = 100
 // 1. Explicit check: require!(vault.balance >= amount) → FAILS
 // 2. Even if bypassed: checked_sub(200) → returns None → Error
-// Transaction fails with InsufficientBalance or ArithmeticUnderflow
+// Transaction fails with InsufficientFunds or Underflow
 //
 // OVERFLOW ATTACK BLOCKED:
 // ------------------------
 // Attacker tries: deposit(100) when balance = u64::MAX - 50
 // 1. Bounds check: balance <= MAX_BALANCE - amount → FAILS
 // 2. Even if bypassed: checked_add(100) → returns None → Error
-// Transaction fails with BalanceExceedsMaximum or ArithmeticOverflow
+// Transaction fails with BalanceExceedsMaximum or Overflow
 //
 // MULTIPLICATION OVERFLOW BLOCKED:
 // --------------------------------
@@ -401,3 +984,78 @@ pub enum ErrorCode {
 // 3. Final result verified to fit in u64
 // 4. Rewards capped at pool balance
 // Transaction either succeeds with correct value or fails safely
+//
+// PRECISION LOSS BLOCKED:
+// -----------------------
+// Naive accrual: reward = amount * rate * dt / SCALE / SECONDS_PER_YEAR
+// Called every second with a small stake, each call truncates to 0.
+// 1. Numerator and remainder are combined: amount * rate * dt + remainder
+// 2. Single division by SCALE * SECONDS_PER_YEAR
+// 3. New remainder stored in accumulated_remainder
+// 1000 one-second accruals now sum to exactly one 1000-second accrual
+//
+// MID-ACCRUAL RATE CHANGE HANDLED:
+// --------------------------------
+// Naive: rewards = amount * CURRENT rate * (now - last_accrual)
+// Raising the rate pays the new rate retroactively for the whole period.
+// 1. Executing a queued rate change first checkpoints
+//    reward_index += old_rate * dt
+// 2. Stakers accrue amount * (reward_index_now - their checkpoint)
+// 3. Each second is paid at the rate that was in effect, however many
+//    times the rate changed between a staker's accruals
+//
+// OVERFLOW DIAGNOSTICS (simulate_rewards):
+// ----------------------------------------
+// amount * rate * seconds / (SCALE * SECONDS_PER_YEAR) can fail in two places:
+// 1. The u128 product: u64::MAX * u64::MAX * 2        → RewardMultiplyOverflow
+// 2. The u64 result:   u64::MAX * u64::MAX * 1 / D    → RewardDivideOverflow
+// simulate_rewards runs the same reward_math as every accrual, so its
+// verdict is what an accrual over the same rate * seconds would hit.
+//
+// CHECKED VS SATURATING (OverflowMode):
+// -------------------------------------
+// deposit(100) with total_deposited = u64::MAX - 50:
+//   Revert:   checked_add → None → Overflow, nothing is written
+//   Saturate: saturating_add → u64::MAX, saturated = true in DepositMade
+// Saturating is acceptable for a statistic like total_deposited, where a
+// pinned value is merely wrong. It is never acceptable for a balance:
+// crediting less than was deposited silently loses user funds. So the
+// MAX_BALANCE and InsufficientFunds checks run first in both modes, and
+// in practice only the lifetime totals can saturate.
+//
+// ROLE-BASED ADMIN (access_control):
+// ----------------------------------
+// A single pool.authority key can pause, change rates and everything else,
+// so the key kept hot enough to pause quickly in an incident is also the
+// key that can set reward_rate = u64::MAX.
+// 1. set_paused requires Pauser, queuing a rate change requires
+//    RateSetter, checked against the AccessControl PDA at
+//    ["access_control", pool]
+// 2. The seeds bind the role list to the pool, so a list the attacker
+//    created for another pool fails ConstraintSeeds
+// 3. Only an Admin grants or revokes roles, and the last Admin cannot be
+//    revoked
+// A leaked Pauser key can stop swaps, which an Admin undoes by revoking
+// it. It cannot touch rates: queue_admin_action fails with MissingRole.
+//
+// TIMELOCKED RATE CHANGES:
+// ------------------------
+// A RateSetter that can cut reward_rate to 0 instantly can do it just
+// after a wave of long stakes lands.
+// 1. queue_admin_action(SetRewardRate) records the rate and
+//    action_ready_at = now + ADMIN_ACTION_DELAY, and emits it
+// 2. execute_admin_action fails with ActionNotReady before then, and
+//    NoQueuedAction when nothing is queued
+// 3. The rate only changes at execution, so every second before it is
+//    still paid at the old rate
+//
+// IMPLAUSIBLE STAKE DURATIONS:
+// ----------------------------
+// start_time = 0 (never set, or zeroed) makes now - start_time over 50
+// years; with any real rate the reward hits the pool balance cap on
+// every call.
+// 1. start_time <= 0 fails with ImplausibleDuration before any math
+// 2. One accrual covers at most MAX_STAKE_PERIOD seconds, paid at the
+//    period's average rate; the rest is forfeited
+// A stake that really sat untouched for 4+ years loses the excess, which
+// is the price of a hard ceiling on what one call can pay out.
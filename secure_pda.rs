@@ -7,6 +7,9 @@
 //! 2. Verify PDA derivation in all instructions
 //! 3. Store and validate bump seeds
 //! 4. Use has_one for authority checks
+//! 5. Keep who pays rent separate from who owns the vault
+//! 6. Reject vaults not written at `CURRENT_VERSION` until `migrate_vault`
+//!    rewrites them
 //! 
 //! ## Why This Works
 //! - Each user gets their own unique PDA even with same name
@@ -14,13 +17,42 @@
 //! - Attackers cannot create colliding accounts
 
 use anchor_lang::prelude::*;
+#[cfg(feature = "bench")]
+use anchor_lang::solana_program::log::sol_log_compute_units;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::{assert_authority, CommonError};
+use logging::log_event;
 
 declare_id!("Secure4444444444444444444444444444444444444");
 
+/// Maximum number of vault names tracked per authority
+const MAX_REGISTRY_ENTRIES: usize = 20;
+
+/// Layout version of `Vault` written by `create_vault`; mutating handlers
+/// reject vaults at any other version
+pub const CURRENT_VERSION: u8 = 1;
+
 #[program]
 pub mod secure_pda {
     use super::*;
 
+    /// ✅ SECURE: Create the per-authority vault registry
+    /// 
+    /// Seeds are ["registry", authority], so each user has exactly one registry
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.authority = ctx.accounts.authority.key();
+        registry.names = Vec::new();
+        registry.bump = ctx.bumps.registry;
+        
+        log_event!("initialize_registry", registry = registry.key(), authority = registry.authority);
+        Ok(())
+    }
+
     /// ✅ SECURE: Create vault with user-bound PDA
     /// 
     /// Seeds include authority pubkey, so each user gets unique PDA
@@ -29,78 +61,177 @@ pub mod secure_pda {
         ctx: Context<CreateVault>,
         vault_name: String,
     ) -> Result<()> {
-        // Validate name length
+        let authority = ctx.accounts.authority.key();
+        open_vault(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.registry,
+            authority,
+            ctx.bumps.vault,
+            vault_name,
+        )?;
+        
+        log_event!("create_vault", vault = ctx.accounts.vault.key(), name = ctx.accounts.vault.name, authority = authority);
+        Ok(())
+    }
+
+    /// ✅ SECURE: `create_vault` with rent paid by a separate `payer`
+    /// 
+    /// A sponsor (relayer, onboarding service) funds the account; the
+    /// `authority` signs only to consent and is the one recorded as owner.
+    /// The payer appears in no seed and no field, so paying for a vault
+    /// never makes anyone its owner.
+    pub fn create_sponsored_vault(
+        ctx: Context<CreateSponsoredVault>,
+        vault_name: String,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        open_vault(
+            &mut ctx.accounts.vault,
+            &mut ctx.accounts.registry,
+            authority,
+            ctx.bumps.vault,
+            vault_name,
+        )?;
+        
+        log_event!(
+            "create_sponsored_vault",
+            vault = ctx.accounts.vault.key(),
+            name = ctx.accounts.vault.name,
+            authority = authority,
+            payer = ctx.accounts.payer.key()
+        );
+        Ok(())
+    }
+
+    /// ✅ SECURE: Create a sub-vault nested under an existing parent vault
+    /// 
+    /// Seeds are ["subvault", parent_vault, sub_name]. The parent must be a
+    /// valid vault PDA owned by the same authority.
+    pub fn create_subvault(
+        ctx: Context<CreateSubvault>,
+        parent_name: String,
+        sub_name: String,
+    ) -> Result<()> {
         require!(
-            vault_name.len() > 0 && vault_name.len() <= 32,
+            sub_name.len() > 0 && sub_name.len() <= 32,
             ErrorCode::InvalidVaultName
         );
         
-        let vault = &mut ctx.accounts.vault;
-        vault.authority = ctx.accounts.authority.key();
-        vault.balance = 0;
-        vault.name = vault_name.clone();
-        vault.bump = ctx.bumps.vault;  // ✅ Store bump for efficient re-derivation
-        vault.created_at = Clock::get()?.unix_timestamp;
+        let parent = &ctx.accounts.parent_vault;
         
-        emit!(VaultCreated {
-            vault: vault.key(),
-            authority: vault.authority,
-            name: vault_name,
+        // ✅ Defense-in-depth: parent verified by seeds + has_one,
+        // re-check the relationship explicitly
+        require_keys_eq!(
+            parent.authority,
+            ctx.accounts.authority.key(),
+            ErrorCode::ParentMismatch
+        );
+        require!(parent.name == parent_name, ErrorCode::ParentMismatch);
+        
+        let subvault = &mut ctx.accounts.subvault;
+        subvault.authority = ctx.accounts.authority.key();
+        subvault.parent = parent.key();
+        subvault.balance = 0;
+        subvault.name = sub_name.clone();
+        subvault.bump = ctx.bumps.subvault;
+        subvault.created_at = Clock::get()?.unix_timestamp;
+        subvault.sequence = 0;
+        
+        emit!(SubvaultCreated {
+            subvault: subvault.key(),
+            parent: subvault.parent,
+            authority: subvault.authority,
+            name: sub_name,
+            sequence: subvault.sequence,
         });
         
-        msg!("Created vault '{}' for user {}", vault.name, vault.authority);
+        log_event!("create_subvault", name = subvault.name, parent = parent_name);
         Ok(())
     }
 
     /// ✅ SECURE: Withdraw with full PDA verification
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &mut ctx.accounts.vault;
         
+        // ✅ Defense-in-depth: seeds + has_one already tie the vault to the signer
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
+        
         require!(
             vault.balance >= amount,
-            ErrorCode::InsufficientFunds
+            CommonError::InsufficientFunds
         );
         
         vault.balance = vault.balance
             .checked_sub(amount)
-            .ok_or(ErrorCode::Underflow)?;
+            .ok_or(CommonError::Underflow)?;
+        
+        let sequence = vault.next_sequence()?;
         
         emit!(WithdrawalMade {
             vault: vault.key(),
             authority: ctx.accounts.authority.key(),
             amount,
             remaining_balance: vault.balance,
+            sequence,
         });
         
-        msg!("Withdrew {} from vault '{}'. Remaining: {}", 
-            amount, vault.name, vault.balance);
+        log_event!("withdraw", vault = vault.key(), name = vault.name, amount = amount, balance = vault.balance);
         Ok(())
     }
 
-    /// ✅ SECURE: Deposit with PDA verification
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+    /// ✅ SECURE: Withdraw the entire vault balance with full PDA verification
+    pub fn withdraw_all(ctx: Context<Withdraw>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
         
         let vault = &mut ctx.accounts.vault;
+        let amount = vault.balance;
         
-        vault.balance = vault.balance
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+        require!(amount > 0, CommonError::InsufficientFunds);
+        
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
+        
+        vault.balance = 0;
         
-        emit!(DepositMade {
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
             vault: vault.key(),
-            depositor: ctx.accounts.depositor.key(),
+            authority: ctx.accounts.authority.key(),
             amount,
-            new_balance: vault.balance,
+            remaining_balance: vault.balance,
+            sequence,
         });
         
-        msg!("Deposited {} to vault '{}'. New balance: {}", 
-            amount, vault.name, vault.balance);
+        log_event!("withdraw_all", vault = vault.key(), name = vault.name, amount = amount);
         Ok(())
     }
 
+    /// ✅ SECURE: Deposit with PDA verification
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        // Bench: everything before this line is account validation
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        
+        record_deposit(&mut ctx.accounts.vault, ctx.accounts.depositor.key(), amount)
+    }
+
+    /// ✅ SECURE (but slower): `deposit` with the bump recomputed
+    /// 
+    /// Identical to `deposit` except the accounts struct uses a bare `bump`,
+    /// so Anchor runs `find_program_address` on every call instead of one
+    /// `create_program_address` with `vault.bump`. Kept only as the baseline
+    /// for `tests/bump_cost.rs`.
+    pub fn deposit_recomputed_bump(ctx: Context<DepositRecomputedBump>, amount: u64) -> Result<()> {
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        
+        record_deposit(&mut ctx.accounts.vault, ctx.accounts.depositor.key(), amount)
+    }
+
     /// ✅ SECURE: Transfer using PDA as signer
     /// 
     /// Demonstrates how to use stored bump for CPI signing
@@ -108,14 +239,16 @@ pub mod secure_pda {
         ctx: Context<TransferFromVault>,
         amount: u64,
     ) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &ctx.accounts.vault;
         let authority_key = ctx.accounts.authority.key();
         
         require!(
             vault.balance >= amount,
-            ErrorCode::InsufficientFunds
+            CommonError::InsufficientFunds
         );
         
         // ✅ SECURE: Reconstruct seeds for PDA signing
@@ -135,13 +268,17 @@ pub mod secure_pda {
         // );
         // token::transfer(cpi_ctx, amount)?;
         
-        msg!("Transfer {} from vault PDA authorized", amount);
+        log_event!("transfer_from_vault", amount = amount);
         Ok(())
     }
 
     /// ✅ SECURE: Close vault and reclaim rent
     pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
-        let vault = &ctx.accounts.vault;
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
         
         // Ensure vault is empty before closing
         require!(
@@ -149,16 +286,198 @@ pub mod secure_pda {
             ErrorCode::VaultNotEmpty
         );
         
+        ctx.accounts.registry.names.retain(|name| name != &vault.name);
+        
+        let sequence = vault.next_sequence()?;
+        
         emit!(VaultClosed {
             vault: vault.key(),
             authority: ctx.accounts.authority.key(),
+            sequence,
+        });
+        
+        log_event!("close_vault", vault = vault.key(), name = vault.name);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Sweep remaining tokens to the authority, then close
+    /// 
+    /// Transfer and close happen in one instruction, so either both succeed
+    /// or the whole transaction reverts and the vault stays open and funded.
+    pub fn drain_and_close(ctx: Context<DrainAndClose>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        let authority_key = ctx.accounts.authority.key();
+        let remaining = ctx.accounts.vault_tokens.amount;
+        
+        assert_authority(&vault.authority, &authority_key)?;
+        
+        if remaining > 0 {
+            // ✅ SECURE: Vault PDA signs with stored bump
+            let seeds = &[
+                b"vault".as_ref(),
+                authority_key.as_ref(),
+                vault.name.as_bytes(),
+                &[vault.bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+            
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault_tokens.to_account_info(),
+                to: ctx.accounts.authority_tokens.to_account_info(),
+                authority: vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, remaining)
+                .map_err(|_| ErrorCode::SweepFailed)?;
+        }
+        
+        vault.balance = 0;
+        ctx.accounts.registry.names.retain(|name| name != &vault.name);
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(VaultClosed {
+            vault: vault.key(),
+            authority: authority_key,
+            sequence,
+        });
+        
+        log_event!("drain_and_close", vault = vault.key(), name = vault.name, swept = remaining);
+        // Account closed (rent returned) by the `close = authority` constraint
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a vault left at the previous layout version
+    ///
+    /// The only way out of `VersionMismatch` (authority only). The layout
+    /// has not changed since versioning began, so only the version is
+    /// rewritten; a future `CURRENT_VERSION` bump converts the old fields
+    /// here.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let from_version = vault.version;
+        check_migratable(from_version)?;
+        
+        vault.version = CURRENT_VERSION;
+        let sequence = vault.next_sequence()?;
+        
+        emit!(AccountMigrated {
+            account: vault.key(),
+            from_version,
+            to_version: vault.version,
+            sequence,
         });
         
-        msg!("Closed vault '{}'", vault.name);
+        log_event!("migrate_vault", vault = vault.key(), from_version = from_version, to_version = vault.version);
         Ok(())
     }
 }
 
+/// Shared body of `create_vault` and `create_sponsored_vault`
+fn open_vault(
+    vault: &mut Account<Vault>,
+    registry: &mut Account<VaultRegistry>,
+    authority: Pubkey,
+    bump: u8,
+    vault_name: String,
+) -> Result<()> {
+    // Validate name length
+    require!(
+        vault_name.len() > 0 && vault_name.len() <= 32,
+        ErrorCode::InvalidVaultName
+    );
+    
+    // ✅ Track the vault in the registry so it can be enumerated
+    require!(
+        !registry.names.contains(&vault_name),
+        ErrorCode::DuplicateVaultName
+    );
+    require!(
+        registry.names.len() < MAX_REGISTRY_ENTRIES,
+        ErrorCode::RegistryFull
+    );
+    registry.names.push(vault_name.clone());
+    
+    vault.authority = authority;
+    vault.balance = 0;
+    vault.name = vault_name.clone();
+    vault.bump = bump;  // ✅ Store bump for efficient re-derivation
+    vault.created_at = Clock::get()?.unix_timestamp;
+    vault.sequence = 0;
+    vault.version = CURRENT_VERSION;
+    
+    emit!(VaultCreated {
+        vault: vault.key(),
+        authority: vault.authority,
+        name: vault_name,
+        sequence: vault.sequence,
+    });
+    
+    Ok(())
+}
+
+/// Shared body of `deposit` and `deposit_recomputed_bump`
+fn record_deposit(vault: &mut Account<Vault>, depositor: Pubkey, amount: u64) -> Result<()> {
+    vault.check_version()?;
+    
+    require!(amount > 0, CommonError::InvalidAmount);
+    
+    vault.balance = vault.balance
+        .checked_add(amount)
+        .ok_or(CommonError::Overflow)?;
+    
+    let sequence = vault.next_sequence()?;
+    
+    emit!(DepositMade {
+        vault: vault.key(),
+        depositor,
+        amount,
+        new_balance: vault.balance,
+        sequence,
+    });
+    
+    log_event!("deposit", vault = vault.key(), name = vault.name, amount = amount, balance = vault.balance);
+    Ok(())
+}
+
+/// ✅ Only a vault one version behind can be migrated
+///
+/// A current vault fails with `AlreadyMigrated`. Anything older, or written
+/// by a newer deployment, fails with `VersionMismatch`: there is no
+/// conversion for it here.
+fn check_migratable(version: u8) -> Result<()> {
+    require!(version != CURRENT_VERSION, ErrorCode::AlreadyMigrated);
+    require!(
+        version.checked_add(1) == Some(CURRENT_VERSION),
+        ErrorCode::VersionMismatch
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    // ✅ SECURE: One registry per authority
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultRegistry::INIT_SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 #[instruction(vault_name: String)]
 pub struct CreateVault<'info> {
@@ -178,6 +497,88 @@ pub struct CreateVault<'info> {
     )]
     pub vault: Account<'info, Vault>,
     
+    // ✅ SECURE: Registry PDA bound to the same authority
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(vault_name: String)]
+pub struct CreateSponsoredVault<'info> {
+    // ✅ SECURE: Seeds are the authority's, never the payer's
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [
+            b"vault",
+            authority.key().as_ref(),
+            vault_name.as_bytes()
+        ],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+    
+    // ✅ SECURE: Still a Signer, so nobody can open (or fill the registry
+    // with) vaults in someone else's name; not `mut`, it pays nothing
+    pub authority: Signer<'info>,
+    
+    /// Funds the rent and gets nothing else
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(parent_name: String, sub_name: String)]
+pub struct CreateSubvault<'info> {
+    // ✅ SECURE: Parent must be a real vault PDA owned by this authority
+    #[account(
+        seeds = [
+            b"vault",
+            authority.key().as_ref(),
+            parent_name.as_bytes()
+        ],
+        bump = parent_vault.bump,
+        has_one = authority @ ErrorCode::ParentMismatch
+    )]
+    pub parent_vault: Account<'info, Vault>,
+    
+    // ✅ SECURE: Own prefix, keyed by the parent's address
+    // ["subvault", savings_vault, "travel"] is unique per parent, and can't
+    // alias a top-level ["vault", user_pubkey, "savingstravel"]
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SubVault::INIT_SPACE,
+        seeds = [
+            b"subvault",
+            parent_vault.key().as_ref(),
+            sub_name.as_bytes()
+        ],
+        bump
+    )]
+    pub subvault: Account<'info, SubVault>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -195,7 +596,7 @@ pub struct Withdraw<'info> {
             vault.name.as_bytes()
         ],
         bump = vault.bump,  // ✅ Use stored bump
-        has_one = authority @ ErrorCode::Unauthorized
+        has_one = authority @ CommonError::Unauthorized
     )]
     pub vault: Account<'info, Vault>,
     
@@ -219,6 +620,25 @@ pub struct Deposit<'info> {
     pub depositor: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct DepositRecomputedBump<'info> {
+    // Same check as `Deposit`, but the bare `bump` makes Anchor search for
+    // the canonical bump (up to 255 sha256 attempts) instead of trusting
+    // the stored one
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault.authority.as_ref(),
+            vault.name.as_bytes()
+        ],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub depositor: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct TransferFromVault<'info> {
     #[account(
@@ -229,7 +649,7 @@ pub struct TransferFromVault<'info> {
             vault.name.as_bytes()
         ],
         bump = vault.bump,
-        has_one = authority @ ErrorCode::Unauthorized
+        has_one = authority @ CommonError::Unauthorized
     )]
     pub vault: Account<'info, Vault>,
     
@@ -246,15 +666,87 @@ pub struct CloseVault<'info> {
             vault.name.as_bytes()
         ],
         bump = vault.bump,
-        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = authority @ CommonError::Unauthorized,
         close = authority  // ✅ Return rent to authority
     )]
     pub vault: Account<'info, Vault>,
     
+    // ✅ SECURE: Registry PDA bound to the same authority
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct DrainAndClose<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            authority.key().as_ref(),
+            vault.name.as_bytes()
+        ],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized,
+        close = authority  // ✅ Return rent to authority after the sweep
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    // ✅ SECURE: Source must be owned by the vault PDA
+    #[account(
+        mut,
+        constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Destination must belong to the authority and share the mint
+    #[account(
+        mut,
+        constraint = authority_tokens.owner == authority.key() @ CommonError::InvalidOwner,
+        constraint = authority_tokens.mint == vault_tokens.mint @ CommonError::MintMismatch
+    )]
+    pub authority_tokens: Account<'info, TokenAccount>,
+    
+    // ✅ SECURE: Registry PDA bound to the same authority
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    // ✅ Only the vault's own authority can migrate it
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            authority.key().as_ref(),
+            vault.name.as_bytes()
+        ],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
@@ -269,6 +761,59 @@ pub struct Vault {
     pub bump: u8,
     /// Creation timestamp
     pub created_at: i64,
+    /// Bumped by every mutating instruction and emitted with its event,
+    /// so indexers get a total order per vault and can detect gaps
+    pub sequence: u64,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl Vault {
+    /// ✅ Reject vaults written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Advance `sequence` and return it for this instruction's event
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        Ok(self.sequence)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SubVault {
+    /// The authority who owns this sub-vault (same as the parent's)
+    pub authority: Pubkey,
+    /// The parent vault this sub-vault is nested under
+    pub parent: Pubkey,
+    /// Current balance
+    pub balance: u64,
+    /// Sub-vault name (part of PDA seeds)
+    #[max_len(32)]
+    pub name: String,
+    /// Stored bump for efficient PDA operations
+    pub bump: u8,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Event sequence, as on `Vault`
+    pub sequence: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultRegistry {
+    /// The authority whose vaults are tracked
+    pub authority: Pubkey,
+    /// Names of the authority's open vaults
+    #[max_len(20, 32)]
+    pub names: Vec<String>,
+    /// Stored bump for efficient PDA operations
+    pub bump: u8,
 }
 
 #[event]
@@ -276,6 +821,16 @@ pub struct VaultCreated {
     pub vault: Pubkey,
     pub authority: Pubkey,
     pub name: String,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct SubvaultCreated {
+    pub subvault: Pubkey,
+    pub parent: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub sequence: u64,
 }
 
 #[event]
@@ -284,6 +839,7 @@ pub struct DepositMade {
     pub depositor: Pubkey,
     pub amount: u64,
     pub new_balance: u64,
+    pub sequence: u64,
 }
 
 #[event]
@@ -292,30 +848,43 @@ pub struct WithdrawalMade {
     pub authority: Pubkey,
     pub amount: u64,
     pub remaining_balance: u64,
+    pub sequence: u64,
 }
 
 #[event]
 pub struct VaultClosed {
     pub vault: Pubkey,
     pub authority: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    /// The vault rewritten
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub sequence: u64,
 }
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Unauthorized access to vault")]
-    Unauthorized,
-    #[msg("Insufficient funds in vault")]
-    InsufficientFunds,
-    #[msg("Invalid amount - must be greater than zero")]
-    InvalidAmount,
     #[msg("Invalid vault name - must be 1-32 characters")]
     InvalidVaultName,
-    #[msg("Arithmetic overflow")]
-    Overflow,
-    #[msg("Arithmetic underflow")]
-    Underflow,
     #[msg("Vault must be empty before closing")]
     VaultNotEmpty,
+    #[msg("Failed to sweep remaining balance")]
+    SweepFailed,
+    #[msg("Vault registry is full")]
+    RegistryFull,
+    #[msg("A vault with this name already exists")]
+    DuplicateVaultName,
+    #[msg("Parent vault does not match")]
+    ParentMismatch,
+    #[msg("Account was written by a different program version")]
+    VersionMismatch,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
 }
 
 // ============================================================================
@@ -340,7 +909,7 @@ pub enum ErrorCode {
 // 1. PDA includes victim's pubkey
 // 2. Attacker doesn't know victim's pubkey in advance
 // 3. Even if they did, they can't create PDA for another user
-//    (init requires authority to sign and pay)
+//    (init requires authority to sign; see SPONSORED CREATION for who pays)
 //
 // FAKE ACCOUNT ATTACK BLOCKED:
 // ----------------------------
@@ -355,3 +924,32 @@ pub enum ErrorCode {
 // 1. bump = vault.bump uses stored value
 // 2. Can't pass arbitrary bump
 // 3. Derivation must match exactly
+//
+// Storing the bump is also the cheaper option. `bump = vault.bump` costs one
+// `create_program_address`; a bare `bump` costs a `find_program_address`,
+// which tries bumps from 255 downward and pays a full hash per attempt.
+// `deposit_recomputed_bump` exists only to measure that difference
+// (tests/bump_cost.rs).
+//
+// SUB-VAULT HIJACK BLOCKED:
+// -------------------------
+// Attacker tries to nest a sub-vault under the victim's parent vault:
+// 1. Parent seeds include authority.key(), attacker's key derives a different parent
+// 2. has_one = authority on parent_vault fails for any other signer
+// 3. Sub-vault seeds include parent_vault.key(), itself derived from authority
+// Transaction fails with "seeds constraint violated" or "Parent vault does not match"
+//
+// Note on seed concatenation: PDA derivation hashes the seeds back to back,
+// so ["vault", A, "ab"] and ["vault", A, "a", "b"] derive the SAME address.
+// Sub-vaults once used the second form, so a sub-vault "b" under "a" and a
+// top-level vault "ab" blocked each other. The "subvault" prefix and the
+// fixed-length parent key keep the two seed sets from ever lining up.
+//
+// SPONSORED CREATION (create_sponsored_vault):
+// --------------------------------------------
+// With payer = authority, whoever funds the account is its owner. Split
+// them naively (payer signs, authority is just a Pubkey) and a griefer
+// can open vaults in anyone's name or fill their registry to RegistryFull.
+// 1. payer = payer: the sponsor's lamports fund the rent, nothing more
+// 2. vault.authority and the seeds come from `authority`, never `payer`
+// 3. authority is still a Signer, so every vault has its owner's consent
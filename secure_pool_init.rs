@@ -0,0 +1,132 @@
+//! # Secure Pool Initialization Example
+//!
+//! This program demonstrates a pool PDA whose seeds are the mints it
+//! trades, so there is exactly one address per mint pair and nothing to
+//! gain by creating it first.
+//!
+//! ## Security Measures
+//! 1. The pool lives at `["pool", token_mint, reward_mint]`; the mints are
+//!    read from the seeds, never from separate arguments
+//! 2. `token_mint != reward_mint`, so a pair always names two mints
+//! 3. The pool has no authority and its fee is the program constant
+//!    `FEE_BPS`: the initializer pays rent and gets nothing else
+//!
+//! ## Why This Works
+//! Squatting needs a first caller to decide something. Here the address
+//! determines the mints and the program determines the rest, so every
+//! user who initializes a given pair derives the same PDA and writes the
+//! same pool. An attacker going first just pays the rent for it. Pools for
+//! a fake mint are harmless: they live at the fake mint's own address,
+//! which no client derives for the real pair.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNN");
+
+/// Fee every pool charges; not configurable per pool
+pub const FEE_BPS: u16 = 30;
+
+#[program]
+pub mod secure_pool_init {
+    use super::*;
+
+    /// ✅ SECURE: Create the canonical pool for a mint pair
+    ///
+    /// An attacker CANNOT:
+    /// - Create a second pool for the same pair at another address
+    /// - Attach other mints to the pair's address
+    /// - Gain any role by being first
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.token_mint = ctx.accounts.token_mint.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.fee_bps = FEE_BPS;
+        pool.bump = ctx.bumps.pool;
+
+        emit!(PoolInitialized {
+            pool: pool.key(),
+            token_mint: pool.token_mint,
+            reward_mint: pool.reward_mint,
+            payer: ctx.accounts.payer.key(),
+        });
+
+        log_event!("initialize_pool", pool = pool.key(), token_mint = pool.token_mint, reward_mint = pool.reward_mint);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    // ✅ SECURE: The mint pair is the address
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", token_mint.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = reward_mint.key() != token_mint.key() @ CommonError::MintMismatch
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    /// Pays rent; recorded only in the event
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub payer: Pubkey,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_pool_init.rs FAILS here:
+//
+// Step                                   ["pool", USDC, SOL] holds
+// ----                                   -------------------------
+// Attacker: initialize_pool(USDC, SOL)   USDC / SOL, fee 30, no authority
+// Team:     initialize_pool(USDC, SOL)   fails: already in use, and
+//                                        nothing needs changing
+// Attacker: initialize_pool(FAKE, SOL)   lands at ["pool", FAKE, SOL],
+//                                        an address nobody looks up
+//
+// Notes:
+// - The order of the seeds matters: (USDC, SOL) and (SOL, USDC) are two
+//   pools. Protocols that want one pool per unordered pair sort the mints
+//   before deriving
+// - If a pool does need an admin, it must come from somewhere the
+//   initializer doesn't control (a program-wide config set by the upgrade
+//   authority), never from the signer of initialize_pool
+// - secure_cpi derives its pools the same way; secure_matching keys them
+//   by token_mint alone, one pool per staked mint
@@ -0,0 +1,164 @@
+//! # Secure Program Owner Example
+//!
+//! This program demonstrates checking that a vault passed as raw
+//! `AccountInfo` is owned by this program before trusting any field in it.
+//!
+//! ## Security Measures
+//! 1. `owner = crate::ID @ WrongProgramOwner` on the `AccountInfo`
+//! 2. The handler repeats the owner check before deserializing
+//! 3. The vault address must be this program's PDA for the stored authority
+//! 4. `authority` and `balance` are only read after all of the above
+//!
+//! ## Why This Works
+//! Only the owning program can write an account's data. A clone with the
+//! right discriminator and fields, owned by any other program, fails the
+//! owner check before its bytes are read. `Account<'info, T>` performs the
+//! same check implicitly; `AccountInfo` paths have to ask for it.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQQ");
+
+#[program]
+pub mod secure_program_owner {
+    use super::*;
+
+    /// Create the vault PDA for `authority` holding `balance`
+    pub fn initialize(ctx: Context<Initialize>, balance: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.balance = balance;
+        vault.bump = ctx.bumps.vault;
+
+        log_event!("initialize", vault = vault.key(), authority = vault.authority, balance = balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw against a vault this program owns
+    ///
+    /// An attacker CANNOT:
+    /// - Pass a clone owned by another program (WrongProgramOwner)
+    /// - Pass a vault of this program at a non-PDA address
+    /// - Withdraw from someone else's vault (authority must sign)
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let vault_info = &ctx.accounts.vault;
+
+        // ✅ Defense-in-depth: Explicit owner check before touching the data
+        require_keys_eq!(*vault_info.owner, crate::ID, ErrorCode::WrongProgramOwner);
+
+        let data = vault_info.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..])?;
+
+        // ✅ The address is the PDA this program derives for that authority
+        let expected = Pubkey::create_program_address(
+            &[b"vault", vault.authority.as_ref(), &[vault.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidVaultAddress)?;
+        require_keys_eq!(vault_info.key(), expected, ErrorCode::InvalidVaultAddress);
+
+        require_keys_eq!(
+            vault.authority,
+            ctx.accounts.authority.key(),
+            CommonError::Unauthorized
+        );
+        require!(vault.balance >= amount, CommonError::InsufficientFunds);
+
+        emit!(WithdrawalApproved {
+            vault: vault_info.key(),
+            authority: vault.authority,
+            amount,
+        });
+
+        log_event!("withdraw", vault = vault_info.key(), amount = amount, balance = vault.balance);
+
+        // In production: transfer out of the program's treasury here
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    // ✅ SECURE: Explicit owner constraint on a raw AccountInfo
+    /// CHECK: Owner verified by constraint and again in the handler
+    #[account(owner = crate::ID @ ErrorCode::WrongProgramOwner)]
+    pub vault: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct WithdrawalApproved {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Vault is not owned by this program")]
+    WrongProgramOwner,
+    #[msg("Vault is not at this program's PDA for its authority")]
+    InvalidVaultAddress,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_program_owner.rs FAILS here:
+//
+// CLONED VAULT BLOCKED:
+// ---------------------
+//   withdraw(10^18) with vault = clone (owner = attacker's program)
+//     owner = crate::ID constraint         ✗ WrongProgramOwner
+//   The clone's bytes are never deserialized.
+//
+// WHY CHECK THE ADDRESS TOO:
+// --------------------------
+// The owner check proves this program wrote the data; the PDA check pins
+// which Vault it is. If a later instruction ever creates Vaults at other
+// addresses, they still can't stand in for the authority's PDA vault.
+// Account<'info, Vault> with seeds = [...] gives both; this is the
+// AccountInfo equivalent.
+//
+// WHY THE RUNTIME DOESN'T SAVE YOU:
+// ---------------------------------
+// The runtime rejects WRITES to accounts a program doesn't own. READS are
+// unrestricted, so any handler that only reads an AccountInfo must check
+// the owner itself.
@@ -0,0 +1,190 @@
+//! # Secure Account Space Example
+//!
+//! This program demonstrates how to grow an account that holds a `Vec`
+//! instead of guessing its final size up front.
+//!
+//! ## Security Measures
+//! 1. `VaultRegistry::space_for` computes the exact serialized size
+//! 2. `add_name` grows the account with `realloc(new_len, false)` before
+//!    Anchor serializes it on exit
+//! 3. The rent difference is transferred in first, from a signing `payer`;
+//!    `RentTopUpRequired` if the payer cannot cover it
+//! 4. Names are length-capped so each realloc stays far below the
+//!    per-instruction growth limit
+//!
+//! ## Why This Works
+//! The account is always exactly as large as its contents, and always
+//! rent-exempt at that size. There is no capacity to exhaust, so no input
+//! sequence can leave the registry unable to serialize.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDD");
+
+/// Longest name `add_name` accepts, in bytes
+pub const MAX_NAME_LEN: usize = 32;
+
+#[program]
+pub mod secure_realloc {
+    use super::*;
+
+    /// Create an empty registry PDA for `authority`, sized for no names
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.authority = ctx.accounts.authority.key();
+        registry.bump = ctx.bumps.registry;
+        registry.names = Vec::new();
+        Ok(())
+    }
+
+    /// ✅ SECURE: Append a name, growing the account to fit
+    ///
+    /// An attacker CANNOT:
+    /// - Fill the registry until it can no longer be written
+    /// - Leave it below rent exemption after it grows
+    pub fn add_name(ctx: Context<AddName>, name: String) -> Result<()> {
+        require!(!name.is_empty(), CommonError::InvalidAmount);
+        require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+
+        let registry = &mut ctx.accounts.registry;
+        registry.names.push(name);
+        let new_len = registry.space_for();
+
+        let info = registry.to_account_info();
+        if new_len > info.data_len() {
+            // ✅ SECURE: Fund the new size before taking it
+            let required = Rent::get()?.minimum_balance(new_len);
+            let top_up = required.saturating_sub(info.lamports());
+            if top_up > 0 {
+                require!(
+                    ctx.accounts.payer.lamports() >= top_up,
+                    ErrorCode::RentTopUpRequired
+                );
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: info.clone(),
+                };
+                system_program::transfer(
+                    CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+                    top_up,
+                )?;
+            }
+
+            // ✅ SECURE: Grow before Anchor serializes `names` on exit
+            info.realloc(new_len, false)?;
+
+            emit!(RegistryResized {
+                registry: info.key(),
+                new_len: new_len as u64,
+                rent_paid: top_up,
+            });
+        }
+
+        log_event!("add_name", names = registry.names.len(), bytes = new_len);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = VaultRegistry::EMPTY_SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddName<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    pub authority: Signer<'info>,
+
+    /// Pays the rent for any growth
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct VaultRegistry {
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub names: Vec<String>,
+}
+
+impl VaultRegistry {
+    /// Discriminator + authority + bump + empty vec length prefix
+    pub const EMPTY_SPACE: usize = 8 + 32 + 1 + 4;
+
+    /// Exact account size needed to serialize `self`
+    pub fn space_for(&self) -> usize {
+        self.names
+            .iter()
+            .fold(Self::EMPTY_SPACE, |len, name| len + 4 + name.len())
+    }
+}
+
+#[event]
+pub struct RegistryResized {
+    pub registry: Pubkey,
+    pub new_len: u64,
+    pub rent_paid: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Name exceeds MAX_NAME_LEN bytes")]
+    NameTooLong,
+    #[msg("Payer cannot cover the rent for the larger account")]
+    RentTopUpRequired,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_realloc.rs FAILS here:
+//
+//   initialize           45 bytes, rent-exempt at 45
+//   add_name("a" * 28)   space_for = 45 + 32 = 77
+//                        top_up = rent(77) - rent(45), payer → registry
+//                        realloc(77) → exit serializes 77 bytes  ✓
+//   add_name(...)        same again, for as long as the payer pays
+//
+// Notes:
+// - The transfer happens before realloc: a realloc that is not rent-exempt
+//   fails the transaction at the end of the instruction anyway, but
+//   RentTopUpRequired says why, up front
+// - realloc(_, false) skips zeroing: the new bytes are fresh (never used
+//   in this instruction) and Anchor overwrites them on exit. Pass `true`
+//   when shrinking and regrowing within one instruction
+// - A single instruction may grow an account by at most 10 KiB
+//   (MAX_PERMITTED_DATA_INCREASE); MAX_NAME_LEN keeps add_name far below
+// - Anchor's `realloc = .., realloc::payer = .., realloc::zero = ..`
+//   constraint does the same transfer + realloc declaratively when the
+//   new size is known from instruction arguments
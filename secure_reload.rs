@@ -0,0 +1,203 @@
+//! # Secure Account Reload Example
+//!
+//! This program demonstrates re-reading a token account after a CPI before
+//! making any decision on its balance.
+//!
+//! ## Security Measures
+//! 1. `ctx.accounts.vault_tokens.reload()?` after `token::transfer`, so
+//!    `amount` is deserialized again from the account's current data
+//! 2. The reloaded balance must equal the pre-CPI balance minus the amount
+//!    moved; anything else fails with `StaleBalanceRead`
+//! 3. The reserve floor and `last_balance` use only the reloaded value
+//!
+//! ## Why This Works
+//! A CPI writes to the account's data, not to the struct Anchor built from
+//! it at the start of the instruction. `reload()` rebuilds the struct from
+//! the data, so every check after the CPI sees the balance the token
+//! program left behind. A breached reserve fails the instruction, which
+//! reverts the transfer with it.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG");
+
+#[program]
+pub mod secure_reload {
+    use super::*;
+
+    /// Create the vault PDA for `authority` over a token account it owns
+    pub fn initialize(ctx: Context<Initialize>, min_reserve: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.min_reserve = min_reserve;
+        vault.last_balance = ctx.accounts.vault_tokens.amount;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw, reload, then check the reserve
+    ///
+    /// An attacker CANNOT:
+    /// - Take the vault below `min_reserve` (the check sees the new balance)
+    /// - Leave `last_balance` out of step with the token account
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let before = ctx.accounts.vault_tokens.amount;
+        require!(before >= amount, CommonError::InsufficientFunds);
+
+        let authority = ctx.accounts.authority.key();
+        let seeds = &[b"reserve_vault".as_ref(), authority.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        // ✅ SECURE: The cached struct predates the transfer; rebuild it
+        ctx.accounts.vault_tokens.reload()?;
+        let remaining = ctx.accounts.vault_tokens.amount;
+
+        // ✅ The balance must reflect the transfer we just made
+        let expected = before
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        require!(remaining == expected, ErrorCode::StaleBalanceRead);
+
+        require!(
+            remaining >= ctx.accounts.vault.min_reserve,
+            ErrorCode::ReserveBreached
+        );
+        ctx.accounts.vault.last_balance = remaining;
+
+        emit!(Withdrawn {
+            vault: ctx.accounts.vault.key(),
+            amount,
+            remaining,
+        });
+
+        log_event!("withdraw", vault = ctx.accounts.vault.key(), amount = amount, balance = remaining);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReserveVault::INIT_SPACE,
+        seeds = [b"reserve_vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+
+    #[account(constraint = vault_tokens.owner == vault.key() @ CommonError::InvalidOwner)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"reserve_vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority @ CommonError::Unauthorized,
+        has_one = vault_tokens @ CommonError::InvalidOwner
+    )]
+    pub vault: Account<'info, ReserveVault>,
+
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == vault_tokens.mint @ CommonError::MintMismatch
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveVault {
+    pub authority: Pubkey,
+    pub vault_tokens: Pubkey,
+    /// Tokens `vault_tokens` must keep after any withdrawal
+    pub min_reserve: u64,
+    /// `vault_tokens.amount` as of the last instruction
+    pub last_balance: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Withdrawal would leave the vault below its reserve")]
+    ReserveBreached,
+    #[msg("Token balance after the CPI does not reflect the transfer")]
+    StaleBalanceRead,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_reload.rs FAILS here:
+//
+// STALE RESERVE CHECK BLOCKED:
+// ----------------------------
+// vault_tokens: 1_000, min_reserve: 100
+//
+//   withdraw(1_000)
+//     before = 1_000
+//     token::transfer(1_000)               account data  = 0
+//     vault_tokens.reload()                cached amount = 0
+//     0 == 1_000 - 1_000                   ✓ StaleBalanceRead not hit
+//     0 >= 100                             ✗ ReserveBreached
+//   The instruction fails and the transfer is rolled back with it.
+//
+// WHEN TO RELOAD:
+// ---------------
+// Any Account<T> the CPI can write (the token accounts it moved funds
+// between, an account the callee owns) must be reloaded before it is read
+// again. Accounts the CPI cannot write don't need it. Reading `before`
+// BEFORE the CPI is fine; that value is current at that point.
+//
+// STALE BALANCE GUARD:
+// --------------------
+// remaining == before - amount catches a missing reload during review: drop
+// the reload() call and every successful withdrawal fails with
+// StaleBalanceRead instead of silently passing the reserve check.
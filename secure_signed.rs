@@ -0,0 +1,188 @@
+//! # Secure Signed Integer Example
+//!
+//! This program demonstrates SAFE handling of `i64` timestamps and durations.
+//!
+//! ## Security Measures
+//! 1. Take `start_time` from the `Clock` sysvar, never from instruction data
+//! 2. Use `checked_sub` on `i64` so subtraction itself cannot overflow
+//! 3. Reject negative durations explicitly BEFORE converting to `u64`
+//! 4. Convert with `u64::try_from` instead of `as`
+//!
+//! ## Best Practices
+//! - Treat every signed-to-unsigned cast as a potential sign bug
+//! - Validate the sign of a duration, not just its magnitude
+//! - Prefer `try_from`/`try_into` over `as` for all narrowing or sign-changing casts
+
+use anchor_lang::prelude::*;
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("Secure7777777777777777777777777777777777777");
+
+/// Scale factor for fixed-point rates (6 decimals)
+const SCALE: u64 = 1_000_000;
+
+/// Seconds in a (non-leap) year, used to annualize the reward rate
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[program]
+pub mod secure_signed {
+    use super::*;
+
+    /// ✅ SECURE: Stake with the start time taken from the Clock sysvar
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let staking = &mut ctx.accounts.staking;
+        staking.owner = ctx.accounts.owner.key();
+        staking.amount = amount;
+        staking.rate = 100_000; // 10% APY, scaled by SCALE
+
+        // ✅ SECURE: The caller cannot choose a future start time
+        staking.start_time = Clock::get()?.unix_timestamp;
+        staking.pending_rewards = 0;
+
+        emit!(Staked {
+            staking_account: staking.key(),
+            owner: staking.owner,
+            amount,
+            start_time: staking.start_time,
+        });
+
+        log_event!("stake", amount = amount, start_time = staking.start_time);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Reward calculation with sign-checked duration
+    pub fn calculate_rewards(ctx: Context<CalculateRewards>) -> Result<()> {
+        let staking = &mut ctx.accounts.staking;
+        let clock = Clock::get()?;
+
+        // ✅ SECURE: checked_sub catches i64 overflow,
+        // filter rejects a negative duration (start_time in the future)
+        let duration = clock.unix_timestamp
+            .checked_sub(staking.start_time)
+            .filter(|d| *d >= 0)
+            .ok_or(ErrorCode::NegativeDuration)?;
+
+        // ✅ SECURE: Sign already verified, try_from documents the intent
+        let time_staked = u64::try_from(duration)
+            .map_err(|_| ErrorCode::NegativeDuration)?;
+
+        let rewards_u128 = (staking.amount as u128)
+            .checked_mul(staking.rate as u128)
+            .ok_or(CommonError::Overflow)?
+            .checked_mul(time_staked as u128)
+            .ok_or(CommonError::Overflow)?
+            .checked_div(SCALE as u128)
+            .ok_or(CommonError::Overflow)?
+            .checked_div(SECONDS_PER_YEAR as u128)
+            .ok_or(CommonError::Overflow)?;
+
+        // ✅ SECURE: Verify result fits in u64 instead of truncating
+        let rewards = u64::try_from(rewards_u128)
+            .map_err(|_| ErrorCode::RewardsTooLarge)?;
+
+        staking.pending_rewards = staking.pending_rewards
+            .checked_add(rewards)
+            .ok_or(CommonError::Overflow)?;
+
+        emit!(RewardsCalculated {
+            staking_account: staking.key(),
+            owner: staking.owner,
+            rewards,
+            time_staked,
+        });
+
+        log_event!("calculate_rewards", rewards = rewards, time_staked = time_staked);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakingAccount::INIT_SPACE
+    )]
+    pub staking: Account<'info, StakingAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CalculateRewards<'info> {
+    #[account(
+        mut,
+        has_one = owner @ CommonError::Unauthorized
+    )]
+    pub staking: Account<'info, StakingAccount>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakingAccount {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub rate: u64,
+    pub start_time: i64,
+    pub pending_rewards: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub staking_account: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub start_time: i64,
+}
+
+#[event]
+pub struct RewardsCalculated {
+    pub staking_account: Pubkey,
+    pub owner: Pubkey,
+    pub rewards: u64,
+    pub time_staked: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Duration is negative - start time is in the future")]
+    NegativeDuration,
+    #[msg("Calculated rewards exceed maximum")]
+    RewardsTooLarge,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_signed.rs FAILS here:
+//
+// FUTURE START TIME BLOCKED:
+// --------------------------
+// 1. stake() no longer accepts start_time as an argument
+// 2. start_time is read from Clock::get()?.unix_timestamp
+// 3. The attacker has no way to place start_time ahead of the clock
+//
+// NEGATIVE DURATION BLOCKED:
+// --------------------------
+// Even if start_time were in the future (e.g. a migrated account):
+// 1. now.checked_sub(start_time) = Some(-3600)
+// 2. .filter(|d| *d >= 0) = None
+// 3. .ok_or(NegativeDuration)? → Error
+// Transaction fails with NegativeDuration instead of minting 5.8 * 10^16 rewards
+//
+// TRUNCATION BLOCKED:
+// -------------------
+// u64::try_from on the final u128 result fails with RewardsTooLarge
+// instead of silently dropping the high bits like `as u64`.
@@ -7,6 +7,11 @@
 //! 2. Add constraint checks to verify the signer matches stored authority
 //! 3. Use `has_one` constraint for automatic authority matching
 //! 4. Implement defense-in-depth with multiple validation layers
+//! 5. Optional destination whitelist limits where `withdraw_to` can send funds
+//! 6. Guardian recovery only takes effect after a delay the authority can cancel in
+//! 7. Session keys withdraw within a capped, expiring allowance, never as the authority
+//! 8. Reject vaults not written at `CURRENT_VERSION` until `migrate_vault`
+//!    rewrites them
 //! 
 //! ## Why This Works
 //! - Solana runtime enforces that `Signer` accounts must have signed the transaction
@@ -14,9 +19,94 @@
 //! - Even if an attacker knows the authority pubkey, they can't sign without the private key
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::{assert_authority, CommonError};
+use logging::log_event;
 
 declare_id!("Secure1111111111111111111111111111111111111");
 
+/// Most destinations a vault's withdrawal whitelist can hold
+pub const MAX_ALLOWED_DESTINATIONS: usize = 8;
+
+/// Shortest recovery delay `set_guardian` accepts (1 day)
+pub const MIN_RECOVERY_DELAY: i64 = 24 * 60 * 60;
+
+/// Layout version of `Vault` written by `initialize`; every other handler
+/// rejects vaults at any other version
+pub const CURRENT_VERSION: u8 = 1;
+
+/// Bytes the vault authority signs off-chain to pre-authorize a withdrawal:
+/// `vault || amount || expiry || nonce`, integers little-endian
+pub fn withdrawal_message(vault: &Pubkey, amount: u64, expiry: i64, nonce: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 8 + 8 + 8);
+    message.extend_from_slice(vault.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// Size of the ed25519 program's per-signature offsets struct
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Header before the offsets: signature count + padding
+const ED25519_HEADER_LEN: usize = 2;
+
+/// ✅ SECURE: Verify that the instruction just before this one is an
+/// ed25519 program check of `signer` signing exactly `message`
+///
+/// The ed25519 program rejects the whole transaction if the signature is
+/// bad, so reaching this point means it held. What this function proves is
+/// that the verified signature is the one we need: right program, one
+/// signature, data embedded in that instruction, expected key and message.
+fn verify_ed25519_instruction(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidSignature);
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions)?;
+    
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidSignature);
+    require!(ix.accounts.is_empty(), ErrorCode::InvalidSignature);
+    
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN && data[0] == 1,
+        ErrorCode::InvalidSignature
+    );
+    
+    let offsets = &data[ED25519_HEADER_LEN..ED25519_HEADER_LEN + ED25519_OFFSETS_LEN];
+    let read_u16 = |i: usize| u16::from_le_bytes([offsets[i], offsets[i + 1]]);
+    let public_key_offset = read_u16(4) as usize;
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+    
+    // ✅ Signature, key and message must all live in the ed25519
+    // instruction itself, not be pointed at some other instruction
+    for index_at in [2, 6, 12] {
+        require!(read_u16(index_at) == u16::MAX, ErrorCode::InvalidSignature);
+    }
+    
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidSignature)?;
+    
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidSignature);
+    require!(signed_message == message, ErrorCode::InvalidSignature);
+    Ok(())
+}
+
 #[program]
 pub mod secure_signer {
     use super::*;
@@ -28,35 +118,49 @@ pub mod secure_signer {
         vault.balance = 0;
         vault.total_withdrawn = 0;
         vault.withdrawal_count = 0;
+        vault.nonce = 0;
+        vault.sequence = 0;
+        vault.allowed_destinations = Vec::new();
+        vault.guardian = Pubkey::default();
+        vault.recovery_delay = 0;
+        vault.pending_authority = None;
+        vault.recovery_unlock = 0;
+        vault.version = CURRENT_VERSION;
         
         emit!(VaultInitialized {
             vault: vault.key(),
             authority: vault.authority,
+            sequence: vault.sequence,
         });
         
-        msg!("Vault initialized for authority: {}", vault.authority);
+        log_event!("initialize", vault = vault.key(), authority = vault.authority);
         Ok(())
     }
 
     /// Deposit funds into the vault
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
         // Validate amount
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &mut ctx.accounts.vault;
         
         vault.balance = vault.balance
             .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
         
         emit!(DepositMade {
             vault: vault.key(),
             depositor: ctx.accounts.depositor.key(),
             amount,
             new_balance: vault.balance,
+            sequence,
         });
         
-        msg!("Deposited {} lamports. New balance: {}", amount, vault.balance);
+        log_event!("deposit", vault = vault.key(), amount = amount, balance = vault.balance);
         Ok(())
     }
 
@@ -72,44 +176,45 @@ pub mod secure_signer {
     /// - Forge a signature without the private key
     /// - Bypass the has_one constraint
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
         // Validate amount
-        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount > 0, CommonError::InvalidAmount);
         
         let vault = &mut ctx.accounts.vault;
         
         // ✅ Defense-in-depth: Explicit authority check
         // This is redundant with has_one but provides extra safety
-        require_keys_eq!(
-            ctx.accounts.authority.key(),
-            vault.authority,
-            ErrorCode::UnauthorizedAuthority
-        );
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
         
         // Check sufficient balance
         require!(
             vault.balance >= amount,
-            ErrorCode::InsufficientFunds
+            CommonError::InsufficientFunds
         );
         
         // Update state
         vault.balance = vault.balance
             .checked_sub(amount)
-            .ok_or(ErrorCode::Underflow)?;
+            .ok_or(CommonError::Underflow)?;
         vault.total_withdrawn = vault.total_withdrawn
             .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
         vault.withdrawal_count = vault.withdrawal_count
             .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
         
         emit!(WithdrawalMade {
             vault: vault.key(),
             authority: ctx.accounts.authority.key(),
             amount,
             remaining_balance: vault.balance,
+            sequence,
         });
         
-        msg!("Withdrew {} lamports. Remaining balance: {}", amount, vault.balance);
+        log_event!("withdraw", vault = vault.key(), amount = amount, balance = vault.balance);
         
         // In production: Transfer SOL/tokens here
         // The transfer would go to an account owned by the verified signer
@@ -117,30 +222,526 @@ pub mod secure_signer {
         Ok(())
     }
 
+    /// ✅ SECURE: Withdraw the entire vault balance
+    /// 
+    /// Same accounts and checks as `withdraw`, with the amount read from the vault
+    pub fn withdraw_all(ctx: Context<Withdraw>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        let amount = vault.balance;
+        
+        // Nothing to withdraw from an empty vault
+        require!(amount > 0, CommonError::InsufficientFunds);
+        
+        // ✅ Defense-in-depth: Explicit authority check
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
+        
+        // Update state
+        vault.balance = 0;
+        vault.total_withdrawn = vault.total_withdrawn
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.withdrawal_count = vault.withdrawal_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("withdraw_all", vault = vault.key(), amount = amount, balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw to a recipient on the vault's whitelist
+    /// 
+    /// Same checks as `withdraw`, plus: if `allowed_destinations` is
+    /// non-empty, `recipient` must be on it. An empty list allows any
+    /// recipient.
+    /// 
+    /// An attacker who steals the authority key CANNOT:
+    /// - Send funds to an address the authority did not whitelist beforehand
+    pub fn withdraw_to(ctx: Context<WithdrawTo>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let vault = &mut ctx.accounts.vault;
+        let recipient = ctx.accounts.recipient.key();
+        
+        // ✅ Defense-in-depth: Explicit authority check
+        assert_authority(&vault.authority, &ctx.accounts.authority.key())?;
+        
+        // ✅ SECURE: Funds only leave to a whitelisted address
+        require!(
+            vault.allows_destination(&recipient),
+            ErrorCode::DestinationNotAllowed
+        );
+        
+        require!(
+            vault.balance >= amount,
+            CommonError::InsufficientFunds
+        );
+        
+        // Update state
+        vault.balance = vault.balance
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        vault.total_withdrawn = vault.total_withdrawn
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.withdrawal_count = vault.withdrawal_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("withdraw_to", vault = vault.key(), recipient = recipient, amount = amount, balance = vault.balance);
+        
+        // In production: Transfer SOL/tokens to `recipient` here
+        
+        Ok(())
+    }
+
+    /// ✅ SECURE: Whitelist a withdrawal destination (authority only)
+    pub fn add_destination(ctx: Context<UpdateDestinations>, destination: Pubkey) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        require!(
+            !vault.allowed_destinations.contains(&destination),
+            ErrorCode::DestinationAlreadyAllowed
+        );
+        require!(
+            vault.allowed_destinations.len() < MAX_ALLOWED_DESTINATIONS,
+            ErrorCode::TooManyDestinations
+        );
+        
+        vault.allowed_destinations.push(destination);
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(DestinationUpdated {
+            vault: vault.key(),
+            destination,
+            allowed: true,
+            sequence,
+        });
+        
+        log_event!("add_destination", vault = vault.key(), destination = destination);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Remove a withdrawal destination (authority only)
+    /// 
+    /// Removing the last entry leaves the list empty, which allows any
+    /// recipient again.
+    pub fn remove_destination(ctx: Context<UpdateDestinations>, destination: Pubkey) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        require!(
+            vault.allowed_destinations.contains(&destination),
+            ErrorCode::DestinationNotAllowed
+        );
+        
+        vault.allowed_destinations.retain(|allowed| *allowed != destination);
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(DestinationUpdated {
+            vault: vault.key(),
+            destination,
+            allowed: false,
+            sequence,
+        });
+        
+        log_event!("remove_destination", vault = vault.key(), destination = destination);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw with an off-chain authorization from the vault authority
+    /// 
+    /// Anyone may submit the transaction, but it must contain an ed25519
+    /// program instruction, immediately before this one, in which
+    /// `vault.authority` signs `withdrawal_message(vault, amount, expiry, nonce)`.
+    /// 
+    /// An attacker CANNOT:
+    /// - Change the amount, vault or expiry (the message would not match)
+    /// - Replay an authorization (nonce must exceed the last one used)
+    /// - Use an authorization at or after `expiry`, as for `SessionKey::expiry`
+    pub fn withdraw_with_signature(
+        ctx: Context<WithdrawWithSignature>,
+        amount: u64,
+        expiry: i64,
+        nonce: u64,
+    ) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        // ✅ Authorization window
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < expiry, ErrorCode::SignatureExpired);
+        
+        // ✅ Each authorization is usable once, in order
+        require!(nonce > vault.nonce, ErrorCode::BadNonce);
+        
+        // ✅ The authority signed exactly this withdrawal
+        let message = withdrawal_message(&vault.key(), amount, expiry, nonce);
+        verify_ed25519_instruction(
+            &ctx.accounts.instructions.to_account_info(),
+            &vault.authority,
+            &message,
+        )?;
+        
+        require!(
+            vault.balance >= amount,
+            CommonError::InsufficientFunds
+        );
+        
+        // Update state
+        vault.nonce = nonce;
+        vault.balance = vault.balance
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        vault.total_withdrawn = vault.total_withdrawn
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.withdrawal_count = vault.withdrawal_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: vault.authority,
+            amount,
+            remaining_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("withdraw_with_signature", vault = vault.key(), amount = amount, nonce = nonce, balance = vault.balance);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Delegate a capped, expiring allowance to a session key
+    /// 
+    /// The session key can withdraw up to `limit` in total until `expiry`.
+    /// It cannot call anything else: every other instruction still checks
+    /// `vault.authority`.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        limit: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(limit > 0, CommonError::InvalidAmount);
+        
+        let now = Clock::get()?.unix_timestamp;
+        require!(expiry > now, ErrorCode::SessionExpired);
+        
+        let session = &mut ctx.accounts.session;
+        session.vault = ctx.accounts.vault.key();
+        session.session_key = session_key;
+        session.limit = limit;
+        session.spent = 0;
+        session.expiry = expiry;
+        session.bump = ctx.bumps.session;
+        
+        let sequence = ctx.accounts.vault.next_sequence()?;
+        
+        emit!(SessionCreated {
+            vault: session.vault,
+            session_key,
+            limit,
+            expiry,
+            sequence,
+        });
+        
+        log_event!("create_session", session_key = session_key, limit = limit, expiry = expiry);
+        Ok(())
+    }
+
+    /// ✅ SECURE: End a session early and reclaim its rent (authority only)
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let session_key = ctx.accounts.session.session_key;
+        let sequence = ctx.accounts.vault.next_sequence()?;
+        
+        emit!(SessionRevoked {
+            vault: ctx.accounts.vault.key(),
+            session_key,
+            sequence,
+        });
+        
+        log_event!("revoke_session", session_key = session_key);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw with a session key instead of the authority
+    /// 
+    /// A session key CANNOT:
+    /// - Withdraw more than `limit` in total, across any number of calls
+    /// - Withdraw at or after `expiry`
+    /// - Use another vault's session (seeds bind it to this vault and key)
+    pub fn withdraw_via_session(ctx: Context<WithdrawViaSession>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        require!(amount > 0, CommonError::InvalidAmount);
+        
+        let session = &mut ctx.accounts.session;
+        let vault = &mut ctx.accounts.vault;
+        
+        // ✅ Session window
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < session.expiry, ErrorCode::SessionExpired);
+        
+        // ✅ Cumulative cap, not per-call
+        let spent = session.spent
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        require!(spent <= session.limit, ErrorCode::SessionLimitExceeded);
+        
+        require!(
+            vault.balance >= amount,
+            CommonError::InsufficientFunds
+        );
+        
+        // Update state
+        session.spent = spent;
+        vault.balance = vault.balance
+            .checked_sub(amount)
+            .ok_or(CommonError::Underflow)?;
+        vault.total_withdrawn = vault.total_withdrawn
+            .checked_add(amount)
+            .ok_or(CommonError::Overflow)?;
+        vault.withdrawal_count = vault.withdrawal_count
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: session.session_key,
+            amount,
+            remaining_balance: vault.balance,
+            sequence,
+        });
+        
+        log_event!("withdraw_via_session", vault = vault.key(), session_key = session.session_key, amount = amount, spent = session.spent, limit = session.limit);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Appoint a recovery guardian (authority only)
+    /// 
+    /// Replacing the guardian drops any recovery the old one started.
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey, recovery_delay: i64) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        // ✅ The delay is the authority's window to notice and cancel
+        require!(recovery_delay >= MIN_RECOVERY_DELAY, ErrorCode::InvalidRecoveryDelay);
+        
+        let vault = &mut ctx.accounts.vault;
+        vault.guardian = guardian;
+        vault.recovery_delay = recovery_delay;
+        vault.pending_authority = None;
+        vault.recovery_unlock = 0;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(GuardianSet {
+            vault: vault.key(),
+            guardian,
+            recovery_delay,
+            sequence,
+        });
+        
+        log_event!("set_guardian", vault = vault.key(), guardian = guardian, recovery_delay = recovery_delay);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Guardian proposes a new authority, effective after the delay
+    /// 
+    /// Starting again while a recovery is pending replaces it and restarts
+    /// the delay.
+    /// 
+    /// A guardian CANNOT:
+    /// - Take over immediately (finalize waits for `recovery_unlock`)
+    /// - Take over if the authority is still active (it can cancel)
+    pub fn initiate_recovery(ctx: Context<InitiateRecovery>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        let now = Clock::get()?.unix_timestamp;
+        vault.pending_authority = Some(new_authority);
+        vault.recovery_unlock = now
+            .checked_add(vault.recovery_delay)
+            .ok_or(CommonError::Overflow)?;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(RecoveryInitiated {
+            vault: vault.key(),
+            guardian: vault.guardian,
+            new_authority,
+            recovery_unlock: vault.recovery_unlock,
+            sequence,
+        });
+        
+        log_event!("initiate_recovery", vault = vault.key(), new_authority = new_authority, unlock = vault.recovery_unlock);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Authority cancels a pending recovery
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        let new_authority = vault.pending_authority
+            .take()
+            .ok_or(ErrorCode::NoRecoveryInProgress)?;
+        vault.recovery_unlock = 0;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(RecoveryCancelled {
+            vault: vault.key(),
+            new_authority,
+            sequence,
+        });
+        
+        log_event!("cancel_recovery", vault = vault.key(), new_authority = new_authority);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Hand the vault to the recovered authority once the delay has passed
+    /// 
+    /// Anyone may submit it; the outcome was fixed by `initiate_recovery`.
+    pub fn finalize_recovery(ctx: Context<FinalizeRecovery>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
+        let vault = &mut ctx.accounts.vault;
+        
+        let new_authority = vault.pending_authority
+            .ok_or(ErrorCode::NoRecoveryInProgress)?;
+        
+        // ✅ Timelock: the authority had until now to cancel
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= vault.recovery_unlock, ErrorCode::RecoveryNotReady);
+        
+        let old_authority = vault.authority;
+        vault.authority = new_authority;
+        vault.pending_authority = None;
+        vault.recovery_unlock = 0;
+        
+        let sequence = vault.next_sequence()?;
+        
+        emit!(AuthorityTransferred {
+            vault: vault.key(),
+            old_authority,
+            new_authority,
+            sequence,
+        });
+        
+        log_event!("finalize_recovery", vault = vault.key(), old_authority = old_authority, new_authority = new_authority);
+        Ok(())
+    }
+
     /// ✅ SECURE: Transfer authority to a new owner
     /// 
-    /// Both current and new authority must sign
+    /// Both current and new authority must sign. Any pending recovery is
+    /// dropped: it was proposed against the old authority, and finalizing it
+    /// would take the vault from the new one.
     pub fn transfer_authority(ctx: Context<TransferAuthority>) -> Result<()> {
+        ctx.accounts.vault.check_version()?;
+        
         let vault = &mut ctx.accounts.vault;
         let old_authority = vault.authority;
         
         vault.authority = ctx.accounts.new_authority.key();
+        vault.pending_authority = None;
+        vault.recovery_unlock = 0;
+        
+        let sequence = vault.next_sequence()?;
         
         emit!(AuthorityTransferred {
             vault: vault.key(),
             old_authority,
             new_authority: vault.authority,
+            sequence,
         });
         
-        msg!(
-            "Authority transferred from {} to {}", 
-            old_authority, 
-            vault.authority
-        );
+        log_event!("transfer_authority", vault = vault.key(), old_authority = old_authority, new_authority = vault.authority);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Rewrite a vault left at the previous layout version
+    ///
+    /// The only way out of `VersionMismatch` (authority only). The layout
+    /// has not changed since versioning began, so only the version is
+    /// rewritten; a future `CURRENT_VERSION` bump converts the old fields
+    /// here.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        let from_version = vault.version;
+        check_migratable(from_version)?;
+        
+        vault.version = CURRENT_VERSION;
+        let sequence = vault.next_sequence()?;
+        
+        emit!(AccountMigrated {
+            account: vault.key(),
+            from_version,
+            to_version: vault.version,
+            sequence,
+        });
+        
+        log_event!("migrate_vault", vault = vault.key(), from_version = from_version, to_version = vault.version);
         Ok(())
     }
 }
 
+/// ✅ Only a vault one version behind can be migrated
+///
+/// A current vault fails with `AlreadyMigrated`. Anything older, or written
+/// by a newer deployment, fails with `VersionMismatch`: there is no
+/// conversion for it here.
+fn check_migratable(version: u8) -> Result<()> {
+    require!(version != CURRENT_VERSION, ErrorCode::AlreadyMigrated);
+    require!(
+        version.checked_add(1) == Some(CURRENT_VERSION),
+        ErrorCode::VersionMismatch
+    );
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(
@@ -179,6 +780,152 @@ pub struct Withdraw<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct WithdrawTo<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Only its address is used, checked against allowed_destinations
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDestinations<'info> {
+    // ✅ Only the vault authority can change where funds may go
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithSignature<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    
+    // The authority does not sign the transaction; its ed25519 signature
+    // is checked through the instructions sysvar instead
+    pub submitter: Signer<'info>,
+    
+    /// CHECK: Address-checked instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    // ✅ One session per (vault, key); seeds bind it to both
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SessionKey::INIT_SPACE,
+        seeds = [b"session", vault.key().as_ref(), session_key.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"session", vault.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        has_one = vault
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawViaSession<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    
+    // ✅ SECURE: The session belongs to this vault and to the signing key
+    #[account(
+        mut,
+        seeds = [b"session", vault.key().as_ref(), session_key.key().as_ref()],
+        bump = session.bump,
+        has_one = vault,
+        has_one = session_key
+    )]
+    pub session: Account<'info, SessionKey>,
+    
+    // ✅ The delegated key signs instead of the vault authority
+    pub session_key: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateRecovery<'info> {
+    // ✅ Only the guardian the authority appointed
+    #[account(
+        mut,
+        has_one = guardian @ ErrorCode::NotGuardian
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeRecovery<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
 #[derive(Accounts)]
 pub struct TransferAuthority<'info> {
     #[account(
@@ -194,6 +941,17 @@ pub struct TransferAuthority<'info> {
     pub new_authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::UnauthorizedAuthority
+    )]
+    pub vault: Account<'info, Vault>,
+    
+    pub authority: Signer<'info>,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Vault {
@@ -205,12 +963,69 @@ pub struct Vault {
     pub total_withdrawn: u64,
     /// Number of withdrawals made
     pub withdrawal_count: u64,
+    /// Last nonce used by `withdraw_with_signature`
+    pub nonce: u64,
+    /// Bumped by every mutating instruction and emitted with its event,
+    /// so indexers get a total order per vault and can detect gaps
+    pub sequence: u64,
+    /// Recipients `withdraw_to` may pay; empty means any recipient
+    #[max_len(MAX_ALLOWED_DESTINATIONS)]
+    pub allowed_destinations: Vec<Pubkey>,
+    /// Key that can start a recovery; `Pubkey::default()` means none
+    pub guardian: Pubkey,
+    /// Seconds between `initiate_recovery` and `finalize_recovery`
+    pub recovery_delay: i64,
+    /// Authority a pending recovery will install
+    pub pending_authority: Option<Pubkey>,
+    /// Earliest time the pending recovery can be finalized
+    pub recovery_unlock: i64,
+    /// `CURRENT_VERSION` when written; checked by `check_version`
+    pub version: u8,
+}
+
+impl Vault {
+    /// ✅ Reject vaults written by another program version until migrated
+    pub fn check_version(&self) -> Result<()> {
+        require!(self.version == CURRENT_VERSION, ErrorCode::VersionMismatch);
+        Ok(())
+    }
+    
+    /// Advance `sequence` and return it for this instruction's event
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence
+            .checked_add(1)
+            .ok_or(CommonError::Overflow)?;
+        Ok(self.sequence)
+    }
+    
+    /// An empty whitelist allows any destination
+    pub fn allows_destination(&self, destination: &Pubkey) -> bool {
+        self.allowed_destinations.is_empty() || self.allowed_destinations.contains(destination)
+    }
+}
+
+/// Capped, expiring withdrawal allowance for a key other than the authority
+#[account]
+#[derive(InitSpace)]
+pub struct SessionKey {
+    pub vault: Pubkey,
+    /// Key that signs `withdraw_via_session`
+    pub session_key: Pubkey,
+    /// Most the session can withdraw in total
+    pub limit: u64,
+    /// Withdrawn so far through this session
+    pub spent: u64,
+    /// First unix timestamp at which the session is unusable: it works while
+    /// `now < expiry`. Signed withdrawals treat their `expiry` the same way.
+    pub expiry: i64,
+    pub bump: u8,
 }
 
 #[event]
 pub struct VaultInitialized {
     pub vault: Pubkey,
     pub authority: Pubkey,
+    pub sequence: u64,
 }
 
 #[event]
@@ -219,6 +1034,7 @@ pub struct DepositMade {
     pub depositor: Pubkey,
     pub amount: u64,
     pub new_balance: u64,
+    pub sequence: u64,
 }
 
 #[event]
@@ -227,6 +1043,55 @@ pub struct WithdrawalMade {
     pub authority: Pubkey,
     pub amount: u64,
     pub remaining_balance: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct DestinationUpdated {
+    pub vault: Pubkey,
+    pub destination: Pubkey,
+    pub allowed: bool,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct SessionCreated {
+    pub vault: Pubkey,
+    pub session_key: Pubkey,
+    pub limit: u64,
+    pub expiry: i64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub vault: Pubkey,
+    pub session_key: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct GuardianSet {
+    pub vault: Pubkey,
+    pub guardian: Pubkey,
+    pub recovery_delay: i64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct RecoveryInitiated {
+    pub vault: Pubkey,
+    pub guardian: Pubkey,
+    pub new_authority: Pubkey,
+    pub recovery_unlock: i64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct RecoveryCancelled {
+    pub vault: Pubkey,
+    pub new_authority: Pubkey,
+    pub sequence: u64,
 }
 
 #[event]
@@ -234,20 +1099,50 @@ pub struct AuthorityTransferred {
     pub vault: Pubkey,
     pub old_authority: Pubkey,
     pub new_authority: Pubkey,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    /// The vault rewritten
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub sequence: u64,
 }
 
 #[error_code]
 pub enum ErrorCode {
     #[msg("Unauthorized authority for this vault")]
     UnauthorizedAuthority,
-    #[msg("Insufficient funds in vault")]
-    InsufficientFunds,
-    #[msg("Invalid amount - must be greater than zero")]
-    InvalidAmount,
-    #[msg("Arithmetic overflow")]
-    Overflow,
-    #[msg("Arithmetic underflow")]
-    Underflow,
+    #[msg("Withdrawal authorization has expired")]
+    SignatureExpired,
+    #[msg("Nonce must be greater than the last one used")]
+    BadNonce,
+    #[msg("Missing or mismatched ed25519 signature")]
+    InvalidSignature,
+    #[msg("Destination is not on the vault's whitelist")]
+    DestinationNotAllowed,
+    #[msg("Destination is already whitelisted")]
+    DestinationAlreadyAllowed,
+    #[msg("Destination whitelist is full")]
+    TooManyDestinations,
+    #[msg("Only the vault's guardian can start a recovery")]
+    NotGuardian,
+    #[msg("Recovery delay is below the minimum")]
+    InvalidRecoveryDelay,
+    #[msg("No recovery is in progress")]
+    NoRecoveryInProgress,
+    #[msg("Recovery delay has not passed yet")]
+    RecoveryNotReady,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Withdrawal exceeds the session's remaining limit")]
+    SessionLimitExceeded,
+    #[msg("Account was written by a different program version")]
+    VersionMismatch,
+    #[msg("Account is already at the current version")]
+    AlreadyMigrated,
 }
 
 // ============================================================================
@@ -268,3 +1163,40 @@ pub enum ErrorCode {
 // - Events provide audit trail for monitoring
 // - Explicit balance checks prevent edge cases
 // - Checked arithmetic prevents overflow/underflow
+//
+// Signed withdrawals (withdraw_with_signature):
+// - The ed25519 program verifies the signature; this program checks the
+//   verified instruction is the RIGHT one: key == vault.authority, message
+//   == (vault, amount, expiry, nonce), all offsets inside that instruction
+// - Without the offset-index check, an attacker could point the ed25519
+//   program at bytes in another instruction and pass it a different message
+// - nonce > vault.nonce blocks replay; now < expiry bounds the window
+//
+// Destination whitelist (withdraw_to):
+// - A signer check proves WHO asked, not WHERE the funds go. With a
+//   populated whitelist, a stolen authority key can only pay addresses the
+//   authority chose before the theft
+// - The whitelist is only as strong as the key that edits it: the thief can
+//   call add_destination, so pair it with monitoring of DestinationUpdated
+//   (or a timelock on additions) to get a window to react
+// - Removing the last entry reopens withdrawals to any recipient
+//
+// Guardian recovery (initiate / cancel / finalize_recovery):
+// - For a LOST authority key, not a stolen one: whoever holds the key can
+//   cancel every recovery, so a thief keeps control either way
+// - The delay protects against a compromised guardian. RecoveryInitiated is
+//   public; a live authority has recovery_delay seconds to cancel_recovery
+// - Without the delay, the guardian key alone would be a second authority
+// - set_guardian clears any pending recovery, so a replaced guardian's
+//   proposal cannot be finalized later
+// - transfer_authority clears it too, so a proposal made before the handover
+//   cannot take the vault from its new owner
+//
+// Session keys (withdraw_via_session):
+// - A hot key on a game client or bot can be leaked. Its damage is capped at
+//   limit - spent and ends at expiry; the authority key stays offline
+// - spent is cumulative, so many small withdrawals hit the same cap as one
+//   large one
+// - has_one = session_key plus the [vault, session_key] seeds stop a key
+//   from spending through another key's or another vault's session
+// - revoke_session closes the account, ending the session immediately
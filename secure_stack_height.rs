@@ -0,0 +1,149 @@
+//! # Secure Stack Height Example
+//!
+//! This program demonstrates rejecting a sensitive instruction unless it
+//! runs as a top-level instruction of the transaction, using the runtime's
+//! invoke stack height.
+//!
+//! ## Security Measures
+//! 1. `transfer_authority` calls `get_stack_height()` and requires
+//!    `TRANSACTION_LEVEL_STACK_HEIGHT`, else `UnexpectedStackHeight`
+//! 2. Usual `Signer` + `has_one` checks on the authority
+//!
+//! ## Runtime API
+//! `solana_program::instruction::get_stack_height()` wraps the
+//! `sol_get_stack_height` syscall. It returns how deep the currently
+//! executing instruction is in the invoke stack:
+//! - `TRANSACTION_LEVEL_STACK_HEIGHT` (1): called directly by the
+//!   transaction
+//! - 2: invoked by a top-level program through CPI
+//! - each further nested CPI adds 1, up to the runtime's depth limit
+//!
+//! The syscall is read-only and costs no accounts, unlike the instructions
+//! sysvar check in `secure_introspection`, which needs the sysvar passed in.
+//!
+//! ## Why This Works
+//! Signer privileges survive CPI; the stack height does not lie about it.
+//! Whatever program wraps the call, and however deep, `transfer_authority`
+//! runs at height 2 or more and fails. The wallet prompt the authority
+//! approved therefore names this program whenever the vault changes hands.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKK");
+
+/// ✅ SECURE: Fail unless this instruction was called directly by the
+/// transaction, not through any program's CPI
+fn assert_top_level() -> Result<()> {
+    require_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT, ErrorCode::UnexpectedStackHeight);
+    Ok(())
+}
+
+#[program]
+pub mod secure_stack_height {
+    use super::*;
+
+    /// Create the vault PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Hand the vault to `new_authority`
+    ///
+    /// An attacker CANNOT:
+    /// - Wrap this call in their own program and reuse the authority's signature
+    /// - Nest it deeper behind another program to hide it
+    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        // ✅ SECURE: Direct calls only
+        assert_top_level()?;
+
+        let vault = &mut ctx.accounts.vault;
+        let previous = vault.authority;
+        vault.authority = new_authority;
+
+        emit!(AuthorityTransferred {
+            vault: vault.key(),
+            previous,
+            new_authority,
+        });
+
+        log_event!("transfer_authority", vault = vault.key(), previous = previous, new_authority = new_authority);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    // The vault keeps its original address after a transfer, so bind it by
+    // the stored authority, not by seeds
+    #[account(mut, has_one = authority @ CommonError::Unauthorized)]
+    pub vault: Account<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub vault: Pubkey,
+    pub previous: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Instruction must run at the top level of the transaction")]
+    UnexpectedStackHeight,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the attack from vulnerable_stack_height.rs FAILS here:
+//
+//   [0] attacker_program::claim_points          stack height 1
+//       └─ CPI secure_stack_height::transfer_authority
+//            get_stack_height() = 2 ≠ TRANSACTION_LEVEL_STACK_HEIGHT
+//            → UnexpectedStackHeight
+//
+// Notes:
+// - Compared to secure_introspection: the sysvar check asks "is the
+//   current top-level instruction ours?", which a program that CPIs into
+//   itself could confuse. The stack height is exact at any depth
+// - This blocks composability on purpose: no program can transfer the
+//   vault on a user's behalf. Apply it where that is the intent
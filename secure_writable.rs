@@ -0,0 +1,168 @@
+//! # Secure Writability Example
+//!
+//! This program demonstrates how to make writability part of an
+//! instruction's account validation instead of leaving it to the runtime.
+//!
+//! ## Security Measures
+//! 1. `record` is `Account<'info, Record>` with `#[account(mut)]`
+//! 2. Owner, discriminator, seeds and `has_one = authority` checked by Anchor
+//! 3. No raw `try_borrow_mut_data`: Anchor serializes the account on exit
+//!
+//! ## Why This Works
+//! `#[account(mut)]` makes the generated `try_accounts` check
+//! `is_writable` before the handler runs, failing with `ConstraintMut`
+//! (2000) and naming the account. It also marks the account for
+//! serialization in `exit`, which only happens for `mut` accounts, so a
+//! handler cannot change state that silently never gets written.
+//!
+//! Handlers that must take a raw `AccountInfo` (remaining accounts, optional
+//! accounts, dynamic layouts) get none of this and should check
+//! `is_writable` themselves before borrowing data mutably.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC");
+
+/// ✅ SECURE: What a raw `AccountInfo` handler must do before writing
+pub fn assert_writable(info: &AccountInfo) -> Result<()> {
+    require!(info.is_writable, ErrorCode::AccountNotWritable);
+    Ok(())
+}
+
+#[program]
+pub mod secure_writable {
+    use super::*;
+
+    /// Create the record PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let record = &mut ctx.accounts.record;
+        record.authority = ctx.accounts.authority.key();
+        record.value = 0;
+        record.bump = ctx.bumps.record;
+        Ok(())
+    }
+
+    /// ✅ SECURE: Overwrite the record's value
+    ///
+    /// An attacker CANNOT:
+    /// - Pass `record` read-only and have the handler run anyway
+    /// - Pass another program's account or another authority's record
+    pub fn update(ctx: Context<Update>, value: u64) -> Result<()> {
+        // ✅ SECURE: `mut` already verified; Anchor writes this back on exit
+        ctx.accounts.record.value = value;
+
+        log_event!("update", value = value);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Overwrite the values of records passed as remaining accounts
+    ///
+    /// Anchor validates nothing in `remaining_accounts`, so each one is
+    /// checked by hand, writability first.
+    pub fn update_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateMany<'info>>,
+        value: u64,
+    ) -> Result<()> {
+        for info in ctx.remaining_accounts {
+            // ✅ SECURE: Explicit check a raw AccountInfo needs
+            assert_writable(info)?;
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidRecord);
+
+            let mut record = Record::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                record.authority,
+                ctx.accounts.authority.key(),
+                CommonError::Unauthorized
+            );
+
+            record.value = value;
+            let mut data = info.try_borrow_mut_data()?;
+            record.try_serialize(&mut &mut data[..])?;
+        }
+
+        log_event!("update_many", records = ctx.remaining_accounts.len(), value = value);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Record::INIT_SPACE,
+        seeds = [b"record", authority.key().as_ref()],
+        bump
+    )]
+    pub record: Account<'info, Record>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    // ✅ SECURE: `mut` rejects a read-only account before the handler runs
+    #[account(
+        mut,
+        seeds = [b"record", authority.key().as_ref()],
+        bump = record.bump,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub record: Account<'info, Record>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMany<'info> {
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Record {
+    pub authority: Pubkey,
+    pub value: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Account must be passed writable")]
+    AccountNotWritable,
+    #[msg("Record is not owned by this program")]
+    InvalidRecord,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// update(42) with record passed read-only:
+//
+//   Update::try_accounts
+//     Account<Record>: owner + discriminator   ✓
+//     mut: record.is_writable                  ✗ → ConstraintMut (2000)
+//   handler never runs
+//
+// Notes:
+// - The opposite mistake, passing an account writable that the program
+//   only reads, is not rejected by Anchor and is harmless to this program.
+//   It matters when the program CPIs: a callee inherits the write privilege
+//   for every account the caller passed writable
+// - AccountLoader, Account and UncheckedAccount all honour `mut`;
+//   `remaining_accounts` and `AccountInfo` fields without it honour nothing
+// - is_writable in a CPI can only be downgraded, never upgraded: building a
+//   writable AccountMeta for an account the caller received read-only fails
+//   with PrivilegeEscalation
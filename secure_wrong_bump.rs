@@ -0,0 +1,138 @@
+//! # Secure PDA Bump Example
+//!
+//! This program demonstrates signing for a PDA with the canonical bump
+//! Anchor finds, never a guessed one.
+//!
+//! ## Security Measures
+//! 1. Every instruction constrains the treasury with
+//!    `seeds = [b"treasury", authority]` and a bare `bump`
+//! 2. `withdraw` signs with `ctx.bumps.treasury`, the bump Anchor found
+//!    while checking the address
+//! 3. The treasury must be the signer's own, or `ConstraintSeeds` fails
+//!    before the handler runs
+//!
+//! ## Why This Works
+//! `find_program_address` returns the highest bump that puts the address
+//! off the curve, the same one every client computes. Deposits and
+//! withdrawals therefore always agree on a single address per authority,
+//! whatever that authority's bump turns out to be.
+//!
+//! The treasury holds no data, so there is nowhere to store the bump and
+//! the search runs on every call. Accounts with data should store it at
+//! init and pass `bump = account.bump` instead (see `secure_pda` and
+//! `tests/bump_cost.rs`).
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+pub mod common_errors;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJ");
+
+#[program]
+pub mod secure_wrong_bump {
+    use super::*;
+
+    /// Move lamports from `authority` into its treasury PDA
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        log_event!("deposit", treasury = ctx.accounts.treasury.key(), amount = amount);
+        Ok(())
+    }
+
+    /// ✅ SECURE: Withdraw, signing with the canonical bump
+    ///
+    /// An attacker CANNOT:
+    /// - Pass another authority's treasury (ConstraintSeeds)
+    /// - Make the program sign for a non-canonical treasury address
+    ///
+    /// And no authority is locked out by an unlucky bump.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, CommonError::InvalidAmount);
+        require!(ctx.accounts.treasury.lamports() >= amount, CommonError::InsufficientFunds);
+
+        let authority = ctx.accounts.authority.key();
+        // ✅ SECURE: The bump Anchor found, not a guess
+        let seeds = &[b"treasury".as_ref(), authority.as_ref(), &[ctx.bumps.treasury]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: ctx.accounts.authority.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        log_event!("withdraw", treasury = ctx.accounts.treasury.key(), amount = amount, bump = ctx.bumps.treasury);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, seeds = [b"treasury", authority.key().as_ref()], bump)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    // ✅ SECURE: Canonical address checked, bump exposed as ctx.bumps.treasury
+    #[account(mut, seeds = [b"treasury", authority.key().as_ref()], bump)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Why the failure from vulnerable_wrong_bump.rs CANNOT happen here:
+//
+// UNLUCKY BUMP:
+// -------------
+//   Authority B, canonical bump 253
+//     deposit   → seeds check finds 253, treasury at ["treasury", B] 253
+//     withdraw  → seeds check finds 253 again, ctx.bumps.treasury = 253
+//                 signs with ["treasury", B, 253]        ✓
+//
+// NON-CANONICAL ADDRESS:
+// ----------------------
+//   A bare `bump` only accepts the address find_program_address returns.
+//   ["treasury", C, 254] may be a valid PDA, but it is not the canonical
+//   one when 255 also is, so passing it fails ConstraintSeeds.
+//
+// WHY NOT ACCEPT A BUMP ARGUMENT:
+// -------------------------------
+// `bump = user_supplied` trusts the caller to pick the canonical bump.
+// Any valid lower bump gives the same seeds a second address. Take the
+// bump from ctx.bumps, or from a field this program wrote at init.
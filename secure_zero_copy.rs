@@ -0,0 +1,356 @@
+//! # Secure Zero-Copy Pool Example
+//!
+//! This program runs the `secure_cpi` constant-product swap against a pool
+//! too large to deserialize cheaply: `BigPool` carries `MAX_TIERS` reward
+//! tiers next to its reserves.
+//!
+//! `Account<'info, Pool>` (as in `secure_cpi`) Borsh-deserializes the whole
+//! account onto the stack/heap before the handler runs and serializes it
+//! back afterwards. `AccountLoader<'info, BigPool>` validates owner and
+//! discriminator only; `load()`/`load_mut()` then cast the account's own
+//! bytes to `&BigPool` / `&mut BigPool` in place.
+//!
+//! ## Security Measures
+//! 1. `AccountLoader` still checks program ownership and the discriminator,
+//!    so a zero-copy account cannot be spoofed any more than `Account` can
+//! 2. Pool PDA derived from the traded mints, canonical bump stored on init
+//! 3. `load_mut()` is dropped before any CPI; the token program would
+//!    otherwise fail to borrow the pool account's data
+//! 4. Same checked swap math as `secure_cpi` via `logic::swap_output` and
+//!    `logic::apply_bps`, fee booked outside the reserves
+//!
+//! ## Why This Works
+//! Validation is identical to the `Account<Pool>` version; only the access
+//! path changes. Every field is a fixed-size, `Pod` type laid out `repr(C)`
+//! with explicit padding, so any byte pattern is a valid `BigPool` and the
+//! cast is sound.
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+pub mod common_errors;
+pub mod logic;
+pub mod logging;
+
+use common_errors::CommonError;
+use logging::log_event;
+
+declare_id!("SecureEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE");
+
+/// Number of reward tiers stored inline in `BigPool`
+pub const MAX_TIERS: usize = 64;
+
+/// Highest swap fee `initialize_pool` accepts (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+#[program]
+pub mod secure_zero_copy {
+    use super::*;
+
+    /// Create the zero-copy pool for a mint pair
+    pub fn initialize_pool(ctx: Context<InitializePool>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+
+        // ✅ load_init: the account is fresh, all zeroes, discriminator unset
+        let mut pool = ctx.accounts.pool.load_init()?;
+        pool.authority = ctx.accounts.authority.key();
+        pool.token_in_mint = ctx.accounts.token_in_mint.key();
+        pool.token_out_mint = ctx.accounts.token_out_mint.key();
+        pool.fee_bps = fee_bps;
+        pool.bump = ctx.bumps.pool;
+
+        log_event!("initialize_pool", token_in_mint = pool.token_in_mint, token_out_mint = pool.token_out_mint);
+        Ok(())
+    }
+
+    /// ✅ SECURE: `secure_cpi::swap_tokens`, on a zero-copy pool
+    pub fn swap_tokens(
+        ctx: Context<SwapTokens>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(amount_in > 0, CommonError::InvalidAmount);
+        require!(min_amount_out > 0, ErrorCode::InvalidMinOutput);
+        require!(
+            ctx.accounts.user_token_in.amount >= amount_in,
+            CommonError::InsufficientFunds
+        );
+
+        // ✅ CEI: all state changes happen inside this scope, and the
+        // RefMut it holds is released before the CPIs below
+        let (amount_out, in_mint, out_mint, bump) = {
+            let mut pool = ctx.accounts.pool.load_mut()?;
+
+            let (net_in, fee) = pool.split_fee(amount_in)?;
+            let amount_out = logic::swap_output(net_in, pool.reserve_in, pool.reserve_out)?;
+            require!(amount_out >= min_amount_out, CommonError::SlippageExceeded);
+
+            pool.reserve_in = pool.reserve_in
+                .checked_add(net_in)
+                .ok_or(CommonError::Overflow)?;
+            pool.fees_collected = pool.fees_collected
+                .checked_add(fee)
+                .ok_or(CommonError::Overflow)?;
+            pool.reserve_out = pool.reserve_out
+                .checked_sub(amount_out)
+                .ok_or(CommonError::Underflow)?;
+            pool.total_volume = pool.total_volume
+                .checked_add(amount_in)
+                .ok_or(CommonError::Overflow)?;
+
+            require!(
+                pool.reserve_in > 0 && pool.reserve_out > 0,
+                CommonError::InvariantViolation
+            );
+
+            (amount_out, pool.token_in_mint, pool.token_out_mint, pool.bump)
+        };
+
+        let cpi_accounts_in = Transfer {
+            from: ctx.accounts.user_token_in.to_account_info(),
+            to: ctx.accounts.pool_token_in.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts_in),
+            amount_in,
+        )?;
+
+        // ✅ SECURE: Pool PDA signs with its stored bump
+        let pool_seeds = &[
+            b"big_pool".as_ref(),
+            in_mint.as_ref(),
+            out_mint.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds = &[&pool_seeds[..]];
+
+        let cpi_accounts_out = Transfer {
+            from: ctx.accounts.pool_token_out.to_account_info(),
+            to: ctx.accounts.user_token_out.to_account_info(),
+            authority: ctx.accounts.pool.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts_out,
+                signer_seeds,
+            ),
+            amount_out,
+        )?;
+
+        emit!(SwapExecuted {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_in,
+            amount_out,
+        });
+
+        log_event!("swap", amount_in = amount_in, amount_out = amount_out);
+        Ok(())
+    }
+
+    /// Set one reward tier (pool authority only)
+    ///
+    /// Touches 16 bytes of the account; nothing else is read or written.
+    pub fn set_tier(
+        ctx: Context<SetTier>,
+        index: u8,
+        min_volume: u64,
+        bonus_bps: u16,
+    ) -> Result<()> {
+        let index = index as usize;
+        require!(index < MAX_TIERS, ErrorCode::TierOutOfRange);
+        require!(bonus_bps as u64 <= logic::BPS_DENOMINATOR, ErrorCode::InvalidBonus);
+
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        pool.tiers[index].min_volume = min_volume;
+        pool.tiers[index].bonus_bps = bonus_bps;
+
+        log_event!("set_tier", index = index, min_volume = min_volume, bonus_bps = bonus_bps);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BigPool::LEN,
+        seeds = [
+            b"big_pool",
+            token_in_mint.key().as_ref(),
+            token_out_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool: AccountLoader<'info, BigPool>,
+
+    pub token_in_mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = token_out_mint.key() != token_in_mint.key() @ CommonError::MintMismatch
+    )]
+    pub token_out_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SwapTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_in.owner == user.key() @ CommonError::InvalidOwner
+    )]
+    pub user_token_in: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_out.owner == user.key() @ CommonError::InvalidOwner
+    )]
+    pub user_token_out: Account<'info, TokenAccount>,
+
+    // ✅ SECURE: Seeds over the user's mints prove this is THE pool for
+    // that pair, so the mints need no separate comparison
+    #[account(
+        mut,
+        seeds = [
+            b"big_pool",
+            user_token_in.mint.as_ref(),
+            user_token_out.mint.as_ref()
+        ],
+        bump = pool.load()?.bump
+    )]
+    pub pool: AccountLoader<'info, BigPool>,
+
+    #[account(
+        mut,
+        constraint = pool_token_in.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_in.mint == user_token_in.mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_in: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = pool_token_out.owner == pool.key() @ CommonError::InvalidOwner,
+        constraint = pool_token_out.mint == user_token_out.mint @ CommonError::MintMismatch
+    )]
+    pub pool_token_out: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetTier<'info> {
+    #[account(
+        mut,
+        has_one = authority @ CommonError::Unauthorized
+    )]
+    pub pool: AccountLoader<'info, BigPool>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Reward tier, stored inline in `BigPool`
+#[zero_copy]
+pub struct RewardTier {
+    /// Volume at which the tier applies
+    pub min_volume: u64,
+    /// Bonus in basis points
+    pub bonus_bps: u16,
+    pub _padding: [u8; 6],
+}
+
+/// `secure_cpi::Pool` plus `MAX_TIERS` reward tiers, read in place
+///
+/// Field order keeps every field naturally aligned; `_padding` makes the
+/// implicit tail padding explicit so the struct is `Pod`.
+#[account(zero_copy)]
+pub struct BigPool {
+    pub authority: Pubkey,
+    pub token_in_mint: Pubkey,
+    pub token_out_mint: Pubkey,
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+    pub total_volume: u64,
+    /// Fees taken from swaps, held outside the reserves
+    pub fees_collected: u64,
+    pub fee_bps: u16,
+    pub bump: u8,
+    pub _padding: [u8; 5],
+    pub tiers: [RewardTier; MAX_TIERS],
+}
+
+impl BigPool {
+    /// Account size without the discriminator
+    pub const LEN: usize = std::mem::size_of::<BigPool>();
+
+    /// Split `amount_in` into the part that enters the reserves and the fee
+    pub fn split_fee(&self, amount_in: u64) -> Result<(u64, u64)> {
+        let fee = logic::apply_bps(amount_in, self.fee_bps)?;
+        let net_in = amount_in
+            .checked_sub(fee)
+            .ok_or(CommonError::Underflow)?;
+        Ok((net_in, fee))
+    }
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid minimum output amount")]
+    InvalidMinOutput,
+    #[msg("Fee exceeds MAX_FEE_BPS")]
+    FeeTooHigh,
+    #[msg("Tier index exceeds MAX_TIERS")]
+    TierOutOfRange,
+    #[msg("Tier bonus exceeds 10_000 bps")]
+    InvalidBonus,
+}
+
+// ============================================================================
+// SECURITY ANALYSIS
+// ============================================================================
+//
+// Account<Pool> vs AccountLoader<BigPool>:
+//
+//                      Account<'info, Pool>        AccountLoader<'info, BigPool>
+//   validation         owner + discriminator       owner + discriminator
+//   on entry           Borsh-decode every field    nothing
+//   access             owned copy (stack / heap)   &/&mut into account data
+//   on exit            Borsh-encode, write back    nothing (already in place)
+//   cost               O(account size), every ix   O(fields touched)
+//
+// Stack and heap:
+// - SBF frames are 4 KiB. A 1 KiB+ struct deserialized by value can blow
+//   the stack; Anchor then needs Box<Account<..>>, which moves the copy to
+//   the 32 KiB heap instead. Zero-copy never makes the copy at all
+// - Accounts above 10 KiB cannot be created through the system program
+//   CPI that `init` uses; zero-copy is the only practical way to use them
+//   (created client-side, then `#[account(zero)]` + load_init)
+//
+// Costs of zero-copy:
+// - Layout is the ABI: reordering or resizing fields breaks existing
+//   accounts. Add fields by consuming `_padding` or with a migration
+// - No Vec, String or Option: only fixed-size Pod types
+// - load_mut() holds a RefMut on the account data. Any CPI that includes
+//   the account (here: the pool signs the outbound transfer) must run
+//   after it is dropped, which is why swap_tokens scopes it
+// - Alignment: a u64 after a u8 without explicit padding would not derive
+//   Pod, so the compiler catches most layout mistakes
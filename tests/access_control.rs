@@ -0,0 +1,218 @@
+//! # Access Control Tests
+//!
+//! `solana-program-test` scenarios for the role checks in `secure_overflow`:
+//! a non-admin granted `Pauser` can pause and unpause swaps but fails to
+//! queue a reward rate change with `MissingRole`, only an `Admin` can grant
+//! or revoke roles, and the last `Admin` cannot be revoked.
+//!
+//! ```bash
+//! cargo test --test access_control
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, TestEnv};
+use secure_overflow::access_control::{AccessControl, AccessControlError, Role};
+use secure_overflow::AdminAction;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const RESERVE: u64 = 1_000_000;
+const RATE: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    /// Pool authority, and the only `Admin` after setup
+    authority: Keypair,
+    /// Holds no role until a scenario grants one
+    operator: Keypair,
+    pool: Pubkey,
+    access_control: Pubkey,
+}
+
+/// 1M / 1M pool paying `RATE`, with `initialize_access_control` already run
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_overflow", secure_overflow::ID, processor!(secure_overflow::entry));
+
+    let authority = Keypair::new();
+    let pool = Pubkey::new_unique();
+    let state = secure_overflow::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        reward_rate: RATE,
+        ..common::overflow_pool(authority.pubkey())
+    };
+    add_anchor_account(&mut program_test, pool, secure_overflow::ID, &state);
+
+    let env = TestEnv::start(program_test).await;
+    let (access_control, _) =
+        Pubkey::find_program_address(&[b"access_control", pool.as_ref()], &secure_overflow::ID);
+    let mut setup = Setup { env, authority, operator: Keypair::new(), pool, access_control };
+
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::InitializeAccessControl {
+            access_control,
+            pool,
+            authority: setup.authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::InitializeAccessControl {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+    setup
+}
+
+async fn grant_role(setup: &mut Setup, admin: &Keypair, role: Role, key: Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::UpdateRoles { access_control: setup.access_control, admin: admin.pubkey() }
+            .to_account_metas(None),
+        data: secure_overflow::instruction::GrantRole { role, key }.data(),
+    };
+    setup.env.send(&[ix], &[admin]).await
+}
+
+async fn revoke_role(setup: &mut Setup, admin: &Keypair, role: Role, key: Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::UpdateRoles { access_control: setup.access_control, admin: admin.pubkey() }
+            .to_account_metas(None),
+        data: secure_overflow::instruction::RevokeRole { role, key }.data(),
+    };
+    setup.env.send(&[ix], &[admin]).await
+}
+
+async fn set_paused(setup: &mut Setup, signer: &Keypair, paused: bool) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::SetPaused {
+            pool: setup.pool,
+            access_control: setup.access_control,
+            authority: signer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::SetPaused { paused }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn queue_reward_rate(setup: &mut Setup, signer: &Keypair, new_rate: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::QueueAdminAction {
+            pool: setup.pool,
+            access_control: setup.access_control,
+            authority: signer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::QueueAdminAction { action: AdminAction::SetRewardRate { new_rate } }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn swap(setup: &mut Setup) -> Result<(), TransactionError> {
+    let user = Keypair::new();
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Swap { pool: setup.pool, user: user.pubkey() }.to_account_metas(None),
+        data: secure_overflow::instruction::Swap { amount_in: 1_000, min_amount_out: 1 }.data(),
+    };
+    setup.env.send(&[ix], &[&user]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_overflow::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+async fn roles(setup: &mut Setup) -> AccessControl {
+    setup.env.fetch(setup.access_control).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn pauser_can_pause_but_not_change_rates() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let operator = setup.operator.insecure_clone();
+
+    grant_role(&mut setup, &authority, Role::Pauser, operator.pubkey()).await.unwrap();
+
+    // ✅ Pausing stops swaps
+    set_paused(&mut setup, &operator, true).await.unwrap();
+    assert!(pool_state(&mut setup).await.paused);
+    let err = swap(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(secure_overflow::ErrorCode::PoolPaused));
+
+    // ❌ The same key cannot touch the reward rate
+    let err = queue_reward_rate(&mut setup, &operator, u64::MAX).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+    assert_eq!(pool_state(&mut setup).await.pending_action, None);
+
+    set_paused(&mut setup, &operator, false).await.unwrap();
+    swap(&mut setup).await.unwrap();
+}
+
+#[tokio::test]
+async fn admin_passes_every_role_check() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    queue_reward_rate(&mut setup, &authority, 2 * RATE).await.unwrap();
+    set_paused(&mut setup, &authority, true).await.unwrap();
+
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.pending_action, Some(AdminAction::SetRewardRate { new_rate: 2 * RATE }));
+    assert!(pool.paused);
+}
+
+#[tokio::test]
+async fn non_admin_cannot_grant_roles() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let operator = setup.operator.insecure_clone();
+
+    // A pauser cannot promote itself
+    grant_role(&mut setup, &authority, Role::Pauser, operator.pubkey()).await.unwrap();
+    let err = grant_role(&mut setup, &operator, Role::RateSetter, operator.pubkey()).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+
+    let err = queue_reward_rate(&mut setup, &operator, u64::MAX).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+    assert_eq!(roles(&mut setup).await.grants.len(), 2);
+}
+
+#[tokio::test]
+async fn revoked_pauser_cannot_pause_and_last_admin_stays() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let operator = setup.operator.insecure_clone();
+
+    grant_role(&mut setup, &authority, Role::Pauser, operator.pubkey()).await.unwrap();
+    revoke_role(&mut setup, &authority, Role::Pauser, operator.pubkey()).await.unwrap();
+
+    let err = set_paused(&mut setup, &operator, true).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+    assert!(!pool_state(&mut setup).await.paused);
+
+    // ✅ Revoking the only admin would lock the role list forever
+    let err = revoke_role(&mut setup, &authority, Role::Admin, authority.pubkey()).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::LastAdmin));
+    assert!(roles(&mut setup).await.has_role(Role::Admin, &authority.pubkey()));
+}
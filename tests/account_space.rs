@@ -0,0 +1,393 @@
+//! # Account Space Tests
+//!
+//! Serializes each `#[account]` struct with every `String`, `Vec` and
+//! `Option` at its cap and checks the result is exactly `8 + INIT_SPACE`
+//! bytes, then deserializes it back. An `INIT_SPACE` that under-counts
+//! means `init` allocates an account too small for a full struct, and the
+//! instruction that fills it fails at exit with `AccountDidNotSerialize`.
+//! Plain `#[test]`s, no validator.
+//!
+//! ```bash
+//! cargo test --test account_space
+//! ```
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, Space};
+use solana_sdk::pubkey::Pubkey;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Serialize `account`, assert it fills exactly `8 + T::INIT_SPACE`, and
+/// check it deserializes back to the same bytes
+fn assert_max_size<T: AccountSerialize + AccountDeserialize + Space>(name: &str, account: &T) {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+
+    let space = 8 + T::INIT_SPACE;
+    assert_eq!(
+        data.len(),
+        space,
+        "{name}: serialized {} bytes at max size, but `init` allocates 8 + INIT_SPACE = {space}",
+        data.len(),
+    );
+
+    let decoded = T::try_deserialize(&mut data.as_slice()).unwrap();
+    let mut round_trip = Vec::new();
+    decoded.try_serialize(&mut round_trip).unwrap();
+    assert_eq!(round_trip, data, "{name}: round trip changed the serialized bytes");
+}
+
+fn keys(count: usize) -> Vec<Pubkey> {
+    (0..count).map(|_| Pubkey::new_unique()).collect()
+}
+
+/// `max_len` counts bytes, so a full name is `len` bytes of UTF-8
+fn full_name(len: usize) -> String {
+    "v".repeat(len)
+}
+
+// ============================================================================
+// secure_pda
+// ============================================================================
+
+#[test]
+fn pda_vault_with_32_byte_name() {
+    assert_max_size(
+        "secure_pda::Vault",
+        &secure_pda::Vault {
+            authority: Pubkey::new_unique(),
+            balance: u64::MAX,
+            name: full_name(32),
+            bump: u8::MAX,
+            created_at: i64::MAX,
+            sequence: u64::MAX,
+            version: u8::MAX,
+        },
+    );
+}
+
+#[test]
+fn pda_vault_with_multibyte_name() {
+    // 16 two-byte characters: 32 bytes, what `vault_name.len() <= 32` allows
+    assert_max_size(
+        "secure_pda::Vault",
+        &secure_pda::Vault {
+            authority: Pubkey::new_unique(),
+            balance: 0,
+            name: "é".repeat(16),
+            bump: 0,
+            created_at: 0,
+            sequence: 0,
+            version: 0,
+        },
+    );
+}
+
+#[test]
+fn pda_sub_vault_with_32_byte_name() {
+    assert_max_size(
+        "secure_pda::SubVault",
+        &secure_pda::SubVault {
+            authority: Pubkey::new_unique(),
+            parent: Pubkey::new_unique(),
+            balance: u64::MAX,
+            name: full_name(32),
+            bump: u8::MAX,
+            created_at: i64::MAX,
+            sequence: u64::MAX,
+        },
+    );
+}
+
+#[test]
+fn pda_registry_with_20_full_names() {
+    // MAX_REGISTRY_ENTRIES names of 32 bytes each
+    assert_max_size(
+        "secure_pda::VaultRegistry",
+        &secure_pda::VaultRegistry {
+            authority: Pubkey::new_unique(),
+            names: (0..20).map(|_| full_name(32)).collect(),
+            bump: u8::MAX,
+        },
+    );
+}
+
+// ============================================================================
+// secure_signer
+// ============================================================================
+
+#[test]
+fn signer_vault_with_full_whitelist_and_pending_recovery() {
+    assert_max_size(
+        "secure_signer::Vault",
+        &secure_signer::Vault {
+            authority: Pubkey::new_unique(),
+            balance: u64::MAX,
+            total_withdrawn: u64::MAX,
+            withdrawal_count: u64::MAX,
+            nonce: u64::MAX,
+            sequence: u64::MAX,
+            allowed_destinations: keys(secure_signer::MAX_ALLOWED_DESTINATIONS),
+            guardian: Pubkey::new_unique(),
+            recovery_delay: i64::MAX,
+            pending_authority: Some(Pubkey::new_unique()),
+            recovery_unlock: i64::MAX,
+            version: u8::MAX,
+        },
+    );
+}
+
+#[test]
+fn signer_session_key() {
+    assert_max_size(
+        "secure_signer::SessionKey",
+        &secure_signer::SessionKey {
+            vault: Pubkey::new_unique(),
+            session_key: Pubkey::new_unique(),
+            limit: u64::MAX,
+            spent: u64::MAX,
+            expiry: i64::MAX,
+            bump: u8::MAX,
+        },
+    );
+}
+
+// ============================================================================
+// secure_cpi
+// ============================================================================
+
+#[test]
+fn cpi_vault() {
+    assert_max_size(
+        "secure_cpi::Vault",
+        &secure_cpi::Vault {
+            authority: Pubkey::new_unique(),
+            balance: u64::MAX,
+            total_deposited: u64::MAX,
+            total_withdrawn: u64::MAX,
+            deposit_count: u64::MAX,
+            bump: u8::MAX,
+            locked: true,
+            surplus: u64::MAX,
+            sequence: u64::MAX,
+            permit_nonce: u64::MAX,
+            recent_deposit_keys: [[u8::MAX; 32]; secure_cpi::RECENT_DEPOSIT_KEYS],
+            next_deposit_key: u8::MAX,
+            frozen: true,
+            freeze_reason: u8::MAX,
+            frozen_allows_deposits: true,
+            version: secure_cpi::CURRENT_VERSION,
+        },
+    );
+}
+
+#[test]
+fn cpi_pool_with_each_curve() {
+    for curve in [secure_cpi::CurveType::ConstantProduct, secure_cpi::CurveType::StableSwap] {
+        assert_max_size(
+            "secure_cpi::Pool",
+            &secure_cpi::Pool {
+                authority: Pubkey::new_unique(),
+                token_in_mint: Pubkey::new_unique(),
+                token_out_mint: Pubkey::new_unique(),
+                reserve_in: u64::MAX,
+                reserve_out: u64::MAX,
+                total_volume: u64::MAX,
+                fee_bps: u16::MAX,
+                fees_collected: u64::MAX,
+                curve,
+                amp: u64::MAX,
+                lp_supply: u64::MAX,
+                price_cumulative: u128::MAX,
+                last_twap_update: i64::MAX,
+                bump: u8::MAX,
+                sequence: u64::MAX,
+                pending_action: Some(secure_cpi::AdminAction::SetFee { fee_bps: u16::MAX }),
+                action_ready_at: i64::MAX,
+                version: secure_cpi::CURRENT_VERSION,
+            },
+        );
+    }
+}
+
+#[test]
+fn cpi_full_program_whitelist() {
+    assert_max_size(
+        "secure_cpi::ProgramWhitelist",
+        &secure_cpi::ProgramWhitelist {
+            authority: Pubkey::new_unique(),
+            allowed: keys(secure_cpi::MAX_WHITELISTED_PROGRAMS),
+            bump: u8::MAX,
+        },
+    );
+}
+
+#[test]
+fn cpi_lp_position_and_twap_snapshot() {
+    assert_max_size(
+        "secure_cpi::LpPosition",
+        &secure_cpi::LpPosition {
+            owner: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            shares: u64::MAX,
+            bump: u8::MAX,
+        },
+    );
+    assert_max_size(
+        "secure_cpi::TwapSnapshot",
+        &secure_cpi::TwapSnapshot {
+            owner: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            price_cumulative: u128::MAX,
+            timestamp: i64::MAX,
+            bump: u8::MAX,
+        },
+    );
+}
+
+// ============================================================================
+// secure_matching
+// ============================================================================
+
+#[test]
+fn matching_pool_with_all_lock_tiers_set() {
+    let tier = secure_matching::LockTier { min_duration: i64::MAX, multiplier_bps: u16::MAX };
+    assert_max_size(
+        "secure_matching::Pool",
+        &secure_matching::Pool {
+            authority: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            reward_mint: Pubkey::new_unique(),
+            reward_vault: Pubkey::new_unique(),
+            total_deposits: u64::MAX,
+            total_shares: u64::MAX,
+            total_staked: u64::MAX,
+            min_stake_duration: i64::MAX,
+            early_exit_penalty_bps: u16::MAX,
+            max_total_deposits: u64::MAX,
+            per_user_cap: u64::MAX,
+            total_rewards_funded: u64::MAX,
+            reward_rate: u64::MAX,
+            acc_reward_per_share: u128::MAX,
+            last_reward_time: i64::MAX,
+            lock_tiers: [tier; secure_matching::MAX_LOCK_TIERS],
+            bump: u8::MAX,
+            pending_action: Some(secure_matching::AdminAction::SetCap { max_total_deposits: u64::MAX }),
+            action_ready_at: i64::MAX,
+            version: secure_matching::CURRENT_VERSION,
+        },
+    );
+}
+
+#[test]
+fn matching_staking_account() {
+    assert_max_size(
+        "secure_matching::StakingAccount",
+        &secure_matching::StakingAccount {
+            owner: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            amount: u64::MAX,
+            pending_rewards: u64::MAX,
+            total_claimed: u64::MAX,
+            last_stake_time: i64::MAX,
+            lock_duration: i64::MAX,
+            multiplier_bps: u16::MAX,
+            reward_debt: u128::MAX,
+            shares: u64::MAX,
+            total_deposited: u64::MAX,
+            bump: u8::MAX,
+            version: secure_matching::CURRENT_VERSION,
+        },
+    );
+}
+
+// ============================================================================
+// secure_overflow
+// ============================================================================
+
+#[test]
+fn overflow_vault_pool_and_staking_account() {
+    assert_max_size(
+        "secure_overflow::Vault",
+        &secure_overflow::Vault {
+            authority: Pubkey::new_unique(),
+            balance: u64::MAX,
+            total_deposited: u64::MAX,
+            total_withdrawn: u64::MAX,
+            overflow_mode: secure_overflow::OverflowMode::Saturate,
+            version: u8::MAX,
+        },
+    );
+    assert_max_size(
+        "secure_overflow::Pool",
+        &secure_overflow::Pool {
+            authority: Pubkey::new_unique(),
+            reserve_in: u64::MAX,
+            reserve_out: u64::MAX,
+            reward_rate: u64::MAX,
+            rate_updated_at: i64::MAX,
+            reward_index: u128::MAX,
+            paused: true,
+            pending_action: Some(secure_overflow::AdminAction::SetRewardRate { new_rate: u64::MAX }),
+            action_ready_at: i64::MAX,
+            version: u8::MAX,
+        },
+    );
+    assert_max_size(
+        "secure_overflow::StakingAccount",
+        &secure_overflow::StakingAccount {
+            owner: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            amount: u64::MAX,
+            start_time: i64::MAX,
+            pending_rewards: u64::MAX,
+            pool_balance: u64::MAX,
+            last_accrual_time: i64::MAX,
+            accumulated_remainder: u128::MAX,
+            reward_index: u128::MAX,
+            version: u8::MAX,
+        },
+    );
+}
+
+// ============================================================================
+// access_control
+// ============================================================================
+
+#[test]
+fn access_control_with_every_grant_slot_used() {
+    use secure_overflow::access_control::{AccessControl, Role, RoleGrant, MAX_ROLE_GRANTS};
+
+    assert_max_size(
+        "access_control::AccessControl",
+        &AccessControl {
+            scope: Pubkey::new_unique(),
+            grants: keys(MAX_ROLE_GRANTS)
+                .into_iter()
+                .map(|key| RoleGrant { role: Role::FeeCollector, key })
+                .collect(),
+            bump: u8::MAX,
+        },
+    );
+}
+
+// ============================================================================
+// secure_realloc
+// ============================================================================
+
+#[test]
+fn realloc_space_for_matches_serialized_length() {
+    // No INIT_SPACE: the registry grows, so `space_for` is the size to check
+    let mut registry = secure_realloc::VaultRegistry {
+        authority: Pubkey::new_unique(),
+        bump: u8::MAX,
+        names: Vec::new(),
+    };
+    for count in 0..=4 {
+        let mut data = Vec::new();
+        registry.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), registry.space_for(), "secure_realloc::VaultRegistry with {count} names");
+        registry.names.push(full_name(secure_realloc::MAX_NAME_LEN));
+    }
+}
@@ -0,0 +1,440 @@
+//! # Account Version Tests
+//!
+//! Every `Vault`, `Pool` and `StakingAccount` in `secure_cpi`,
+//! `secure_matching`, `secure_overflow`, `secure_signer` and `secure_pda`
+//! records the `CURRENT_VERSION` it was written at. Plain `#[test]`s check
+//! `check_version` against the versions either side of each constant, as if
+//! it had just been bumped. The `solana-program-test` scenarios show a stale
+//! `secure_cpi` account rejected with `VersionMismatch` until
+//! `migrate_vault` / `migrate_pool` rewrite it; migrating an account already
+//! at the current version fails with `AlreadyMigrated`. Each of the other
+//! programs migrates its own accounts the same way.
+//!
+//! ```bash
+//! cargo test --test account_version
+//! ```
+
+mod common;
+
+use anchor_lang::{prelude::Error, InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, events, TestEnv};
+use secure_cpi::{AdminAction, FREEZE_REASON_INCIDENT};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+/// What an account written before the constant was bumped still holds
+const STALE: u8 = secure_cpi::CURRENT_VERSION - 1;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn cpi_vault(authority: Pubkey, bump: u8, version: u8) -> secure_cpi::Vault {
+    secure_cpi::Vault { version, ..common::cpi_vault(authority, 0, bump) }
+}
+
+fn cpi_pool(authority: Pubkey, mints: (Pubkey, Pubkey), bump: u8, version: u8) -> secure_cpi::Pool {
+    secure_cpi::Pool { version, ..common::cpi_pool(authority, mints, bump) }
+}
+
+fn matching_pool(version: u8) -> secure_matching::Pool {
+    let pool = common::matching_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        255,
+    );
+    secure_matching::Pool { version, ..pool }
+}
+
+fn matching_staking(version: u8) -> secure_matching::StakingAccount {
+    secure_matching::StakingAccount {
+        version,
+        ..common::matching_stake(Pubkey::new_unique(), Pubkey::new_unique(), 0, 255)
+    }
+}
+
+fn overflow_vault(version: u8) -> secure_overflow::Vault {
+    secure_overflow::Vault {
+        authority: Pubkey::new_unique(),
+        balance: 0,
+        total_deposited: 0,
+        total_withdrawn: 0,
+        overflow_mode: secure_overflow::OverflowMode::Revert,
+        version,
+    }
+}
+
+fn overflow_pool(version: u8) -> secure_overflow::Pool {
+    secure_overflow::Pool { version, ..common::overflow_pool(Pubkey::new_unique()) }
+}
+
+fn overflow_stake(version: u8) -> secure_overflow::StakingAccount {
+    secure_overflow::StakingAccount {
+        version,
+        ..common::overflow_stake(Pubkey::new_unique(), Pubkey::new_unique(), 0, 0)
+    }
+}
+
+fn signer_vault(version: u8) -> secure_signer::Vault {
+    secure_signer::Vault {
+        authority: Pubkey::new_unique(),
+        balance: 0,
+        total_withdrawn: 0,
+        withdrawal_count: 0,
+        nonce: 0,
+        sequence: 0,
+        allowed_destinations: Vec::new(),
+        guardian: Pubkey::default(),
+        recovery_delay: 0,
+        pending_authority: None,
+        recovery_unlock: 0,
+        version,
+    }
+}
+
+fn pda_vault(version: u8) -> secure_pda::Vault {
+    secure_pda::Vault { version, ..common::pda_vault(Pubkey::new_unique(), "savings", 0, 255) }
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+    pool: Pubkey,
+}
+
+/// A vault and a pool owned by one authority, both written at `version`
+async fn setup(version: u8) -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let authority = Keypair::new();
+
+    let (vault, vault_bump) =
+        Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref()], &secure_cpi::ID);
+    let state = cpi_vault(authority.pubkey(), vault_bump, version);
+    add_anchor_account(&mut program_test, vault, secure_cpi::ID, &state);
+
+    let mints = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, pool_bump) =
+        Pubkey::find_program_address(&[b"pool", mints.0.as_ref(), mints.1.as_ref()], &secure_cpi::ID);
+    let state = cpi_pool(authority.pubkey(), mints, pool_bump, version);
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &state);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, vault, pool }
+}
+
+fn freeze_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SetVaultFreeze { authority: setup.authority.pubkey(), vault: setup.vault }
+            .to_account_metas(None),
+        data: secure_cpi::instruction::FreezeVault { reason: FREEZE_REASON_INCIDENT, allow_deposits: false }.data(),
+    }
+}
+
+fn queue_fee_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::QueueAdminAction { pool: setup.pool, authority: setup.authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_cpi::instruction::QueueAdminAction { action: AdminAction::SetFee { fee_bps: 50 } }.data(),
+    }
+}
+
+fn migrate_vault_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::MigrateVault { authority: setup.authority.pubkey(), vault: setup.vault }
+            .to_account_metas(None),
+        data: secure_cpi::instruction::MigrateVault {}.data(),
+    }
+}
+
+fn migrate_pool_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::MigratePool { pool: setup.pool, authority: setup.authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_cpi::instruction::MigratePool {}.data(),
+    }
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), TransactionError> {
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+fn version_mismatch() -> TransactionError {
+    custom(secure_cpi::ErrorCode::VersionMismatch)
+}
+
+// ============================================================================
+// CHECK_VERSION
+// ============================================================================
+
+#[test]
+fn only_the_current_version_passes() {
+    let current = secure_cpi::CURRENT_VERSION;
+    let authority = Pubkey::new_unique();
+    let mints = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert!(cpi_vault(authority, 255, current).check_version().is_ok());
+    assert!(cpi_pool(authority, mints, 255, current).check_version().is_ok());
+    assert!(matching_pool(secure_matching::CURRENT_VERSION).check_version().is_ok());
+    assert!(matching_staking(secure_matching::CURRENT_VERSION).check_version().is_ok());
+
+    // One behind (written before a bump) and one ahead (a newer deployment)
+    for version in [current - 1, current + 1] {
+        let expected = Error::from(secure_cpi::ErrorCode::VersionMismatch);
+        assert_eq!(cpi_vault(authority, 255, version).check_version().unwrap_err(), expected);
+        assert_eq!(cpi_pool(authority, mints, 255, version).check_version().unwrap_err(), expected);
+    }
+    for version in [secure_matching::CURRENT_VERSION - 1, secure_matching::CURRENT_VERSION + 1] {
+        let expected = Error::from(secure_matching::ErrorCode::VersionMismatch);
+        assert_eq!(matching_pool(version).check_version().unwrap_err(), expected);
+        assert_eq!(matching_staking(version).check_version().unwrap_err(), expected);
+    }
+}
+
+#[test]
+fn only_the_current_version_passes_outside_secure_cpi() {
+    assert!(overflow_vault(secure_overflow::CURRENT_VERSION).check_version().is_ok());
+    assert!(overflow_pool(secure_overflow::CURRENT_VERSION).check_version().is_ok());
+    assert!(overflow_stake(secure_overflow::CURRENT_VERSION).check_version().is_ok());
+    assert!(signer_vault(secure_signer::CURRENT_VERSION).check_version().is_ok());
+    assert!(pda_vault(secure_pda::CURRENT_VERSION).check_version().is_ok());
+
+    for version in [secure_overflow::CURRENT_VERSION - 1, secure_overflow::CURRENT_VERSION + 1] {
+        let expected = Error::from(secure_overflow::ErrorCode::VersionMismatch);
+        assert_eq!(overflow_vault(version).check_version().unwrap_err(), expected);
+        assert_eq!(overflow_pool(version).check_version().unwrap_err(), expected);
+        assert_eq!(overflow_stake(version).check_version().unwrap_err(), expected);
+    }
+    for version in [secure_signer::CURRENT_VERSION - 1, secure_signer::CURRENT_VERSION + 1] {
+        let expected = Error::from(secure_signer::ErrorCode::VersionMismatch);
+        assert_eq!(signer_vault(version).check_version().unwrap_err(), expected);
+    }
+    for version in [secure_pda::CURRENT_VERSION - 1, secure_pda::CURRENT_VERSION + 1] {
+        let expected = Error::from(secure_pda::ErrorCode::VersionMismatch);
+        assert_eq!(pda_vault(version).check_version().unwrap_err(), expected);
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn stale_vault_is_rejected_until_migrated() {
+    let mut setup = setup(STALE).await;
+
+    let ix = freeze_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), version_mismatch());
+
+    let ix = migrate_vault_ix(&setup);
+    let (result, logs) = setup.env.send_with_logs(&[ix], &[&setup.authority]).await;
+    result.unwrap();
+    let migrated = events::<secure_cpi::AccountMigrated>(&logs);
+    assert_eq!(migrated.len(), 1);
+    assert_eq!(migrated[0].account, setup.vault);
+    assert_eq!((migrated[0].from_version, migrated[0].to_version), (STALE, secure_cpi::CURRENT_VERSION));
+
+    // ✅ Handlers accept the vault once it is rewritten
+    let ix = freeze_ix(&setup);
+    send(&mut setup, ix).await.unwrap();
+}
+
+#[tokio::test]
+async fn stale_pool_is_rejected_until_migrated() {
+    let mut setup = setup(STALE).await;
+
+    let ix = queue_fee_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), version_mismatch());
+
+    let ix = migrate_pool_ix(&setup);
+    send(&mut setup, ix).await.unwrap();
+    let pool: secure_cpi::Pool = setup.env.fetch(setup.pool).await;
+    assert_eq!(pool.version, secure_cpi::CURRENT_VERSION);
+
+    // ✅ Handlers accept the pool once it is rewritten
+    let ix = queue_fee_ix(&setup);
+    send(&mut setup, ix).await.unwrap();
+}
+
+#[tokio::test]
+async fn current_accounts_fail_with_already_migrated() {
+    let mut setup = setup(secure_cpi::CURRENT_VERSION).await;
+
+    let ix = migrate_vault_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), custom(secure_cpi::ErrorCode::AlreadyMigrated));
+    let ix = migrate_pool_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), custom(secure_cpi::ErrorCode::AlreadyMigrated));
+}
+
+#[tokio::test]
+async fn newer_accounts_cannot_be_migrated_down() {
+    let mut setup = setup(secure_cpi::CURRENT_VERSION + 1).await;
+
+    // ❌ Written by a newer deployment: this program can't convert it
+    let ix = migrate_vault_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), version_mismatch());
+    let ix = migrate_pool_ix(&setup);
+    assert_eq!(send(&mut setup, ix).await.unwrap_err(), version_mismatch());
+    let vault: secure_cpi::Vault = setup.env.fetch(setup.vault).await;
+    assert_eq!(vault.version, secure_cpi::CURRENT_VERSION + 1);
+}
+
+// ============================================================================
+// MIGRATING OUTSIDE SECURE_CPI
+// ============================================================================
+
+#[tokio::test]
+async fn matching_pool_and_stake_migrate_separately() {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+    let stale = secure_matching::CURRENT_VERSION - 1;
+    let (authority, user) = (Keypair::new(), Keypair::new());
+
+    let mint = Pubkey::new_unique();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let state = common::matching_pool(authority.pubkey(), mint, mint, Pubkey::new_unique(), bump);
+    let state = secure_matching::Pool { version: stale, ..state };
+    add_anchor_account(&mut program_test, pool, secure_matching::ID, &state);
+
+    let (staking_account, bump) =
+        Pubkey::find_program_address(&[b"staking", user.pubkey().as_ref(), pool.as_ref()], &secure_matching::ID);
+    let state = common::matching_stake(user.pubkey(), pool, 0, bump);
+    let state = secure_matching::StakingAccount { version: stale, ..state };
+    add_anchor_account(&mut program_test, staking_account, secure_matching::ID, &state);
+    let mut env = TestEnv::start(program_test).await;
+
+    // ✅ The owner can migrate the stake without waiting on the pool
+    let migrate_stake = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::MigrateStakingAccount { user: user.pubkey(), staking_account }
+            .to_account_metas(None),
+        data: secure_matching::instruction::MigrateStakingAccount {}.data(),
+    };
+    env.send(&[migrate_stake.clone()], &[&user]).await.unwrap();
+    let state: secure_matching::StakingAccount = env.fetch(staking_account).await;
+    assert_eq!(state.version, secure_matching::CURRENT_VERSION);
+    let pool_state: secure_matching::Pool = env.fetch(pool).await;
+    assert_eq!(pool_state.version, stale);
+
+    let migrate_pool = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::MigratePool { pool, authority: authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_matching::instruction::MigratePool {}.data(),
+    };
+    env.send(&[migrate_pool], &[&authority]).await.unwrap();
+    let pool_state: secure_matching::Pool = env.fetch(pool).await;
+    assert_eq!(pool_state.version, secure_matching::CURRENT_VERSION);
+
+    let err = env.send(&[migrate_stake], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::AlreadyMigrated));
+}
+
+#[tokio::test]
+async fn overflow_accounts_migrate_to_the_current_version() {
+    let mut program_test =
+        ProgramTest::new("secure_overflow", secure_overflow::ID, processor!(secure_overflow::entry));
+    let stale = secure_overflow::CURRENT_VERSION - 1;
+    let (vault, pool, staking) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let authority = Keypair::new();
+
+    let state = secure_overflow::Vault { authority: authority.pubkey(), ..overflow_vault(stale) };
+    add_anchor_account(&mut program_test, vault, secure_overflow::ID, &state);
+    let state = secure_overflow::Pool { version: stale, ..common::overflow_pool(authority.pubkey()) };
+    add_anchor_account(&mut program_test, pool, secure_overflow::ID, &state);
+    let state = common::overflow_stake(authority.pubkey(), pool, 0, 0);
+    let state = secure_overflow::StakingAccount { version: stale, ..state };
+    add_anchor_account(&mut program_test, staking, secure_overflow::ID, &state);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ixs = [
+        Instruction {
+            program_id: secure_overflow::ID,
+            accounts: secure_overflow::accounts::MigrateVault { vault, authority: authority.pubkey() }
+                .to_account_metas(None),
+            data: secure_overflow::instruction::MigrateVault {}.data(),
+        },
+        Instruction {
+            program_id: secure_overflow::ID,
+            accounts: secure_overflow::accounts::MigratePool { pool, authority: authority.pubkey() }
+                .to_account_metas(None),
+            data: secure_overflow::instruction::MigratePool {}.data(),
+        },
+        Instruction {
+            program_id: secure_overflow::ID,
+            accounts: secure_overflow::accounts::MigrateStaking { staking, owner: authority.pubkey() }
+                .to_account_metas(None),
+            data: secure_overflow::instruction::MigrateStaking {}.data(),
+        },
+    ];
+    let (result, logs) = env.send_with_logs(&ixs, &[&authority]).await;
+    result.unwrap();
+
+    let migrated = events::<secure_overflow::AccountMigrated>(&logs);
+    assert_eq!(
+        migrated.iter().map(|event| event.account).collect::<Vec<_>>(),
+        vec![vault, pool, staking]
+    );
+    let vault_state: secure_overflow::Vault = env.fetch(vault).await;
+    let pool_state: secure_overflow::Pool = env.fetch(pool).await;
+    let staking_state: secure_overflow::StakingAccount = env.fetch(staking).await;
+    for version in [vault_state.version, pool_state.version, staking_state.version] {
+        assert_eq!(version, secure_overflow::CURRENT_VERSION);
+    }
+}
+
+#[tokio::test]
+async fn signer_vault_migrates_to_the_current_version() {
+    let mut program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let stale = secure_signer::CURRENT_VERSION - 1;
+    let authority = Keypair::new();
+    let vault = Pubkey::new_unique();
+    let state = secure_signer::Vault { authority: authority.pubkey(), ..signer_vault(stale) };
+    add_anchor_account(&mut program_test, vault, secure_signer::ID, &state);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::MigrateVault { vault, authority: authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_signer::instruction::MigrateVault {}.data(),
+    };
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    let state: secure_signer::Vault = env.fetch(vault).await;
+    assert_eq!((state.version, state.sequence), (secure_signer::CURRENT_VERSION, 1));
+}
+
+#[tokio::test]
+async fn pda_vault_migrates_to_the_current_version() {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+    let stale = secure_pda::CURRENT_VERSION - 1;
+    let authority = Keypair::new();
+    let (vault, bump) =
+        Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref(), b"savings"], &secure_pda::ID);
+    let state = secure_pda::Vault { version: stale, ..common::pda_vault(authority.pubkey(), "savings", 0, bump) };
+    add_anchor_account(&mut program_test, vault, secure_pda::ID, &state);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::MigrateVault { vault, authority: authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_pda::instruction::MigrateVault {}.data(),
+    };
+    env.send(&[ix], &[&authority]).await.unwrap();
+
+    let state: secure_pda::Vault = env.fetch(vault).await;
+    assert_eq!((state.version, state.sequence), (secure_pda::CURRENT_VERSION, 1));
+}
@@ -0,0 +1,187 @@
+//! # Add Liquidity Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::add_liquidity`: the
+//! first deposit into an empty pool mints `sqrt(a * b)` LP shares and sets
+//! the price, a later deposit at the reserve ratio mints pro rata, and
+//! off-ratio or one-sided deposits are rejected.
+//!
+//! ```bash
+//! cargo test --test add_liquidity
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const BALANCE: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    user: Keypair,
+    pool: Pubkey,
+    pool_token_in: Pubkey,
+    pool_token_out: Pubkey,
+    user_in: Pubkey,
+    user_out: Pubkey,
+    lp_position: Pubkey,
+}
+
+/// Pool at `reserve_in` / `reserve_out` with `lp_supply` shares, all held
+/// by someone other than `user`. `user` has an empty LP position and
+/// `BALANCE` of each token.
+async fn setup(reserve_in: u64, reserve_out: u64, lp_supply: u64) -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+
+    let mint_in = Pubkey::new_unique();
+    let mint_out = Pubkey::new_unique();
+    let user = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(
+        &[b"pool", mint_in.as_ref(), mint_out.as_ref()],
+        &secure_cpi::ID,
+    );
+    let state = secure_cpi::Pool {
+        reserve_in,
+        reserve_out,
+        fee_bps: 0,
+        lp_supply,
+        ..common::cpi_pool(Pubkey::new_unique(), (mint_in, mint_out), bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &state);
+
+    let (lp_position, bump) = Pubkey::find_program_address(
+        &[b"lp", pool.as_ref(), user.pubkey().as_ref()],
+        &secure_cpi::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        lp_position,
+        secure_cpi::ID,
+        &secure_cpi::LpPosition { owner: user.pubkey(), pool, shares: 0, bump },
+    );
+
+    let pool_token_in = add_token_account(&mut program_test, mint_in, pool, reserve_in);
+    let pool_token_out = add_token_account(&mut program_test, mint_out, pool, reserve_out);
+    let user_in = add_token_account(&mut program_test, mint_in, user.pubkey(), BALANCE);
+    let user_out = add_token_account(&mut program_test, mint_out, user.pubkey(), BALANCE);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, user, pool, pool_token_in, pool_token_out, user_in, user_out, lp_position }
+}
+
+async fn add_liquidity(setup: &mut Setup, amount_a: u64, amount_b: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::AddLiquidity {
+            user: setup.user.pubkey(),
+            user_token_in: setup.user_in,
+            user_token_out: setup.user_out,
+            pool: setup.pool,
+            pool_token_in: setup.pool_token_in,
+            pool_token_out: setup.pool_token_out,
+            lp_position: setup.lp_position,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::AddLiquidity { amount_a, amount_b }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_cpi::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+async fn position_shares(setup: &mut Setup) -> u64 {
+    setup.env.fetch::<secure_cpi::LpPosition>(setup.lp_position).await.shares
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn first_deposit_mints_geometric_mean_and_sets_price() {
+    let mut setup = setup(0, 0, 0).await;
+
+    // Any ratio is accepted: this deposit defines it
+    add_liquidity(&mut setup, 40_000, 10_000).await.unwrap();
+
+    // sqrt(40_000 * 10_000)
+    assert_eq!(position_shares(&mut setup).await, 20_000);
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.reserve_in, pool.reserve_out), (40_000, 10_000));
+    assert_eq!(pool.lp_supply, 20_000);
+    assert_eq!(setup.env.token_balance(setup.pool_token_in).await, 40_000);
+    assert_eq!(setup.env.token_balance(setup.pool_token_out).await, 10_000);
+}
+
+#[tokio::test]
+async fn ratio_preserving_deposit_mints_pro_rata() {
+    // 4:1 pool with 20_000 shares outstanding
+    let mut setup = setup(40_000, 10_000, 20_000).await;
+
+    // 10% more of each side → 10% more shares
+    add_liquidity(&mut setup, 4_000, 1_000).await.unwrap();
+
+    assert_eq!(position_shares(&mut setup).await, 2_000);
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.reserve_in, pool.reserve_out), (44_000, 11_000));
+    assert_eq!(pool.lp_supply, 22_000);
+    assert_eq!(setup.env.token_balance(setup.user_in).await, BALANCE - 4_000);
+    assert_eq!(setup.env.token_balance(setup.user_out).await, BALANCE - 1_000);
+}
+
+#[tokio::test]
+async fn deposit_within_tolerance_mints_the_smaller_side() {
+    let mut setup = setup(40_000, 10_000, 20_000).await;
+
+    // 0.4% extra on side b: accepted, but shares follow side a
+    add_liquidity(&mut setup, 4_000, 1_004).await.unwrap();
+
+    assert_eq!(position_shares(&mut setup).await, 2_000);
+    assert_eq!(pool_state(&mut setup).await.reserve_out, 11_004);
+}
+
+#[tokio::test]
+async fn off_ratio_deposit_is_rejected() {
+    let mut setup = setup(40_000, 10_000, 20_000).await;
+
+    // 1:1 into a 4:1 pool would drag the price toward 1:1
+    let err = add_liquidity(&mut setup, 4_000, 4_000).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::RatioMismatch));
+
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.reserve_in, pool.reserve_out, pool.lp_supply), (40_000, 10_000, 20_000));
+    assert_eq!(position_shares(&mut setup).await, 0);
+}
+
+#[tokio::test]
+async fn one_sided_deposit_is_rejected() {
+    let mut setup = setup(40_000, 10_000, 20_000).await;
+
+    let err = add_liquidity(&mut setup, 4_000, 0).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ZeroLiquidity));
+}
+
+#[tokio::test]
+async fn deposit_minting_zero_shares_is_rejected() {
+    // Only 100 shares backing 40_000 / 10_000
+    let mut setup = setup(40_000, 10_000, 100).await;
+
+    // On ratio, but 4 * 100 / 40_000 rounds to zero shares
+    let err = add_liquidity(&mut setup, 4, 1).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ZeroLiquidity));
+    assert_eq!(setup.env.token_balance(setup.user_in).await, BALANCE);
+}
@@ -0,0 +1,152 @@
+//! # Admin Timelock Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::queue_admin_action` and
+//! `execute_admin_action`. A queued fee change cannot be executed before
+//! `ADMIN_ACTION_DELAY` has passed, then applies for anyone who submits it.
+//! The `Clock` sysvar is moved forward directly instead of waiting.
+//!
+//! ```bash
+//! cargo test --test admin_timelock
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, TestEnv};
+use secure_cpi::{AdminAction, ErrorCode, ADMIN_ACTION_DELAY};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const OLD_FEE_BPS: u16 = 30;
+const NEW_FEE_BPS: u16 = 100;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    pool: Pubkey,
+}
+
+/// Pool charging `OLD_FEE_BPS` with nothing queued
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+
+    let mint_in = Pubkey::new_unique();
+    let mint_out = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(
+        &[b"pool", mint_in.as_ref(), mint_out.as_ref()],
+        &secure_cpi::ID,
+    );
+
+    let state = secure_cpi::Pool {
+        fee_bps: OLD_FEE_BPS,
+        ..common::cpi_pool(authority.pubkey(), (mint_in, mint_out), bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &state);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, pool }
+}
+
+async fn queue_fee(setup: &mut Setup, signer: &Keypair, fee_bps: u16) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::QueueAdminAction { pool: setup.pool, authority: signer.pubkey() }
+            .to_account_metas(None),
+        data: secure_cpi::instruction::QueueAdminAction { action: AdminAction::SetFee { fee_bps } }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+/// Submitted by the payer alone: execution needs no authority
+async fn execute(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::ExecuteAdminAction { pool: setup.pool }.to_account_metas(None),
+        data: secure_cpi::instruction::ExecuteAdminAction {}.data(),
+    };
+    setup.env.send(&[ix], &[]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_cpi::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn fee_change_applies_only_after_the_delay() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    queue_fee(&mut setup, &authority, NEW_FEE_BPS).await.unwrap();
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.pending_action, Some(AdminAction::SetFee { fee_bps: NEW_FEE_BPS }));
+    assert_eq!(pool.fee_bps, OLD_FEE_BPS);
+
+    // ❌ One second early
+    setup.env.advance_clock(ADMIN_ACTION_DELAY - 1).await;
+    let err = execute(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::ActionNotReady));
+    assert_eq!(pool_state(&mut setup).await.fee_bps, OLD_FEE_BPS);
+
+    // ✅ Delay passed: anyone can apply it
+    setup.env.advance_clock(1).await;
+    execute(&mut setup).await.unwrap();
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.fee_bps, NEW_FEE_BPS);
+    assert_eq!(pool.pending_action, None);
+    assert_eq!(pool.action_ready_at, 0);
+
+    // Executed actions cannot be replayed
+    let err = execute(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::NoQueuedAction));
+}
+
+#[tokio::test]
+async fn execute_with_nothing_queued_fails() {
+    let mut setup = setup().await;
+
+    let err = execute(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::NoQueuedAction));
+}
+
+#[tokio::test]
+async fn requeue_replaces_the_action_and_restarts_the_delay() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    queue_fee(&mut setup, &authority, NEW_FEE_BPS).await.unwrap();
+    setup.env.advance_clock(ADMIN_ACTION_DELAY - 1).await;
+    queue_fee(&mut setup, &authority, OLD_FEE_BPS + 1).await.unwrap();
+
+    // The first action's deadline has passed, but it no longer exists
+    setup.env.advance_clock(1).await;
+    let err = execute(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::ActionNotReady));
+
+    setup.env.advance_clock(ADMIN_ACTION_DELAY).await;
+    execute(&mut setup).await.unwrap();
+    assert_eq!(pool_state(&mut setup).await.fee_bps, OLD_FEE_BPS + 1);
+}
+
+#[tokio::test]
+async fn only_the_authority_can_queue() {
+    let mut setup = setup().await;
+    let attacker = Keypair::new();
+
+    let err = queue_fee(&mut setup, &attacker, secure_cpi::MAX_FEE_BPS).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::common_errors::CommonError::Unauthorized));
+    assert_eq!(pool_state(&mut setup).await.pending_action, None);
+}
@@ -0,0 +1,117 @@
+//! # Stored vs Recomputed Bump
+//!
+//! Measures what `secure_pda` saves by storing `Vault::bump`. `deposit`
+//! verifies the vault with `bump = vault.bump` (a single
+//! `create_program_address`), while `deposit_recomputed_bump` uses a bare
+//! `bump` and pays for `find_program_address` on every call. The handler
+//! bodies are identical, so the difference in "units remaining" at handler
+//! entry is the cost of the search.
+//!
+//! `find_program_address` starts at bump 255 and charges a full hash for
+//! each off-curve miss, so the savings scale with `255 - bump`. The test
+//! picks a vault name whose canonical bump is at most `MAX_BUMP`; with 255
+//! the two paths would cost almost the same. Expect about 1,500 CU per
+//! skipped attempt.
+//!
+//! Needs the real BPF build, since `processor!` runs natively and is not
+//! metered:
+//!
+//! ```bash
+//! anchor build -- --features bench
+//! cargo test --test bump_cost -- --nocapture
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, TestEnv};
+use solana_program_test::ProgramTest;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+/// Canonical bump the benchmark vault must not exceed, i.e. at least
+/// `255 - MAX_BUMP` extra attempts for the recomputing path
+const MAX_BUMP: u8 = 250;
+
+/// Conservative lower bound on the cost of one extra
+/// `find_program_address` attempt
+const MIN_CU_PER_ATTEMPT: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    vault: Pubkey,
+    bump: u8,
+}
+
+/// First "bench-N" vault under a fresh authority whose canonical bump is
+/// at most `MAX_BUMP`
+fn low_bump_vault(authority: &Pubkey) -> (String, Pubkey, u8) {
+    (0..)
+        .map(|i| format!("bench-{}", i))
+        .find_map(|name| {
+            let (vault, bump) =
+                Pubkey::find_program_address(&[b"vault", authority.as_ref(), name.as_bytes()], &secure_pda::ID);
+            (bump <= MAX_BUMP).then_some((name, vault, bump))
+        })
+        .unwrap()
+}
+
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("secure_pda", secure_pda::ID, None);
+
+    let authority = Pubkey::new_unique();
+    let (name, vault, bump) = low_bump_vault(&authority);
+    add_anchor_account(&mut program_test, vault, secure_pda::ID, &common::pda_vault(authority, &name, 0, bump));
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, vault, bump }
+}
+
+// ============================================================================
+// MEASUREMENT
+// ============================================================================
+
+#[tokio::test]
+async fn stored_bump_is_cheaper_than_recomputing() {
+    let mut setup = setup().await;
+    let depositor = setup.env.payer();
+    let stored = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::Deposit { vault: setup.vault, depositor }.to_account_metas(None),
+        data: secure_pda::instruction::Deposit { amount: 1 }.data(),
+    };
+    let recomputed = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::DepositRecomputedBump { vault: setup.vault, depositor }.to_account_metas(None),
+        data: secure_pda::instruction::DepositRecomputedBump { amount: 2 }.data(),
+    };
+
+    let stored = setup.env.measure(&[stored], &[]).await;
+    let recomputed = setup.env.measure(&[recomputed], &[]).await;
+
+    // Built without `bench` there is nothing to compare
+    assert_eq!(stored.checkpoints.len(), 1, "rebuild with --features bench");
+    assert_eq!(recomputed.checkpoints.len(), 1, "rebuild with --features bench");
+
+    let savings = stored.checkpoints[0] - recomputed.checkpoints[0];
+    let extra_attempts = u64::from(255 - setup.bump);
+
+    println!("secure_pda deposit compute units (canonical bump {})", setup.bump);
+    println!("  bump = vault.bump total       {:>7}", stored.consumed);
+    println!("  bare bump total               {:>7}", recomputed.consumed);
+    println!("  saved by stored bump          {:>7}", savings);
+    println!("  per skipped attempt           {:>7}", savings / extra_attempts);
+
+    assert!(
+        savings >= extra_attempts * MIN_CU_PER_ATTEMPT,
+        "stored bump saved {} CU over {} skipped attempts, expected >= {} each",
+        savings,
+        extra_attempts,
+        MIN_CU_PER_ATTEMPT
+    );
+}
@@ -0,0 +1,126 @@
+//! # Close Griefing Tests
+//!
+//! A non-authority tries to close someone else's empty vault in
+//! `vulnerable_close_griefing` (succeeds and pockets the rent) and in
+//! `secure_pda` (rejected, vault intact). Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test close_griefing
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+const NAME: &str = "savings";
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    attacker: Keypair,
+    vulnerable_vault: Pubkey,
+    secure_vault: Pubkey,
+    secure_registry: Pubkey,
+    /// Rent held by each vault
+    vault_rent: u64,
+}
+
+/// One empty "savings" vault per program, both owned by a victim who never
+/// signs anything here, and a funded attacker
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_close_griefing",
+        vulnerable_close_griefing::ID,
+        processor!(vulnerable_close_griefing::entry),
+    );
+    program_test.add_program("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+
+    let victim = Pubkey::new_unique();
+    let (vulnerable_vault, bump) = Pubkey::find_program_address(
+        &[b"vault", victim.as_ref(), NAME.as_bytes()],
+        &vulnerable_close_griefing::ID,
+    );
+    let state = vulnerable_close_griefing::Vault { authority: victim, balance: 0, name: NAME.to_string(), bump };
+    let vault_rent = Rent::default().minimum_balance(common::serialize(&state).len());
+    add_anchor_account(&mut program_test, vulnerable_vault, vulnerable_close_griefing::ID, &state);
+
+    let (secure_vault, bump) =
+        Pubkey::find_program_address(&[b"vault", victim.as_ref(), NAME.as_bytes()], &secure_pda::ID);
+    add_anchor_account(&mut program_test, secure_vault, secure_pda::ID, &common::pda_vault(victim, NAME, 0, bump));
+    let (secure_registry, bump) = Pubkey::find_program_address(&[b"registry", victim.as_ref()], &secure_pda::ID);
+    add_anchor_account(
+        &mut program_test,
+        secure_registry,
+        secure_pda::ID,
+        &secure_pda::VaultRegistry { authority: victim, names: vec![NAME.to_string()], bump },
+    );
+
+    let mut env = TestEnv::start(program_test).await;
+    let attacker = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    Setup { env, attacker, vulnerable_vault, secure_vault, secure_registry, vault_rent }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_non_authority_closes_vault_and_takes_rent() {
+    let mut setup = setup().await;
+    let attacker = setup.attacker.insecure_clone();
+    let before = setup.env.lamports(attacker.pubkey()).await;
+
+    let ix = Instruction {
+        program_id: vulnerable_close_griefing::ID,
+        accounts: vulnerable_close_griefing::accounts::CloseVault {
+            vault: setup.vulnerable_vault,
+            closer: attacker.pubkey(),
+        }
+        .to_account_metas(None),
+        data: vulnerable_close_griefing::instruction::CloseVault {}.data(),
+    };
+    setup.env.send(&[ix], &[&attacker]).await.unwrap();
+
+    // ❌ Victim's vault is gone; its rent went to the attacker
+    assert!(setup.env.account(setup.vulnerable_vault).await.is_none());
+    let after = setup.env.lamports(attacker.pubkey()).await;
+    assert_eq!(after - before, setup.vault_rent);
+}
+
+#[tokio::test]
+async fn secure_non_authority_cannot_close_vault() {
+    let mut setup = setup().await;
+    let attacker = setup.attacker.insecure_clone();
+    let before = setup.env.lamports(attacker.pubkey()).await;
+
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::CloseVault {
+            vault: setup.secure_vault,
+            registry: setup.secure_registry,
+            authority: attacker.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::CloseVault {}.data(),
+    };
+    let err = setup.env.send(&[ix], &[&attacker]).await.unwrap_err();
+
+    // ✅ Seeds are derived from the signer, so the victim's vault does not
+    // match before has_one (Unauthorized) is even reached
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+    assert!(setup.env.account(setup.secure_vault).await.is_some());
+    assert_eq!(setup.env.lamports(attacker.pubkey()).await, before);
+}
@@ -0,0 +1,262 @@
+//! # Swap Fee Collection Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::collect_fees`: swaps
+//! accrue fees outside the reserves, a `FeeCollector` collects them, and
+//! the reserves do not move. Fee changes themselves are timelocked; see
+//! `admin_timelock.rs`.
+//!
+//! ```bash
+//! cargo test --test collect_fees
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, Space, ToAccountMetas};
+use common::{add_account, add_anchor_account, add_token_account, custom, TestEnv};
+use secure_cpi::access_control::{AccessControl, AccessControlError, Role, RoleGrant};
+use secure_cpi::AdminAction;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const RESERVE: u64 = 1_000_000;
+const SWAP_IN: u64 = 10_000;
+const FEE_BPS: u16 = 30;
+/// 10_000 * 30 / 10_000
+const FEE_PER_SWAP: u64 = 30;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    trader: Keypair,
+    pool: Pubkey,
+    access_control: Pubkey,
+    pool_token_in: Pubkey,
+    pool_token_out: Pubkey,
+    trader_in: Pubkey,
+    trader_out: Pubkey,
+    admin_tokens: Pubkey,
+}
+
+/// 1M / 1M pool charging `FEE_BPS`, with the authority as its only `Admin`,
+/// a trader holding `SWAP_IN * 10`, and an empty authority token account
+/// for the input mint
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+
+    let mint_in = Pubkey::new_unique();
+    let mint_out = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let trader = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(
+        &[b"pool", mint_in.as_ref(), mint_out.as_ref()],
+        &secure_cpi::ID,
+    );
+
+    let state = secure_cpi::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        fee_bps: FEE_BPS,
+        ..common::cpi_pool(authority.pubkey(), mint_in, mint_out, bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &state);
+
+    let (access_control, bump) =
+        Pubkey::find_program_address(&[b"access_control", pool.as_ref()], &secure_cpi::ID);
+    let state = AccessControl {
+        scope: pool,
+        grants: vec![RoleGrant { role: Role::Admin, key: authority.pubkey() }],
+        bump,
+    };
+    let mut data = common::serialize(&state);
+    // Full size, as `init` allocates, so grant_role has room to grow the list
+    data.resize(8 + AccessControl::INIT_SPACE, 0);
+    add_account(&mut program_test, access_control, secure_cpi::ID, data);
+
+    let pool_token_in = add_token_account(&mut program_test, mint_in, pool, RESERVE);
+    let pool_token_out = add_token_account(&mut program_test, mint_out, pool, RESERVE);
+    let trader_in = add_token_account(&mut program_test, mint_in, trader.pubkey(), SWAP_IN * 10);
+    let trader_out = add_token_account(&mut program_test, mint_out, trader.pubkey(), 0);
+    let admin_tokens = add_token_account(&mut program_test, mint_in, authority.pubkey(), 0);
+
+    let env = TestEnv::start(program_test).await;
+    Setup {
+        env,
+        authority,
+        trader,
+        pool,
+        access_control,
+        pool_token_in,
+        pool_token_out,
+        trader_in,
+        trader_out,
+        admin_tokens,
+    }
+}
+
+fn queue_fee_ix(setup: &Setup, authority: Pubkey, fee_bps: u16) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::QueueAdminAction { pool: setup.pool, authority }.to_account_metas(None),
+        data: secure_cpi::instruction::QueueAdminAction { action: AdminAction::SetFee { fee_bps } }.data(),
+    }
+}
+
+fn swap_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SwapTokens {
+            user: setup.trader.pubkey(),
+            user_token_in: setup.trader_in,
+            user_token_out: setup.trader_out,
+            pool: setup.pool,
+            pool_token_in: setup.pool_token_in,
+            pool_token_out: setup.pool_token_out,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::SwapTokens { amount_in: SWAP_IN, min_amount_out: 1, reserve_tolerance: None }.data(),
+    }
+}
+
+fn collect_ix(setup: &Setup, admin: Pubkey, admin_tokens: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::CollectFees {
+            admin,
+            pool: setup.pool,
+            access_control: setup.access_control,
+            pool_token_in: setup.pool_token_in,
+            admin_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::CollectFees {}.data(),
+    }
+}
+
+fn update_roles_ix(setup: &Setup, admin: Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::UpdateRoles { access_control: setup.access_control, admin }
+            .to_account_metas(None),
+        data,
+    }
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_cpi::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+/// Run `swaps` swaps of `SWAP_IN` at `FEE_BPS`
+async fn accrue(setup: &mut Setup, swaps: u64) {
+    for _ in 0..swaps {
+        let ix = swap_ix(setup);
+        setup.env.send(&[ix], &[&setup.trader]).await.unwrap();
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn swaps_accrue_fees_and_authority_collects_them() {
+    let mut setup = setup().await;
+    accrue(&mut setup, 3).await;
+
+    // Fees sit in pool_token_in but outside reserve_in
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.fees_collected, 3 * FEE_PER_SWAP);
+    assert_eq!(pool.reserve_in, RESERVE + 3 * (SWAP_IN - FEE_PER_SWAP));
+    assert_eq!(
+        setup.env.token_balance(setup.pool_token_in).await,
+        pool.reserve_in + pool.fees_collected
+    );
+    let reserves = (pool.reserve_in, pool.reserve_out);
+
+    let authority = setup.authority.insecure_clone();
+    let ix = collect_ix(&setup, authority.pubkey(), setup.admin_tokens);
+    setup.env.send(&[ix], &[&authority]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.admin_tokens).await, 3 * FEE_PER_SWAP);
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.fees_collected, 0);
+    // Constant-product reserves untouched, and still fully backed
+    assert_eq!((pool.reserve_in, pool.reserve_out), reserves);
+    assert_eq!(setup.env.token_balance(setup.pool_token_in).await, pool.reserve_in);
+}
+
+#[tokio::test]
+async fn collecting_twice_fails_with_no_fees() {
+    let mut setup = setup().await;
+    accrue(&mut setup, 1).await;
+    let authority = setup.authority.insecure_clone();
+
+    let ix = collect_ix(&setup, authority.pubkey(), setup.admin_tokens);
+    setup.env.send(&[ix], &[&authority]).await.unwrap();
+
+    let ix = collect_ix(&setup, authority.pubkey(), setup.admin_tokens);
+    let err = setup.env.send(&[ix], &[&authority]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::NoFeesToCollect));
+}
+
+#[tokio::test]
+async fn non_authority_cannot_collect() {
+    let mut setup = setup().await;
+    accrue(&mut setup, 2).await;
+    let trader = setup.trader.insecure_clone();
+
+    let ix = collect_ix(&setup, trader.pubkey(), setup.trader_in);
+    let err = setup.env.send(&[ix], &[&trader]).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+    assert_eq!(pool_state(&mut setup).await.fees_collected, 2 * FEE_PER_SWAP);
+}
+
+#[tokio::test]
+async fn granted_fee_collector_collects_until_revoked() {
+    let mut setup = setup().await;
+    accrue(&mut setup, 2).await;
+    let authority = setup.authority.insecure_clone();
+    let trader = setup.trader.insecure_clone();
+
+    // The trader holds no authority over the pool, only the role
+    let grant = secure_cpi::instruction::GrantRole { role: Role::FeeCollector, key: trader.pubkey() };
+    let ix = update_roles_ix(&setup, authority.pubkey(), grant.data());
+    setup.env.send(&[ix], &[&authority]).await.unwrap();
+
+    let ix = collect_ix(&setup, trader.pubkey(), setup.trader_in);
+    setup.env.send(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(setup.env.token_balance(setup.trader_in).await, SWAP_IN * 8 + 2 * FEE_PER_SWAP);
+
+    // ❌ Still cannot reprice the pool: queuing a fee checks pool.authority
+    let ix = queue_fee_ix(&setup, trader.pubkey(), 0);
+    let err = setup.env.send(&[ix], &[&trader]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::common_errors::CommonError::Unauthorized));
+
+    let revoke = secure_cpi::instruction::RevokeRole { role: Role::FeeCollector, key: trader.pubkey() };
+    let ix = update_roles_ix(&setup, authority.pubkey(), revoke.data());
+    setup.env.send(&[ix], &[&authority]).await.unwrap();
+
+    accrue(&mut setup, 1).await;
+    let ix = collect_ix(&setup, trader.pubkey(), setup.trader_in);
+    let err = setup.env.send(&[ix], &[&trader]).await.unwrap_err();
+    assert_eq!(err, custom(AccessControlError::MissingRole));
+}
+
+#[tokio::test]
+async fn fee_above_maximum_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let ix = queue_fee_ix(&setup, authority.pubkey(), secure_cpi::MAX_FEE_BPS + 1);
+    let err = setup.env.send(&[ix], &[&authority]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::FeeTooHigh));
+}
@@ -0,0 +1,435 @@
+//! # Shared Test Fixtures
+//!
+//! Helpers the `solana-program-test` suites have in common: accounts
+//! written straight into genesis (SPL token accounts and mints, Anchor
+//! accounts), a `TestEnv` that signs every transaction with the payer and
+//! a fresh blockhash, and default states for the account structs several
+//! suites preload. A new field on one of those structs is added to its
+//! default here and nowhere else; suites override what they test with `..`.
+//!
+//! Every suite pulls this in with `mod common;`, and none uses all of it.
+
+#![allow(dead_code)]
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_banks_interface::TransactionMetadata;
+use solana_program_test::{BanksClient, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+// ============================================================================
+// GENESIS ACCOUNTS
+// ============================================================================
+
+/// Rent-exempt account holding `data`
+pub fn add_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Anchor account, discriminator included
+pub fn serialize<T: AccountSerialize>(state: &T) -> Vec<u8> {
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    data
+}
+
+pub fn add_anchor_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    program_id: Pubkey,
+    state: &T,
+) {
+    add_account(program_test, address, program_id, serialize(state));
+}
+
+/// SPL Token state (`Account` or `Mint`) owned by the token program
+pub fn add_packed<T: Pack>(program_test: &mut ProgramTest, address: Pubkey, state: T) {
+    let mut data = vec![0; T::LEN];
+    state.pack_into_slice(&mut data);
+    add_account(program_test, address, spl_token::ID, data);
+}
+
+/// Initialized token account with no delegate
+pub fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> TokenAccount {
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+}
+
+pub fn add_token_account(program_test: &mut ProgramTest, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_packed(program_test, address, token_account(mint, owner, amount));
+    address
+}
+
+/// Six-decimal mint with no supply and no freeze authority
+pub fn mint(mint_authority: Option<Pubkey>) -> Mint {
+    Mint {
+        mint_authority: mint_authority.into(),
+        supply: 0,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+}
+
+pub fn add_mint(program_test: &mut ProgramTest, mint_authority: Option<Pubkey>) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_packed(program_test, address, mint(mint_authority));
+    address
+}
+
+/// System account holding `lamports`, able to pay fees and rent itself
+pub fn add_funded_keypair(program_test: &mut ProgramTest, lamports: u64) -> Keypair {
+    let keypair = Keypair::new();
+    program_test.add_account(
+        keypair.pubkey(),
+        Account { lamports, data: vec![], owner: system_program::ID, executable: false, rent_epoch: 0 },
+    );
+    keypair
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+/// Custom program error raised by the first instruction
+pub fn custom(code: impl Into<u32>) -> TransactionError {
+    custom_at(0, code)
+}
+
+pub fn custom_at(index: u8, code: impl Into<u32>) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(code.into()))
+}
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+/// Decode every event of type `T` emitted in `logs`
+pub fn events<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .map(|data| STANDARD.decode(data).unwrap())
+        .filter(|data| data.len() >= 8 && data[..8] == T::DISCRIMINATOR[..])
+        .map(|data| T::deserialize(&mut &data[8..]).unwrap())
+        .collect()
+}
+
+// ============================================================================
+// TEST ENVIRONMENT
+// ============================================================================
+
+/// Compute units reported for one transaction
+pub struct Measurement {
+    /// Total consumed, from transaction metadata
+    pub consumed: u64,
+    /// "units remaining" from each `sol_log_compute_units()` call, in order
+    pub checkpoints: Vec<u64>,
+}
+
+/// A started `ProgramTest` with its payer
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+}
+
+impl TestEnv {
+    pub async fn start(program_test: ProgramTest) -> Self {
+        Self { context: program_test.start_with_context().await }
+    }
+
+    pub fn banks(&mut self) -> &mut BanksClient {
+        &mut self.context.banks_client
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// `ixs` in one transaction, paid for by the payer and signed by it and
+    /// `signers`. Each call waits for a new blockhash, so sending the same
+    /// instructions twice is two transactions, not a duplicate
+    pub async fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), TransactionError> {
+        let tx = self.sign(ixs, None, signers).await;
+        self.context.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+    }
+
+    /// As `send`, with `fee_payer` paying instead of the payer
+    pub async fn send_paid_by(
+        &mut self,
+        ixs: &[Instruction],
+        fee_payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let tx = self.sign(ixs, Some(fee_payer), signers).await;
+        self.context.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+    }
+
+    /// As `send`, also returning the transaction's log messages
+    pub async fn send_with_logs(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> (Result<(), TransactionError>, Vec<String>) {
+        let (result, metadata) = self.send_with_metadata(ixs, signers).await;
+        (result, metadata.log_messages)
+    }
+
+    /// As `send`, also returning logs and compute units consumed
+    pub async fn send_with_metadata(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> (Result<(), TransactionError>, TransactionMetadata) {
+        let tx = self.sign(ixs, None, signers).await;
+        let result = self.context.banks_client.process_transaction_with_metadata(tx).await.unwrap();
+        (result.result, result.metadata.unwrap())
+    }
+
+    /// Run `ixs`, which must succeed, and report the compute units it used
+    pub async fn measure(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Measurement {
+        let (result, metadata) = self.send_with_metadata(ixs, signers).await;
+        result.unwrap();
+
+        let checkpoints = metadata
+            .log_messages
+            .iter()
+            .filter_map(|line| line.strip_prefix("Program consumption: "))
+            .filter_map(|rest| rest.strip_suffix(" units remaining"))
+            .map(|units| units.parse().unwrap())
+            .collect();
+
+        Measurement { consumed: metadata.compute_units_consumed, checkpoints }
+    }
+
+    async fn sign(&mut self, ixs: &[Instruction], fee_payer: Option<&Keypair>, signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let fee_payer = fee_payer.unwrap_or(&self.context.payer);
+        let mut all_signers = vec![fee_payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(ixs, Some(&fee_payer.pubkey()), &all_signers, blockhash)
+    }
+
+    /// Move `lamports` from the payer to a new keypair, so it can pay rent
+    /// as a separate signer
+    pub async fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let ix = system_instruction::transfer(&self.payer(), &keypair.pubkey(), lamports);
+        self.send(&[ix], &[]).await.unwrap();
+        keypair
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(address).await.unwrap()
+    }
+
+    /// Deserialize the Anchor account at `address`; panics if it is missing
+    pub async fn fetch<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self.account(address).await.unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        let account = self.account(address).await.unwrap();
+        TokenAccount::unpack(&account.data).unwrap().amount
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    /// Overwrite `Clock::unix_timestamp`; the slot does not move
+    pub async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Move `Clock::unix_timestamp` forward instead of waiting
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let now = self.unix_timestamp().await;
+        self.set_unix_timestamp(now + seconds).await;
+    }
+}
+
+// ============================================================================
+// ACCOUNT STATES
+// ============================================================================
+//
+// Neutral defaults at the current version: empty balances, no pending admin
+// action, no limits. Suites set what they test with struct update syntax.
+
+/// `secure_cpi` constant-product pool, 1M / 1M reserves, 30 bps fee
+pub fn cpi_pool(authority: Pubkey, mints: (Pubkey, Pubkey), bump: u8) -> secure_cpi::Pool {
+    secure_cpi::Pool {
+        authority,
+        token_in_mint: mints.0,
+        token_out_mint: mints.1,
+        reserve_in: 1_000_000,
+        reserve_out: 1_000_000,
+        total_volume: 0,
+        fee_bps: 30,
+        fees_collected: 0,
+        curve: secure_cpi::CurveType::ConstantProduct,
+        amp: 0,
+        lp_supply: 0,
+        price_cumulative: 0,
+        last_twap_update: 0,
+        bump,
+        sequence: 0,
+        pending_action: None,
+        action_ready_at: 0,
+        version: secure_cpi::CURRENT_VERSION,
+    }
+}
+
+/// `secure_cpi` vault, unlocked and not frozen
+pub fn cpi_vault(authority: Pubkey, balance: u64, bump: u8) -> secure_cpi::Vault {
+    secure_cpi::Vault {
+        authority,
+        balance,
+        total_deposited: balance,
+        total_withdrawn: 0,
+        deposit_count: 0,
+        bump,
+        locked: false,
+        surplus: 0,
+        sequence: 0,
+        permit_nonce: 0,
+        recent_deposit_keys: [[0; 32]; secure_cpi::RECENT_DEPOSIT_KEYS],
+        next_deposit_key: 0,
+        frozen: false,
+        freeze_reason: 0,
+        frozen_allows_deposits: false,
+        version: secure_cpi::CURRENT_VERSION,
+    }
+}
+
+/// `secure_matching` pool with no caps, no rewards and no lock tiers
+pub fn matching_pool(
+    authority: Pubkey,
+    token_mint: Pubkey,
+    reward_mint: Pubkey,
+    reward_vault: Pubkey,
+    bump: u8,
+) -> secure_matching::Pool {
+    secure_matching::Pool {
+        authority,
+        token_mint,
+        reward_mint,
+        reward_vault,
+        total_deposits: 0,
+        total_shares: 0,
+        total_staked: 0,
+        min_stake_duration: 0,
+        early_exit_penalty_bps: 0,
+        max_total_deposits: u64::MAX,
+        per_user_cap: u64::MAX,
+        total_rewards_funded: 0,
+        reward_rate: 0,
+        acc_reward_per_share: 0,
+        last_reward_time: 0,
+        lock_tiers: [secure_matching::LockTier::default(); secure_matching::MAX_LOCK_TIERS],
+        bump,
+        pending_action: None,
+        action_ready_at: 0,
+        version: secure_matching::CURRENT_VERSION,
+    }
+}
+
+/// `secure_matching` stake of `amount`, unlocked, full multiplier
+pub fn matching_stake(owner: Pubkey, pool: Pubkey, amount: u64, bump: u8) -> secure_matching::StakingAccount {
+    secure_matching::StakingAccount {
+        owner,
+        pool,
+        amount,
+        pending_rewards: 0,
+        total_claimed: 0,
+        last_stake_time: 0,
+        lock_duration: 0,
+        multiplier_bps: 10_000,
+        reward_debt: 0,
+        shares: 0,
+        total_deposited: 0,
+        bump,
+        version: secure_matching::CURRENT_VERSION,
+    }
+}
+
+/// `secure_pda` vault called `name`
+pub fn pda_vault(authority: Pubkey, name: &str, balance: u64, bump: u8) -> secure_pda::Vault {
+    secure_pda::Vault {
+        authority,
+        balance,
+        name: name.to_string(),
+        bump,
+        created_at: 0,
+        sequence: 0,
+        version: secure_pda::CURRENT_VERSION,
+    }
+}
+
+/// `secure_overflow` pool, unpaused, paying nothing
+pub fn overflow_pool(authority: Pubkey) -> secure_overflow::Pool {
+    secure_overflow::Pool {
+        authority,
+        reserve_in: 0,
+        reserve_out: 0,
+        reward_rate: 0,
+        rate_updated_at: 0,
+        reward_index: 0,
+        paused: false,
+        pending_action: None,
+        action_ready_at: 0,
+        version: secure_overflow::CURRENT_VERSION,
+    }
+}
+
+/// `secure_overflow` stake of `amount` opened at `start_time`
+pub fn overflow_stake(owner: Pubkey, pool: Pubkey, amount: u64, start_time: i64) -> secure_overflow::StakingAccount {
+    secure_overflow::StakingAccount {
+        owner,
+        pool,
+        amount,
+        start_time,
+        pending_rewards: 0,
+        pool_balance: 0,
+        last_accrual_time: start_time,
+        accumulated_remainder: 0,
+        reward_index: 0,
+        version: secure_overflow::CURRENT_VERSION,
+    }
+}
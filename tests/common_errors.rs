@@ -0,0 +1,74 @@
+//! # Common Error Tests
+//!
+//! Clients and tests match on numeric error codes, so `CommonError` codes
+//! must never move. If one of these fails, a variant was reordered or
+//! removed. Append new variants instead.
+//!
+//! Also covers `assert_authority`, the shared handler-side authority check.
+//!
+//! ```bash
+//! cargo test --test common_errors
+//! ```
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::Pubkey;
+use secure_cpi::common_errors::{assert_authority, CommonError};
+
+fn code(error: CommonError) -> u32 {
+    error.into()
+}
+
+#[test]
+fn common_error_codes_are_stable() {
+    assert_eq!(code(CommonError::Overflow), 6500);
+    assert_eq!(code(CommonError::Underflow), 6501);
+    assert_eq!(code(CommonError::InvalidAmount), 6502);
+    assert_eq!(code(CommonError::InsufficientFunds), 6503);
+    assert_eq!(code(CommonError::InvalidOwner), 6504);
+    assert_eq!(code(CommonError::MintMismatch), 6505);
+    assert_eq!(code(CommonError::Unauthorized), 6506);
+    assert_eq!(code(CommonError::SlippageExceeded), 6507);
+    assert_eq!(code(CommonError::InvariantViolation), 6508);
+}
+
+#[test]
+fn common_errors_are_identical_across_programs() {
+    // Every program compiles the same module, so the codes must agree
+    assert_eq!(
+        u32::from(secure_overflow::common_errors::CommonError::Overflow),
+        code(CommonError::Overflow)
+    );
+    assert_eq!(
+        u32::from(secure_pda::common_errors::CommonError::Unauthorized),
+        code(CommonError::Unauthorized)
+    );
+    assert_eq!(
+        u32::from(secure_min_output::common_errors::CommonError::SlippageExceeded),
+        code(CommonError::SlippageExceeded)
+    );
+}
+
+#[test]
+fn ranges_do_not_collide() {
+    // Program ErrorCodes start at 6000 and stay well below 6500
+    assert_eq!(u32::from(secure_cpi::ErrorCode::InvalidMinOutput), ERROR_CODE_OFFSET);
+    assert!(u32::from(secure_cpi::ErrorCode::WhitelistFull) < code(CommonError::Overflow));
+
+    // LogicError starts at 7000, above the last CommonError
+    assert!(code(CommonError::InvariantViolation) < u32::from(secure_cpi::logic::LogicError::Overflow));
+
+    // AccessControlError starts at 7500
+    assert_eq!(u32::from(secure_cpi::access_control::AccessControlError::MissingRole), 7500);
+}
+
+#[test]
+fn assert_authority_accepts_matching_key() {
+    let authority = Pubkey::new_unique();
+    assert!(assert_authority(&authority, &authority).is_ok());
+}
+
+#[test]
+fn assert_authority_rejects_other_key() {
+    let err = assert_authority(&Pubkey::new_unique(), &Pubkey::new_unique()).unwrap_err();
+    assert_eq!(err, CommonError::Unauthorized.into());
+}
@@ -0,0 +1,76 @@
+//! # Compound Interest Tests
+//!
+//! Plain `#[test]`s for `secure_compound_interest::InterestAccount::accrue`:
+//! whole-period compounding, carrying the partial period, and splitting
+//! accruals never paying more than one accrual over the same time.
+//!
+//! ```bash
+//! cargo test --test compound_interest
+//! ```
+
+use anchor_lang::prelude::*;
+use secure_compound_interest::{InterestAccount, PERIOD_SECONDS};
+
+const DAY: i64 = PERIOD_SECONDS;
+
+/// 1_000_000 at `rate_per_period`, last accrued at t = 0
+fn account(rate_per_period: u64) -> InterestAccount {
+    InterestAccount {
+        owner: Pubkey::new_unique(),
+        balance: 1_000_000,
+        rate_per_period,
+        last_accrual: 0,
+        bump: 255,
+    }
+}
+
+#[test]
+fn compounds_over_whole_periods() {
+    // 10% per period, 3 periods: 1_000_000 * 1.331
+    let mut account = account(100_000);
+
+    assert_eq!(account.accrue(3 * DAY).unwrap(), 331_000);
+    assert_eq!(account.balance, 1_331_000);
+    assert_eq!(account.last_accrual, 3 * DAY);
+}
+
+#[test]
+fn partial_period_carries_over() {
+    let mut account = account(100_000);
+
+    // Not a full period yet: nothing accrues, the clock does not move
+    assert_eq!(account.accrue(DAY - 1).unwrap(), 0);
+    assert_eq!(account.last_accrual, 0);
+
+    // 1.5 periods: one accrues, the half period is kept
+    account.accrue(DAY + DAY / 2).unwrap();
+    assert_eq!(account.balance, 1_100_000);
+    assert_eq!(account.last_accrual, DAY);
+
+    // The other half completes the second period
+    account.accrue(2 * DAY).unwrap();
+    assert_eq!(account.balance, 1_210_000);
+}
+
+#[test]
+fn splitting_accruals_never_earns_more() {
+    // 0.0333% per period, so every step rounds
+    let mut once = account(333);
+    let mut daily = account(333);
+
+    once.accrue(365 * DAY).unwrap();
+    for day in 1..=365 {
+        daily.accrue(day * DAY).unwrap();
+    }
+
+    assert!(daily.balance <= once.balance);
+}
+
+#[test]
+fn clock_going_backwards_is_rejected() {
+    let mut account = account(100_000);
+    account.last_accrual = DAY;
+
+    assert!(account.accrue(0).is_err());
+    assert_eq!(account.balance, 1_000_000);
+}
@@ -0,0 +1,129 @@
+//! # Compound Rewards Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::compound_rewards`.
+//! Pending rewards move from the reward vault into `pool_tokens` and are
+//! added to both the stake and `total_staked`; `total_claimed` is left
+//! alone because nothing left the pool. A pool paying rewards in another
+//! mint fails with `CannotCompoundDifferentMint`.
+//!
+//! ```bash
+//! cargo test --test compound_rewards
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const STAKED: u64 = 1_000;
+const PENDING: u64 = 250;
+const REWARD_RESERVES: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    staker: Keypair,
+    pool: Pubkey,
+    staking_account: Pubkey,
+    pool_tokens: Pubkey,
+    reward_vault: Pubkey,
+}
+
+/// A stake of `STAKED` with `PENDING` rewards owed, in a pool that pays
+/// rewards in `reward_mint` (the staked token unless set otherwise)
+async fn setup(reward_mint: Option<Pubkey>) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let reward_mint = reward_mint.unwrap_or(mint);
+    let staker = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, reward_mint, pool, REWARD_RESERVES);
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, STAKED);
+
+    let pool_state = secure_matching::Pool {
+        total_staked: STAKED,
+        total_rewards_funded: REWARD_RESERVES,
+        ..common::matching_pool(Pubkey::new_unique(), mint, reward_mint, reward_vault, bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_matching::ID, &pool_state);
+
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", staker.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    let staking_state = secure_matching::StakingAccount {
+        pending_rewards: PENDING,
+        ..common::matching_stake(staker.pubkey(), pool, STAKED, bump)
+    };
+    add_anchor_account(&mut program_test, staking_account, secure_matching::ID, &staking_state);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, staker, pool, staking_account, pool_tokens, reward_vault }
+}
+
+async fn compound(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::CompoundRewards {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            pool: setup.pool,
+            reward_vault: setup.reward_vault,
+            pool_tokens: setup.pool_tokens,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::CompoundRewards {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.staker]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn pending_rewards_become_stake() {
+    let mut setup = setup(None).await;
+
+    compound(&mut setup).await.unwrap();
+
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(staking.amount, STAKED + PENDING);
+    assert_eq!(staking.pending_rewards, 0);
+    // ✅ Compounded, not claimed: nothing left the pool
+    assert_eq!(staking.total_claimed, 0);
+
+    let pool: secure_matching::Pool = setup.env.fetch(setup.pool).await;
+    assert_eq!(pool.total_staked, STAKED + PENDING);
+
+    // The backing tokens moved with the accounting
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, REWARD_RESERVES - PENDING);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, STAKED + PENDING);
+}
+
+#[tokio::test]
+async fn different_reward_mint_fails_with_cannot_compound() {
+    let mut setup = setup(Some(Pubkey::new_unique())).await;
+
+    let err = compound(&mut setup).await.unwrap_err();
+
+    // ✅ Reward tokens can't be counted as stake of another mint
+    assert_eq!(err, custom(secure_matching::ErrorCode::CannotCompoundDifferentMint));
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!((staking.amount, staking.pending_rewards), (STAKED, PENDING));
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, REWARD_RESERVES);
+}
@@ -0,0 +1,124 @@
+//! # Compute Budget Harness
+//!
+//! Measures what `secure_matching::transfer_tokens` pays for its account
+//! constraints compared with `vulnerable_matching::transfer_tokens`, which
+//! validates nothing.
+//!
+//! Both handlers call `sol_log_compute_units()` on entry and exit when built
+//! with the `bench` feature (the secure one also around its token CPI). Both
+//! transactions start from the same budget, so the difference in "units
+//! remaining" at handler entry is exactly the extra cost of deserializing
+//! and checking the secure accounts.
+//!
+//! Needs the real BPF builds, since `processor!` runs natively and is not
+//! metered:
+//!
+//! ```bash
+//! anchor build -- --features bench
+//! cargo test --test compute_budget -- --nocapture
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_token_account, TestEnv};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Upper bound for the secure version's extra validation cost. Two token
+/// account deserializations plus owner/mint/key/state comparisons land well
+/// under this; anything above it deserves a look.
+const MAX_VALIDATION_OVERHEAD: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("secure_matching", secure_matching::ID, None);
+    program_test.add_program("vulnerable_matching", vulnerable_matching::ID, None);
+
+    let mint = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let source = add_token_account(&mut program_test, mint, authority.pubkey(), 1_000_000);
+    let destination = add_token_account(&mut program_test, mint, Pubkey::new_unique(), 0);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, source, destination }
+}
+
+// ============================================================================
+// MEASUREMENT
+// ============================================================================
+
+#[tokio::test]
+async fn secure_constraints_add_modest_overhead() {
+    let mut setup = setup().await;
+
+    let vulnerable = Instruction {
+        program_id: vulnerable_matching::ID,
+        accounts: vulnerable_matching::accounts::TransferTokens {
+            from_account: setup.source,
+            to_account: setup.destination,
+            authority: setup.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: vulnerable_matching::instruction::TransferTokens { amount: 100 }.data(),
+    };
+    let secure = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::TransferTokens {
+            from_account: setup.source,
+            to_account: setup.destination,
+            authority: setup.authority.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::TransferTokens { amount: 100 }.data(),
+    };
+
+    let vulnerable = setup.env.measure(&[vulnerable], &[&setup.authority]).await;
+    let secure = setup.env.measure(&[secure], &[&setup.authority]).await;
+
+    // Built without `bench` there is nothing to compare
+    assert_eq!(vulnerable.checkpoints.len(), 2, "rebuild with --features bench");
+    assert_eq!(secure.checkpoints.len(), 4, "rebuild with --features bench");
+
+    let [v_entry, v_exit] = vulnerable.checkpoints[..] else { unreachable!() };
+    let [s_entry, s_before_cpi, s_after_cpi, s_exit] = secure.checkpoints[..] else { unreachable!() };
+
+    let validation_overhead = v_entry - s_entry;
+    let cpi = s_before_cpi - s_after_cpi;
+    let secure_handler = (s_entry - s_before_cpi) + (s_after_cpi - s_exit);
+    let vulnerable_handler = v_entry - v_exit;
+
+    println!("transfer_tokens compute units");
+    println!("  vulnerable total              {:>7}", vulnerable.consumed);
+    println!("  secure total                  {:>7}", secure.consumed);
+    println!("  secure validation overhead    {:>7}", validation_overhead);
+    println!("  secure handler (excl. CPI)    {:>7}", secure_handler);
+    println!("  vulnerable handler            {:>7}", vulnerable_handler);
+    println!("  token::transfer CPI           {:>7}", cpi);
+
+    // The vulnerable handler never moves tokens, so the CPI is not part of
+    // the security cost
+    assert!(
+        validation_overhead <= MAX_VALIDATION_OVERHEAD,
+        "secure validation costs {} CU more, expected <= {}",
+        validation_overhead,
+        MAX_VALIDATION_OVERHEAD
+    );
+}
@@ -0,0 +1,243 @@
+//! # Cross-Pool Claim Tests
+//!
+//! Two pools, A and B, pay rewards in the same mint. An attacker stakes
+//! honestly in pool A, then claims against pool B's reward vault. In
+//! `vulnerable_matching` pool B's PDA signs the transfer and B's stakers
+//! pay A's rewards. In `secure_matching` the same claim fails on
+//! `staking_account.pool == pool.key()`, and pairing pool A with B's vault
+//! fails on `has_one = reward_vault`. Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test cross_pool_claim
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VAULT: u64 = 10_000;
+const PENDING: u64 = 2_500;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// One program's pools A and B and the attacker's pool A staking account
+struct Pools {
+    pool_a: Pubkey,
+    pool_b: Pubkey,
+    vault_a: Pubkey,
+    vault_b: Pubkey,
+    staking_account: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    attacker: Keypair,
+    attacker_rewards: Pubkey,
+    secure: Pools,
+    vulnerable: Pools,
+}
+
+/// Pool PDA for a fresh staked mint and its reward vault holding `VAULT`
+fn add_pool_pda(program_test: &mut ProgramTest, program_id: Pubkey, reward_mint: Pubkey) -> (Pubkey, Pubkey, Pubkey, u8) {
+    let token_mint = Pubkey::new_unique();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", token_mint.as_ref()], &program_id);
+    let vault = add_token_account(program_test, reward_mint, pool, VAULT);
+    (pool, token_mint, vault, bump)
+}
+
+fn add_secure_pools(program_test: &mut ProgramTest, reward_mint: Pubkey, attacker: Pubkey) -> Pools {
+    let mut pools = Vec::new();
+    for _ in 0..2 {
+        let (pool, token_mint, vault, bump) = add_pool_pda(program_test, secure_matching::ID, reward_mint);
+        add_anchor_account(
+            program_test,
+            pool,
+            secure_matching::ID,
+            &secure_matching::Pool {
+                total_rewards_funded: VAULT,
+                ..common::matching_pool(Pubkey::new_unique(), token_mint, reward_mint, vault, bump)
+            },
+        );
+        pools.push((pool, vault));
+    }
+    let [(pool_a, vault_a), (pool_b, vault_b)] = [pools[0], pools[1]];
+
+    // Honest stake in pool A, at its PDA
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", attacker.as_ref(), pool_a.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        program_test,
+        staking_account,
+        secure_matching::ID,
+        &secure_matching::StakingAccount {
+            pending_rewards: PENDING,
+            ..common::matching_stake(attacker, pool_a, 1_000, bump)
+        },
+    );
+
+    Pools { pool_a, pool_b, vault_a, vault_b, staking_account }
+}
+
+fn add_vulnerable_pools(program_test: &mut ProgramTest, reward_mint: Pubkey, attacker: Pubkey) -> Pools {
+    let mut pools = Vec::new();
+    for _ in 0..2 {
+        let (pool, token_mint, vault, bump) = add_pool_pda(program_test, vulnerable_matching::ID, reward_mint);
+        add_anchor_account(
+            program_test,
+            pool,
+            vulnerable_matching::ID,
+            &vulnerable_matching::Pool {
+                authority: Pubkey::new_unique(),
+                total_deposits: 0,
+                token_mint,
+                reward_vault: vault,
+                bump,
+            },
+        );
+        pools.push((pool, vault));
+    }
+    let [(pool_a, vault_a), (pool_b, vault_b)] = [pools[0], pools[1]];
+
+    let staking_account = Pubkey::new_unique();
+    add_anchor_account(
+        program_test,
+        staking_account,
+        vulnerable_matching::ID,
+        &vulnerable_matching::StakingAccount {
+            owner: attacker,
+            pool: pool_a,
+            amount: 1_000,
+            pending_rewards: PENDING,
+        },
+    );
+
+    Pools { pool_a, pool_b, vault_a, vault_b, staking_account }
+}
+
+/// Pools A and B in both programs, every vault holding `VAULT` of one
+/// reward mint, and an attacker owed `PENDING` by each program's pool A
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+    program_test.add_program(
+        "vulnerable_matching",
+        vulnerable_matching::ID,
+        processor!(vulnerable_matching::entry),
+    );
+
+    let reward_mint = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let secure = add_secure_pools(&mut program_test, reward_mint, attacker.pubkey());
+    let vulnerable = add_vulnerable_pools(&mut program_test, reward_mint, attacker.pubkey());
+    let attacker_rewards = add_token_account(&mut program_test, reward_mint, attacker.pubkey(), 0);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, attacker, attacker_rewards, secure, vulnerable }
+}
+
+fn secure_claim_ix(setup: &Setup, pool: Pubkey, reward_vault: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ClaimRewards {
+            user: setup.attacker.pubkey(),
+            staking_account: setup.secure.staking_account,
+            pool,
+            reward_vault,
+            user_reward_account: setup.attacker_rewards,
+            owner: setup.attacker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::ClaimRewards {}.data(),
+    }
+}
+
+fn vulnerable_claim_ix(setup: &Setup, pool: Pubkey, reward_vault: Pubkey) -> Instruction {
+    Instruction {
+        program_id: vulnerable_matching::ID,
+        accounts: vulnerable_matching::accounts::ClaimRewards {
+            user: setup.attacker.pubkey(),
+            staking_account: setup.vulnerable.staking_account,
+            pool,
+            reward_vault,
+            user_reward_account: setup.attacker_rewards,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_matching::instruction::ClaimRewards {}.data(),
+    }
+}
+
+/// Neither secure vault moved and the attacker received nothing
+async fn assert_secure_untouched(setup: &mut Setup) {
+    assert_eq!(setup.env.token_balance(setup.secure.vault_a).await, VAULT);
+    assert_eq!(setup.env.token_balance(setup.secure.vault_b).await, VAULT);
+    assert_eq!(setup.env.token_balance(setup.attacker_rewards).await, 0);
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_claim_drains_the_other_pools_vault() {
+    let mut setup = setup().await;
+
+    // Pool A staking account, pool B and B's vault
+    let ix = vulnerable_claim_ix(&setup, setup.vulnerable.pool_b, setup.vulnerable.vault_b);
+    setup.env.send(&[ix], &[&setup.attacker]).await.unwrap();
+
+    // Pool A's rewards were paid by pool B's stakers
+    assert_eq!(setup.env.token_balance(setup.attacker_rewards).await, PENDING);
+    assert_eq!(setup.env.token_balance(setup.vulnerable.vault_b).await, VAULT - PENDING);
+    assert_eq!(setup.env.token_balance(setup.vulnerable.vault_a).await, VAULT);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_claim_against_the_other_pool_is_rejected() {
+    let mut setup = setup().await;
+
+    let ix = secure_claim_ix(&setup, setup.secure.pool_b, setup.secure.vault_b);
+    let err = setup.env.send(&[ix], &[&setup.attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_matching::ErrorCode::PoolMismatch));
+    assert_secure_untouched(&mut setup).await;
+}
+
+#[tokio::test]
+async fn secure_claim_with_the_other_pools_vault_is_rejected() {
+    let mut setup = setup().await;
+
+    let ix = secure_claim_ix(&setup, setup.secure.pool_a, setup.secure.vault_b);
+    let err = setup.env.send(&[ix], &[&setup.attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_matching::ErrorCode::InvalidRewardVault));
+    assert_secure_untouched(&mut setup).await;
+}
+
+#[tokio::test]
+async fn secure_claim_from_its_own_pool_pays_from_its_own_vault() {
+    let mut setup = setup().await;
+
+    let ix = secure_claim_ix(&setup, setup.secure.pool_a, setup.secure.vault_a);
+    setup.env.send(&[ix], &[&setup.attacker]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.attacker_rewards).await, PENDING);
+    assert_eq!(setup.env.token_balance(setup.secure.vault_a).await, VAULT - PENDING);
+    assert_eq!(setup.env.token_balance(setup.secure.vault_b).await, VAULT);
+}
@@ -0,0 +1,90 @@
+//! # Pool Curve Tests
+//!
+//! Plain `#[test]`s for `secure_cpi::Pool::quote` under each `CurveType`.
+//! A balanced stablecoin pool should slip far less on StableSwap than on
+//! constant product, and a misconfigured curve must not price anything.
+//!
+//! ```bash
+//! cargo test --test curve
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::*;
+use secure_cpi::{logic::MAX_AMP, CurveType, ErrorCode};
+
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_IN: u64 = 10_000;
+
+fn pool(curve: CurveType, amp: u64, reserve_in: u64, reserve_out: u64) -> secure_cpi::Pool {
+    secure_cpi::Pool {
+        reserve_in,
+        reserve_out,
+        fee_bps: 0,
+        curve,
+        amp,
+        ..common::cpi_pool(Pubkey::new_unique(), (Pubkey::new_unique(), Pubkey::new_unique()), 255)
+    }
+}
+
+fn unsupported_curve() -> Error {
+    ErrorCode::UnsupportedCurve.into()
+}
+
+// ============================================================================
+// SLIPPAGE
+// ============================================================================
+
+#[test]
+fn balanced_stable_pool_slips_less_than_constant_product() {
+    let constant_product = pool(CurveType::ConstantProduct, 0, RESERVE, RESERVE).quote(AMOUNT_IN).unwrap();
+    let stable = pool(CurveType::StableSwap, 100, RESERVE, RESERVE).quote(AMOUNT_IN).unwrap();
+
+    // 1:1 is the fair price for a balanced stable pair
+    let constant_product_slippage = AMOUNT_IN - constant_product;
+    let stable_slippage = AMOUNT_IN - stable;
+
+    assert_eq!(constant_product_slippage, 100); // 1%
+    assert!(stable_slippage <= 1);
+}
+
+#[test]
+fn stable_pool_still_prices_imbalance() {
+    let balanced = pool(CurveType::StableSwap, 100, RESERVE, RESERVE).quote(AMOUNT_IN).unwrap();
+    // Already heavy on the input side: each unit in buys less
+    let skewed = pool(CurveType::StableSwap, 100, 3 * RESERVE / 2, RESERVE / 2).quote(AMOUNT_IN).unwrap();
+
+    assert!(skewed < balanced);
+    assert!(skewed < AMOUNT_IN);
+}
+
+#[test]
+fn fee_applies_to_both_curves() {
+    let mut stable = pool(CurveType::StableSwap, 100, RESERVE, RESERVE);
+    let no_fee = stable.quote(AMOUNT_IN).unwrap();
+    stable.fee_bps = 30;
+
+    assert!(stable.quote(AMOUNT_IN).unwrap() < no_fee);
+}
+
+// ============================================================================
+// MISCONFIGURATION
+// ============================================================================
+
+#[test]
+fn stable_swap_without_amp_is_unsupported() {
+    let err = pool(CurveType::StableSwap, 0, RESERVE, RESERVE).quote(AMOUNT_IN).unwrap_err();
+    assert_eq!(err, unsupported_curve());
+}
+
+#[test]
+fn stable_swap_amp_above_max_is_unsupported() {
+    let err = pool(CurveType::StableSwap, MAX_AMP + 1, RESERVE, RESERVE).quote(AMOUNT_IN).unwrap_err();
+    assert_eq!(err, unsupported_curve());
+}
+
+#[test]
+fn constant_product_with_amp_is_unsupported() {
+    let err = pool(CurveType::ConstantProduct, 100, RESERVE, RESERVE).check_curve().unwrap_err();
+    assert_eq!(err, unsupported_curve());
+}
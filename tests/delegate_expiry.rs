@@ -0,0 +1,161 @@
+//! # Delegate Expiry Tests
+//!
+//! `solana-program-test` scenarios for the `delegate_expiry` that
+//! `secure_matching::approve_delegate` records next to an SPL approval.
+//! `transfer_as_delegate` succeeds up to and including the expiry and
+//! fails with `DelegationExpired` after it, with allowance left over. An
+//! approval expiring in the past is refused up front, and one made with
+//! plain SPL `approve` has no `Delegation` to pass. The `Clock` sysvar is
+//! moved forward directly instead of waiting.
+//!
+//! ```bash
+//! cargo test --test delegate_expiry
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_token_account, custom, TestEnv};
+use secure_matching::ErrorCode;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const BALANCE: u64 = 1_000;
+const ALLOWANCE: u64 = 500;
+/// Seconds the approval lasts
+const LIFETIME: i64 = 60 * 60;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    owner: Keypair,
+    delegate: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+    delegation: Pubkey,
+}
+
+/// `source` holds `BALANCE` for `owner`, nothing approved yet
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let source = add_token_account(&mut program_test, mint, owner.pubkey(), BALANCE);
+    let destination = add_token_account(&mut program_test, mint, Pubkey::new_unique(), 0);
+    let (delegation, _) =
+        Pubkey::find_program_address(&[b"delegation", source.as_ref()], &secure_matching::ID);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, owner, delegate: Keypair::new(), source, destination, delegation }
+}
+
+async fn approve(setup: &mut Setup, delegate_expiry: i64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ApproveDelegate {
+            owner: setup.owner.pubkey(),
+            token_account: setup.source,
+            delegate: setup.delegate.pubkey(),
+            delegation: setup.delegation,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::ApproveDelegate { amount: ALLOWANCE, delegate_expiry }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.owner]).await
+}
+
+async fn transfer_as_delegate(setup: &mut Setup, amount: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::TransferAsDelegate {
+            from_account: setup.source,
+            to_account: setup.destination,
+            delegation: setup.delegation,
+            authority: setup.delegate.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::TransferAsDelegate { amount }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.delegate]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn delegate_transfers_until_expiry() {
+    let mut setup = setup().await;
+    let expiry = setup.env.unix_timestamp().await + LIFETIME;
+    approve(&mut setup, expiry).await.unwrap();
+
+    let delegation: secure_matching::Delegation = setup.env.fetch(setup.delegation).await;
+    assert_eq!(delegation.delegate, setup.delegate.pubkey());
+    assert_eq!(delegation.delegate_expiry, expiry);
+
+    transfer_as_delegate(&mut setup, 100).await.unwrap();
+
+    // The expiry itself is still inside the window
+    setup.env.set_unix_timestamp(expiry).await;
+    transfer_as_delegate(&mut setup, 100).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.destination).await, 200);
+}
+
+#[tokio::test]
+async fn delegate_transfer_after_expiry_fails() {
+    let mut setup = setup().await;
+    let expiry = setup.env.unix_timestamp().await + LIFETIME;
+    approve(&mut setup, expiry).await.unwrap();
+
+    setup.env.set_unix_timestamp(expiry + 1).await;
+
+    // ✅ Allowance is left, but the approval has run out
+    let err = transfer_as_delegate(&mut setup, 100).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::DelegationExpired));
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
+
+#[tokio::test]
+async fn approval_already_expired_is_rejected() {
+    let mut setup = setup().await;
+    let past = setup.env.unix_timestamp().await - 1;
+
+    let err = approve(&mut setup, past).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::DelegationExpired));
+    assert!(setup.env.account(setup.delegation).await.is_none());
+}
+
+#[tokio::test]
+async fn plain_spl_approval_has_no_delegation() {
+    let mut setup = setup().await;
+    let ix = spl_token::instruction::approve(
+        &spl_token::ID,
+        &setup.source,
+        &setup.delegate.pubkey(),
+        &setup.owner.pubkey(),
+        &[],
+        ALLOWANCE,
+    )
+    .unwrap();
+    setup.env.send(&[ix], &[&setup.owner]).await.unwrap();
+
+    // ✅ No recorded expiry, so no delegate transfer
+    let err = transfer_as_delegate(&mut setup, 100).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::AccountNotInitialized));
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
@@ -0,0 +1,240 @@
+//! # Deposit Path Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::deposit_to_pool`:
+//! - The same token account passed as both `user_tokens` and `pool_tokens`.
+//!   A self-transfer moves nothing, so crediting `amount` would inflate
+//!   `total_deposits` for free.
+//! - Deposits against `max_total_deposits`, and the timelocked cap change.
+//! - A source account frozen by the mint fails with `TokenAccountFrozen`.
+//!
+//! ```bash
+//! cargo test --test deposit_to_pool
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+const BALANCE: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn add_token_account_in_state(
+    program_test: &mut ProgramTest,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    state: AccountState,
+) -> Pubkey {
+    let address = Pubkey::new_unique();
+    add_packed(program_test, address, TokenAccount { state, ..common::token_account(mint, owner, amount) });
+    address
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    user: Keypair,
+    mint: Pubkey,
+    pool: Pubkey,
+    staking_account: Pubkey,
+    user_tokens: Pubkey,
+    pool_tokens: Pubkey,
+    /// Second user token account holding `BALANCE`, frozen by the mint
+    frozen_tokens: Pubkey,
+}
+
+/// Empty pool PDA for a fresh mint with deposit cap `cap`,
+/// plus a user token account holding `BALANCE`
+async fn setup(cap: u64) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    add_packed(&mut program_test, mint, Mint { supply: u64::MAX, ..common::mint(None) });
+
+    let authority = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let state = secure_matching::Pool {
+        max_total_deposits: cap,
+        ..common::matching_pool(authority.pubkey(), mint, mint, Pubkey::new_unique(), bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_matching::ID, &state);
+
+    let user = Keypair::new();
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", user.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        staking_account,
+        secure_matching::ID,
+        &common::matching_stake(user.pubkey(), pool, 0, bump),
+    );
+    let user_tokens = add_token_account(&mut program_test, mint, user.pubkey(), BALANCE);
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, 0);
+    let frozen_tokens =
+        add_token_account_in_state(&mut program_test, mint, user.pubkey(), BALANCE, AccountState::Frozen);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, user, mint, pool, staking_account, user_tokens, pool_tokens, frozen_tokens }
+}
+
+async fn deposit(
+    setup: &mut Setup,
+    user_tokens: Pubkey,
+    pool_tokens: Pubkey,
+    amount: u64,
+) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::DepositToPool {
+            user: setup.user.pubkey(),
+            user_tokens,
+            pool_tokens,
+            token_mint: setup.mint,
+            pool: setup.pool,
+            staking_account: setup.staking_account,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::DepositToPool { amount }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await
+}
+
+async fn queue_cap(setup: &mut Setup, signer: &Keypair, max_total_deposits: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::QueueAdminAction {
+            pool: setup.pool,
+            authority: signer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::QueueAdminAction {
+            action: secure_matching::AdminAction::SetCap { max_total_deposits },
+        }
+        .data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn execute_admin_action(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ExecuteAdminAction { pool: setup.pool }.to_account_metas(None),
+        data: secure_matching::instruction::ExecuteAdminAction {}.data(),
+    };
+    setup.env.send(&[ix], &[]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_matching::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn aliased_token_accounts_are_rejected() {
+    let mut setup = setup(u64::MAX).await;
+    let aliased = setup.user_tokens;
+
+    // ❌ Same account as source and destination
+    let err = deposit(&mut setup, aliased, aliased, BALANCE).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::DuplicateAccount));
+
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.total_deposits, 0);
+    assert_eq!(pool.total_shares, 0);
+}
+
+#[tokio::test]
+async fn frozen_source_is_rejected_before_the_token_cpi() {
+    let mut setup = setup(u64::MAX).await;
+    let (frozen_tokens, pool_tokens) = (setup.frozen_tokens, setup.pool_tokens);
+
+    // TokenAccountFrozen, not the token program's opaque CPI failure
+    let err = deposit(&mut setup, frozen_tokens, pool_tokens, BALANCE).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::TokenAccountFrozen));
+    assert_eq!(pool_state(&mut setup).await.total_deposits, 0);
+}
+
+#[tokio::test]
+async fn deposit_credits_actual_balance_delta() {
+    let mut setup = setup(u64::MAX).await;
+    let (user_tokens, pool_tokens) = (setup.user_tokens, setup.pool_tokens);
+
+    deposit(&mut setup, user_tokens, pool_tokens, BALANCE).await.unwrap();
+
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.total_deposits, BALANCE);
+    assert_eq!(pool.total_shares, BALANCE);
+
+    assert_eq!(setup.env.token_balance(pool_tokens).await, pool.total_deposits);
+
+    // Shares are credited to the depositor's position
+    let position: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(position.shares, BALANCE);
+}
+
+#[tokio::test]
+async fn deposit_up_to_exactly_the_cap_succeeds() {
+    let mut setup = setup(BALANCE).await;
+    let (user_tokens, pool_tokens) = (setup.user_tokens, setup.pool_tokens);
+
+    deposit(&mut setup, user_tokens, pool_tokens, BALANCE).await.unwrap();
+
+    assert_eq!(pool_state(&mut setup).await.total_deposits, BALANCE);
+}
+
+#[tokio::test]
+async fn deposit_one_over_the_cap_fails() {
+    let mut setup = setup(BALANCE - 1).await;
+    let (user_tokens, pool_tokens) = (setup.user_tokens, setup.pool_tokens);
+
+    let err = deposit(&mut setup, user_tokens, pool_tokens, BALANCE).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::DepositCapExceeded));
+
+    assert_eq!(pool_state(&mut setup).await.total_deposits, 0);
+}
+
+#[tokio::test]
+async fn queued_cap_raise_waits_for_the_delay() {
+    let mut setup = setup(BALANCE - 1).await;
+    let authority = setup.authority.insecure_clone();
+
+    queue_cap(&mut setup, &authority, BALANCE).await.unwrap();
+
+    // Recorded, but the old cap still applies
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(pool.pending_action, Some(secure_matching::AdminAction::SetCap { max_total_deposits: BALANCE }));
+    assert_eq!(pool.max_total_deposits, BALANCE - 1);
+
+    let err = execute_admin_action(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::ActionNotReady));
+    assert_eq!(pool_state(&mut setup).await.max_total_deposits, BALANCE - 1);
+}
+
+#[tokio::test]
+async fn non_authority_cannot_queue_cap() {
+    let mut setup = setup(BALANCE - 1).await;
+    let attacker = Keypair::new();
+
+    let err = queue_cap(&mut setup, &attacker, u64::MAX).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::common_errors::CommonError::Unauthorized));
+    assert_eq!(pool_state(&mut setup).await.pending_action, None);
+}
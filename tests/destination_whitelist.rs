@@ -0,0 +1,201 @@
+//! # Destination Whitelist Tests
+//!
+//! `solana-program-test` scenarios for `secure_signer::withdraw_to`. With an
+//! empty `allowed_destinations` list the authority may withdraw to any
+//! recipient; once the list is populated, only listed recipients are paid.
+//!
+//! ```bash
+//! cargo test --test destination_whitelist
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::TransactionError,
+};
+
+const DEPOSIT: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+}
+
+/// A vault holding `DEPOSIT`, owned by `authority`, with an empty whitelist
+async fn setup() -> Setup {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = Keypair::new();
+    let vault = Keypair::new();
+    let fund = system_instruction::transfer(&env.payer(), &authority.pubkey(), LAMPORTS_PER_SOL);
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount: DEPOSIT }.data(),
+    };
+    env.send(&[fund, init, deposit], &[&vault, &authority]).await.unwrap();
+
+    Setup { env, authority, vault: vault.pubkey() }
+}
+
+fn withdraw_to_ix(setup: &Setup, recipient: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::WithdrawTo {
+            vault: setup.vault,
+            authority: setup.authority.pubkey(),
+            recipient,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::WithdrawTo { amount }.data(),
+    }
+}
+
+fn add_destination_ix(setup: &Setup, authority: Pubkey, destination: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::UpdateDestinations { vault: setup.vault, authority }.to_account_metas(None),
+        data: secure_signer::instruction::AddDestination { destination }.data(),
+    }
+}
+
+fn remove_destination_ix(setup: &Setup, destination: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::UpdateDestinations {
+            vault: setup.vault,
+            authority: setup.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::RemoveDestination { destination }.data(),
+    }
+}
+
+async fn withdraw_to(setup: &mut Setup, recipient: Pubkey, amount: u64) -> Result<(), TransactionError> {
+    let ix = withdraw_to_ix(setup, recipient, amount);
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+async fn add_destination(setup: &mut Setup, destination: Pubkey) {
+    let ix = add_destination_ix(setup, setup.authority.pubkey(), destination);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_signer::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+// ============================================================================
+// EMPTY WHITELIST
+// ============================================================================
+
+#[tokio::test]
+async fn empty_whitelist_allows_any_destination() {
+    let mut setup = setup().await;
+
+    withdraw_to(&mut setup, Pubkey::new_unique(), 300).await.unwrap();
+    withdraw_to(&mut setup, Pubkey::new_unique(), 200).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert!(vault.allowed_destinations.is_empty());
+    assert_eq!((vault.balance, vault.withdrawal_count), (DEPOSIT - 500, 2));
+}
+
+// ============================================================================
+// POPULATED WHITELIST
+// ============================================================================
+
+#[tokio::test]
+async fn populated_whitelist_allows_listed_destination() {
+    let mut setup = setup().await;
+    let treasury = Pubkey::new_unique();
+    add_destination(&mut setup, treasury).await;
+
+    withdraw_to(&mut setup, treasury, 400).await.unwrap();
+
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - 400);
+}
+
+#[tokio::test]
+async fn populated_whitelist_rejects_other_destinations() {
+    let mut setup = setup().await;
+    add_destination(&mut setup, Pubkey::new_unique()).await;
+
+    let err = withdraw_to(&mut setup, Pubkey::new_unique(), 400).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_signer::ErrorCode::DestinationNotAllowed));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
+
+#[tokio::test]
+async fn removing_a_destination_revokes_it() {
+    let mut setup = setup().await;
+    let [treasury, cold_wallet] = [Pubkey::new_unique(), Pubkey::new_unique()];
+    add_destination(&mut setup, treasury).await;
+    add_destination(&mut setup, cold_wallet).await;
+
+    let ix = remove_destination_ix(&setup, treasury);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    let err = withdraw_to(&mut setup, treasury, 400).await.unwrap_err();
+    assert_eq!(err, custom(secure_signer::ErrorCode::DestinationNotAllowed));
+    withdraw_to(&mut setup, cold_wallet, 400).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.allowed_destinations, vec![cold_wallet]);
+}
+
+#[tokio::test]
+async fn whitelist_is_capped() {
+    let mut setup = setup().await;
+    for _ in 0..secure_signer::MAX_ALLOWED_DESTINATIONS {
+        add_destination(&mut setup, Pubkey::new_unique()).await;
+    }
+
+    let ix = add_destination_ix(&setup, setup.authority.pubkey(), Pubkey::new_unique());
+    let err = setup.env.send(&[ix], &[&setup.authority]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_signer::ErrorCode::TooManyDestinations));
+}
+
+// ============================================================================
+// ACCESS CONTROL
+// ============================================================================
+
+#[tokio::test]
+async fn only_the_authority_can_add_destinations() {
+    let mut setup = setup().await;
+    let attacker = Keypair::new();
+
+    let ix = add_destination_ix(&setup, attacker.pubkey(), attacker.pubkey());
+    let err = setup.env.send(&[ix], &[&attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_signer::ErrorCode::UnauthorizedAuthority));
+    assert!(vault_state(&mut setup).await.allowed_destinations.is_empty());
+}
@@ -0,0 +1,68 @@
+//! # Discriminator Check Tests
+//!
+//! Plain `#[test]`s for `secure_discriminator::check_discriminator`. A
+//! `Position` has the same layout as a `Pool`, so only the discriminator
+//! tells them apart.
+//!
+//! ```bash
+//! cargo test --test discriminator
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::serialize;
+use secure_discriminator::{check_discriminator, ErrorCode, Pool, Position};
+
+/// Run `f` against an `AccountInfo` owned by `secure_discriminator` holding `data`
+fn with_account<R>(mut data: Vec<u8>, f: impl FnOnce(&AccountInfo) -> R) -> R {
+    let key = Pubkey::new_unique();
+    let owner = secure_discriminator::ID;
+    let mut lamports = 1_000_000;
+    let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    f(&info)
+}
+
+fn pool() -> Pool {
+    Pool { authority: Pubkey::new_unique(), reserve: 1_000 }
+}
+
+fn position() -> Position {
+    Position { owner: Pubkey::new_unique(), amount: 1_000_000_000_000 }
+}
+
+#[test]
+fn pool_passes_pool_check() {
+    with_account(serialize(&pool()), |info| {
+        assert!(check_discriminator::<Pool>(info).is_ok());
+    });
+}
+
+#[test]
+fn position_is_rejected_as_pool() {
+    // Same length, same field offsets, different type
+    assert_eq!(serialize(&position()).len(), serialize(&pool()).len());
+
+    with_account(serialize(&position()), |info| {
+        let err = check_discriminator::<Pool>(info).unwrap_err();
+        assert_eq!(err, ErrorCode::BadDiscriminator.into());
+    });
+}
+
+#[test]
+fn pool_is_rejected_as_position() {
+    with_account(serialize(&pool()), |info| {
+        let err = check_discriminator::<Position>(info).unwrap_err();
+        assert_eq!(err, ErrorCode::BadDiscriminator.into());
+    });
+}
+
+#[test]
+fn short_or_empty_data_is_rejected() {
+    for len in [0, 7] {
+        with_account(vec![0; len], |info| {
+            let err = check_discriminator::<Pool>(info).unwrap_err();
+            assert_eq!(err, ErrorCode::BadDiscriminator.into());
+        });
+    }
+}
@@ -0,0 +1,240 @@
+//! # Donation Tests
+//!
+//! A direct SPL transfer into `vault_tokens` against `vulnerable_donation`
+//! and `secure_donation`. Each vault starts with 10_000 tokens backing
+//! 10_000 shares; the attacker donates 10_000 more, then the victim
+//! deposits. `share_price` is read from the instruction's return data.
+//! Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test donation
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+/// Tokens and shares already in the vault, held by honest depositors
+const EXISTING: u64 = 10_000;
+const DONATION: u64 = 10_000;
+const VICTIM_DEPOSIT: u64 = 10_000;
+const SCALE: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    attacker: Keypair,
+    victim: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    attacker_tokens: Pubkey,
+    victim_tokens: Pubkey,
+    position: Pubkey,
+}
+
+/// Vulnerable vault holding `EXISTING` tokens against `EXISTING` shares
+async fn vulnerable_setup() -> Setup {
+    let id = vulnerable_donation::ID;
+    let mut program_test = ProgramTest::new("vulnerable_donation", id, processor!(vulnerable_donation::entry));
+
+    let mint = Pubkey::new_unique();
+    let (attacker, victim) = (Keypair::new(), Keypair::new());
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &id);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, EXISTING);
+    let attacker_tokens = add_token_account(&mut program_test, mint, attacker.pubkey(), DONATION);
+    let victim_tokens = add_token_account(&mut program_test, mint, victim.pubkey(), VICTIM_DEPOSIT);
+
+    add_anchor_account(
+        &mut program_test,
+        vault,
+        id,
+        &vulnerable_donation::Vault { mint, vault_tokens, total_shares: EXISTING, bump },
+    );
+    let (position, bump) =
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), victim.pubkey().as_ref()], &id);
+    add_anchor_account(
+        &mut program_test,
+        position,
+        id,
+        &vulnerable_donation::Position { owner: victim.pubkey(), vault, shares: 0, bump },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, attacker, victim, vault, vault_tokens, attacker_tokens, victim_tokens, position }
+}
+
+/// Secure vault recording and holding `EXISTING` tokens against `EXISTING` shares
+async fn secure_setup() -> Setup {
+    let id = secure_donation::ID;
+    let mut program_test = ProgramTest::new("secure_donation", id, processor!(secure_donation::entry));
+
+    let mint = Pubkey::new_unique();
+    let (attacker, victim) = (Keypair::new(), Keypair::new());
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &id);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, EXISTING);
+    let attacker_tokens = add_token_account(&mut program_test, mint, attacker.pubkey(), DONATION);
+    let victim_tokens = add_token_account(&mut program_test, mint, victim.pubkey(), VICTIM_DEPOSIT);
+
+    add_anchor_account(
+        &mut program_test,
+        vault,
+        id,
+        &secure_donation::Vault { mint, vault_tokens, total_assets: EXISTING, total_shares: EXISTING, bump },
+    );
+    let (position, bump) =
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), victim.pubkey().as_ref()], &id);
+    add_anchor_account(
+        &mut program_test,
+        position,
+        id,
+        &secure_donation::Position { owner: victim.pubkey(), vault, shares: 0, bump },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, attacker, victim, vault, vault_tokens, attacker_tokens, victim_tokens, position }
+}
+
+/// ❌ Plain SPL transfer to the vault's token account; no program involved
+async fn donate(setup: &mut Setup) {
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &setup.attacker_tokens,
+        &setup.vault_tokens,
+        &setup.attacker.pubkey(),
+        &[],
+        DONATION,
+    )
+    .unwrap();
+    setup.env.send(&[ix], &[&setup.attacker]).await.unwrap();
+}
+
+/// Run `ix` without committing it and decode its `u64` return value
+async fn returned_u64(setup: &mut Setup, ix: Instruction) -> u64 {
+    let context = &mut setup.env.context;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    simulation.result.unwrap().unwrap();
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    u64::from_le_bytes(return_data.data.try_into().unwrap())
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+async fn vulnerable_price(setup: &mut Setup) -> u64 {
+    let ix = Instruction {
+        program_id: vulnerable_donation::ID,
+        accounts: vulnerable_donation::accounts::SharePrice { vault: setup.vault, vault_tokens: setup.vault_tokens }
+            .to_account_metas(None),
+        data: vulnerable_donation::instruction::SharePrice {}.data(),
+    };
+    returned_u64(setup, ix).await
+}
+
+#[tokio::test]
+async fn vulnerable_donation_doubles_the_share_price() {
+    let mut setup = vulnerable_setup().await;
+    assert_eq!(vulnerable_price(&mut setup).await, SCALE);
+
+    donate(&mut setup).await;
+
+    // ❌ No deposit, no new shares, and the price has doubled
+    assert_eq!(vulnerable_price(&mut setup).await, 2 * SCALE);
+}
+
+#[tokio::test]
+async fn vulnerable_deposit_after_donation_mints_half_the_shares() {
+    let mut setup = vulnerable_setup().await;
+    donate(&mut setup).await;
+
+    let ix = Instruction {
+        program_id: vulnerable_donation::ID,
+        accounts: vulnerable_donation::accounts::Deposit {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            position: setup.position,
+            user_tokens: setup.victim_tokens,
+            user: setup.victim.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_donation::instruction::Deposit { amount: VICTIM_DEPOSIT }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+
+    let position: vulnerable_donation::Position = setup.env.fetch(setup.position).await;
+    assert_eq!(position.shares, VICTIM_DEPOSIT / 2);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+async fn secure_price(setup: &mut Setup) -> u64 {
+    let ix = Instruction {
+        program_id: secure_donation::ID,
+        accounts: secure_donation::accounts::SharePrice { vault: setup.vault }.to_account_metas(None),
+        data: secure_donation::instruction::SharePrice {}.data(),
+    };
+    returned_u64(setup, ix).await
+}
+
+async fn secure_vault(setup: &mut Setup) -> secure_donation::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+#[tokio::test]
+async fn secure_donation_leaves_the_share_price_unchanged() {
+    let mut setup = secure_setup().await;
+    assert_eq!(secure_price(&mut setup).await, SCALE);
+
+    donate(&mut setup).await;
+
+    // ✅ The tokens arrived, but nothing the program prices with moved
+    let vault_tokens = setup.vault_tokens;
+    assert_eq!(setup.env.token_balance(vault_tokens).await, EXISTING + DONATION);
+    assert_eq!(secure_vault(&mut setup).await.total_assets, EXISTING);
+    assert_eq!(secure_price(&mut setup).await, SCALE);
+}
+
+#[tokio::test]
+async fn secure_deposit_after_donation_mints_full_shares() {
+    let mut setup = secure_setup().await;
+    donate(&mut setup).await;
+
+    let ix = Instruction {
+        program_id: secure_donation::ID,
+        accounts: secure_donation::accounts::Deposit {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            position: setup.position,
+            user_tokens: setup.victim_tokens,
+            user: setup.victim.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_donation::instruction::Deposit { amount: VICTIM_DEPOSIT }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+
+    let position: secure_donation::Position = setup.env.fetch(setup.position).await;
+    assert_eq!(position.shares, VICTIM_DEPOSIT);
+    assert_eq!(secure_vault(&mut setup).await.total_assets, EXISTING + VICTIM_DEPOSIT);
+}
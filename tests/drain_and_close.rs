@@ -0,0 +1,125 @@
+//! # Drain and Close Tests
+//!
+//! `solana-program-test` scenarios for `secure_pda::drain_and_close`. The
+//! vault PDA signs the sweep of `vault_tokens` to the authority, the name
+//! leaves the registry, and the vault's rent goes back to the authority, all
+//! in one instruction. A `vault_tokens` account the vault doesn't own fails
+//! with `InvalidOwner` and nothing moves.
+//!
+//! ```bash
+//! cargo test --test drain_and_close
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, Space, ToAccountMetas};
+use common::{add_account, add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const NAME: &str = "savings";
+const OTHER: &str = "checking";
+const SWEPT: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    /// Holds no lamports, so everything it ends up with came from the close
+    authority: Keypair,
+    registry: Pubkey,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    authority_tokens: Pubkey,
+    /// Same mint and balance as `vault_tokens`, owned by someone else
+    foreign_tokens: Pubkey,
+}
+
+/// `NAME` and `OTHER` registered, with `SWEPT` tokens held by the `NAME` vault
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+
+    let authority = Keypair::new();
+    let (registry, bump) = Pubkey::find_program_address(&[b"registry", authority.pubkey().as_ref()], &secure_pda::ID);
+    let names = vec![NAME.to_string(), OTHER.to_string()];
+    let mut data = common::serialize(&secure_pda::VaultRegistry { authority: authority.pubkey(), names, bump });
+    data.resize(8 + secure_pda::VaultRegistry::INIT_SPACE, 0);
+    add_account(&mut program_test, registry, secure_pda::ID, data);
+
+    let (vault, bump) =
+        Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref(), NAME.as_bytes()], &secure_pda::ID);
+    let state = common::pda_vault(authority.pubkey(), NAME, SWEPT, bump);
+    add_anchor_account(&mut program_test, vault, secure_pda::ID, &state);
+
+    let mint = Pubkey::new_unique();
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, SWEPT);
+    let authority_tokens = add_token_account(&mut program_test, mint, authority.pubkey(), 0);
+    let foreign_tokens = add_token_account(&mut program_test, mint, Pubkey::new_unique(), SWEPT);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, registry, vault, vault_tokens, authority_tokens, foreign_tokens }
+}
+
+async fn drain_and_close(setup: &mut Setup, vault_tokens: Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::DrainAndClose {
+            vault: setup.vault,
+            vault_tokens,
+            authority_tokens: setup.authority_tokens,
+            registry: setup.registry,
+            authority: setup.authority.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::DrainAndClose {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn tokens_and_rent_return_to_the_authority() {
+    let mut setup = setup().await;
+    let vault_tokens = setup.vault_tokens;
+    let rent = setup.env.lamports(setup.vault).await;
+
+    drain_and_close(&mut setup, vault_tokens).await.unwrap();
+
+    // ✅ Swept by the vault PDA
+    assert_eq!(setup.env.token_balance(setup.authority_tokens).await, SWEPT);
+    assert_eq!(setup.env.token_balance(setup.vault_tokens).await, 0);
+
+    // Only the closed vault leaves the registry
+    let registry: secure_pda::VaultRegistry = setup.env.fetch(setup.registry).await;
+    assert_eq!(registry.names, vec![OTHER.to_string()]);
+
+    assert!(setup.env.account(setup.vault).await.is_none());
+    assert_eq!(setup.env.lamports(setup.authority.pubkey()).await, rent);
+}
+
+#[tokio::test]
+async fn foreign_vault_tokens_fail_with_invalid_owner() {
+    let mut setup = setup().await;
+
+    let foreign_tokens = setup.foreign_tokens;
+    let err = drain_and_close(&mut setup, foreign_tokens).await.unwrap_err();
+
+    // ✅ The source must belong to the vault PDA
+    assert_eq!(err, custom(secure_pda::common_errors::CommonError::InvalidOwner));
+    assert_eq!(setup.env.token_balance(setup.foreign_tokens).await, SWEPT);
+    assert_eq!(setup.env.token_balance(setup.authority_tokens).await, 0);
+    let registry: secure_pda::VaultRegistry = setup.env.fetch(setup.registry).await;
+    assert_eq!(registry.names.len(), 2);
+    assert!(setup.env.account(setup.vault).await.is_some());
+}
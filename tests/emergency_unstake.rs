@@ -0,0 +1,143 @@
+//! # Emergency Unstake Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::emergency_unstake`:
+//! a locked stake with pending rewards exits immediately, gets its principal
+//! back, and forfeits the rewards. The reward vault is never touched, and
+//! the emptied staking account is closed.
+//!
+//! ```bash
+//! cargo test --test emergency_unstake
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, events, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const STAKED: u64 = 1_000;
+const PENDING: u64 = 400;
+const REWARD_RESERVES: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    staker: Keypair,
+    pool: Pubkey,
+    staking_account: Pubkey,
+    user_tokens: Pubkey,
+    pool_tokens: Pubkey,
+    reward_vault: Pubkey,
+}
+
+/// A stake of `STAKED` with `PENDING` rewards owed, locked for a year.
+/// Rewards are paid in the staked token, so the reward vault is a valid
+/// token account for the same mint.
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let staker = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, mint, pool, REWARD_RESERVES);
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, STAKED);
+    let user_tokens = add_token_account(&mut program_test, mint, staker.pubkey(), 0);
+
+    add_anchor_account(
+        &mut program_test,
+        pool,
+        secure_matching::ID,
+        &secure_matching::Pool {
+            total_staked: STAKED,
+            min_stake_duration: 365 * 24 * 60 * 60,
+            early_exit_penalty_bps: 5_000,
+            total_rewards_funded: REWARD_RESERVES,
+            reward_rate: 1,
+            ..common::matching_pool(Pubkey::new_unique(), mint, mint, reward_vault, bump)
+        },
+    );
+
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", staker.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        staking_account,
+        secure_matching::ID,
+        &secure_matching::StakingAccount {
+            pending_rewards: PENDING,
+            last_stake_time: i64::MAX / 2,
+            ..common::matching_stake(staker.pubkey(), pool, STAKED, bump)
+        },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, staker, pool, staking_account, user_tokens, pool_tokens, reward_vault }
+}
+
+fn emergency_unstake_ix(setup: &Setup, pool_tokens: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::EmergencyUnstake {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens,
+            pool: setup.pool,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::EmergencyUnstake {}.data(),
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn principal_returned_and_rewards_forfeited() {
+    let mut setup = setup().await;
+
+    // Still locked: the stake was made "in the future"
+    let ix = emergency_unstake_ix(&setup, setup.pool_tokens);
+    let (result, logs) = setup.env.send_with_logs(&[ix], &[&setup.staker]).await;
+    result.unwrap();
+
+    // Full principal, no penalty
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, STAKED);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, 0);
+    // Reward vault untouched
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, REWARD_RESERVES);
+
+    let unstaked = events::<secure_matching::EmergencyUnstaked>(&logs);
+    assert_eq!(unstaked.len(), 1);
+    assert_eq!((unstaked[0].amount_returned, unstaked[0].rewards_forfeited), (STAKED, PENDING));
+
+    // ✅ Nothing left to claim, so the rent went back to the staker
+    assert!(setup.env.account(setup.staking_account).await.is_none());
+
+    let pool: secure_matching::Pool = setup.env.fetch(setup.pool).await;
+    assert_eq!(pool.total_staked, 0);
+}
+
+#[tokio::test]
+async fn reward_vault_cannot_be_used_as_principal_source() {
+    let mut setup = setup().await;
+
+    let ix = emergency_unstake_ix(&setup, setup.reward_vault);
+    let err = setup.env.send(&[ix], &[&setup.staker]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InvalidRewardVault));
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, REWARD_RESERVES);
+}
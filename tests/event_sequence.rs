@@ -0,0 +1,126 @@
+//! # Event Sequence Tests
+//!
+//! Runs deposit → withdraw → deposit against `secure_signer` in
+//! `solana-program-test`, decodes the `Program data:` log lines, and checks
+//! every event carries the vault's `sequence`, strictly increasing.
+//!
+//! ```bash
+//! cargo test --test event_sequence
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{events, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Send `ixs`, which must succeed, and return the transaction's log lines
+async fn send(env: &mut TestEnv, ixs: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+    let (result, logs) = env.send_with_logs(ixs, signers).await;
+    result.unwrap();
+    logs
+}
+
+fn deposit_ix(vault: Pubkey, depositor: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit { vault, depositor }.to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount }.data(),
+    }
+}
+
+fn withdraw_ix(vault: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Withdraw { vault, authority }.to_account_metas(None),
+        data: secure_signer::instruction::Withdraw { amount }.data(),
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn sequence_strictly_increases_across_deposit_withdraw_deposit() {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+
+    let vault = Keypair::new();
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let logs = send(&mut env, &[init], &[&vault, &authority]).await;
+    let initialized = events::<secure_signer::VaultInitialized>(&logs);
+    assert_eq!(initialized.len(), 1);
+    let mut sequences = vec![initialized[0].sequence];
+
+    let logs = send(&mut env, &[deposit_ix(vault.pubkey(), authority.pubkey(), 1_000)], &[&authority]).await;
+    let deposits = events::<secure_signer::DepositMade>(&logs);
+    assert_eq!(deposits.len(), 1);
+    sequences.push(deposits[0].sequence);
+
+    let logs = send(&mut env, &[withdraw_ix(vault.pubkey(), authority.pubkey(), 400)], &[&authority]).await;
+    let withdrawals = events::<secure_signer::WithdrawalMade>(&logs);
+    assert_eq!(withdrawals.len(), 1);
+    sequences.push(withdrawals[0].sequence);
+
+    let logs = send(&mut env, &[deposit_ix(vault.pubkey(), authority.pubkey(), 250)], &[&authority]).await;
+    let deposits = events::<secure_signer::DepositMade>(&logs);
+    assert_eq!(deposits.len(), 1);
+    sequences.push(deposits[0].sequence);
+
+    // Strictly increasing with no gaps
+    assert_eq!(sequences, vec![0, 1, 2, 3]);
+
+    let state: secure_signer::Vault = env.fetch(vault.pubkey()).await;
+    assert_eq!(state.sequence, 3);
+}
+
+#[tokio::test]
+async fn failed_instruction_does_not_consume_a_sequence() {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+    let payer = env.payer();
+
+    let vault = Keypair::new();
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    send(&mut env, &[init], &[&vault]).await;
+    send(&mut env, &[deposit_ix(vault.pubkey(), payer, 100)], &[]).await;
+
+    // Overdraw: rejected, state rolled back
+    assert!(env.send(&[withdraw_ix(vault.pubkey(), payer, 101)], &[]).await.is_err());
+
+    let logs = send(&mut env, &[withdraw_ix(vault.pubkey(), payer, 100)], &[]).await;
+    let withdrawals = events::<secure_signer::WithdrawalMade>(&logs);
+    assert_eq!(withdrawals[0].sequence, 2);
+}
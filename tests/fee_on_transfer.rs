@@ -0,0 +1,304 @@
+//! # Fee-on-Transfer Deposit Tests
+//!
+//! A Token-2022 mint with a `TransferFee` extension withholds `FEE_BPS` of
+//! every transfer in the destination account. Depositing `DEPOSIT` moves
+//! only `DEPOSIT - FEE` into the pool's spendable balance:
+//! - `vulnerable_matching::deposit_to_pool` records `total_deposits +=
+//!   amount`, so the pool claims `FEE` more than its token account holds.
+//! - `secure_matching::deposit_to_pool` reloads `pool_tokens` after the CPI
+//!   and credits the balance delta, so `total_deposits` matches the vault
+//!   and the last redeemer can be paid in full.
+//!
+//! Runs in `solana-program-test` against the bundled Token-2022 program.
+//!
+//! ```bash
+//! cargo test --test fee_on_transfer
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{instruction::initialize_transfer_fee_config, TransferFeeAmount},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    instruction::{initialize_account3, initialize_mint2, mint_to, transfer_checked},
+    state::{Account as TokenAccount, Mint},
+};
+
+const DECIMALS: u8 = 6;
+const FEE_BPS: u16 = 100;
+const DEPOSIT: u64 = 1_000;
+/// 1% of `DEPOSIT`, withheld in the destination account
+const FEE: u64 = 10;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    user: Keypair,
+    mint: Pubkey,
+    user_tokens: Pubkey,
+    secure_pool: Pubkey,
+    secure_pool_tokens: Pubkey,
+    staking_account: Pubkey,
+    vulnerable_pool: Pubkey,
+    vulnerable_pool_tokens: Pubkey,
+}
+
+/// Token-2022 account for the fee mint, with room for `TransferFeeAmount`
+async fn create_token_account(setup: &mut Setup, owner: Pubkey) -> Pubkey {
+    let account = Keypair::new();
+    let space = ExtensionType::try_calculate_account_len::<TokenAccount>(&[ExtensionType::TransferFeeAmount])
+        .unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &setup.env.payer(),
+            &account.pubkey(),
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        initialize_account3(&spl_token_2022::ID, &account.pubkey(), &setup.mint, &owner).unwrap(),
+    ];
+    setup.env.send(&ixs, &[&account]).await.unwrap();
+    account.pubkey()
+}
+
+/// A 1% fee-on-transfer Token-2022 mint, a user holding `DEPOSIT`, and an
+/// empty pool for that mint in each program
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+    program_test.add_program(
+        "vulnerable_matching",
+        vulnerable_matching::ID,
+        processor!(vulnerable_matching::entry),
+    );
+
+    let mint = Keypair::new();
+    let user = Keypair::new();
+
+    let (secure_pool, bump) =
+        Pubkey::find_program_address(&[b"pool", mint.pubkey().as_ref()], &secure_matching::ID);
+    add_anchor_account(
+        &mut program_test,
+        secure_pool,
+        secure_matching::ID,
+        &common::matching_pool(Pubkey::new_unique(), mint.pubkey(), mint.pubkey(), Pubkey::new_unique(), bump),
+    );
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", user.pubkey().as_ref(), secure_pool.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        staking_account,
+        secure_matching::ID,
+        &common::matching_stake(user.pubkey(), secure_pool, 0, bump),
+    );
+
+    let vulnerable_pool = Pubkey::new_unique();
+    add_anchor_account(
+        &mut program_test,
+        vulnerable_pool,
+        vulnerable_matching::ID,
+        &vulnerable_matching::Pool {
+            authority: Pubkey::new_unique(),
+            total_deposits: 0,
+            token_mint: mint.pubkey(),
+            reward_vault: Pubkey::new_unique(),
+            bump: 0,
+        },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    let mut setup = Setup {
+        env,
+        user,
+        mint: mint.pubkey(),
+        user_tokens: Pubkey::default(),
+        secure_pool,
+        secure_pool_tokens: Pubkey::default(),
+        staking_account,
+        vulnerable_pool,
+        vulnerable_pool_tokens: Pubkey::default(),
+    };
+
+    // Fee config must be initialized before the mint itself
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+    let payer = setup.env.payer();
+    let ixs = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        ),
+        initialize_transfer_fee_config(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            Some(&payer),
+            Some(&payer),
+            FEE_BPS,
+            u64::MAX,
+        )
+        .unwrap(),
+        initialize_mint2(&spl_token_2022::ID, &mint.pubkey(), &payer, None, DECIMALS).unwrap(),
+    ];
+    setup.env.send(&ixs, &[&mint]).await.unwrap();
+
+    let user = setup.user.pubkey();
+    setup.user_tokens = create_token_account(&mut setup, user).await;
+    setup.secure_pool_tokens = create_token_account(&mut setup, secure_pool).await;
+    setup.vulnerable_pool_tokens = create_token_account(&mut setup, vulnerable_pool).await;
+
+    let ix = mint_to(&spl_token_2022::ID, &setup.mint, &setup.user_tokens, &payer, &[], DEPOSIT).unwrap();
+    setup.env.send(&[ix], &[]).await.unwrap();
+
+    setup
+}
+
+async fn token_account(setup: &mut Setup, address: Pubkey) -> (u64, u64) {
+    let account = setup.env.account(address).await.unwrap();
+    let state = StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap();
+    let withheld = state.get_extension::<TransferFeeAmount>().unwrap().withheld_amount;
+    (state.base.amount, u64::from(withheld))
+}
+
+async fn secure_pool_state(setup: &mut Setup) -> secure_matching::Pool {
+    setup.env.fetch(setup.secure_pool).await
+}
+
+async fn secure_deposit(setup: &mut Setup, amount: u64) {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::DepositToPool {
+            user: setup.user.pubkey(),
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.secure_pool_tokens,
+            token_mint: setup.mint,
+            pool: setup.secure_pool,
+            staking_account: setup.staking_account,
+            token_program: spl_token_2022::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::DepositToPool { amount }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn naive_accounting_over_counts_fee_on_transfer_deposit() {
+    let mut setup = setup().await;
+
+    // ❌ The vulnerable handler moves no tokens itself, so the user's
+    // transfer rides in the same transaction, as an integrator would send it
+    let transfer = transfer_checked(
+        &spl_token_2022::ID,
+        &setup.user_tokens,
+        &setup.mint,
+        &setup.vulnerable_pool_tokens,
+        &setup.user.pubkey(),
+        &[],
+        DEPOSIT,
+        DECIMALS,
+    )
+    .unwrap();
+    let deposit = Instruction {
+        program_id: vulnerable_matching::ID,
+        accounts: vulnerable_matching::accounts::DepositToPool {
+            user: setup.user.pubkey(),
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.vulnerable_pool_tokens,
+            pool: setup.vulnerable_pool,
+        }
+        .to_account_metas(None),
+        data: vulnerable_matching::instruction::DepositToPool { amount: DEPOSIT }.data(),
+    };
+    setup.env.send(&[transfer, deposit], &[&setup.user]).await.unwrap();
+
+    let pool_tokens = setup.vulnerable_pool_tokens;
+    let (received, withheld) = token_account(&mut setup, pool_tokens).await;
+    assert_eq!(received, DEPOSIT - FEE);
+    assert_eq!(withheld, FEE);
+
+    let pool: vulnerable_matching::Pool = setup.env.fetch(setup.vulnerable_pool).await;
+
+    // ❌ The pool records the requested amount, FEE more than it can pay out
+    assert_eq!(pool.total_deposits, DEPOSIT);
+    assert!(pool.total_deposits > received);
+}
+
+#[tokio::test]
+async fn deposit_credits_balance_delta_net_of_fee() {
+    let mut setup = setup().await;
+
+    secure_deposit(&mut setup, DEPOSIT).await;
+
+    let pool_tokens = setup.secure_pool_tokens;
+    let (received, withheld) = token_account(&mut setup, pool_tokens).await;
+    assert_eq!(received, DEPOSIT - FEE);
+    assert_eq!(withheld, FEE);
+
+    // ✅ Shares and deposits are backed by what actually arrived
+    let pool = secure_pool_state(&mut setup).await;
+    assert_eq!(pool.total_deposits, received);
+    assert_eq!(pool.total_shares, received);
+
+    let position: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(position.shares, received);
+}
+
+#[tokio::test]
+async fn sole_depositor_redeems_everything_the_pool_received() {
+    let mut setup = setup().await;
+    secure_deposit(&mut setup, DEPOSIT).await;
+    let shares = secure_pool_state(&mut setup).await.total_shares;
+
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::RedeemShares {
+            user: setup.user.pubkey(),
+            staking_account: setup.staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.secure_pool_tokens,
+            token_mint: setup.mint,
+            pool: setup.secure_pool,
+            token_program: spl_token_2022::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::RedeemShares { shares }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+
+    // ✅ The recorded deposit is exactly the spendable balance, so the
+    // final redemption empties the pool instead of failing short
+    let pool = secure_pool_state(&mut setup).await;
+    assert_eq!(pool.total_deposits, 0);
+    assert_eq!(pool.total_shares, 0);
+    let (pool_tokens, user_tokens) = (setup.secure_pool_tokens, setup.user_tokens);
+    assert_eq!(token_account(&mut setup, pool_tokens).await.0, 0);
+
+    // The outbound transfer pays the fee again, withheld at the user's side
+    let (balance, withheld) = token_account(&mut setup, user_tokens).await;
+    assert_eq!(balance, DEPOSIT - FEE - FEE);
+    assert_eq!(withheld, FEE);
+}
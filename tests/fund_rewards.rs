@@ -0,0 +1,197 @@
+//! # Reward Funding Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::fund_rewards`:
+//! the pool authority funds `reward_vault`, a staker claims from it, and
+//! everyone else is turned away. Also covers `claim_rewards` against an
+//! underfunded vault.
+//!
+//! ```bash
+//! cargo test --test fund_rewards
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const FUNDING: u64 = 10_000;
+const PENDING: u64 = 2_500;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    admin: Keypair,
+    attacker: Keypair,
+    staker: Keypair,
+    pool: Pubkey,
+    reward_vault: Pubkey,
+    decoy_vault: Pubkey,
+    admin_tokens: Pubkey,
+    attacker_tokens: Pubkey,
+    staking_account: Pubkey,
+    staker_rewards: Pubkey,
+}
+
+/// Pool with an empty reward vault, a funded admin and attacker,
+/// and a staker with `PENDING` rewards owed
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let token_mint = Pubkey::new_unique();
+    let reward_mint = Pubkey::new_unique();
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+    let staker = Keypair::new();
+
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", token_mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, reward_mint, pool, 0);
+    let decoy_vault = add_token_account(&mut program_test, reward_mint, attacker.pubkey(), 0);
+    add_anchor_account(
+        &mut program_test,
+        pool,
+        secure_matching::ID,
+        &common::matching_pool(admin.pubkey(), token_mint, reward_mint, reward_vault, bump),
+    );
+
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", staker.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        staking_account,
+        secure_matching::ID,
+        &secure_matching::StakingAccount {
+            pending_rewards: PENDING,
+            ..common::matching_stake(staker.pubkey(), pool, 0, bump)
+        },
+    );
+
+    let admin_tokens = add_token_account(&mut program_test, reward_mint, admin.pubkey(), FUNDING);
+    let attacker_tokens = add_token_account(&mut program_test, reward_mint, attacker.pubkey(), FUNDING);
+    let staker_rewards = add_token_account(&mut program_test, reward_mint, staker.pubkey(), 0);
+
+    let env = TestEnv::start(program_test).await;
+    Setup {
+        env,
+        admin,
+        attacker,
+        staker,
+        pool,
+        reward_vault,
+        decoy_vault,
+        admin_tokens,
+        attacker_tokens,
+        staking_account,
+        staker_rewards,
+    }
+}
+
+fn fund_ix(setup: &Setup, admin: Pubkey, reward_vault: Pubkey, admin_tokens: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::FundRewards {
+            admin,
+            pool: setup.pool,
+            reward_vault,
+            admin_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::FundRewards { amount }.data(),
+    }
+}
+
+fn claim_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ClaimRewards {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            pool: setup.pool,
+            reward_vault: setup.reward_vault,
+            user_reward_account: setup.staker_rewards,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::ClaimRewards {}.data(),
+    }
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_matching::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn fund_then_claim() {
+    let mut setup = setup().await;
+
+    let ix = fund_ix(&setup, setup.admin.pubkey(), setup.reward_vault, setup.admin_tokens, FUNDING);
+    setup.env.send(&[ix], &[&setup.admin]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, FUNDING);
+    assert_eq!(setup.env.token_balance(setup.admin_tokens).await, 0);
+    assert_eq!(pool_state(&mut setup).await.total_rewards_funded, FUNDING);
+
+    let ix = claim_ix(&setup);
+    setup.env.send(&[ix], &[&setup.staker]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.staker_rewards).await, PENDING);
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, FUNDING - PENDING);
+    // Funding total is cumulative, not a live balance
+    assert_eq!(pool_state(&mut setup).await.total_rewards_funded, FUNDING);
+}
+
+#[tokio::test]
+async fn non_authority_cannot_fund() {
+    let mut setup = setup().await;
+
+    let ix = fund_ix(&setup, setup.attacker.pubkey(), setup.reward_vault, setup.attacker_tokens, FUNDING);
+    let err = setup.env.send(&[ix], &[&setup.attacker]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::UnauthorizedFunder));
+    assert_eq!(pool_state(&mut setup).await.total_rewards_funded, 0);
+}
+
+#[tokio::test]
+async fn funding_a_different_vault_is_rejected() {
+    let mut setup = setup().await;
+
+    // Would record funding the pool never receives
+    let ix = fund_ix(&setup, setup.admin.pubkey(), setup.decoy_vault, setup.admin_tokens, FUNDING);
+    let err = setup.env.send(&[ix], &[&setup.admin]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InvalidRewardVault));
+    assert_eq!(setup.env.token_balance(setup.decoy_vault).await, 0);
+}
+
+#[tokio::test]
+async fn claim_exceeding_vault_balance_changes_nothing() {
+    let mut setup = setup().await;
+
+    let ix = fund_ix(&setup, setup.admin.pubkey(), setup.reward_vault, setup.admin_tokens, PENDING - 1);
+    setup.env.send(&[ix], &[&setup.admin]).await.unwrap();
+
+    let ix = claim_ix(&setup);
+    let err = setup.env.send(&[ix], &[&setup.staker]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InsufficientRewardReserves));
+
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(staking.pending_rewards, PENDING);
+    assert_eq!(staking.total_claimed, 0);
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, PENDING - 1);
+    assert_eq!(setup.env.token_balance(setup.staker_rewards).await, 0);
+}
@@ -0,0 +1,157 @@
+//! # Idempotent Deposit Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::deposit` with an
+//! `idempotency_key`. A client retrying a deposit resends the same key
+//! under a fresh blockhash; the vault must credit it once. Keys are scoped
+//! to the depositor and forgotten after `RECENT_DEPOSIT_KEYS` newer ones.
+//!
+//! ```bash
+//! cargo test --test idempotent_deposit
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, TestEnv};
+use secure_cpi::RECENT_DEPOSIT_KEYS;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const AMOUNT: u64 = 100;
+const BALANCE: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Depositor {
+    keypair: Keypair,
+    tokens: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    depositors: [Depositor; 2],
+}
+
+/// Empty vault and two depositors holding `BALANCE` each
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mint = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", authority.as_ref()], &secure_cpi::ID);
+    let state = common::cpi_vault(authority, 0, bump);
+    add_anchor_account(&mut program_test, vault, secure_cpi::ID, &state);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, 0);
+
+    let depositors = [(); 2].map(|_| {
+        let keypair = Keypair::new();
+        let tokens = add_token_account(&mut program_test, mint, keypair.pubkey(), BALANCE);
+        Depositor { keypair, tokens }
+    });
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, vault, vault_tokens, depositors }
+}
+
+async fn deposit(setup: &mut Setup, depositor: usize, idempotency_key: Option<[u8; 32]>) {
+    let user = &setup.depositors[depositor].keypair;
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Deposit {
+            user: user.pubkey(),
+            user_tokens: setup.depositors[depositor].tokens,
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Deposit { amount: AMOUNT, idempotency_key }.data(),
+    };
+    // A retry is a new transaction, so only the program can deduplicate it
+    setup.env.send(&[ix], &[user]).await.unwrap();
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_cpi::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn retried_key_is_credited_once() {
+    let mut setup = setup().await;
+    let key = [7; 32];
+
+    deposit(&mut setup, 0, Some(key)).await;
+    // ✅ The retry succeeds, so the client stops retrying, but moves nothing
+    deposit(&mut setup, 0, Some(key)).await;
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.balance, AMOUNT);
+    assert_eq!(vault.deposit_count, 1);
+    assert_eq!(vault.sequence, 1);
+    assert!(!vault.locked);
+
+    let (vault_tokens, user_tokens) = (setup.vault_tokens, setup.depositors[0].tokens);
+    assert_eq!(setup.env.token_balance(vault_tokens).await, AMOUNT);
+    assert_eq!(setup.env.token_balance(user_tokens).await, BALANCE - AMOUNT);
+}
+
+#[tokio::test]
+async fn deposits_without_a_key_are_each_credited() {
+    let mut setup = setup().await;
+
+    deposit(&mut setup, 0, None).await;
+    deposit(&mut setup, 0, None).await;
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.balance, 2 * AMOUNT);
+    assert_eq!(vault.deposit_count, 2);
+    assert_eq!(vault.recent_deposit_keys, [[0; 32]; RECENT_DEPOSIT_KEYS]);
+}
+
+#[tokio::test]
+async fn same_key_from_another_depositor_is_credited() {
+    let mut setup = setup().await;
+    let key = [7; 32];
+
+    // ✅ Copying a pending key does not swallow the victim's deposit
+    deposit(&mut setup, 1, Some(key)).await;
+    deposit(&mut setup, 0, Some(key)).await;
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.balance, 2 * AMOUNT);
+    assert_eq!(vault.deposit_count, 2);
+}
+
+#[tokio::test]
+async fn key_is_forgotten_after_the_ring_buffer_wraps() {
+    let mut setup = setup().await;
+    let first = [1; 32];
+
+    deposit(&mut setup, 0, Some(first)).await;
+    for i in 0..RECENT_DEPOSIT_KEYS {
+        deposit(&mut setup, 0, Some([i as u8 + 2; 32])).await;
+    }
+    assert_eq!(vault_state(&mut setup).await.deposit_count, RECENT_DEPOSIT_KEYS as u64 + 1);
+
+    // Evicted by the newer keys, so this resend is a fresh deposit
+    deposit(&mut setup, 0, Some(first)).await;
+    assert_eq!(vault_state(&mut setup).await.deposit_count, RECENT_DEPOSIT_KEYS as u64 + 2);
+
+    // The most recent keys are still remembered
+    deposit(&mut setup, 0, Some([RECENT_DEPOSIT_KEYS as u8 + 1; 32])).await;
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.deposit_count, RECENT_DEPOSIT_KEYS as u64 + 2);
+    assert_eq!(vault.balance, (RECENT_DEPOSIT_KEYS as u64 + 2) * AMOUNT);
+}
@@ -0,0 +1,208 @@
+//! # Share Inflation Tests
+//!
+//! The first-depositor donation attack against `vulnerable_inflation` and
+//! `secure_inflation`. Each scenario starts from the state right after the
+//! attacker's first deposit and donation, then sends the victim's deposit
+//! through `solana-program-test`. Plain `#[test]`s cover the share math.
+//!
+//! ```bash
+//! cargo test --test inflation
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VICTIM_DEPOSIT: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    victim: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    position: Pubkey,
+    victim_tokens: Pubkey,
+}
+
+/// Vulnerable vault after `deposit(1)` + donation: 1 share backed by `1 + donation`
+async fn vulnerable_setup(donation: u64) -> Setup {
+    let id = vulnerable_inflation::ID;
+    let mut program_test =
+        ProgramTest::new("vulnerable_inflation", id, processor!(vulnerable_inflation::entry));
+
+    let mint = Pubkey::new_unique();
+    let victim = Keypair::new();
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &id);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, 1 + donation);
+    let victim_tokens = add_token_account(&mut program_test, mint, victim.pubkey(), VICTIM_DEPOSIT);
+
+    add_anchor_account(
+        &mut program_test,
+        vault,
+        id,
+        &vulnerable_inflation::Vault { mint, vault_tokens, total_shares: 1, bump },
+    );
+    let (position, bump) =
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), victim.pubkey().as_ref()], &id);
+    add_anchor_account(
+        &mut program_test,
+        position,
+        id,
+        &vulnerable_inflation::Position { owner: victim.pubkey(), vault, shares: 0, bump },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, victim, vault, vault_tokens, position, victim_tokens }
+}
+
+/// Secure vault after `deposit(1_001)` + donation:
+/// 1 attacker share + `MINIMUM_LIQUIDITY` dead shares backed by `1_001 + donation`
+async fn secure_setup(donation: u64) -> Setup {
+    let id = secure_inflation::ID;
+    let mut program_test = ProgramTest::new("secure_inflation", id, processor!(secure_inflation::entry));
+
+    let first_deposit = secure_inflation::MINIMUM_LIQUIDITY + 1;
+    let mint = Pubkey::new_unique();
+    let victim = Keypair::new();
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &id);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, first_deposit + donation);
+    let victim_tokens = add_token_account(&mut program_test, mint, victim.pubkey(), VICTIM_DEPOSIT);
+
+    add_anchor_account(
+        &mut program_test,
+        vault,
+        id,
+        &secure_inflation::Vault {
+            mint,
+            vault_tokens,
+            share_mint: Pubkey::new_unique(),
+            total_shares: first_deposit,
+            locked: false,
+            bump,
+        },
+    );
+    let (position, bump) =
+        Pubkey::find_program_address(&[b"position", vault.as_ref(), victim.pubkey().as_ref()], &id);
+    add_anchor_account(
+        &mut program_test,
+        position,
+        id,
+        &secure_inflation::Position { owner: victim.pubkey(), vault, shares: 0, bump },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, victim, vault, vault_tokens, position, victim_tokens }
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_victim_receives_zero_shares() {
+    let mut setup = vulnerable_setup(VICTIM_DEPOSIT).await;
+
+    let ix = Instruction {
+        program_id: vulnerable_inflation::ID,
+        accounts: vulnerable_inflation::accounts::Deposit {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            position: setup.position,
+            user_tokens: setup.victim_tokens,
+            user: setup.victim.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_inflation::instruction::Deposit { amount: VICTIM_DEPOSIT }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+
+    // Deposit "succeeded": tokens gone, no shares
+    let position: vulnerable_inflation::Position = setup.env.fetch(setup.position).await;
+    assert_eq!(position.shares, 0);
+    assert_eq!(setup.env.token_balance(setup.victim_tokens).await, 0);
+
+    // The attacker's single share now backs everything
+    let vault: vulnerable_inflation::Vault = setup.env.fetch(setup.vault).await;
+    assert_eq!(vault.total_shares, 1);
+    assert_eq!(setup.env.token_balance(setup.vault_tokens).await, 1 + 2 * VICTIM_DEPOSIT);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+fn secure_deposit(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_inflation::ID,
+        accounts: secure_inflation::accounts::Deposit {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            position: setup.position,
+            user_tokens: setup.victim_tokens,
+            user: setup.victim.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_inflation::instruction::Deposit { amount: VICTIM_DEPOSIT }.data(),
+    }
+}
+
+#[tokio::test]
+async fn secure_victim_still_receives_shares_after_donation() {
+    // Donating 1000x the victim's deposit is not enough to round them to zero
+    let mut setup = secure_setup(1_000 * VICTIM_DEPOSIT).await;
+
+    let ix = secure_deposit(&setup);
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+
+    let position: secure_inflation::Position = setup.env.fetch(setup.position).await;
+    assert!(position.shares > 0);
+}
+
+#[tokio::test]
+async fn secure_zero_share_deposit_is_rejected() {
+    // Large enough donation to round the victim down: the deposit fails
+    // instead of taking the tokens
+    let mut setup = secure_setup(2_000 * VICTIM_DEPOSIT).await;
+
+    let ix = secure_deposit(&setup);
+    let err = setup.env.send(&[ix], &[&setup.victim]).await.unwrap_err();
+    assert_eq!(err, custom(secure_inflation::ErrorCode::ZeroShares));
+    assert_eq!(setup.env.token_balance(setup.victim_tokens).await, VICTIM_DEPOSIT);
+}
+
+// ============================================================================
+// SHARE MATH
+// ============================================================================
+
+#[test]
+fn vulnerable_math_rounds_victim_to_zero() {
+    assert_eq!(vulnerable_inflation::shares_for_deposit(1, 0, 0).unwrap(), 1);
+    assert_eq!(
+        vulnerable_inflation::shares_for_deposit(VICTIM_DEPOSIT, 1 + VICTIM_DEPOSIT, 1).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn secure_first_deposit_requires_minimum_liquidity() {
+    use secure_inflation::{shares_for_deposit, ErrorCode, MINIMUM_LIQUIDITY};
+
+    for amount in [1, MINIMUM_LIQUIDITY] {
+        let err = shares_for_deposit(amount, 0, 0).unwrap_err();
+        assert_eq!(err, ErrorCode::MinimumLiquidity.into());
+    }
+    assert_eq!(shares_for_deposit(MINIMUM_LIQUIDITY + 1, 0, 0).unwrap(), 1);
+}
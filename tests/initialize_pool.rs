@@ -0,0 +1,163 @@
+//! # Pool Initialization Tests
+//!
+//! `solana-program-test` scenarios for `initialize_pool` in `secure_matching`
+//! (`["pool", token_mint]`) and `secure_cpi` (`["pool", token_in_mint, token_out_mint]`):
+//! derive the PDA off-chain, create it, fetch it back.
+//!
+//! ```bash
+//! cargo test --test initialize_pool
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use common::{add_mint, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program};
+
+// ============================================================================
+// SECURE_MATCHING
+// ============================================================================
+
+struct MatchingSetup {
+    env: TestEnv,
+    pool: Pubkey,
+    bump: u8,
+    token_mint: Pubkey,
+    reward_mint: Pubkey,
+    reward_vault: Pubkey,
+}
+
+/// Mints plus a reward vault owned by `vault_owner(pool_pda)`
+async fn matching_setup(vault_owner: impl FnOnce(Pubkey) -> Pubkey) -> MatchingSetup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let token_mint = add_mint(&mut program_test, None);
+    let reward_mint = add_mint(&mut program_test, None);
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", token_mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, reward_mint, vault_owner(pool), 0);
+
+    let env = TestEnv::start(program_test).await;
+    MatchingSetup { env, pool, bump, token_mint, reward_mint, reward_vault }
+}
+
+fn matching_init_ix(setup: &MatchingSetup, early_exit_penalty_bps: u16) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::InitializePool {
+            pool: setup.pool,
+            token_mint: setup.token_mint,
+            reward_mint: setup.reward_mint,
+            reward_vault: setup.reward_vault,
+            authority: setup.env.payer(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::InitializePool {
+            min_stake_duration: 86_400,
+            early_exit_penalty_bps,
+            reward_rate: 100,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn matching_pool_is_created_at_the_derived_pda() {
+    let mut setup = matching_setup(|pool| pool).await;
+
+    let ix = matching_init_ix(&setup, 500);
+    setup.env.send(&[ix], &[]).await.unwrap();
+
+    let account = setup.env.account(setup.pool).await.unwrap();
+    assert_eq!(account.owner, secure_matching::ID);
+    let pool = secure_matching::Pool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(pool.authority, setup.env.payer());
+    assert_eq!(pool.token_mint, setup.token_mint);
+    assert_eq!(pool.reward_mint, setup.reward_mint);
+    assert_eq!(pool.reward_vault, setup.reward_vault);
+    assert_eq!(pool.min_stake_duration, 86_400);
+    assert_eq!(pool.early_exit_penalty_bps, 500);
+    assert_eq!(pool.max_total_deposits, u64::MAX);
+    assert_eq!(pool.per_user_cap, u64::MAX);
+    assert_eq!(pool.reward_rate, 100);
+    assert_eq!(pool.acc_reward_per_share, 0);
+    assert_eq!(pool.bump, setup.bump);
+}
+
+#[tokio::test]
+async fn matching_reward_vault_must_be_owned_by_pool() {
+    let mut setup = matching_setup(|_| Pubkey::new_unique()).await;
+
+    let ix = matching_init_ix(&setup, 500);
+    let err = setup.env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InvalidRewardVault));
+    assert!(setup.env.account(setup.pool).await.is_none());
+}
+
+#[tokio::test]
+async fn matching_penalty_above_100_percent_is_rejected() {
+    let mut setup = matching_setup(|pool| pool).await;
+
+    let ix = matching_init_ix(&setup, 10_001);
+    let err = setup.env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InvalidPenalty));
+}
+
+// ============================================================================
+// SECURE_CPI
+// ============================================================================
+
+fn cpi_init_ix(authority: Pubkey, token_in_mint: Pubkey, token_out_mint: Pubkey) -> (Instruction, Pubkey, u8) {
+    let (pool, bump) = Pubkey::find_program_address(
+        &[b"pool", token_in_mint.as_ref(), token_out_mint.as_ref()],
+        &secure_cpi::ID,
+    );
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::InitializePool {
+            pool,
+            token_in_mint,
+            token_out_mint,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::InitializePool {}.data(),
+    };
+    (ix, pool, bump)
+}
+
+#[tokio::test]
+async fn cpi_pool_is_created_at_the_derived_pda() {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let token_in_mint = add_mint(&mut program_test, None);
+    let token_out_mint = add_mint(&mut program_test, None);
+    let mut env = TestEnv::start(program_test).await;
+
+    let (ix, address, bump) = cpi_init_ix(env.payer(), token_in_mint, token_out_mint);
+    env.send(&[ix], &[]).await.unwrap();
+
+    let pool: secure_cpi::Pool = env.fetch(address).await;
+    assert_eq!(pool.authority, env.payer());
+    assert_eq!(pool.token_in_mint, token_in_mint);
+    assert_eq!(pool.token_out_mint, token_out_mint);
+    assert_eq!((pool.reserve_in, pool.reserve_out), (0, 0));
+    assert_eq!(pool.bump, bump);
+
+    // The reverse direction is a different pool
+    let (_, reverse, _) = cpi_init_ix(env.payer(), token_out_mint, token_in_mint);
+    assert_ne!(reverse, address);
+}
+
+#[tokio::test]
+async fn cpi_pool_with_identical_mints_is_rejected() {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mint = add_mint(&mut program_test, None);
+    let mut env = TestEnv::start(program_test).await;
+
+    let (ix, _, _) = cpi_init_ix(env.payer(), mint, mint);
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::common_errors::CommonError::MintMismatch));
+}
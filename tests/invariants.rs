@@ -0,0 +1,79 @@
+//! # Pool Invariant Tests
+//!
+//! Plain `#[test]`s for the `assert_pool_invariants` post-conditions in
+//! `secure_cpi` (non-empty reserves) and `secure_matching` (shares exist iff
+//! deposits exist). Each test builds a `Pool` in a specific state.
+//!
+//! ```bash
+//! cargo test --test invariants
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::*;
+use secure_cpi::common_errors::CommonError;
+
+fn cpi_pool(reserve_in: u64, reserve_out: u64) -> secure_cpi::Pool {
+    secure_cpi::Pool {
+        reserve_in,
+        reserve_out,
+        fee_bps: 0,
+        ..common::cpi_pool(Pubkey::new_unique(), (Pubkey::new_unique(), Pubkey::new_unique()), 255)
+    }
+}
+
+fn matching_pool(total_deposits: u64, total_shares: u64) -> secure_matching::Pool {
+    let (token_mint, reward_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    secure_matching::Pool {
+        total_deposits,
+        total_shares,
+        ..common::matching_pool(Pubkey::new_unique(), token_mint, reward_mint, Pubkey::new_unique(), 255)
+    }
+}
+
+fn invariant_violation() -> Error {
+    CommonError::InvariantViolation.into()
+}
+
+// ============================================================================
+// RESERVES (secure_cpi)
+// ============================================================================
+
+#[test]
+fn reserves_both_positive_pass() {
+    assert!(secure_cpi::assert_pool_invariants(&cpi_pool(1, 1)).is_ok());
+}
+
+#[test]
+fn empty_reserve_in_is_rejected() {
+    let err = secure_cpi::assert_pool_invariants(&cpi_pool(0, 1_000)).unwrap_err();
+    assert_eq!(err, invariant_violation());
+}
+
+#[test]
+fn empty_reserve_out_is_rejected() {
+    let err = secure_cpi::assert_pool_invariants(&cpi_pool(1_000, 0)).unwrap_err();
+    assert_eq!(err, invariant_violation());
+}
+
+// ============================================================================
+// SHARES / DEPOSITS (secure_matching)
+// ============================================================================
+
+#[test]
+fn empty_and_funded_pools_pass() {
+    assert!(secure_matching::assert_pool_invariants(&matching_pool(0, 0)).is_ok());
+    assert!(secure_matching::assert_pool_invariants(&matching_pool(1_000, 900)).is_ok());
+}
+
+#[test]
+fn shares_without_deposits_are_rejected() {
+    let err = secure_matching::assert_pool_invariants(&matching_pool(0, 100)).unwrap_err();
+    assert_eq!(err, invariant_violation());
+}
+
+#[test]
+fn deposits_without_shares_are_rejected() {
+    let err = secure_matching::assert_pool_invariants(&matching_pool(100, 0)).unwrap_err();
+    assert_eq!(err, invariant_violation());
+}
@@ -0,0 +1,183 @@
+//! # Lock Tier Tests
+//!
+//! Plain `#[test]`s for `secure_matching` lock tiers: `Pool::multiplier_for`
+//! picks the tier a lock duration meets, `StakingAccount::settle_rewards`
+//! scales the staker's share of the emission by it, and
+//! `Pool::validate_lock_tiers` rejects tables `multiplier_for` can't read.
+//!
+//! ```bash
+//! cargo test --test lock_tiers
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::*;
+use secure_matching::{ErrorCode, LockTier, Pool, StakingAccount, MAX_LOCK_TIERS};
+
+const T0: i64 = 1_700_000_000;
+const DAY: i64 = 24 * 60 * 60;
+const STAKE: u64 = 1_000;
+
+/// Unlocked 50%, 30 days 75%, 90 days 100%
+const TIERS: [LockTier; MAX_LOCK_TIERS] = [
+    LockTier { min_duration: 0, multiplier_bps: 5_000 },
+    LockTier { min_duration: 30 * DAY, multiplier_bps: 7_500 },
+    LockTier { min_duration: 90 * DAY, multiplier_bps: 10_000 },
+    LockTier { min_duration: 0, multiplier_bps: 0 },
+];
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Pool emitting `reward_rate` per second to `total_staked`, checkpointed at `T0`
+fn pool(lock_tiers: [LockTier; MAX_LOCK_TIERS], reward_rate: u64, total_staked: u64) -> Pool {
+    let (mint, reward_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    Pool {
+        total_staked,
+        reward_rate,
+        last_reward_time: T0,
+        lock_tiers,
+        ..common::matching_pool(Pubkey::new_unique(), mint, reward_mint, Pubkey::new_unique(), 255)
+    }
+}
+
+/// `STAKE` staked at `T0` with what `stake` would record for `lock_duration`
+fn staking(pool: &Pool, lock_duration: i64) -> StakingAccount {
+    StakingAccount {
+        last_stake_time: T0,
+        lock_duration,
+        multiplier_bps: pool.multiplier_for(lock_duration).unwrap(),
+        ..common::matching_stake(Pubkey::new_unique(), Pubkey::new_unique(), STAKE, 255)
+    }
+}
+
+fn invalid_lock_tier() -> Error {
+    ErrorCode::InvalidLockTier.into()
+}
+
+// ============================================================================
+// REWARDS ACROSS TIERS
+// ============================================================================
+
+#[test]
+fn longer_lock_earns_a_larger_share_of_the_emission() {
+    // Three equal stakes: 100_000 each before multipliers
+    let mut pool = pool(TIERS, 300, 3 * STAKE);
+    let mut unlocked = staking(&pool, 0);
+    let mut month = staking(&pool, 30 * DAY);
+    let mut quarter = staking(&pool, 90 * DAY);
+
+    pool.update_rewards(T0 + 1_000).unwrap();
+    for staker in [&mut unlocked, &mut month, &mut quarter] {
+        staker.settle_rewards(&pool).unwrap();
+    }
+
+    assert_eq!(unlocked.pending_rewards, 50_000);
+    assert_eq!(month.pending_rewards, 75_000);
+    assert_eq!(quarter.pending_rewards, 100_000);
+}
+
+#[test]
+fn multipliers_never_pay_out_more_than_was_emitted() {
+    let mut pool = pool(TIERS, 300, 3 * STAKE);
+    let mut stakers = [staking(&pool, 0), staking(&pool, 45 * DAY), staking(&pool, 365 * DAY)];
+
+    pool.update_rewards(T0 + 1_000).unwrap();
+    let paid: u64 = stakers
+        .iter_mut()
+        .map(|staker| {
+            staker.settle_rewards(&pool).unwrap();
+            staker.pending_rewards
+        })
+        .sum();
+
+    // The reduced tiers' remainder stays in the vault
+    assert!(paid <= 300 * 1_000);
+}
+
+#[test]
+fn settling_twice_at_the_same_time_pays_once() {
+    let mut pool = pool(TIERS, 100, STAKE);
+    let mut staker = staking(&pool, 30 * DAY);
+
+    pool.update_rewards(T0 + 1_000).unwrap();
+    staker.settle_rewards(&pool).unwrap();
+    staker.settle_rewards(&pool).unwrap();
+
+    assert_eq!(staker.pending_rewards, 75_000);
+}
+
+// ============================================================================
+// TIER SELECTION
+// ============================================================================
+
+#[test]
+fn lock_between_tiers_gets_the_lower_tier() {
+    let pool = pool(TIERS, 0, 0);
+
+    assert_eq!(pool.multiplier_for(30 * DAY - 1).unwrap(), 5_000);
+    assert_eq!(pool.multiplier_for(60 * DAY).unwrap(), 7_500);
+    assert_eq!(pool.multiplier_for(365 * DAY).unwrap(), 10_000);
+}
+
+#[test]
+fn negative_or_unmatched_lock_is_rejected() {
+    let mut tiers = TIERS;
+    // No unlocked tier: anything under 30 days matches nothing
+    tiers[0] = LockTier { min_duration: 7 * DAY, multiplier_bps: 5_000 };
+    let pool = pool(tiers, 0, 0);
+
+    assert_eq!(pool.multiplier_for(-1).unwrap_err(), invalid_lock_tier());
+    assert_eq!(pool.multiplier_for(DAY).unwrap_err(), invalid_lock_tier());
+    assert_eq!(pool.multiplier_for(7 * DAY).unwrap(), 5_000);
+}
+
+#[test]
+fn pool_without_tiers_pays_in_full_but_only_unlocked() {
+    let pool = pool([LockTier::default(); MAX_LOCK_TIERS], 0, 0);
+
+    assert_eq!(pool.multiplier_for(0).unwrap(), 10_000);
+    assert_eq!(pool.multiplier_for(DAY).unwrap_err(), invalid_lock_tier());
+}
+
+#[test]
+fn lock_ends_at_stake_time_plus_duration() {
+    let pool = pool(TIERS, 0, 0);
+
+    assert_eq!(staking(&pool, 90 * DAY).lock_end().unwrap(), T0 + 90 * DAY);
+    assert_eq!(staking(&pool, 0).lock_end().unwrap(), T0);
+}
+
+// ============================================================================
+// TIER VALIDATION
+// ============================================================================
+
+#[test]
+fn well_formed_tiers_pass() {
+    assert!(Pool::validate_lock_tiers(&TIERS).is_ok());
+    assert!(Pool::validate_lock_tiers(&[LockTier::default(); MAX_LOCK_TIERS]).is_ok());
+}
+
+#[test]
+fn malformed_tiers_are_rejected() {
+    let mut unsorted = TIERS;
+    unsorted.swap(1, 2);
+
+    let mut over_full = TIERS;
+    over_full[2].multiplier_bps = 10_001;
+
+    let mut decreasing = TIERS;
+    decreasing[2].multiplier_bps = 6_000;
+
+    // A gap would hide the tiers after it from multiplier_for
+    let mut gap = TIERS;
+    gap[1] = LockTier::default();
+
+    let mut negative = TIERS;
+    negative[0].min_duration = -1;
+
+    for tiers in [unsorted, over_full, decreasing, gap, negative] {
+        assert_eq!(Pool::validate_lock_tiers(&tiers).unwrap_err(), invalid_lock_tier());
+    }
+}
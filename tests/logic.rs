@@ -0,0 +1,582 @@
+//! # Pure Logic Unit Tests
+//!
+//! Plain `#[test]`s for the math in `logic.rs`. No validator, no accounts.
+//!
+//! ```bash
+//! cargo test --test logic
+//! ```
+
+use secure_cpi::logic::{
+    acc_reward_per_share, apply_bps, assets_for_shares, check_fresh, checked_pow_fixed,
+    lp_shares_for_deposit, normalize_amount, normalize_amount_with, pending_reward, reward_debt,
+    reward_math, rewards, rewards_for_index, share_price, shares_for_deposit, simulate_rewards,
+    stable_swap_output, swap_input, swap_output, RewardOverflow, Rounding, Truncation, BPS_DENOMINATOR, MAX_AMP,
+    SCALE, SECONDS_PER_YEAR,
+};
+
+const YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+// ============================================================================
+// apply_bps
+// ============================================================================
+
+#[test]
+fn zero_bps_is_zero() {
+    assert_eq!(apply_bps(1_000, 0).unwrap(), 0);
+    assert_eq!(apply_bps(u64::MAX, 0).unwrap(), 0);
+}
+
+#[test]
+fn full_bps_is_identity() {
+    let full = BPS_DENOMINATOR as u16;
+    assert_eq!(apply_bps(1_000, full).unwrap(), 1_000);
+    assert_eq!(apply_bps(u64::MAX, full).unwrap(), u64::MAX);
+}
+
+#[test]
+fn bps_rounds_down() {
+    // 30 bps of 999 = 2.997 → 2
+    assert_eq!(apply_bps(999, 30).unwrap(), 2);
+    assert_eq!(apply_bps(1, 9_999).unwrap(), 0);
+}
+
+#[test]
+fn bps_survives_what_a_u64_multiply_would_not() {
+    // u64::MAX * 9_999 overflows u64; the u128 intermediate does not
+    assert!(u64::MAX.checked_mul(9_999).is_none());
+    let expected = (u64::MAX as u128 * 9_999 / 10_000) as u64;
+    assert_eq!(apply_bps(u64::MAX, 9_999).unwrap(), expected);
+}
+
+#[test]
+fn bps_above_100_percent_is_rejected() {
+    assert!(apply_bps(1_000, 10_001).is_err());
+    assert!(apply_bps(0, u16::MAX).is_err());
+}
+
+// ============================================================================
+// checked_pow_fixed
+// ============================================================================
+
+#[test]
+fn pow_with_zero_exponent_is_scale() {
+    assert_eq!(checked_pow_fixed(0, 0, SCALE as u128).unwrap(), SCALE as u128);
+    assert_eq!(checked_pow_fixed(u128::MAX, 0, SCALE as u128).unwrap(), SCALE as u128);
+}
+
+#[test]
+fn pow_matches_hand_computed_values() {
+    let scale = SCALE as u128;
+    // 1.5^2 = 2.25
+    assert_eq!(checked_pow_fixed(1_500_000, 2, scale).unwrap(), 2_250_000);
+    // 1.1^3 = 1.331
+    assert_eq!(checked_pow_fixed(1_100_000, 3, scale).unwrap(), 1_331_000);
+    // 2^10 = 1024
+    assert_eq!(checked_pow_fixed(2_000_000, 10, scale).unwrap(), 1_024_000_000);
+    // 0.5^3 = 0.125
+    assert_eq!(checked_pow_fixed(500_000, 3, scale).unwrap(), 125_000);
+}
+
+#[test]
+fn pow_rounds_down() {
+    // 1.000001^2 = 1.000002000001 → 1.000002 at 6 decimals
+    assert_eq!(checked_pow_fixed(1_000_001, 2, SCALE as u128).unwrap(), 1_000_002);
+}
+
+#[test]
+fn pow_overflow_is_an_error() {
+    // 2^200 does not fit in u128 at any scale
+    assert!(checked_pow_fixed(2_000_000, 200, SCALE as u128).is_err());
+    // 1% daily for ~27 years: 1.01^10_000 is ~1.6e43
+    assert!(checked_pow_fixed(1_010_000, 10_000, SCALE as u128).is_err());
+    // ~14 years still fits (~4e21, scaled ~4e27)
+    assert!(checked_pow_fixed(1_010_000, 5_000, SCALE as u128).is_ok());
+}
+
+#[test]
+fn pow_rejects_zero_scale() {
+    assert!(checked_pow_fixed(1, 1, 0).is_err());
+}
+
+// ============================================================================
+// normalize_amount
+// ============================================================================
+
+#[test]
+fn normalize_scales_up_6_to_9() {
+    assert_eq!(normalize_amount(1_500_000, 6, 9).unwrap(), 1_500_000_000);
+    assert_eq!(normalize_amount(0, 6, 9).unwrap(), 0);
+}
+
+#[test]
+fn normalize_same_decimals_is_identity() {
+    assert_eq!(normalize_amount(u64::MAX, 9, 9).unwrap(), u64::MAX);
+}
+
+#[test]
+fn normalize_scales_down_9_to_6_exactly() {
+    assert_eq!(normalize_amount(1_500_000_000, 9, 6).unwrap(), 1_500_000);
+}
+
+#[test]
+fn normalize_rejects_truncation_by_default() {
+    // 1.500000001 has no 6-decimal representation
+    assert!(normalize_amount(1_500_000_001, 9, 6).is_err());
+    assert!(normalize_amount(999, 9, 6).is_err());
+}
+
+#[test]
+fn normalize_rounds_down_when_allowed() {
+    assert_eq!(
+        normalize_amount_with(1_500_000_999, 9, 6, Truncation::RoundDown).unwrap(),
+        1_500_000
+    );
+    assert_eq!(normalize_amount_with(999, 9, 6, Truncation::RoundDown).unwrap(), 0);
+}
+
+#[test]
+fn normalize_rejects_overflow_at_18_decimals() {
+    // 1e11 at 9 decimals → 1e20 at 18, above u64::MAX (~1.8e19)
+    assert!(normalize_amount(100_000_000_000, 9, 18).is_err());
+    assert!(normalize_amount(u64::MAX, 0, 18).is_err());
+    // 18 whole tokens still fit
+    assert_eq!(normalize_amount(18, 0, 18).unwrap(), 18_000_000_000_000_000_000);
+}
+
+#[test]
+fn normalize_rejects_factor_beyond_u128() {
+    assert!(normalize_amount(1, 0, 40).is_err());
+    assert!(normalize_amount_with(1, 40, 0, Truncation::RoundDown).is_err());
+}
+
+// ============================================================================
+// swap_output
+// ============================================================================
+
+#[test]
+fn swap_output_matches_constant_product() {
+    // 100 * 1000 / (1000 + 100) = 90.9 → 90
+    assert_eq!(swap_output(100, 1_000, 1_000).unwrap(), 90);
+}
+
+#[test]
+fn swap_output_rounds_down_to_zero_for_dust() {
+    // 1 * 10 / (1_000_000 + 1) < 1
+    assert_eq!(swap_output(1, 1_000_000, 10).unwrap(), 0);
+}
+
+#[test]
+fn swap_output_handles_u64_max_without_overflow() {
+    // u64::MAX * u64::MAX only fits because of the u128 intermediate
+    assert_eq!(
+        swap_output(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+        u64::MAX / 2
+    );
+}
+
+#[test]
+fn swap_output_rejects_empty_pool_and_zero_input() {
+    assert!(swap_output(0, 0, 1_000).is_err());
+}
+
+// ============================================================================
+// swap_input
+// ============================================================================
+
+#[test]
+fn swap_input_matches_constant_product() {
+    // 1000 * 90 / (1000 - 90) = 98.9 → 99
+    assert_eq!(swap_input(90, 1_000, 1_000).unwrap(), 99);
+}
+
+#[test]
+fn swap_input_is_the_least_exact_in_amount() {
+    // Exact-in and exact-out agree on every trade: the quoted input buys
+    // at least amount_out, and one unit less does not
+    for (reserve_in, reserve_out) in [(1_000, 1_000), (1_000_000, 1_000_000), (5_000_000, 20_000), (7, 1_000_003)] {
+        for amount_out in [1, 2, 17, reserve_out / 3, reserve_out / 2, reserve_out - 1] {
+            let amount_in = swap_input(amount_out, reserve_in, reserve_out).unwrap();
+            assert!(swap_output(amount_in, reserve_in, reserve_out).unwrap() >= amount_out);
+            assert!(swap_output(amount_in - 1, reserve_in, reserve_out).unwrap() < amount_out);
+        }
+    }
+}
+
+#[test]
+fn swap_input_round_trips_exact_in() {
+    // What exact-in pays out for 100, exact-out charges at most 100 for
+    let amount_out = swap_output(100, 1_000, 1_000).unwrap();
+    assert!(swap_input(amount_out, 1_000, 1_000).unwrap() <= 100);
+}
+
+#[test]
+fn swap_input_rejects_draining_the_pool() {
+    assert!(swap_input(1_000, 1_000, 1_000).is_err());
+    assert!(swap_input(1_001, 1_000, 1_000).is_err());
+}
+
+// ============================================================================
+// stable_swap_output
+// ============================================================================
+
+#[test]
+fn stable_swap_is_near_one_to_one_at_balance() {
+    // Constant product would give 9_900; one unit goes to rounding
+    assert_eq!(stable_swap_output(10_000, 1_000_000, 1_000_000, 100).unwrap(), 9_999);
+}
+
+#[test]
+fn stable_swap_output_grows_with_amp() {
+    let out = |amp| stable_swap_output(500_000, 1_000_000, 1_000_000, amp).unwrap();
+    assert!(out(1) < out(10));
+    assert!(out(10) < out(100));
+    assert!(out(100) < out(MAX_AMP));
+    assert!(out(MAX_AMP) < 500_000);
+}
+
+#[test]
+fn stable_swap_zero_input_is_zero() {
+    assert_eq!(stable_swap_output(0, 1_000_000, 1_000_000, 100).unwrap(), 0);
+}
+
+#[test]
+fn stable_swap_rejects_amp_out_of_range() {
+    assert!(stable_swap_output(10, 1_000, 1_000, 0).is_err());
+    assert!(stable_swap_output(10, 1_000, 1_000, MAX_AMP + 1).is_err());
+}
+
+#[test]
+fn stable_swap_rejects_reserves_too_large_for_u128() {
+    // Fails with Overflow instead of mispricing
+    assert!(stable_swap_output(1, u64::MAX, u64::MAX, MAX_AMP).is_err());
+}
+
+// ============================================================================
+// shares_for_deposit
+// ============================================================================
+
+#[test]
+fn first_deposit_mints_one_to_one() {
+    assert_eq!(shares_for_deposit(500, 0, 0).unwrap(), 500);
+}
+
+#[test]
+fn later_deposit_mints_pro_rata() {
+    // Pool: 1000 deposited, 500 shares → 0.5 shares per token
+    assert_eq!(shares_for_deposit(200, 1_000, 500).unwrap(), 100);
+}
+
+#[test]
+fn shares_round_down_in_favor_of_pool() {
+    // 1 * 2 / 3 = 0.66 → 0
+    assert_eq!(shares_for_deposit(1, 3, 2).unwrap(), 0);
+}
+
+#[test]
+fn shares_reject_zero_deposits_with_outstanding_shares() {
+    assert!(shares_for_deposit(100, 0, 1_000).is_err());
+}
+
+#[test]
+fn shares_reject_result_above_u64() {
+    // u64::MAX * u64::MAX / 1 cannot be represented
+    assert!(shares_for_deposit(u64::MAX, 1, u64::MAX).is_err());
+}
+
+// ============================================================================
+// lp_shares_for_deposit
+// ============================================================================
+
+#[test]
+fn first_lp_deposit_mints_geometric_mean() {
+    assert_eq!(lp_shares_for_deposit(40_000, 10_000, 0, 0, 0).unwrap(), 20_000);
+    // Rounds down: sqrt(2) → 1
+    assert_eq!(lp_shares_for_deposit(1, 2, 0, 0, 0).unwrap(), 1);
+    assert_eq!(lp_shares_for_deposit(u64::MAX, u64::MAX, 0, 0, 0).unwrap(), u64::MAX);
+}
+
+#[test]
+fn later_lp_deposit_mints_the_smaller_side() {
+    // Side a alone would give 2_000, side b 2_200
+    assert_eq!(lp_shares_for_deposit(4_000, 1_100, 40_000, 10_000, 20_000).unwrap(), 2_000);
+}
+
+// ============================================================================
+// share_price
+// ============================================================================
+
+#[test]
+fn empty_pool_is_priced_at_one() {
+    assert_eq!(share_price(0, 0).unwrap(), SCALE);
+    // Leftover deposits with no shares still quote 1.0, not a division by zero
+    assert_eq!(share_price(500, 0).unwrap(), SCALE);
+}
+
+#[test]
+fn share_price_is_deposits_per_share() {
+    assert_eq!(share_price(1_000, 1_000).unwrap(), SCALE);
+    assert_eq!(share_price(1_500, 1_000).unwrap(), SCALE * 3 / 2);
+    // 10 / 3 = 3.333333 → 3_333_333
+    assert_eq!(share_price(10, 3).unwrap(), 3_333_333);
+}
+
+#[test]
+fn share_price_rejects_result_above_u64() {
+    assert!(share_price(u64::MAX, 1).is_err());
+}
+
+// ============================================================================
+// assets_for_shares
+// ============================================================================
+
+#[test]
+fn redemption_rounds_down_and_reports_dust() {
+    // 2 * 10 / 3 = 6 remainder 2
+    assert_eq!(assets_for_shares(2, 10, 3, Rounding::Down).unwrap(), (6, 2));
+    assert_eq!(assets_for_shares(2, 10, 3, Rounding::Up).unwrap(), (7, 2));
+}
+
+#[test]
+fn exact_redemption_has_no_dust() {
+    assert_eq!(assets_for_shares(3, 10, 3, Rounding::Down).unwrap(), (10, 0));
+    assert_eq!(assets_for_shares(3, 10, 3, Rounding::Up).unwrap(), (10, 0));
+}
+
+#[test]
+fn redemption_rejects_zero_total_shares() {
+    assert!(assets_for_shares(1, 10, 0, Rounding::Down).is_err());
+}
+
+// ============================================================================
+// rewards
+// ============================================================================
+
+#[test]
+fn rewards_full_rate_for_one_year_equals_stake() {
+    let accrual = rewards(1_000_000, SCALE, 0, YEAR, 0, u64::MAX).unwrap();
+    assert_eq!(accrual.rewards, 1_000_000);
+    assert_eq!(accrual.capped_rewards, 1_000_000);
+    assert_eq!(accrual.remainder, 0);
+    assert_eq!(accrual.time_staked, SECONDS_PER_YEAR);
+}
+
+#[test]
+fn rewards_are_capped_at_pool_balance() {
+    let accrual = rewards(1_000_000, SCALE, 0, YEAR, 0, 10).unwrap();
+    assert_eq!(accrual.rewards, 1_000_000);
+    assert_eq!(accrual.capped_rewards, 10);
+}
+
+#[test]
+fn rewards_reject_negative_duration() {
+    assert!(rewards(1_000, SCALE, 100, 99, 0, u64::MAX).is_err());
+}
+
+#[test]
+fn rewards_reject_i64_overflow_in_duration() {
+    assert!(rewards(1_000, SCALE, i64::MIN, i64::MAX, 0, u64::MAX).is_err());
+}
+
+#[test]
+fn rewards_reject_u128_overflow() {
+    assert!(rewards(u64::MAX, u64::MAX, 0, i64::MAX, 0, u64::MAX).is_err());
+}
+
+#[test]
+fn rewards_fine_grained_accrual_matches_coarse() {
+    // ~31.7 rewards per second: naive per-call truncation would lose the .7
+    let (amount, rate) = (1_000_000_000, SCALE);
+
+    let coarse = rewards(amount, rate, 0, 1_000, 0, u64::MAX).unwrap();
+
+    let mut total = 0u64;
+    let mut remainder = 0u128;
+    for t in 0..1_000 {
+        let step = rewards(amount, rate, t, t + 1, remainder, u64::MAX).unwrap();
+        total += step.rewards;
+        remainder = step.remainder;
+    }
+
+    assert_eq!(total, coarse.rewards);
+    assert_eq!(remainder, coarse.remainder);
+}
+
+#[test]
+fn rewards_for_index_matches_constant_rate() {
+    let index_delta = SCALE as u128 * 1_000;
+    let by_rate = rewards(1_000_000_000, SCALE, 0, 1_000, 0, u64::MAX).unwrap();
+    let by_index = rewards_for_index(1_000_000_000, index_delta, 0, 1_000, 0, u64::MAX).unwrap();
+
+    assert_eq!(by_index.rewards, by_rate.rewards);
+    assert_eq!(by_index.remainder, by_rate.remainder);
+    assert_eq!(by_index.time_staked, 1_000);
+}
+
+#[test]
+fn rewards_for_index_sums_rate_periods() {
+    // Half a year at 100%, half a year at 300%
+    let half = YEAR / 2;
+    let index_delta = SCALE as u128 * half as u128 + 3 * SCALE as u128 * half as u128;
+    let accrual = rewards_for_index(1_000_000, index_delta, 0, YEAR, 0, u64::MAX).unwrap();
+    assert_eq!(accrual.rewards, 2_000_000);
+}
+
+// ============================================================================
+// simulate_rewards
+// ============================================================================
+
+#[test]
+fn simulate_rewards_matches_accrual() {
+    let accrual = rewards(1_000_000_000, SCALE, 0, 1_000, 0, u64::MAX).unwrap();
+    assert_eq!(simulate_rewards(1_000_000_000, SCALE, 1_000), Ok(accrual.rewards));
+}
+
+#[test]
+fn simulate_rewards_overflows_at_multiply() {
+    // u64::MAX² fits in u128; one more factor of 2 does not
+    assert_eq!(simulate_rewards(u64::MAX, u64::MAX, 2), Err(RewardOverflow::Multiply));
+    assert_eq!(simulate_rewards(u64::MAX, u64::MAX, u64::MAX), Err(RewardOverflow::Multiply));
+}
+
+#[test]
+fn simulate_rewards_overflows_at_divide() {
+    // Product fits in u128, but / (SCALE * SECONDS_PER_YEAR) still exceeds u64
+    assert_eq!(simulate_rewards(u64::MAX, u64::MAX, 1), Err(RewardOverflow::Divide));
+}
+
+#[test]
+fn simulate_rewards_largest_result_fits() {
+    // Exactly u64::MAX after scaling down
+    let denominator = SCALE as u128 * SECONDS_PER_YEAR as u128;
+    let rate_seconds = denominator;
+    assert_eq!(reward_math(u64::MAX, rate_seconds, 0), Ok((u64::MAX, 0)));
+    assert_eq!(reward_math(u64::MAX, rate_seconds, denominator), Err(RewardOverflow::Divide));
+}
+
+#[test]
+fn reward_math_overflows_at_remainder() {
+    assert_eq!(reward_math(1, u128::MAX, 1), Err(RewardOverflow::Multiply));
+}
+
+// ============================================================================
+// check_fresh
+// ============================================================================
+
+const NOW: i64 = 1_700_000_000;
+
+#[test]
+fn fresh_data_passes() {
+    assert!(check_fresh(NOW, 60, NOW).is_ok());
+    assert!(check_fresh(NOW - 59, 60, NOW).is_ok());
+}
+
+#[test]
+fn data_exactly_max_age_old_passes() {
+    assert!(check_fresh(NOW - 60, 60, NOW).is_ok());
+}
+
+#[test]
+fn stale_data_is_rejected() {
+    assert!(check_fresh(NOW - 61, 60, NOW).is_err());
+    assert!(check_fresh(0, 60, NOW).is_err());
+}
+
+#[test]
+fn backward_clock_is_rejected() {
+    // last_update in the future, even with an unlimited max age
+    assert!(check_fresh(NOW + 1, 60, NOW).is_err());
+    assert!(check_fresh(NOW + 1, i64::MAX, NOW).is_err());
+}
+
+#[test]
+fn freshness_rejects_negative_max_age_and_i64_overflow() {
+    assert!(check_fresh(NOW, -1, NOW).is_err());
+    assert!(check_fresh(i64::MIN, i64::MAX, NOW).is_err());
+}
+
+// ============================================================================
+// acc_reward_per_share / pending_reward
+// ============================================================================
+
+/// Minimal MasterChef pool driven by the logic helpers, the way
+/// `secure_matching` drives them from its handlers
+struct Emission {
+    rate: u64,
+    acc: u128,
+    last: u64,
+    total_staked: u64,
+}
+
+struct Stake {
+    amount: u64,
+    debt: u128,
+    pending: u64,
+}
+
+impl Emission {
+    fn update(&mut self, now: u64) {
+        self.acc = acc_reward_per_share(self.acc, self.rate, now - self.last, self.total_staked).unwrap();
+        self.last = now;
+    }
+
+    fn stake(&mut self, stake: &mut Stake, amount: u64, now: u64) {
+        self.update(now);
+        stake.pending += pending_reward(stake.amount, self.acc, stake.debt).unwrap();
+        stake.amount += amount;
+        stake.debt = reward_debt(stake.amount, self.acc).unwrap();
+        self.total_staked += amount;
+    }
+
+    fn settle(&mut self, stake: &mut Stake, now: u64) -> u64 {
+        self.update(now);
+        stake.pending += pending_reward(stake.amount, self.acc, stake.debt).unwrap();
+        stake.debt = reward_debt(stake.amount, self.acc).unwrap();
+        stake.pending
+    }
+}
+
+fn empty_stake() -> Stake {
+    Stake { amount: 0, debt: 0, pending: 0 }
+}
+
+#[test]
+fn late_staker_only_shares_emissions_after_joining() {
+    let mut pool = Emission { rate: 10, acc: 0, last: 0, total_staked: 0 };
+    let (mut a, mut b) = (empty_stake(), empty_stake());
+
+    // t=0..100: A alone with 100 → 1_000
+    // t=100..200: A 100 / B 300 → A 250, B 750
+    pool.stake(&mut a, 100, 0);
+    pool.stake(&mut b, 300, 100);
+
+    assert_eq!(pool.settle(&mut a, 200), 1_250);
+    assert_eq!(pool.settle(&mut b, 200), 750);
+}
+
+#[test]
+fn equal_stakes_over_equal_time_earn_equally() {
+    let mut pool = Emission { rate: 7, acc: 0, last: 0, total_staked: 0 };
+    let (mut a, mut b) = (empty_stake(), empty_stake());
+
+    pool.stake(&mut a, 1_000, 0);
+    pool.stake(&mut b, 1_000, 0);
+
+    let (ra, rb) = (pool.settle(&mut a, 1_000), pool.settle(&mut b, 1_000));
+    assert_eq!(ra, rb);
+    assert_eq!(ra + rb, 7_000);
+}
+
+#[test]
+fn nothing_accrues_while_nothing_is_staked() {
+    assert_eq!(acc_reward_per_share(42, 1_000, 1_000, 0).unwrap(), 42);
+}
+
+#[test]
+fn settling_twice_pays_once() {
+    let mut pool = Emission { rate: 10, acc: 0, last: 0, total_staked: 0 };
+    let mut a = empty_stake();
+
+    pool.stake(&mut a, 100, 0);
+    assert_eq!(pool.settle(&mut a, 50), 500);
+    assert_eq!(pool.settle(&mut a, 50), 500);
+}
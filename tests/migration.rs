@@ -0,0 +1,144 @@
+//! # Account Migration Tests
+//!
+//! `secure_migration::migrate_vault_v1_to_v2` against a vault written in
+//! the previous deployment's `VaultV1` layout: fields carried over, account
+//! grown and kept rent-exempt, and every second attempt rejected. Runs in
+//! `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test migration
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas};
+use common::{add_account, add_funded_keypair, custom, TestEnv};
+use secure_migration::{ErrorCode, Vault, VaultV1, VAULT_V1, VAULT_V2};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const BALANCE: u64 = 5_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn vault_pda(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", authority.as_ref()], &secure_migration::ID)
+}
+
+/// A vault as the previous deployment left it: `Vault` discriminator
+/// followed by the v1 fields, with `version` overridable
+fn add_v1_vault(program_test: &mut ProgramTest, authority: &Pubkey, version: u8) -> Pubkey {
+    let (address, _) = vault_pda(authority);
+    let mut data = Vault::DISCRIMINATOR.to_vec();
+    VaultV1 { version, authority: *authority, balance: BALANCE }
+        .serialize(&mut data)
+        .unwrap();
+    add_account(program_test, address, secure_migration::ID, data);
+    address
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    attacker: Keypair,
+    vault: Pubkey,
+}
+
+/// The authority's vault at the given version; authority and attacker
+/// each pay their own fees and rent
+async fn setup(version: u8) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_migration", secure_migration::ID, processor!(secure_migration::entry));
+
+    let authority = add_funded_keypair(&mut program_test, LAMPORTS_PER_SOL);
+    let attacker = add_funded_keypair(&mut program_test, LAMPORTS_PER_SOL);
+    let vault = add_v1_vault(&mut program_test, &authority.pubkey(), version);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, attacker, vault }
+}
+
+async fn migrate(setup: &mut Setup, signer: &Keypair) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_migration::ID,
+        accounts: secure_migration::accounts::MigrateVault {
+            vault: setup.vault,
+            authority: signer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_migration::instruction::MigrateVaultV1ToV2 {}.data(),
+    };
+    setup.env.send_paid_by(&[ix], signer, &[]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn v1_vault_is_migrated_in_place() {
+    let mut setup = setup(VAULT_V1).await;
+    let authority = setup.authority.insecure_clone();
+
+    migrate(&mut setup, &authority).await.unwrap();
+
+    let account = setup.env.account(setup.vault).await.unwrap();
+    assert_eq!(account.data.len(), 8 + Vault::INIT_SPACE);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(8 + Vault::INIT_SPACE));
+
+    let vault = Vault::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(vault.version, VAULT_V2);
+    assert_eq!(vault.authority, authority.pubkey());
+    assert_eq!(vault.balance, BALANCE);
+    assert_eq!(vault.bump, vault_pda(&authority.pubkey()).1);
+}
+
+#[tokio::test]
+async fn second_migration_is_rejected() {
+    let mut setup = setup(VAULT_V1).await;
+    let authority = setup.authority.insecure_clone();
+
+    migrate(&mut setup, &authority).await.unwrap();
+    let err = migrate(&mut setup, &authority).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::AlreadyMigrated));
+}
+
+#[tokio::test]
+async fn unknown_versions_are_rejected() {
+    for version in [0, VAULT_V2 + 1] {
+        let mut setup = setup(version).await;
+        let authority = setup.authority.insecure_clone();
+
+        let err = migrate(&mut setup, &authority).await.unwrap_err();
+
+        assert_eq!(err, custom(ErrorCode::UnsupportedVersion));
+        // Untouched: still the v1-sized account
+        let account = setup.env.account(setup.vault).await.unwrap();
+        assert_eq!(account.data.len(), VaultV1::SPACE);
+    }
+}
+
+#[tokio::test]
+async fn another_authority_cannot_migrate() {
+    let mut setup = setup(VAULT_V1).await;
+    let attacker = setup.attacker.insecure_clone();
+
+    // Seeds are derived from the signer, so the victim's vault never matches
+    let err = migrate(&mut setup, &attacker).await.unwrap_err();
+
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+    let account = setup.env.account(setup.vault).await.unwrap();
+    assert_eq!(account.data.len(), VaultV1::SPACE);
+}
@@ -0,0 +1,370 @@
+//! # Mint Rewards Tests
+//!
+//! Reward pools in `vulnerable_mint_rewards` and `secure_mint_rewards` over a
+//! mint whose `mint_authority` is either the admin's own key or the pool
+//! PDA. The vulnerable pool works with the admin's mint, and the admin can
+//! mint around it. The secure pool refuses any mint it is not the sole
+//! issuer of, and never mints past its `reward_cap`. Runs in
+//! `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test mint_rewards
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_account, add_packed, add_token_account, custom, serialize, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+const PENDING: u64 = 100;
+const OUTSIDE_MINT: u64 = 1_000_000;
+/// Two and a half `PENDING` claims
+const CAP: u64 = 250;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Who `mint_authority` is on a side's reward mint
+#[derive(Clone, Copy)]
+enum Issuer {
+    Admin,
+    Pool,
+}
+
+/// One program's reward mint, pool PDA and the user's accounts in it
+struct Side {
+    mint: Pubkey,
+    pool: Pubkey,
+    rewards: Pubkey,
+    user_tokens: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    admin: Keypair,
+    user: Keypair,
+    vulnerable: Side,
+    secure: Side,
+}
+
+/// Empty reward mint for `program_id` issued by `issuer`, and an empty token
+/// account for `user`. With `with_pool`, the pool PDA and the user's reward
+/// account are created from `state(mint, pool_bump, pool, rewards_bump)`,
+/// as if initialized and credited already.
+fn add_side(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    issuer: Issuer,
+    admin: Pubkey,
+    user: Pubkey,
+    with_pool: bool,
+    state: impl FnOnce(Pubkey, u8, Pubkey, u8) -> (Vec<u8>, Vec<u8>),
+) -> Side {
+    let mint = Pubkey::new_unique();
+    let (pool, pool_bump) = Pubkey::find_program_address(&[b"reward_pool", mint.as_ref()], &program_id);
+    let mint_authority = match issuer {
+        Issuer::Admin => admin,
+        Issuer::Pool => pool,
+    };
+    add_packed(program_test, mint, common::mint(Some(mint_authority)));
+
+    let (rewards, rewards_bump) =
+        Pubkey::find_program_address(&[b"rewards", pool.as_ref(), user.as_ref()], &program_id);
+    if with_pool {
+        let (pool_data, rewards_data) = state(mint, pool_bump, pool, rewards_bump);
+        add_account(program_test, pool, program_id, pool_data);
+        add_account(program_test, rewards, program_id, rewards_data);
+    }
+
+    let user_tokens = add_token_account(program_test, mint, user, 0);
+
+    Side { mint, pool, rewards, user_tokens }
+}
+
+/// Both programs with a mint issued by `issuer`; pools already exist with
+/// `PENDING` credited to the user when `with_pool` is set
+async fn setup(issuer: Issuer, with_pool: bool) -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_mint_rewards",
+        vulnerable_mint_rewards::ID,
+        processor!(vulnerable_mint_rewards::entry),
+    );
+    program_test.add_program("secure_mint_rewards", secure_mint_rewards::ID, processor!(secure_mint_rewards::entry));
+
+    let admin = Keypair::new();
+    let user = Keypair::new();
+    let (admin_key, user_key) = (admin.pubkey(), user.pubkey());
+
+    let vulnerable = add_side(
+        &mut program_test,
+        vulnerable_mint_rewards::ID,
+        issuer,
+        admin_key,
+        user_key,
+        with_pool,
+        |mint, pool_bump, pool, rewards_bump| {
+            (
+                serialize(&vulnerable_mint_rewards::RewardPool {
+                    authority: admin_key,
+                    reward_mint: mint,
+                    total_minted: 0,
+                    bump: pool_bump,
+                }),
+                serialize(&vulnerable_mint_rewards::RewardAccount {
+                    owner: user_key,
+                    pool,
+                    pending: PENDING,
+                    bump: rewards_bump,
+                }),
+            )
+        },
+    );
+    let secure = add_side(
+        &mut program_test,
+        secure_mint_rewards::ID,
+        issuer,
+        admin_key,
+        user_key,
+        with_pool,
+        |mint, pool_bump, pool, rewards_bump| {
+            (
+                serialize(&secure_mint_rewards::RewardPool {
+                    authority: admin_key,
+                    reward_mint: mint,
+                    total_minted: 0,
+                    reward_cap: CAP,
+                    bump: pool_bump,
+                }),
+                serialize(&secure_mint_rewards::RewardAccount {
+                    owner: user_key,
+                    pool,
+                    pending: PENDING,
+                    bump: rewards_bump,
+                }),
+            )
+        },
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, admin, user, vulnerable, secure }
+}
+
+fn vulnerable_initialize_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: vulnerable_mint_rewards::ID,
+        accounts: vulnerable_mint_rewards::accounts::InitializePool {
+            pool: setup.vulnerable.pool,
+            reward_mint: setup.vulnerable.mint,
+            authority: setup.admin.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_mint_rewards::instruction::InitializePool {}.data(),
+    }
+}
+
+fn secure_initialize_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_mint_rewards::ID,
+        accounts: secure_mint_rewards::accounts::InitializePool {
+            pool: setup.secure.pool,
+            reward_mint: setup.secure.mint,
+            authority: setup.admin.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_mint_rewards::instruction::InitializePool { reward_cap: CAP }.data(),
+    }
+}
+
+fn vulnerable_claim_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: vulnerable_mint_rewards::ID,
+        accounts: vulnerable_mint_rewards::accounts::ClaimRewards {
+            pool: setup.vulnerable.pool,
+            rewards: setup.vulnerable.rewards,
+            reward_mint: setup.vulnerable.mint,
+            user_tokens: setup.vulnerable.user_tokens,
+            owner: setup.user.pubkey(),
+            mint_authority: setup.admin.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_mint_rewards::instruction::ClaimRewards {}.data(),
+    }
+}
+
+fn secure_claim_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_mint_rewards::ID,
+        accounts: secure_mint_rewards::accounts::ClaimRewards {
+            pool: setup.secure.pool,
+            rewards: setup.secure.rewards,
+            reward_mint: setup.secure.mint,
+            user_tokens: setup.secure.user_tokens,
+            owner: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_mint_rewards::instruction::ClaimRewards {}.data(),
+    }
+}
+
+fn secure_credit_ix(setup: &Setup, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_mint_rewards::ID,
+        accounts: secure_mint_rewards::accounts::CreditRewards {
+            pool: setup.secure.pool,
+            rewards: setup.secure.rewards,
+            authority: setup.admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_mint_rewards::instruction::CreditRewards { amount }.data(),
+    }
+}
+
+async fn supply(env: &mut TestEnv, mint: Pubkey) -> u64 {
+    let account = env.account(mint).await.unwrap();
+    Mint::unpack(&account.data).unwrap().supply
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_pool_accepts_a_mint_the_admin_controls() {
+    let mut setup = setup(Issuer::Admin, false).await;
+
+    let ix = vulnerable_initialize_ix(&setup);
+    setup.env.send(&[ix], &[&setup.admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn vulnerable_admin_mints_around_the_pool() {
+    let mut setup = setup(Issuer::Admin, true).await;
+
+    // The claim only goes through because the admin co-signs
+    let ix = vulnerable_claim_ix(&setup);
+    setup.env.send(&[ix], &[&setup.user, &setup.admin]).await.unwrap();
+
+    // Then the admin mints directly; the program never sees it
+    let outside = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &setup.vulnerable.mint,
+        &setup.vulnerable.user_tokens,
+        &setup.admin.pubkey(),
+        &[],
+        OUTSIDE_MINT,
+    )
+    .unwrap();
+    setup.env.send(&[outside], &[&setup.admin]).await.unwrap();
+
+    let pool: vulnerable_mint_rewards::RewardPool = setup.env.fetch(setup.vulnerable.pool).await;
+    assert_eq!(pool.total_minted, PENDING);
+    assert_eq!(supply(&mut setup.env, setup.vulnerable.mint).await, PENDING + OUTSIDE_MINT);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_initialize_refuses_a_mint_the_admin_controls() {
+    let mut setup = setup(Issuer::Admin, false).await;
+
+    let ix = secure_initialize_ix(&setup);
+    let err = setup.env.send(&[ix], &[&setup.admin]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_mint_rewards::ErrorCode::NotMintAuthority));
+    assert!(setup.env.account(setup.secure.pool).await.is_none());
+}
+
+#[tokio::test]
+async fn secure_initialize_accepts_the_pool_as_mint_authority() {
+    let mut setup = setup(Issuer::Pool, false).await;
+
+    let ix = secure_initialize_ix(&setup);
+    setup.env.send(&[ix], &[&setup.admin]).await.unwrap();
+
+    let pool: secure_mint_rewards::RewardPool = setup.env.fetch(setup.secure.pool).await;
+    assert_eq!(pool.reward_mint, setup.secure.mint);
+}
+
+#[tokio::test]
+async fn secure_claim_refuses_when_someone_else_is_mint_authority() {
+    let mut setup = setup(Issuer::Admin, true).await;
+
+    let ix = secure_claim_ix(&setup);
+    let err = setup.env.send(&[ix], &[&setup.user]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_mint_rewards::ErrorCode::NotMintAuthority));
+    assert_eq!(supply(&mut setup.env, setup.secure.mint).await, 0);
+    let rewards: secure_mint_rewards::RewardAccount = setup.env.fetch(setup.secure.rewards).await;
+    assert_eq!(rewards.pending, PENDING);
+}
+
+#[tokio::test]
+async fn secure_claim_mints_with_the_pool_signer() {
+    let mut setup = setup(Issuer::Pool, true).await;
+
+    // Only the user signs; the pool PDA signs the mint
+    let ix = secure_claim_ix(&setup);
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.secure.user_tokens).await, PENDING);
+    assert_eq!(supply(&mut setup.env, setup.secure.mint).await, PENDING);
+    let pool: secure_mint_rewards::RewardPool = setup.env.fetch(setup.secure.pool).await;
+    assert_eq!(pool.total_minted, PENDING);
+    let rewards: secure_mint_rewards::RewardAccount = setup.env.fetch(setup.secure.rewards).await;
+    assert_eq!(rewards.pending, 0);
+}
+
+// ============================================================================
+// REWARD CAP: CAP = 250, claims of PENDING = 100
+// ============================================================================
+
+/// Credit `PENDING` and claim it
+async fn credit_and_claim(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = secure_credit_ix(setup, PENDING);
+    setup.env.send(&[ix], &[&setup.admin]).await.unwrap();
+    let ix = secure_claim_ix(setup);
+    setup.env.send(&[ix], &[&setup.user]).await
+}
+
+#[tokio::test]
+async fn secure_claims_stop_at_the_cap() {
+    let mut setup = setup(Issuer::Pool, true).await;
+
+    // 100 pending from setup, then 100 more: 200 of 250
+    let ix = secure_claim_ix(&setup);
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+    credit_and_claim(&mut setup).await.unwrap();
+    assert_eq!(supply(&mut setup.env, setup.secure.mint).await, 200);
+
+    // Only 50 of the next 100 fit: clamped, the rest stays pending
+    credit_and_claim(&mut setup).await.unwrap();
+    assert_eq!(supply(&mut setup.env, setup.secure.mint).await, CAP);
+    let pool: secure_mint_rewards::RewardPool = setup.env.fetch(setup.secure.pool).await;
+    assert_eq!(pool.total_minted, CAP);
+    let rewards: secure_mint_rewards::RewardAccount = setup.env.fetch(setup.secure.rewards).await;
+    assert_eq!(rewards.pending, 50);
+
+    // No headroom left
+    let err = credit_and_claim(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(secure_mint_rewards::ErrorCode::RewardCapReached));
+    assert_eq!(supply(&mut setup.env, setup.secure.mint).await, CAP);
+    assert_eq!(setup.env.token_balance(setup.secure.user_tokens).await, CAP);
+}
@@ -0,0 +1,557 @@
+//! # Overflow LiteSVM Tests
+//!
+//! Fast, in-process tests for `vulnerable_overflow` and `secure_overflow`
+//! using `litesvm`. No validator is started. Also covers reward accrual
+//! across timelocked `secure_overflow` reward rate changes and the vault's
+//! `OverflowMode` (`Revert` vs `Saturate`).
+//!
+//! The vulnerable program only wraps if it is compiled WITHOUT overflow checks
+//! (the default for Solana release builds unless `overflow-checks = true`):
+//!
+//! ```bash
+//! anchor build
+//! cargo test --test overflow_litesvm
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, Space, ToAccountMetas};
+use litesvm::LiteSVM;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const VULNERABLE_SO: &str = "target/deploy/vulnerable_overflow.so";
+const SECURE_SO: &str = "target/deploy/secure_overflow.so";
+
+const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(vulnerable_overflow::ID, VULNERABLE_SO)
+        .unwrap();
+    svm.add_program_from_file(secure_overflow::ID, SECURE_SO).unwrap();
+
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    (svm, payer)
+}
+
+fn send(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        &all_signers,
+        svm.latest_blockhash(),
+    );
+    let result = svm.send_transaction(tx).map(|_| ()).map_err(|e| e.err);
+    svm.expire_blockhash();
+    result
+}
+
+/// Read back any Anchor account (`Vault`, `StakingAccount`, ...) from the SVM
+fn read<T: AccountDeserialize>(svm: &LiteSVM, address: &Pubkey) -> T {
+    let account = svm.get_account(address).unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Write an Anchor account straight into the SVM, bypassing instructions
+fn write<T: AccountSerialize>(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, state: &T, space: usize) {
+    let mut data = Vec::with_capacity(space);
+    state.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    svm.set_account(
+        address,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+fn set_time(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock: Clock = svm.get_sysvar();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+/// `["access_control", pool]`
+fn access_control_address(pool: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"access_control", pool.as_ref()], &secure_overflow::ID).0
+}
+
+/// Reward pool paying `rate` since `START_TIME`, with `authority` as its
+/// only `Admin`
+fn write_pool(svm: &mut LiteSVM, authority: Pubkey, rate: u64) -> Pubkey {
+    let pool = Pubkey::new_unique();
+    write(
+        svm,
+        pool,
+        secure_overflow::ID,
+        &secure_overflow::Pool { reward_rate: rate, rate_updated_at: START_TIME, ..common::overflow_pool(authority) },
+        8 + secure_overflow::Pool::INIT_SPACE,
+    );
+
+    let (access_control, bump) =
+        Pubkey::find_program_address(&[b"access_control", pool.as_ref()], &secure_overflow::ID);
+    write(
+        svm,
+        access_control,
+        secure_overflow::ID,
+        &secure_overflow::access_control::AccessControl {
+            scope: pool,
+            grants: vec![secure_overflow::access_control::RoleGrant {
+                role: secure_overflow::access_control::Role::Admin,
+                key: authority,
+            }],
+            bump,
+        },
+        8 + secure_overflow::access_control::AccessControl::INIT_SPACE,
+    );
+    pool
+}
+
+/// `STAKE_AMOUNT` staked in `pool` by `owner` since `START_TIME`
+fn write_stake(svm: &mut LiteSVM, owner: Pubkey, pool: Pubkey) -> Pubkey {
+    let staking = Pubkey::new_unique();
+    write(
+        svm,
+        staking,
+        secure_overflow::ID,
+        &secure_overflow::StakingAccount {
+            pool_balance: u64::MAX,
+            last_accrual_time: 0,
+            ..common::overflow_stake(owner, pool, STAKE_AMOUNT, START_TIME)
+        },
+        8 + secure_overflow::StakingAccount::INIT_SPACE,
+    );
+    staking
+}
+
+fn calculate_rewards_ix(staking: Pubkey, pool: Pubkey, owner: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::CalculateRewards { staking, pool, owner }
+            .to_account_metas(None),
+        data: secure_overflow::instruction::CalculateRewards {}.data(),
+    }
+}
+
+fn queue_rate_ix(pool: Pubkey, authority: Pubkey, new_rate: u64) -> Instruction {
+    Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::QueueAdminAction {
+            pool,
+            access_control: access_control_address(pool),
+            authority,
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::QueueAdminAction {
+            action: secure_overflow::AdminAction::SetRewardRate { new_rate },
+        }
+        .data(),
+    }
+}
+
+fn execute_action_ix(pool: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::ExecuteAdminAction { pool }.to_account_metas(None),
+        data: secure_overflow::instruction::ExecuteAdminAction {}.data(),
+    }
+}
+
+fn program_error(code: u32) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(code))
+}
+
+// ============================================================================
+// UNDERFLOW: withdraw(200) on a balance of 100
+// ============================================================================
+
+#[test]
+fn vulnerable_withdraw_wraps_balance() {
+    let (mut svm, payer) = setup();
+    let vault = Keypair::new();
+
+    let init = Instruction {
+        program_id: vulnerable_overflow::ID,
+        accounts: vulnerable_overflow::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_overflow::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: vulnerable_overflow::ID,
+        accounts: vulnerable_overflow::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: payer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: vulnerable_overflow::instruction::Deposit { amount: 100 }.data(),
+    };
+    send(&mut svm, &payer, &[init, deposit], &[&vault]).unwrap();
+
+    let withdraw = Instruction {
+        program_id: vulnerable_overflow::ID,
+        accounts: vulnerable_overflow::accounts::Withdraw {
+            vault: vault.pubkey(),
+            authority: payer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: vulnerable_overflow::instruction::Withdraw { amount: 200 }.data(),
+    };
+    send(&mut svm, &payer, &[withdraw], &[]).unwrap();
+
+    // ❌ 100 - 200 wrapped around
+    let state: vulnerable_overflow::Vault = read(&svm, &vault.pubkey());
+    assert_eq!(state.balance, u64::MAX - 99);
+}
+
+#[test]
+fn secure_withdraw_rejects_insufficient_balance() {
+    let (mut svm, payer) = setup();
+    let vault = Keypair::new();
+
+    let init = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: payer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::Deposit { amount: 100 }.data(),
+    };
+    send(&mut svm, &payer, &[init, deposit], &[&vault]).unwrap();
+
+    let withdraw = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Withdraw {
+            vault: vault.pubkey(),
+            authority: payer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_overflow::instruction::Withdraw { amount: 200 }.data(),
+    };
+    let err = send(&mut svm, &payer, &[withdraw], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::common_errors::CommonError::InsufficientFunds.into())
+    );
+
+    // ✅ Balance untouched
+    let state: secure_overflow::Vault = read(&svm, &vault.pubkey());
+    assert_eq!(state.balance, 100);
+}
+
+// ============================================================================
+// MULTIPLICATION OVERFLOW: amount * rate * time
+// ============================================================================
+
+const STAKE_AMOUNT: u64 = 1_000_000_000_000; // 10^12
+const RATE: u64 = 1_000_000; // 100% APY, scaled by 10^6
+const START_TIME: i64 = 1_700_000_000;
+
+#[test]
+fn vulnerable_calculate_rewards_wraps() {
+    let (mut svm, payer) = setup();
+    let staking = Pubkey::new_unique();
+
+    write(
+        &mut svm,
+        staking,
+        vulnerable_overflow::ID,
+        &vulnerable_overflow::StakingAccount {
+            owner: Pubkey::new_unique(),
+            amount: STAKE_AMOUNT,
+            rate: RATE,
+            start_time: START_TIME,
+            pending_rewards: 0,
+        },
+        8 + vulnerable_overflow::StakingAccount::INIT_SPACE,
+    );
+    set_time(&mut svm, START_TIME + SECONDS_PER_YEAR);
+
+    let ix = Instruction {
+        program_id: vulnerable_overflow::ID,
+        accounts: vulnerable_overflow::accounts::CalculateRewards { staking }
+            .to_account_metas(None),
+        data: vulnerable_overflow::instruction::CalculateRewards {}.data(),
+    };
+    send(&mut svm, &payer, &[ix], &[]).unwrap();
+
+    // ❌ 10^12 * 10^6 * 31_536_000 ≈ 3.15 * 10^25 does not fit in u64
+    let expected = STAKE_AMOUNT
+        .wrapping_mul(RATE)
+        .wrapping_mul(SECONDS_PER_YEAR as u64);
+    let state: vulnerable_overflow::StakingAccount = read(&svm, &staking);
+    assert_eq!(state.pending_rewards, expected);
+}
+
+#[test]
+fn secure_calculate_rewards_uses_u128_intermediate() {
+    let (mut svm, payer) = setup();
+    let pool = write_pool(&mut svm, Pubkey::new_unique(), RATE);
+    let staking = write_stake(&mut svm, payer.pubkey(), pool);
+    set_time(&mut svm, START_TIME + SECONDS_PER_YEAR);
+
+    let ix = calculate_rewards_ix(staking, pool, payer.pubkey());
+    send(&mut svm, &payer, &[ix], &[]).unwrap();
+
+    // ✅ 100% APY for exactly one year: rewards == stake
+    let state: secure_overflow::StakingAccount = read(&svm, &staking);
+    assert_eq!(state.pending_rewards, STAKE_AMOUNT);
+    assert_eq!(state.accumulated_remainder, 0);
+}
+
+// ============================================================================
+// REWARD RATE CHANGES: rate1 for half a year, then rate2
+// ============================================================================
+
+const HALF_YEAR: i64 = SECONDS_PER_YEAR / 2;
+
+/// Queue `new_rate` so that it can execute exactly at `START_TIME + HALF_YEAR`
+fn queue_rate_for_half_year(svm: &mut LiteSVM, payer: &Keypair, pool: Pubkey, new_rate: u64) {
+    set_time(svm, START_TIME + HALF_YEAR - secure_overflow::ADMIN_ACTION_DELAY);
+    send(svm, payer, &[queue_rate_ix(pool, payer.pubkey(), new_rate)], &[]).unwrap();
+}
+
+#[test]
+fn rate_change_checkpoints_accrual_at_old_rate() {
+    let (mut svm, payer) = setup();
+    let pool = write_pool(&mut svm, payer.pubkey(), RATE);
+    let staking = write_stake(&mut svm, payer.pubkey(), pool);
+    queue_rate_for_half_year(&mut svm, &payer, pool, 3 * RATE);
+
+    // The staker does not settle before the change
+    set_time(&mut svm, START_TIME + HALF_YEAR);
+    send(&mut svm, &payer, &[execute_action_ix(pool)], &[]).unwrap();
+
+    set_time(&mut svm, START_TIME + SECONDS_PER_YEAR);
+    send(&mut svm, &payer, &[calculate_rewards_ix(staking, pool, payer.pubkey())], &[]).unwrap();
+
+    // Half a year at 100% + half a year at 300%, not a full year at 300%
+    let state: secure_overflow::StakingAccount = read(&svm, &staking);
+    assert_eq!(state.pending_rewards, STAKE_AMOUNT / 2 + 3 * STAKE_AMOUNT / 2);
+}
+
+#[test]
+fn settling_before_rate_change_gives_same_result() {
+    let (mut svm, payer) = setup();
+    let pool = write_pool(&mut svm, payer.pubkey(), RATE);
+    let staking = write_stake(&mut svm, payer.pubkey(), pool);
+    queue_rate_for_half_year(&mut svm, &payer, pool, 3 * RATE);
+
+    set_time(&mut svm, START_TIME + HALF_YEAR);
+    send(&mut svm, &payer, &[calculate_rewards_ix(staking, pool, payer.pubkey())], &[]).unwrap();
+    let state: secure_overflow::StakingAccount = read(&svm, &staking);
+    assert_eq!(state.pending_rewards, STAKE_AMOUNT / 2);
+
+    send(&mut svm, &payer, &[execute_action_ix(pool)], &[]).unwrap();
+
+    set_time(&mut svm, START_TIME + SECONDS_PER_YEAR);
+    send(&mut svm, &payer, &[calculate_rewards_ix(staking, pool, payer.pubkey())], &[]).unwrap();
+
+    let state: secure_overflow::StakingAccount = read(&svm, &staking);
+    assert_eq!(state.pending_rewards, STAKE_AMOUNT / 2 + 3 * STAKE_AMOUNT / 2);
+}
+
+#[test]
+fn queued_rate_waits_for_the_delay() {
+    let (mut svm, payer) = setup();
+    let pool = write_pool(&mut svm, payer.pubkey(), RATE);
+    set_time(&mut svm, START_TIME);
+
+    send(&mut svm, &payer, &[queue_rate_ix(pool, payer.pubkey(), 3 * RATE)], &[]).unwrap();
+    set_time(&mut svm, START_TIME + secure_overflow::ADMIN_ACTION_DELAY - 1);
+    let err = send(&mut svm, &payer, &[execute_action_ix(pool)], &[]).unwrap_err();
+    assert_eq!(err, program_error(secure_overflow::ErrorCode::ActionNotReady.into()));
+
+    let state: secure_overflow::Pool = read(&svm, &pool);
+    assert_eq!(state.reward_rate, RATE);
+    assert_eq!(state.action_ready_at, START_TIME + secure_overflow::ADMIN_ACTION_DELAY);
+}
+
+#[test]
+fn non_authority_cannot_queue_reward_rate() {
+    let (mut svm, payer) = setup();
+    let pool = write_pool(&mut svm, Pubkey::new_unique(), RATE);
+
+    let err = send(&mut svm, &payer, &[queue_rate_ix(pool, payer.pubkey(), u64::MAX)], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::access_control::AccessControlError::MissingRole.into())
+    );
+
+    let state: secure_overflow::Pool = read(&svm, &pool);
+    assert_eq!(state.pending_action, None);
+}
+
+// ============================================================================
+// OVERFLOW MODE: the same deposit/withdraw under Revert and Saturate
+// ============================================================================
+
+const NEAR_MAX: u64 = u64::MAX - 50;
+
+/// Vault holding 100 whose lifetime totals are both `NEAR_MAX`
+fn write_vault(svm: &mut LiteSVM, authority: Pubkey, overflow_mode: secure_overflow::OverflowMode) -> Pubkey {
+    let vault = Pubkey::new_unique();
+    write(
+        svm,
+        vault,
+        secure_overflow::ID,
+        &secure_overflow::Vault {
+            authority,
+            balance: 100,
+            total_deposited: NEAR_MAX,
+            total_withdrawn: NEAR_MAX,
+            overflow_mode,
+            version: secure_overflow::CURRENT_VERSION,
+        },
+        8 + secure_overflow::Vault::INIT_SPACE,
+    );
+    vault
+}
+
+fn deposit_ix(vault: Pubkey, depositor: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Deposit { vault, depositor }.to_account_metas(None),
+        data: secure_overflow::instruction::Deposit { amount }.data(),
+    }
+}
+
+fn withdraw_ix(vault: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::Withdraw { vault, authority }.to_account_metas(None),
+        data: secure_overflow::instruction::Withdraw { amount }.data(),
+    }
+}
+
+#[test]
+fn revert_mode_deposit_fails_on_total_overflow() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, payer.pubkey(), secure_overflow::OverflowMode::Revert);
+
+    let err = send(&mut svm, &payer, &[deposit_ix(vault, payer.pubkey(), 100)], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::common_errors::CommonError::Overflow.into())
+    );
+
+    let state: secure_overflow::Vault = read(&svm, &vault);
+    assert_eq!((state.balance, state.total_deposited), (100, NEAR_MAX));
+}
+
+#[test]
+fn saturate_mode_deposit_caps_total_at_max() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, payer.pubkey(), secure_overflow::OverflowMode::Saturate);
+
+    send(&mut svm, &payer, &[deposit_ix(vault, payer.pubkey(), 100)], &[]).unwrap();
+
+    // The total is pinned; the balance is still exact
+    let state: secure_overflow::Vault = read(&svm, &vault);
+    assert_eq!(state.total_deposited, u64::MAX);
+    assert_eq!(state.balance, 200);
+}
+
+#[test]
+fn revert_mode_withdraw_fails_on_total_overflow() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, payer.pubkey(), secure_overflow::OverflowMode::Revert);
+
+    let err = send(&mut svm, &payer, &[withdraw_ix(vault, payer.pubkey(), 100)], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::common_errors::CommonError::Overflow.into())
+    );
+
+    let state: secure_overflow::Vault = read(&svm, &vault);
+    assert_eq!((state.balance, state.total_withdrawn), (100, NEAR_MAX));
+}
+
+#[test]
+fn saturate_mode_withdraw_caps_total_at_max() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, payer.pubkey(), secure_overflow::OverflowMode::Saturate);
+
+    send(&mut svm, &payer, &[withdraw_ix(vault, payer.pubkey(), 100)], &[]).unwrap();
+
+    let state: secure_overflow::Vault = read(&svm, &vault);
+    assert_eq!(state.total_withdrawn, u64::MAX);
+    assert_eq!(state.balance, 0);
+}
+
+#[test]
+fn saturate_mode_never_overdraws_the_balance() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, payer.pubkey(), secure_overflow::OverflowMode::Saturate);
+
+    // saturating_sub would clamp 100 - 200 to 0; the balance check runs first
+    let err = send(&mut svm, &payer, &[withdraw_ix(vault, payer.pubkey(), 200)], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::common_errors::CommonError::InsufficientFunds.into())
+    );
+}
+
+#[test]
+fn non_authority_cannot_set_overflow_mode() {
+    let (mut svm, payer) = setup();
+    let vault = write_vault(&mut svm, Pubkey::new_unique(), secure_overflow::OverflowMode::Revert);
+
+    let ix = Instruction {
+        program_id: secure_overflow::ID,
+        accounts: secure_overflow::accounts::SetOverflowMode { vault, authority: payer.pubkey() }
+            .to_account_metas(None),
+        data: secure_overflow::instruction::SetOverflowMode {
+            overflow_mode: secure_overflow::OverflowMode::Saturate,
+        }
+        .data(),
+    };
+    let err = send(&mut svm, &payer, &[ix], &[]).unwrap_err();
+    assert_eq!(
+        err,
+        program_error(secure_overflow::common_errors::CommonError::Unauthorized.into())
+    );
+
+    let state: secure_overflow::Vault = read(&svm, &vault);
+    assert_eq!(state.overflow_mode, secure_overflow::OverflowMode::Revert);
+}
@@ -0,0 +1,181 @@
+//! # Panic-Free Math Tests
+//!
+//! A panic on-chain aborts the transaction with a generic
+//! `ProgramFailedToComplete`, with no error code a client can act on.
+//! These host tests call the math in `logic.rs` on every combination of
+//! boundary inputs (`0`, `1`, `u64::MAX`, `i64::MIN`, `i64::MAX`) inside
+//! `catch_unwind`, and check that bad inputs come back as `Err`.
+//!
+//! Run them in the default test profile: debug arithmetic panics on
+//! overflow, so any unchecked `+`, `-` or `*` left in the math shows up here.
+//!
+//! ## Inputs That Panicked Before The Checked Math
+//! Written as in the vulnerable programs, under debug arithmetic:
+//! - `amount * rate * time_staked` (`vulnerable_overflow::calculate_rewards`)
+//!   with `amount = u64::MAX`: "attempt to multiply with overflow"
+//! - `now - start_time` with `start_time = i64::MIN`: "attempt to subtract
+//!   with overflow"
+//! - `reserve_in + amount_in` (`vulnerable_overflow::swap`) with both near
+//!   `u64::MAX`: "attempt to add with overflow"
+//! - `amount * total_shares / total_assets`
+//!   (`vulnerable_inflation::shares_for_deposit`) with `total_assets = 0`
+//!   and shares outstanding: "attempt to divide by zero", in release too
+//!
+//! ```bash
+//! cargo test --test panic_free
+//! ```
+
+use secure_cpi::logic::{assets_for_shares, rewards, shares_for_deposit, swap_input, swap_output, Rounding, SCALE};
+use std::panic::{catch_unwind, UnwindSafe};
+
+const AMOUNTS: [u64; 4] = [0, 1, SCALE, u64::MAX];
+const TIMES: [i64; 5] = [i64::MIN, -1, 0, 1, i64::MAX];
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Run `f`, failing the test with `what` if it panics instead of returning
+fn no_panic<T>(what: String, f: impl FnOnce() -> anchor_lang::Result<T> + UnwindSafe) -> anchor_lang::Result<T> {
+    catch_unwind(f).unwrap_or_else(|_| panic!("{what} panicked instead of returning"))
+}
+
+// ============================================================================
+// swap_output
+// ============================================================================
+
+#[test]
+fn swap_output_never_panics() {
+    for amount_in in AMOUNTS {
+        for reserve_in in AMOUNTS {
+            for reserve_out in AMOUNTS {
+                let what = format!("swap_output({amount_in}, {reserve_in}, {reserve_out})");
+                let result = no_panic(what, || swap_output(amount_in, reserve_in, reserve_out));
+
+                // Only an empty trade into an empty pool divides by zero
+                assert_eq!(result.is_err(), amount_in == 0 && reserve_in == 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn swap_output_rejects_division_by_zero() {
+    assert!(no_panic("empty swap".into(), || swap_output(0, 0, u64::MAX)).is_err());
+}
+
+#[test]
+fn swap_output_at_max_reserves_fits() {
+    // u64::MAX * u64::MAX overflows u64 but not the u128 intermediate
+    let out = no_panic("max swap".into(), || swap_output(u64::MAX, u64::MAX, u64::MAX)).unwrap();
+    assert_eq!(out, u64::MAX / 2);
+}
+
+#[test]
+fn swap_input_never_panics() {
+    for amount_out in AMOUNTS {
+        for reserve_in in AMOUNTS {
+            for reserve_out in AMOUNTS {
+                let what = format!("swap_input({amount_out}, {reserve_in}, {reserve_out})");
+                let result = no_panic(what, || swap_input(amount_out, reserve_in, reserve_out));
+
+                // The pool can never pay out its whole side, or more
+                if amount_out >= reserve_out {
+                    assert!(result.is_err());
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// rewards
+// ============================================================================
+
+#[test]
+fn rewards_never_panic() {
+    for amount in AMOUNTS {
+        for rate in AMOUNTS {
+            for start in TIMES {
+                for now in TIMES {
+                    let what = format!("rewards({amount}, {rate}, {start}, {now})");
+                    no_panic(what, || rewards(amount, rate, start, now, 0, u64::MAX).map(|a| a.rewards)).ok();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rewards_reject_time_running_backwards() {
+    let result = no_panic("backwards".into(), || rewards(1, SCALE, i64::MAX, i64::MIN, 0, u64::MAX).map(|a| a.rewards));
+    assert!(result.is_err());
+}
+
+#[test]
+fn rewards_reject_i64_overflow_in_elapsed() {
+    // i64::MAX - i64::MIN does not fit in i64
+    let result = no_panic("elapsed".into(), || rewards(1, SCALE, i64::MIN, i64::MAX, 0, u64::MAX).map(|a| a.rewards));
+    assert!(result.is_err());
+}
+
+#[test]
+fn rewards_reject_u128_overflow() {
+    let result = no_panic("max rewards".into(), || {
+        rewards(u64::MAX, u64::MAX, 0, i64::MAX, 0, u64::MAX).map(|a| a.rewards)
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn rewards_reject_result_beyond_u64() {
+    // Fits in u128, too large once scaled down to u64
+    let result = no_panic("one second".into(), || rewards(u64::MAX, u64::MAX, 0, 1, 0, u64::MAX).map(|a| a.rewards));
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// shares
+// ============================================================================
+
+#[test]
+fn shares_for_deposit_never_panics() {
+    for amount in AMOUNTS {
+        for total_deposits in AMOUNTS {
+            for total_shares in AMOUNTS {
+                let what = format!("shares_for_deposit({amount}, {total_deposits}, {total_shares})");
+                let result = no_panic(what, || shares_for_deposit(amount, total_deposits, total_shares));
+
+                // Shares outstanding against nothing deposited can't be priced
+                if total_shares > 0 && total_deposits == 0 {
+                    assert!(result.is_err());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn shares_for_deposit_rejects_result_beyond_u64() {
+    // 1 token backing u64::MAX shares: u64::MAX tokens would mint u64::MAX² shares
+    let result = no_panic("inflated".into(), || shares_for_deposit(u64::MAX, 1, u64::MAX));
+    assert!(result.is_err());
+}
+
+#[test]
+fn assets_for_shares_never_panics() {
+    for shares in AMOUNTS {
+        for total_deposits in AMOUNTS {
+            for total_shares in AMOUNTS {
+                for rounding in [Rounding::Down, Rounding::Up] {
+                    let what = format!("assets_for_shares({shares}, {total_deposits}, {total_shares}, {rounding:?})");
+                    let result = no_panic(what, || assets_for_shares(shares, total_deposits, total_shares, rounding));
+
+                    if total_shares == 0 {
+                        assert!(result.is_err());
+                    }
+                }
+            }
+        }
+    }
+}
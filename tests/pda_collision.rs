@@ -0,0 +1,93 @@
+//! # PDA Seed Collision Harness
+//!
+//! Derives vault PDAs for two different authorities using the same vault name
+//! and shows that `vulnerable_pda` collides while `secure_pda` does not.
+//!
+//! ```bash
+//! cargo test --test pda_collision
+//! ```
+
+use anchor_lang::prelude::Pubkey;
+
+/// Seeds used by `vulnerable_pda::CreateVault`: ["vault", name]
+fn vulnerable_vault_seeds<'a>(_authority: &'a Pubkey, name: &'a str) -> Vec<&'a [u8]> {
+    // ❌ The authority is ignored - this is the bug
+    vec![b"vault".as_ref(), name.as_bytes()]
+}
+
+/// Seeds used by `secure_pda::CreateVault`: ["vault", authority, name]
+fn secure_vault_seeds<'a>(authority: &'a Pubkey, name: &'a str) -> Vec<&'a [u8]> {
+    vec![b"vault".as_ref(), authority.as_ref(), name.as_bytes()]
+}
+
+fn derive(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+#[test]
+fn vulnerable_vaults_collide_across_authorities() {
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+
+    let alice_vault = derive(&vulnerable_vault_seeds(&alice, "savings"), &vulnerable_pda::ID);
+    let bob_vault = derive(&vulnerable_vault_seeds(&bob, "savings"), &vulnerable_pda::ID);
+
+    // ❌ Same address: whoever creates "savings" first owns it for everyone
+    assert_eq!(alice_vault, bob_vault);
+}
+
+#[test]
+fn secure_vaults_do_not_collide_across_authorities() {
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+
+    let alice_vault = derive(&secure_vault_seeds(&alice, "savings"), &secure_pda::ID);
+    let bob_vault = derive(&secure_vault_seeds(&bob, "savings"), &secure_pda::ID);
+
+    // ✅ Different addresses: authority is part of the derivation
+    assert_ne!(alice_vault, bob_vault);
+}
+
+#[test]
+fn secure_seeds_include_authority() {
+    let authority = Pubkey::new_unique();
+    let seeds = secure_vault_seeds(&authority, "savings");
+
+    assert!(seeds.contains(&authority.as_ref()));
+    assert!(!vulnerable_vault_seeds(&authority, "savings").contains(&authority.as_ref()));
+}
+
+#[test]
+fn secure_vaults_differ_by_name_for_same_authority() {
+    let authority = Pubkey::new_unique();
+
+    let savings = derive(&secure_vault_seeds(&authority, "savings"), &secure_pda::ID);
+    let travel = derive(&secure_vault_seeds(&authority, "travel"), &secure_pda::ID);
+
+    assert_ne!(savings, travel);
+}
+
+#[test]
+fn concatenated_seeds_alias_within_one_authority() {
+    // Seeds are hashed back to back, so splitting a name across two seeds
+    // derives the same address. Authority in the seeds keeps this per-user,
+    // and sub-vaults use their own prefix so they never split a name.
+    let authority = Pubkey::new_unique();
+
+    let vault = derive(
+        &[b"vault".as_ref(), authority.as_ref(), b"ab".as_ref()],
+        &secure_pda::ID,
+    );
+    let split = derive(
+        &[b"vault".as_ref(), authority.as_ref(), b"a".as_ref(), b"b".as_ref()],
+        &secure_pda::ID,
+    );
+    assert_eq!(vault, split);
+
+    let other = Pubkey::new_unique();
+    let other_split = derive(
+        &[b"vault".as_ref(), other.as_ref(), b"a".as_ref(), b"b".as_ref()],
+        &secure_pda::ID,
+    );
+    assert_ne!(vault, other_split);
+}
@@ -0,0 +1,185 @@
+//! # PDA Seed Property Tests
+//!
+//! For every PDA the secure programs derive, random authorities, keys and
+//! names never map two distinct inputs to the same address. The same
+//! generator shows `vulnerable_pda`'s `["vault", name]` colliding on the
+//! name alone. Extends `pda_collision.rs` from hand-picked keys to the whole
+//! crate.
+//!
+//! Runs on `TestRunner::deterministic()`, so a failure reproduces on every
+//! run instead of depending on the RNG.
+//!
+//! ```bash
+//! cargo test --test pda_seed_proptest
+//! ```
+
+use anchor_lang::prelude::Pubkey;
+use proptest::prelude::*;
+use proptest::test_runner::TestRunner;
+
+// ============================================================================
+// SEED SCHEMES
+// ============================================================================
+
+/// `[prefix, key]`: one account per key
+const KEYED: &[(&str, Pubkey, &[u8])] = &[
+    ("secure_compound_interest::InterestAccount", secure_compound_interest::ID, b"interest"),
+    ("secure_cpi::Vault", secure_cpi::ID, b"vault"),
+    ("secure_cpi::ProgramWhitelist", secure_cpi::ID, b"whitelist"),
+    ("secure_cpi::AccessControl", secure_cpi::ID, b"access_control"),
+    ("secure_inflation::Vault", secure_inflation::ID, b"vault"),
+    ("secure_inflation vault_tokens", secure_inflation::ID, b"vault_tokens"),
+    ("secure_introspection::Vault", secure_introspection::ID, b"vault"),
+    ("secure_matching::Pool", secure_matching::ID, b"pool"),
+    ("secure_mint_rewards::RewardPool", secure_mint_rewards::ID, b"reward_pool"),
+    ("secure_overflow::AccessControl", secure_overflow::ID, b"access_control"),
+    ("secure_pda::VaultRegistry", secure_pda::ID, b"registry"),
+    ("secure_program_owner::Vault", secure_program_owner::ID, b"vault"),
+    ("secure_realloc::VaultRegistry", secure_realloc::ID, b"registry"),
+    ("secure_reload reserve_vault", secure_reload::ID, b"reserve_vault"),
+    ("secure_writable::Record", secure_writable::ID, b"record"),
+];
+
+/// `[prefix, first, second]`: one account per ordered pair of keys
+const PAIRED: &[(&str, Pubkey, &[u8])] = &[
+    ("secure_cpi::Pool", secure_cpi::ID, b"pool"),
+    ("secure_cpi::LpPosition", secure_cpi::ID, b"lp"),
+    ("secure_cpi::TwapSnapshot", secure_cpi::ID, b"twap"),
+    ("secure_inflation::Position", secure_inflation::ID, b"position"),
+    ("secure_matching::StakingAccount", secure_matching::ID, b"staking"),
+    ("secure_mint_rewards::UserRewards", secure_mint_rewards::ID, b"rewards"),
+    ("secure_signer::SessionKey", secure_signer::ID, b"session"),
+    ("secure_zero_copy::BigPool", secure_zero_copy::ID, b"big_pool"),
+];
+
+fn derive(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// `secure_pda::CreateVault`: ["vault", authority, name]
+fn secure_vault(authority: &Pubkey, name: &str) -> Pubkey {
+    derive(&[b"vault", authority.as_ref(), name.as_bytes()], &secure_pda::ID)
+}
+
+/// `vulnerable_pda::CreateVault`: ["vault", name]
+fn vulnerable_vault(_authority: &Pubkey, name: &str) -> Pubkey {
+    // ❌ The authority is ignored - this is the bug
+    derive(&[b"vault", name.as_bytes()], &vulnerable_pda::ID)
+}
+
+// ============================================================================
+// STRATEGIES
+// ============================================================================
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+/// Vault names as `secure_pda` accepts them: 1 to 32 bytes
+fn name() -> impl Strategy<Value = String> {
+    "[a-z0-9_-]{1,32}"
+}
+
+/// Two inputs that often share one half, so a scheme that ignores a seed
+/// is caught, not only one that ignores both
+fn two_pairs<A, B>(
+    a: impl Strategy<Value = A> + Clone,
+    b: impl Strategy<Value = B> + Clone,
+) -> impl Strategy<Value = ((A, B), (A, B))>
+where
+    A: Clone + std::fmt::Debug,
+    B: Clone + std::fmt::Debug,
+{
+    (a.clone(), b.clone(), a, b, 0..3u8).prop_map(|(a1, b1, a2, b2, share)| match share {
+        0 => ((a1.clone(), b1), (a1, b2)),
+        1 => ((a1, b1.clone()), (a2, b1)),
+        _ => ((a1, b1), (a2, b2)),
+    })
+}
+
+fn runner() -> TestRunner {
+    TestRunner::deterministic()
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[test]
+fn keyed_schemes_never_collide_across_keys() {
+    runner()
+        .run(&(pubkey(), pubkey()), |(first, second)| {
+            prop_assume!(first != second);
+            for (account, program_id, prefix) in KEYED {
+                prop_assert_ne!(
+                    derive(&[*prefix, first.as_ref()], program_id),
+                    derive(&[*prefix, second.as_ref()], program_id),
+                    "{} collided",
+                    account
+                );
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn paired_schemes_never_collide_across_pairs() {
+    runner()
+        .run(&two_pairs(pubkey(), pubkey()), |((a1, b1), (a2, b2))| {
+            prop_assume!((a1, b1) != (a2, b2));
+            for (account, program_id, prefix) in PAIRED {
+                prop_assert_ne!(
+                    derive(&[*prefix, a1.as_ref(), b1.as_ref()], program_id),
+                    derive(&[*prefix, a2.as_ref(), b2.as_ref()], program_id),
+                    "{} collided",
+                    account
+                );
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn paired_schemes_are_order_sensitive() {
+    // [pool, user] and [user, pool] are different accounts
+    runner()
+        .run(&(pubkey(), pubkey()), |(first, second)| {
+            prop_assume!(first != second);
+            for (account, program_id, prefix) in PAIRED {
+                prop_assert_ne!(
+                    derive(&[*prefix, first.as_ref(), second.as_ref()], program_id),
+                    derive(&[*prefix, second.as_ref(), first.as_ref()], program_id),
+                    "{} collided with its arguments swapped",
+                    account
+                );
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn secure_vaults_never_collide_across_authority_and_name() {
+    runner()
+        .run(&two_pairs(pubkey(), name()), |((authority1, name1), (authority2, name2))| {
+            prop_assume!((authority1, &name1) != (authority2, &name2));
+            // ✅ The authority is a fixed 32 bytes, so no name can shift into it
+            prop_assert_ne!(secure_vault(&authority1, &name1), secure_vault(&authority2, &name2));
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn vulnerable_vaults_collide_on_name_alone() {
+    runner()
+        .run(&(pubkey(), pubkey(), name()), |(alice, bob, name)| {
+            prop_assume!(alice != bob);
+            // ❌ Whoever creates `name` first owns it for every authority
+            prop_assert_eq!(vulnerable_vault(&alice, &name), vulnerable_vault(&bob, &name));
+            Ok(())
+        })
+        .unwrap();
+}
@@ -0,0 +1,256 @@
+//! # Deposit Permit Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::deposit_with_permit`.
+//! The vault authority has approved the vault PDA as delegate on its token
+//! account and signs `(vault, user_tokens, amount, deadline, nonce)`
+//! off-chain. A relayer submits an ed25519 program instruction carrying that
+//! signature followed by the deposit, in the same transaction; the
+//! authority never signs a transaction.
+//!
+//! ```bash
+//! cargo test --test permit
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, custom_at, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    ed25519_program,
+    instruction::Instruction,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    sysvar,
+    transaction::TransactionError,
+};
+use spl_token::state::Account as TokenAccount;
+
+const BALANCE: u64 = 10_000;
+const APPROVED: u64 = 5_000;
+const AMOUNT: u64 = 1_000;
+const NO_DEADLINE: i64 = i64::MAX;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Token account with `delegated_amount` approved to `delegate`
+fn add_approved_token_account(
+    program_test: &mut ProgramTest,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    delegate: Pubkey,
+    delegated_amount: u64,
+) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let state = TokenAccount {
+        delegate: COption::Some(delegate),
+        delegated_amount,
+        ..common::token_account(mint, owner, amount)
+    };
+    add_packed(program_test, address, state);
+    address
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    user_tokens: Pubkey,
+}
+
+/// Empty vault for `authority`, whose token account holds `BALANCE` with
+/// `APPROVED` delegated to the vault PDA. The test payer is the relayer.
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+
+    let mint = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref()], &secure_cpi::ID);
+    add_anchor_account(&mut program_test, vault, secure_cpi::ID, &common::cpi_vault(authority.pubkey(), 0, bump));
+
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, 0);
+    let user_tokens =
+        add_approved_token_account(&mut program_test, mint, authority.pubkey(), BALANCE, vault, APPROVED);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, vault, vault_tokens, user_tokens }
+}
+
+/// Ed25519 program instruction with one signature and all data inline:
+/// header, offsets, then public key, signature and message
+fn ed25519_ix(signer: &Keypair, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    const HEADER: u16 = 2 + 14;
+    let public_key_offset = HEADER;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let this_instruction = u16::MAX;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        this_instruction,
+        public_key_offset,
+        this_instruction,
+        message_offset,
+        message.len() as u16,
+        this_instruction,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+}
+
+fn deposit_ix(setup: &Setup, amount: u64, deadline: i64, nonce: u64, signature: [u8; 64]) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::DepositWithPermit {
+            submitter: setup.env.payer(),
+            vault: setup.vault,
+            user_tokens: setup.user_tokens,
+            vault_tokens: setup.vault_tokens,
+            instructions: sysvar::instructions::ID,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::DepositWithPermit { amount, deadline, nonce, signature }.data(),
+    }
+}
+
+/// `signer` permits `(amount, deadline, nonce)`; the deposit asks for the same
+fn permitted_deposit(setup: &Setup, signer: &Keypair, amount: u64, deadline: i64, nonce: u64) -> [Instruction; 2] {
+    let message = secure_cpi::permit_message(&setup.vault, &setup.user_tokens, amount, deadline, nonce);
+    let signature: [u8; 64] = signer.sign_message(&message).as_ref().try_into().unwrap();
+    [ed25519_ix(signer, &signature, &message), deposit_ix(setup, amount, deadline, nonce, signature)]
+}
+
+async fn token_account(env: &mut TestEnv, address: Pubkey) -> TokenAccount {
+    let account = env.account(address).await.unwrap();
+    TokenAccount::unpack(&account.data).unwrap()
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_cpi::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+/// The deposit is the second instruction, after the ed25519 check
+fn deposit_error(error: secure_cpi::ErrorCode) -> TransactionError {
+    custom_at(1, error)
+}
+
+/// Nothing moved and no nonce was consumed
+async fn assert_no_deposit(setup: &mut Setup) {
+    assert_eq!(token_account(&mut setup.env, setup.user_tokens).await.amount, BALANCE);
+    assert_eq!(token_account(&mut setup.env, setup.vault_tokens).await.amount, 0);
+    let vault = vault_state(setup).await;
+    assert_eq!((vault.balance, vault.permit_nonce), (0, 0));
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn permit_deposits_without_the_owner_signing() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let ixs = permitted_deposit(&setup, &authority, AMOUNT, NO_DEADLINE, 1);
+    setup.env.send(&ixs, &[]).await.unwrap();
+
+    let user_tokens = token_account(&mut setup.env, setup.user_tokens).await;
+    assert_eq!(user_tokens.amount, BALANCE - AMOUNT);
+    assert_eq!(user_tokens.delegated_amount, APPROVED - AMOUNT);
+    assert_eq!(token_account(&mut setup.env, setup.vault_tokens).await.amount, AMOUNT);
+    let vault = vault_state(&mut setup).await;
+    assert_eq!((vault.balance, vault.total_deposited, vault.deposit_count), (AMOUNT, AMOUNT, 1));
+    assert_eq!(vault.permit_nonce, 1);
+    assert!(!vault.locked);
+}
+
+#[tokio::test]
+async fn replayed_permit_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let ixs = permitted_deposit(&setup, &authority, AMOUNT, NO_DEADLINE, 1);
+    setup.env.send(&ixs, &[]).await.unwrap();
+
+    let err = setup.env.send(&ixs, &[]).await.unwrap_err();
+    assert_eq!(err, deposit_error(secure_cpi::ErrorCode::InvalidPermit));
+    assert_eq!(vault_state(&mut setup).await.balance, AMOUNT);
+}
+
+#[tokio::test]
+async fn expired_permit_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let ixs = permitted_deposit(&setup, &authority, AMOUNT, 0, 1);
+    let err = setup.env.send(&ixs, &[]).await.unwrap_err();
+
+    assert_eq!(err, deposit_error(secure_cpi::ErrorCode::PermitExpired));
+    assert_no_deposit(&mut setup).await;
+}
+
+#[tokio::test]
+async fn amount_must_match_signed_message() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    // Relayer keeps the valid signature but asks for more
+    let [ed25519, _] = permitted_deposit(&setup, &authority, AMOUNT, NO_DEADLINE, 1);
+    let message = secure_cpi::permit_message(&setup.vault, &setup.user_tokens, AMOUNT, NO_DEADLINE, 1);
+    let signature: [u8; 64] = authority.sign_message(&message).as_ref().try_into().unwrap();
+    let inflated = deposit_ix(&setup, AMOUNT * 2, NO_DEADLINE, 1, signature);
+
+    let err = setup.env.send(&[ed25519, inflated], &[]).await.unwrap_err();
+    assert_eq!(err, deposit_error(secure_cpi::ErrorCode::InvalidPermit));
+    assert_no_deposit(&mut setup).await;
+}
+
+#[tokio::test]
+async fn permit_from_another_key_is_rejected() {
+    let mut setup = setup().await;
+
+    let ixs = permitted_deposit(&setup, &Keypair::new(), AMOUNT, NO_DEADLINE, 1);
+    let err = setup.env.send(&ixs, &[]).await.unwrap_err();
+
+    assert_eq!(err, deposit_error(secure_cpi::ErrorCode::InvalidPermit));
+    assert_no_deposit(&mut setup).await;
+}
+
+#[tokio::test]
+async fn permit_beyond_the_approval_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    // Validly signed, but the SPL approval only covers APPROVED
+    let ixs = permitted_deposit(&setup, &authority, APPROVED + 1, NO_DEADLINE, 1);
+    let err = setup.env.send(&ixs, &[]).await.unwrap_err();
+
+    assert_eq!(err, deposit_error(secure_cpi::ErrorCode::InvalidPermit));
+    assert_no_deposit(&mut setup).await;
+}
+
+#[tokio::test]
+async fn missing_ed25519_instruction_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let [_, deposit] = permitted_deposit(&setup, &authority, AMOUNT, NO_DEADLINE, 1);
+    let err = setup.env.send(&[deposit], &[]).await.unwrap_err();
+
+    // Only instruction in the transaction
+    assert_eq!(err, custom(secure_cpi::ErrorCode::InvalidPermit));
+    assert_no_deposit(&mut setup).await;
+}
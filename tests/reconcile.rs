@@ -0,0 +1,97 @@
+//! # Balance Reconciliation Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::reconcile`: the vault's
+//! token account holds more (a direct donation) or fewer tokens than the
+//! program recorded.
+//!
+//! ```bash
+//! cargo test --test reconcile
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_funded_keypair, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const RECORDED: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Vault PDA recording `RECORDED` tokens, backed by a token account holding `actual`
+async fn setup(actual: u64) -> (TestEnv, Keypair, Pubkey, Pubkey) {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    // Authority pays its own fees
+    let authority = add_funded_keypair(&mut program_test, 1_000_000_000);
+
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref()], &secure_cpi::ID);
+    let state = secure_cpi::Vault { deposit_count: 1, ..common::cpi_vault(authority.pubkey(), RECORDED, bump) };
+    add_anchor_account(&mut program_test, vault, secure_cpi::ID, &state);
+    let vault_tokens = add_token_account(&mut program_test, Pubkey::new_unique(), vault, actual);
+
+    (TestEnv::start(program_test).await, authority, vault, vault_tokens)
+}
+
+async fn reconcile(
+    env: &mut TestEnv,
+    authority: &Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    credit_surplus: bool,
+) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Reconcile {
+            authority: authority.pubkey(),
+            vault,
+            vault_tokens,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Reconcile { credit_surplus }.data(),
+    };
+    env.send_paid_by(&[ix], authority, &[]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn donation_is_credited_to_surplus_once() {
+    let (mut env, authority, vault, vault_tokens) = setup(RECORDED + 250).await;
+
+    reconcile(&mut env, &authority, vault, vault_tokens, true).await.unwrap();
+
+    let state: secure_cpi::Vault = env.fetch(vault).await;
+    assert_eq!(state.balance, RECORDED);
+    assert_eq!(state.surplus, 250);
+
+    // Already accounted for: a second pass credits nothing
+    reconcile(&mut env, &authority, vault, vault_tokens, true).await.unwrap();
+    assert_eq!(env.fetch::<secure_cpi::Vault>(vault).await.surplus, 250);
+}
+
+#[tokio::test]
+async fn donation_is_left_alone_without_credit_flag() {
+    let (mut env, authority, vault, vault_tokens) = setup(RECORDED + 250).await;
+
+    reconcile(&mut env, &authority, vault, vault_tokens, false).await.unwrap();
+
+    assert_eq!(env.fetch::<secure_cpi::Vault>(vault).await.surplus, 0);
+}
+
+#[tokio::test]
+async fn shortfall_violates_invariant() {
+    let (mut env, authority, vault, vault_tokens) = setup(RECORDED - 1).await;
+
+    let err = reconcile(&mut env, &authority, vault, vault_tokens, true).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::BalanceInvariantViolated));
+}
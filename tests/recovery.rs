@@ -0,0 +1,230 @@
+//! # Guardian Recovery Tests
+//!
+//! `solana-program-test` scenarios for `secure_signer`'s guardian recovery.
+//! The authority appoints a guardian with a `MIN_RECOVERY_DELAY`; the
+//! guardian proposes a new authority, and the proposal either gets
+//! cancelled by the authority or finalized once the delay has passed.
+//! Handing the vault to a new owner drops it as well. The `Clock` sysvar is
+//! moved forward directly instead of waiting.
+//!
+//! ```bash
+//! cargo test --test recovery
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use secure_signer::{ErrorCode, MIN_RECOVERY_DELAY};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    guardian: Keypair,
+    vault: Pubkey,
+}
+
+/// A vault owned by `authority` with `guardian` appointed at `MIN_RECOVERY_DELAY`
+async fn setup() -> Setup {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let guardian = Keypair::new();
+    let vault = Keypair::new();
+
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    env.send(&[init], &[&vault, &authority]).await.unwrap();
+
+    let set_guardian = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::SetGuardian {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::SetGuardian {
+            guardian: guardian.pubkey(),
+            recovery_delay: MIN_RECOVERY_DELAY,
+        }
+        .data(),
+    };
+    env.send(&[set_guardian], &[&authority]).await.unwrap();
+
+    Setup { env, authority, guardian, vault: vault.pubkey() }
+}
+
+async fn initiate_recovery(setup: &mut Setup, signer: &Keypair, new_authority: Pubkey) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::InitiateRecovery {
+            vault: setup.vault,
+            guardian: signer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::InitiateRecovery { new_authority }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn cancel_recovery(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::CancelRecovery {
+            vault: setup.vault,
+            authority: setup.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::CancelRecovery {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+async fn finalize_recovery(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::FinalizeRecovery { vault: setup.vault }.to_account_metas(None),
+        data: secure_signer::instruction::FinalizeRecovery {}.data(),
+    };
+    setup.env.send(&[ix], &[]).await
+}
+
+// ============================================================================
+// FINALIZE
+// ============================================================================
+
+#[tokio::test]
+async fn recovery_finalizes_after_the_delay() {
+    let mut setup = setup().await;
+    let guardian = setup.guardian.insecure_clone();
+    let new_authority = Pubkey::new_unique();
+
+    initiate_recovery(&mut setup, &guardian, new_authority).await.unwrap();
+    setup.env.advance_clock(MIN_RECOVERY_DELAY).await;
+    finalize_recovery(&mut setup).await.unwrap();
+
+    let vault: secure_signer::Vault = setup.env.fetch(setup.vault).await;
+    assert_eq!(vault.authority, new_authority);
+    assert_eq!((vault.pending_authority, vault.recovery_unlock), (None, 0));
+}
+
+#[tokio::test]
+async fn recovery_cannot_finalize_before_the_delay() {
+    let mut setup = setup().await;
+    let guardian = setup.guardian.insecure_clone();
+
+    initiate_recovery(&mut setup, &guardian, Pubkey::new_unique()).await.unwrap();
+    setup.env.advance_clock(MIN_RECOVERY_DELAY - 60).await;
+    let err = finalize_recovery(&mut setup).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::RecoveryNotReady));
+    assert_eq!(setup.env.fetch::<secure_signer::Vault>(setup.vault).await.authority, setup.authority.pubkey());
+}
+
+#[tokio::test]
+async fn finalize_without_a_recovery_is_rejected() {
+    let mut setup = setup().await;
+
+    let err = finalize_recovery(&mut setup).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::NoRecoveryInProgress));
+}
+
+// ============================================================================
+// CANCEL
+// ============================================================================
+
+#[tokio::test]
+async fn authority_cancels_a_pending_recovery() {
+    let mut setup = setup().await;
+    let guardian = setup.guardian.insecure_clone();
+
+    initiate_recovery(&mut setup, &guardian, Pubkey::new_unique()).await.unwrap();
+    cancel_recovery(&mut setup).await.unwrap();
+
+    // Still nothing to finalize after the delay
+    setup.env.advance_clock(MIN_RECOVERY_DELAY).await;
+    let err = finalize_recovery(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::NoRecoveryInProgress));
+    assert_eq!(setup.env.fetch::<secure_signer::Vault>(setup.vault).await.authority, setup.authority.pubkey());
+}
+
+#[tokio::test]
+async fn cancel_without_a_recovery_is_rejected() {
+    let mut setup = setup().await;
+
+    let err = cancel_recovery(&mut setup).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::NoRecoveryInProgress));
+}
+
+// ============================================================================
+// TRANSFER
+// ============================================================================
+
+#[tokio::test]
+async fn transfer_authority_drops_a_pending_recovery() {
+    let mut setup = setup().await;
+    let guardian = setup.guardian.insecure_clone();
+    let new_owner = Keypair::new();
+
+    initiate_recovery(&mut setup, &guardian, Pubkey::new_unique()).await.unwrap();
+
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::TransferAuthority {
+            vault: setup.vault,
+            authority: setup.authority.pubkey(),
+            new_authority: new_owner.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::TransferAuthority {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority, &new_owner]).await.unwrap();
+
+    let vault: secure_signer::Vault = setup.env.fetch(setup.vault).await;
+    assert_eq!((vault.pending_authority, vault.recovery_unlock), (None, 0));
+
+    // ✅ The old proposal can't take the vault from its new owner
+    setup.env.advance_clock(MIN_RECOVERY_DELAY).await;
+    let err = finalize_recovery(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::NoRecoveryInProgress));
+    assert_eq!(setup.env.fetch::<secure_signer::Vault>(setup.vault).await.authority, new_owner.pubkey());
+}
+
+// ============================================================================
+// ACCESS CONTROL
+// ============================================================================
+
+#[tokio::test]
+async fn only_the_guardian_can_initiate_recovery() {
+    let mut setup = setup().await;
+    let attacker = Keypair::new();
+
+    let err = initiate_recovery(&mut setup, &attacker, attacker.pubkey()).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::NotGuardian));
+    assert_eq!(setup.env.fetch::<secure_signer::Vault>(setup.vault).await.pending_authority, None);
+}
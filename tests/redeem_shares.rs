@@ -0,0 +1,209 @@
+//! # Share Redemption Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::redeem_shares`:
+//! rounding dust stays in the pool for the remaining holders, and the final
+//! redeemer empties `total_shares` and `total_deposits` together. Partial
+//! redemptions never lower the share price for whoever stays in.
+//!
+//! ```bash
+//! cargo test --test redeem_shares
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use secure_matching::logic::share_price;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Holder {
+    keypair: Keypair,
+    position: Pubkey,
+    tokens: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    mint: Pubkey,
+    pool: Pubkey,
+    pool_tokens: Pubkey,
+    holders: Vec<Holder>,
+}
+
+/// Pool with `total_deposits` against one position per entry of `shares`.
+/// `pool_tokens` holds `pool_balance`.
+async fn setup(total_deposits: u64, shares: &[u64], pool_balance: u64) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    add_packed(&mut program_test, mint, Mint { supply: u64::MAX, ..common::mint(None) });
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    add_anchor_account(
+        &mut program_test,
+        pool,
+        secure_matching::ID,
+        &secure_matching::Pool {
+            total_deposits,
+            total_shares: shares.iter().sum(),
+            ..common::matching_pool(Pubkey::new_unique(), mint, mint, Pubkey::new_unique(), bump)
+        },
+    );
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, pool_balance);
+
+    let holders = shares
+        .iter()
+        .map(|&shares| {
+            let keypair = Keypair::new();
+            let (position, bump) = Pubkey::find_program_address(
+                &[b"staking", keypair.pubkey().as_ref(), pool.as_ref()],
+                &secure_matching::ID,
+            );
+            add_anchor_account(
+                &mut program_test,
+                position,
+                secure_matching::ID,
+                &secure_matching::StakingAccount {
+                    shares,
+                    ..common::matching_stake(keypair.pubkey(), pool, 0, bump)
+                },
+            );
+            let tokens = add_token_account(&mut program_test, mint, keypair.pubkey(), 0);
+            Holder { keypair, position, tokens }
+        })
+        .collect();
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, mint, pool, pool_tokens, holders }
+}
+
+async fn redeem(setup: &mut Setup, holder: usize, shares: u64) -> Result<(), TransactionError> {
+    let Holder { keypair, position, tokens } = &setup.holders[holder];
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::RedeemShares {
+            user: keypair.pubkey(),
+            staking_account: *position,
+            user_tokens: *tokens,
+            pool_tokens: setup.pool_tokens,
+            token_mint: setup.mint,
+            pool: setup.pool,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::RedeemShares { shares }.data(),
+    };
+    setup.env.send(&[ix], &[keypair]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_matching::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+async fn position_shares(setup: &mut Setup, holder: usize) -> u64 {
+    let position: secure_matching::StakingAccount = setup.env.fetch(setup.holders[holder].position).await;
+    position.shares
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn dust_stays_with_remaining_holders_and_final_redeemer_sweeps() {
+    // 10 tokens, 3 shares: alice 2, bob 1
+    let mut setup = setup(10, &[2, 1], 10).await;
+
+    // 2 * 10 / 3 = 6 remainder 2: alice is paid 6, not 6.67
+    redeem(&mut setup, 0, 2).await.unwrap();
+    let alice_tokens = setup.holders[0].tokens;
+    assert_eq!(setup.env.token_balance(alice_tokens).await, 6);
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.total_deposits, pool.total_shares), (4, 1));
+
+    // Bob holds the last share and takes everything left, dust included
+    redeem(&mut setup, 1, 1).await.unwrap();
+    let bob_tokens = setup.holders[1].tokens;
+    assert_eq!(setup.env.token_balance(bob_tokens).await, 4);
+
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.total_deposits, pool.total_shares), (0, 0));
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, 0);
+    assert_eq!(position_shares(&mut setup, 0).await, 0);
+    assert_eq!(position_shares(&mut setup, 1).await, 0);
+}
+
+#[tokio::test]
+async fn single_holder_redeeming_everything_empties_the_pool() {
+    let mut setup = setup(1_000, &[700], 1_000).await;
+
+    redeem(&mut setup, 0, 700).await.unwrap();
+
+    let holder_tokens = setup.holders[0].tokens;
+    assert_eq!(setup.env.token_balance(holder_tokens).await, 1_000);
+    let pool = pool_state(&mut setup).await;
+    assert_eq!((pool.total_deposits, pool.total_shares), (0, 0));
+}
+
+#[tokio::test]
+async fn redemption_rounding_to_zero_is_rejected() {
+    // 1 * 1 / 3 = 0
+    let mut setup = setup(1, &[1, 2], 1).await;
+
+    let err = redeem(&mut setup, 0, 1).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::RedeemRoundsToZero));
+    assert_eq!(position_shares(&mut setup, 0).await, 1);
+}
+
+#[tokio::test]
+async fn final_redeemer_cannot_take_more_than_the_pool_holds() {
+    // Books say 10, the token account only has 9
+    let mut setup = setup(10, &[5], 9).await;
+
+    let err = redeem(&mut setup, 0, 5).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::common_errors::CommonError::InsufficientFunds));
+    assert_eq!(pool_state(&mut setup).await.total_shares, 5);
+}
+
+#[tokio::test]
+async fn cannot_redeem_more_shares_than_held() {
+    let mut setup = setup(10, &[2, 1], 10).await;
+
+    let err = redeem(&mut setup, 1, 2).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::InsufficientShares));
+    assert_eq!(position_shares(&mut setup, 1).await, 1);
+}
+
+#[tokio::test]
+async fn partial_redemption_never_lowers_the_share_price() {
+    // Price is slightly above 1 so every payout rounds
+    let mut setup = setup(1_000_003, &[400_000, 600_000], 1_000_003).await;
+    let price = |pool: &secure_matching::Pool| share_price(pool.total_deposits, pool.total_shares).unwrap();
+    let before = price(&pool_state(&mut setup).await);
+
+    for shares in [123_457, 1, 276_542] {
+        redeem(&mut setup, 0, shares).await.unwrap();
+        let pool = pool_state(&mut setup).await;
+        assert!(price(&pool) >= before);
+        // Books still match the tokens actually held
+        assert_eq!(setup.env.token_balance(setup.pool_tokens).await, pool.total_deposits);
+    }
+
+    // Alice is fully out; Bob's 600_000 shares back everything left
+    let pool = pool_state(&mut setup).await;
+    assert_eq!(position_shares(&mut setup, 0).await, 0);
+    assert_eq!(pool.total_shares, 600_000);
+    let alice_tokens = setup.holders[0].tokens;
+    assert_eq!(setup.env.token_balance(alice_tokens).await + pool.total_deposits, 1_000_003);
+}
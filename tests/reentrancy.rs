@@ -0,0 +1,284 @@
+//! # Reentrancy / CEI Tests
+//!
+//! `solana-program-test` scenarios comparing
+//! `vulnerable_cpi::deposit_with_callback`, which calls out BEFORE updating
+//! state, with `secure_cpi::deposit`, which locks and updates state first.
+//!
+//! The malicious callback lives in `tests/programs/reentrant_callback` and
+//! CPIs straight back into `deposit_with_callback`. The Solana runtime
+//! rejects that A → B → A call with `ReentrancyNotAllowed`. The vulnerable
+//! balance is never inflated, but only because of the runtime, not the
+//! program. The secure program never reaches the callback at all.
+//!
+//! `secure_cpi::withdraw`'s lock is checked end to end with
+//! `tests/programs/reentrant_token`, loaded in place of SPL Token. Its
+//! `Transfer` returns the vault bytes a re-entrant `withdraw` would read
+//! mid-CPI; the test asserts the lock is on in them, replays a `withdraw`
+//! against them (`ReentrancyDetected`), and checks the outer call released
+//! the lock.
+//!
+//! All four programs run as SBF, so build them first:
+//!
+//! ```bash
+//! anchor build
+//! (cd tests/programs/reentrant_callback && cargo build-sbf --sbf-out-dir ../../../target/deploy)
+//! (cd tests/programs/reentrant_token && cargo build-sbf --sbf-out-dir ../../../target/deploy)
+//! BPF_OUT_DIR=target/deploy cargo test --test reentrancy
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+const CALLBACK_ID: Pubkey = solana_sdk::pubkey!("Ca11back11111111111111111111111111111111111");
+const AMOUNT: u64 = 100;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("vulnerable_cpi", vulnerable_cpi::ID, None);
+    program_test.add_program("secure_cpi", secure_cpi::ID, None);
+    program_test.add_program("reentrant_callback", CALLBACK_ID, None);
+    program_test
+}
+
+/// Accounts for `secure_cpi::deposit` and `withdraw`, with the vault's
+/// reentrancy lock and balance preset
+struct SecureVault {
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    user_tokens: Pubkey,
+}
+
+fn add_secure_vault(program_test: &mut ProgramTest, user: Pubkey, locked: bool, balance: u64) -> SecureVault {
+    let mint = Pubkey::new_unique();
+    add_packed(program_test, mint, Mint { supply: u64::MAX, ..common::mint(None) });
+
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", user.as_ref()], &secure_cpi::ID);
+    add_anchor_account(
+        program_test,
+        vault,
+        secure_cpi::ID,
+        &secure_cpi::Vault { total_deposited: 0, locked, ..common::cpi_vault(user, balance, bump) },
+    );
+
+    SecureVault {
+        vault,
+        vault_tokens: add_token_account(program_test, mint, vault, balance),
+        user_tokens: add_token_account(program_test, mint, user, AMOUNT),
+    }
+}
+
+fn secure_deposit_ix(user: Pubkey, accounts: &SecureVault, token_program: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Deposit {
+            user,
+            user_tokens: accounts.user_tokens,
+            vault: accounts.vault,
+            vault_tokens: accounts.vault_tokens,
+            token_program,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Deposit { amount: AMOUNT, idempotency_key: None }.data(),
+    }
+}
+
+/// `secure_cpi` with `reentrant_token` overriding the bundled SPL Token,
+/// so `Program<'info, Token>` accepts it
+fn reentrant_token_program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("secure_cpi", secure_cpi::ID, None);
+    program_test.add_program("reentrant_token", spl_token::ID, None);
+    program_test
+}
+
+fn secure_withdraw_ix(user: Pubkey, accounts: &SecureVault) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Withdraw {
+            authority: user,
+            user_tokens: accounts.user_tokens,
+            vault: accounts.vault,
+            vault_tokens: accounts.vault_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Withdraw { amount: AMOUNT }.data(),
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_callback_reenters_and_is_stopped_only_by_runtime() {
+    let mut program_test = program_test();
+    let vault = Pubkey::new_unique();
+    // deposit_with_callback never checks the authority
+    add_anchor_account(
+        &mut program_test,
+        vault,
+        vulnerable_cpi::ID,
+        &vulnerable_cpi::Vault { authority: Pubkey::new_unique(), balance: 0 },
+    );
+    let mut env = TestEnv::start(program_test).await;
+    let user = env.payer();
+
+    let mut accounts = vulnerable_cpi::accounts::DepositWithCallback {
+        user,
+        vault,
+        callback_program: CALLBACK_ID,
+    }
+    .to_account_metas(None);
+    // Forwarded to the callback, which uses them to call back in
+    accounts.extend([
+        AccountMeta::new(user, true),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(vulnerable_cpi::ID, false),
+        AccountMeta::new_readonly(CALLBACK_ID, false),
+    ]);
+    let ix = Instruction {
+        program_id: vulnerable_cpi::ID,
+        accounts,
+        data: vulnerable_cpi::instruction::DepositWithCallback { amount: AMOUNT }.data(),
+    };
+
+    // ❌ The program happily hands control to the callback with stale state.
+    // The runtime, not the program, refuses the A → B → A call.
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::ReentrancyNotAllowed));
+
+    let state: vulnerable_cpi::Vault = env.fetch(vault).await;
+    assert_eq!(state.balance, 0);
+}
+
+#[tokio::test]
+async fn secure_deposit_rejects_callback_as_token_program() {
+    let mut program_test = program_test();
+    let user = Keypair::new();
+    let accounts = add_secure_vault(&mut program_test, user.pubkey(), false, 0);
+    let mut env = TestEnv::start(program_test).await;
+
+    // ✅ Program<'info, Token> refuses the malicious program before any CPI
+    let ix = secure_deposit_ix(user.pubkey(), &accounts, CALLBACK_ID);
+    let err = env.send(&[ix], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::InvalidProgramId));
+}
+
+#[tokio::test]
+async fn secure_deposit_reverts_while_locked() {
+    let mut program_test = program_test();
+    let user = Keypair::new();
+    // Vault as a re-entrant call would see it: locked by the outer deposit
+    let accounts = add_secure_vault(&mut program_test, user.pubkey(), true, 0);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ix = secure_deposit_ix(user.pubkey(), &accounts, spl_token::ID);
+    let err = env.send(&[ix], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ReentrancyDetected));
+
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert_eq!(state.balance, 0);
+}
+
+#[tokio::test]
+async fn secure_deposit_updates_state_and_releases_lock() {
+    let mut program_test = program_test();
+    let user = Keypair::new();
+    let accounts = add_secure_vault(&mut program_test, user.pubkey(), false, 0);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ix = secure_deposit_ix(user.pubkey(), &accounts, spl_token::ID);
+    env.send(&[ix], &[&user]).await.unwrap();
+
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert_eq!(state.balance, AMOUNT);
+    assert_eq!(state.deposit_count, 1);
+    assert!(!state.locked);
+
+    assert_eq!(env.token_balance(accounts.vault_tokens).await, AMOUNT);
+}
+
+#[tokio::test]
+async fn secure_withdraw_holds_lock_through_cpi_and_releases_it() {
+    let mut program_test = reentrant_token_program_test();
+    let user = Keypair::new();
+    // Enough for two withdrawals, so the replay below stops at the lock
+    // rather than the balance check
+    let accounts = add_secure_vault(&mut program_test, user.pubkey(), false, 2 * AMOUNT);
+    let mut env = TestEnv::start(program_test).await;
+
+    // reentrant_token fails the transfer unless the vault arrives locked
+    let (result, metadata) = env.send_with_metadata(&[secure_withdraw_ix(user.pubkey(), &accounts)], &[&user]).await;
+    result.unwrap();
+    let return_data = metadata.return_data.unwrap();
+    assert_eq!(return_data.program_id, spl_token::ID);
+
+    // The vault as a re-entrant withdraw would have read it. Return data
+    // has its trailing zeros trimmed, so pad back to the account's length.
+    let mut vault_account = env.account(accounts.vault).await.unwrap();
+    let mut mid_cpi = return_data.data;
+    mid_cpi.resize(vault_account.data.len(), 0);
+    let seen = secure_cpi::Vault::try_deserialize(&mut mid_cpi.as_slice()).unwrap();
+    assert!(seen.locked);
+    assert_eq!(seen.balance, AMOUNT);
+
+    // ✅ The outer call completed and released the lock
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert!(!state.locked);
+    assert_eq!(state.balance, AMOUNT);
+    assert_eq!(state.total_withdrawn, AMOUNT);
+
+    // ✅ The inner call, replayed against what it would have seen, is refused
+    vault_account.data = mid_cpi;
+    env.context.set_account(&accounts.vault, &vault_account.into());
+    let ix = secure_withdraw_ix(user.pubkey(), &accounts);
+    let err = env.send(&[ix], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ReentrancyDetected));
+
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert_eq!(state.balance, AMOUNT);
+}
+
+#[tokio::test]
+async fn secure_deposit_failing_after_lock_leaves_vault_usable() {
+    let mut program_test = program_test();
+    let user = Keypair::new();
+    let accounts = add_secure_vault(&mut program_test, user.pubkey(), false, 0);
+    let mut env = TestEnv::start(program_test).await;
+
+    // More than user_tokens holds: the lock is set and the balance credited
+    // before the token CPI fails
+    let mut ix = secure_deposit_ix(user.pubkey(), &accounts, spl_token::ID);
+    ix.data = secure_cpi::instruction::Deposit { amount: AMOUNT + 1, idempotency_key: None }.data();
+    let err = env.send(&[ix], &[&user]).await.unwrap_err();
+    assert_eq!(err, custom(spl_token::error::TokenError::InsufficientFunds as u32));
+
+    // ✅ The failed transaction's writes, lock included, were discarded
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert!(!state.locked);
+    assert_eq!(state.balance, 0);
+
+    let ix = secure_deposit_ix(user.pubkey(), &accounts, spl_token::ID);
+    env.send(&[ix], &[&user]).await.unwrap();
+
+    let state: secure_cpi::Vault = env.fetch(accounts.vault).await;
+    assert_eq!(state.balance, AMOUNT);
+    assert!(!state.locked);
+}
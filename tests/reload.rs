@@ -0,0 +1,197 @@
+//! # Stale Account Read Tests
+//!
+//! Withdraws from a reserve vault in `vulnerable_reload` and
+//! `secure_reload`. The vulnerable handler reads `vault_tokens.amount`
+//! after `token::transfer` without reloading, so it records and checks the
+//! pre-transfer balance. The secure handler reloads and sees the new one.
+//! Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test reload
+//! ```
+
+mod common;
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const BALANCE: u64 = 1_000;
+const MIN_RESERVE: u64 = 100;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// One program's reserve vault and the accounts around it
+struct Side {
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    destination: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vulnerable: Side,
+    secure: Side,
+}
+
+/// Vault PDA for `authority` under `program_id` holding `BALANCE`, with
+/// `state` built from its address, token account and bump
+fn add_vault<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    program_id: Pubkey,
+    mint: Pubkey,
+    authority: Pubkey,
+    state: impl FnOnce(Pubkey, u8) -> T,
+) -> Side {
+    let (vault, bump) = Pubkey::find_program_address(&[b"reserve_vault", authority.as_ref()], &program_id);
+    let vault_tokens = add_token_account(program_test, mint, vault, BALANCE);
+    add_anchor_account(program_test, vault, program_id, &state(vault_tokens, bump));
+
+    let destination = add_token_account(program_test, mint, authority, 0);
+    Side { vault, vault_tokens, destination }
+}
+
+/// A `BALANCE` vault with a `MIN_RESERVE` floor in each program, same authority
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("vulnerable_reload", vulnerable_reload::ID, processor!(vulnerable_reload::entry));
+    program_test.add_program("secure_reload", secure_reload::ID, processor!(secure_reload::entry));
+
+    let mint = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let vulnerable = add_vault(&mut program_test, vulnerable_reload::ID, mint, authority.pubkey(), |vault_tokens, bump| {
+        vulnerable_reload::ReserveVault {
+            authority: authority.pubkey(),
+            vault_tokens,
+            min_reserve: MIN_RESERVE,
+            last_balance: BALANCE,
+            bump,
+        }
+    });
+    let secure = add_vault(&mut program_test, secure_reload::ID, mint, authority.pubkey(), |vault_tokens, bump| {
+        secure_reload::ReserveVault {
+            authority: authority.pubkey(),
+            vault_tokens,
+            min_reserve: MIN_RESERVE,
+            last_balance: BALANCE,
+            bump,
+        }
+    });
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, vulnerable, secure }
+}
+
+fn vulnerable_withdraw_ix(setup: &Setup, amount: u64) -> Instruction {
+    Instruction {
+        program_id: vulnerable_reload::ID,
+        accounts: vulnerable_reload::accounts::Withdraw {
+            vault: setup.vulnerable.vault,
+            vault_tokens: setup.vulnerable.vault_tokens,
+            destination: setup.vulnerable.destination,
+            authority: setup.authority.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_reload::instruction::Withdraw { amount }.data(),
+    }
+}
+
+fn secure_withdraw_ix(setup: &Setup, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_reload::ID,
+        accounts: secure_reload::accounts::Withdraw {
+            vault: setup.secure.vault,
+            vault_tokens: setup.secure.vault_tokens,
+            destination: setup.secure.destination,
+            authority: setup.authority.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_reload::instruction::Withdraw { amount }.data(),
+    }
+}
+
+async fn vulnerable_vault(setup: &mut Setup) -> vulnerable_reload::ReserveVault {
+    setup.env.fetch(setup.vulnerable.vault).await
+}
+
+async fn secure_vault(setup: &mut Setup) -> secure_reload::ReserveVault {
+    setup.env.fetch(setup.secure.vault).await
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_withdraw_records_the_pre_transfer_balance() {
+    let mut setup = setup().await;
+
+    let ix = vulnerable_withdraw_ix(&setup, 300);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.vulnerable.vault_tokens).await, 700);
+    // The handler never saw the transfer
+    assert_eq!(vulnerable_vault(&mut setup).await.last_balance, BALANCE);
+}
+
+#[tokio::test]
+async fn vulnerable_withdraw_breaches_the_reserve() {
+    let mut setup = setup().await;
+
+    // Reserve check compared the cached 1_000 against 100
+    let ix = vulnerable_withdraw_ix(&setup, BALANCE);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.vulnerable.vault_tokens).await, 0);
+    assert_eq!(setup.env.token_balance(setup.vulnerable.destination).await, BALANCE);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_withdraw_sees_the_post_transfer_balance() {
+    let mut setup = setup().await;
+
+    let ix = secure_withdraw_ix(&setup, 300);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    let actual = setup.env.token_balance(setup.secure.vault_tokens).await;
+    assert_eq!(actual, 700);
+    assert_eq!(secure_vault(&mut setup).await.last_balance, actual);
+}
+
+#[tokio::test]
+async fn secure_withdraw_below_the_reserve_is_rejected() {
+    let mut setup = setup().await;
+
+    let ix = secure_withdraw_ix(&setup, BALANCE - MIN_RESERVE + 1);
+    let err = setup.env.send(&[ix], &[&setup.authority]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_reload::ErrorCode::ReserveBreached));
+    // The transfer was rolled back with the instruction
+    assert_eq!(setup.env.token_balance(setup.secure.vault_tokens).await, BALANCE);
+    assert_eq!(secure_vault(&mut setup).await.last_balance, BALANCE);
+}
+
+#[tokio::test]
+async fn secure_withdraw_down_to_the_reserve_succeeds() {
+    let mut setup = setup().await;
+
+    let ix = secure_withdraw_ix(&setup, BALANCE - MIN_RESERVE);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.secure.vault_tokens).await, MIN_RESERVE);
+    assert_eq!(secure_vault(&mut setup).await.last_balance, MIN_RESERVE);
+}
@@ -0,0 +1,155 @@
+//! # Reserve Consistency Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::swap_tokens` in strict
+//! mode (`reserve_tolerance: Some(..)`). With balances that match the
+//! reserves, fees included, a strict swap goes through. After a direct SPL
+//! transfer into `pool_token_in`, it fails with `ReserveMismatch` unless the
+//! difference is within the tolerance; a non-strict swap is unaffected.
+//!
+//! ```bash
+//! cargo test --test reserve_check
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const RESERVE: u64 = 1_000_000;
+const SWAP_IN: u64 = 10_000;
+const DONATION: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    trader: Keypair,
+    /// Holds `DONATION` of the input mint; never trades
+    donor: Keypair,
+    donor_tokens: Pubkey,
+    pool: Pubkey,
+    pool_token_in: Pubkey,
+    pool_token_out: Pubkey,
+    user_token_in: Pubkey,
+    user_token_out: Pubkey,
+}
+
+/// 1M / 1M pool charging 30 bps, with token balances matching its reserves
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let (mint_in, mint_out) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, bump) =
+        Pubkey::find_program_address(&[b"pool", mint_in.as_ref(), mint_out.as_ref()], &secure_cpi::ID);
+
+    let state = secure_cpi::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        ..common::cpi_pool(Pubkey::new_unique(), mint_in, mint_out, bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &state);
+
+    let (trader, donor) = (Keypair::new(), Keypair::new());
+    let pool_token_in = add_token_account(&mut program_test, mint_in, pool, RESERVE);
+    let pool_token_out = add_token_account(&mut program_test, mint_out, pool, RESERVE);
+    let user_token_in = add_token_account(&mut program_test, mint_in, trader.pubkey(), 2 * SWAP_IN);
+    let user_token_out = add_token_account(&mut program_test, mint_out, trader.pubkey(), 0);
+    let donor_tokens = add_token_account(&mut program_test, mint_in, donor.pubkey(), DONATION);
+
+    let env = TestEnv::start(program_test).await;
+    Setup {
+        env,
+        trader,
+        donor,
+        donor_tokens,
+        pool,
+        pool_token_in,
+        pool_token_out,
+        user_token_in,
+        user_token_out,
+    }
+}
+
+async fn swap(setup: &mut Setup, reserve_tolerance: Option<u64>) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SwapTokens {
+            user: setup.trader.pubkey(),
+            user_token_in: setup.user_token_in,
+            user_token_out: setup.user_token_out,
+            pool: setup.pool,
+            pool_token_in: setup.pool_token_in,
+            pool_token_out: setup.pool_token_out,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::SwapTokens { amount_in: SWAP_IN, min_amount_out: 1, reserve_tolerance }
+            .data(),
+    };
+    setup.env.send(&[ix], &[&setup.trader]).await
+}
+
+/// ❌ `amount` straight into `pool_token_in`, bypassing the pool
+async fn donate(setup: &mut Setup, amount: u64) {
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &setup.donor_tokens,
+        &setup.pool_token_in,
+        &setup.donor.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    setup.env.send(&[ix], &[&setup.donor]).await.unwrap();
+}
+
+fn reserve_mismatch() -> TransactionError {
+    custom(secure_cpi::ErrorCode::ReserveMismatch)
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn strict_swap_passes_when_balances_match_reserves() {
+    let mut setup = setup().await;
+
+    // The fee stays in pool_token_in but outside reserve_in; strict mode
+    // accounts for it
+    swap(&mut setup, Some(0)).await.unwrap();
+
+    assert!(setup.env.token_balance(setup.user_token_out).await > 0);
+}
+
+#[tokio::test]
+async fn strict_swap_flags_direct_transfer_into_pool() {
+    let mut setup = setup().await;
+    donate(&mut setup, DONATION).await;
+
+    // ✅ The donation shows up as a mismatch and the swap reverts
+    assert_eq!(swap(&mut setup, Some(0)).await.unwrap_err(), reserve_mismatch());
+    assert_eq!(setup.env.token_balance(setup.user_token_in).await, 2 * SWAP_IN);
+
+    // A tolerance below the drift still fails
+    assert_eq!(swap(&mut setup, Some(DONATION - 1)).await.unwrap_err(), reserve_mismatch());
+
+    // Non-strict swaps price off the reserves and are unaffected
+    swap(&mut setup, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn strict_swap_accepts_drift_within_tolerance() {
+    let mut setup = setup().await;
+    donate(&mut setup, 5).await;
+
+    swap(&mut setup, Some(5)).await.unwrap();
+}
@@ -0,0 +1,155 @@
+//! # Reward Accrual Unit Tests
+//!
+//! Drives `secure_overflow::StakingAccount::accrue_rewards` with a
+//! `MockClock`, so accrual across arbitrary timestamps runs as plain
+//! `#[test]`s. No validator, no `Clock` sysvar. Also covers the
+//! `ImplausibleDuration` check and the `MAX_STAKE_PERIOD` clamp.
+//!
+//! ```bash
+//! cargo test --test reward_accrual
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use secure_overflow::logic::{MockClock, SCALE, SECONDS_PER_YEAR};
+use secure_overflow::{ErrorCode, Pool, StakingAccount, MAX_STAKE_PERIOD};
+
+const T0: i64 = 1_700_000_000;
+const YEAR: i64 = SECONDS_PER_YEAR as i64;
+const STAKE: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Pool paying 100% APY since `T0`
+fn pool() -> Pool {
+    Pool { reward_rate: SCALE, rate_updated_at: T0, ..common::overflow_pool(Pubkey::new_unique()) }
+}
+
+/// `STAKE` staked at `T0` with an uncapped pool balance
+fn staking(pool: Pubkey) -> StakingAccount {
+    StakingAccount { pool_balance: u64::MAX, ..common::overflow_stake(Pubkey::new_unique(), pool, STAKE, T0) }
+}
+
+/// What executing a queued `SetRewardRate` does to the pool at `now`
+fn change_rate(pool: &mut Pool, new_rate: u64, now: i64) {
+    pool.reward_index = pool.reward_index_at(now).unwrap();
+    pool.rate_updated_at = now;
+    pool.reward_rate = new_rate;
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[test]
+fn one_year_at_full_rate_earns_the_stake() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+
+    let accrual = staking.accrue_rewards(&pool, &MockClock(T0 + YEAR)).unwrap();
+
+    assert_eq!(accrual.rewards, STAKE);
+    assert_eq!(accrual.time_staked, YEAR as u64);
+    assert_eq!(staking.pending_rewards, STAKE);
+    assert_eq!(staking.last_accrual_time, T0 + YEAR);
+}
+
+#[test]
+fn accruing_twice_at_the_same_time_pays_once() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+    let clock = MockClock(T0 + YEAR / 4);
+
+    staking.accrue_rewards(&pool, &clock).unwrap();
+    let pending = staking.pending_rewards;
+    let second = staking.accrue_rewards(&pool, &clock).unwrap();
+
+    assert_eq!(second.rewards, 0);
+    assert_eq!(staking.pending_rewards, pending);
+}
+
+#[test]
+fn rate_change_pays_each_period_at_its_own_rate() {
+    let mut pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+
+    // Half a year at 100%, half a year at 300%
+    change_rate(&mut pool, 3 * SCALE, T0 + YEAR / 2);
+    staking.accrue_rewards(&pool, &MockClock(T0 + YEAR)).unwrap();
+
+    assert_eq!(staking.pending_rewards, 2 * STAKE);
+}
+
+#[test]
+fn rewards_are_capped_at_pool_balance() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+    staking.pool_balance = 10;
+
+    let accrual = staking.accrue_rewards(&pool, &MockClock(T0 + YEAR)).unwrap();
+
+    assert_eq!(accrual.rewards, STAKE);
+    assert_eq!(accrual.capped_rewards, 10);
+    assert_eq!(staking.pending_rewards, 10);
+}
+
+#[test]
+fn backward_clock_is_rejected_and_state_untouched() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+
+    // Before the stake started
+    assert!(staking.accrue_rewards(&pool, &MockClock(T0 - 1)).is_err());
+    assert_eq!((staking.pending_rewards, staking.last_accrual_time), (0, T0));
+
+    // Before the last checkpoint
+    staking.accrue_rewards(&pool, &MockClock(T0 + 1_000)).unwrap();
+    let pending = staking.pending_rewards;
+    assert!(staking.accrue_rewards(&pool, &MockClock(T0 + 999)).is_err());
+    assert_eq!((staking.pending_rewards, staking.last_accrual_time), (pending, T0 + 1_000));
+}
+
+#[test]
+fn zero_start_time_is_implausible_and_state_untouched() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+    staking.start_time = 0;
+    staking.last_accrual_time = 0;
+
+    let err = staking.accrue_rewards(&pool, &MockClock(T0 + YEAR)).unwrap_err();
+
+    assert_eq!(err, anchor_lang::error::Error::from(ErrorCode::ImplausibleDuration));
+    assert_eq!((staking.pending_rewards, staking.last_accrual_time), (0, 0));
+}
+
+#[test]
+fn period_within_max_stake_period_is_not_clamped() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+
+    let accrual = staking.accrue_rewards(&pool, &MockClock(T0 + MAX_STAKE_PERIOD)).unwrap();
+
+    // 4 years at 100% APY
+    assert_eq!(accrual.time_staked, MAX_STAKE_PERIOD as u64);
+    assert_eq!(accrual.rewards, 4 * STAKE);
+}
+
+#[test]
+fn period_beyond_max_stake_period_is_clamped() {
+    let pool = pool();
+    let mut staking = staking(Pubkey::new_unique());
+
+    let accrual = staking.accrue_rewards(&pool, &MockClock(T0 + 10 * YEAR)).unwrap();
+
+    // Paid for MAX_STAKE_PERIOD, not 10 years; the rest is forfeited
+    assert_eq!(accrual.time_staked, MAX_STAKE_PERIOD as u64);
+    assert_eq!(accrual.rewards, 4 * STAKE);
+    assert_eq!(staking.last_accrual_time, T0 + 10 * YEAR);
+
+    // The next accrual starts from the new checkpoint
+    let next = staking.accrue_rewards(&pool, &MockClock(T0 + 11 * YEAR)).unwrap();
+    assert_eq!(next.rewards, STAKE);
+}
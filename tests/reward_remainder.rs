@@ -0,0 +1,82 @@
+//! # Reward Remainder Tests
+//!
+//! Plain `#[test]`s for the division remainder `secure_overflow` carries in
+//! `StakingAccount::accumulated_remainder`: splitting a period into many
+//! accruals, each of which alone would round down to nothing, pays the
+//! same as accruing it once.
+//!
+//! ```bash
+//! cargo test --test reward_remainder
+//! ```
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use secure_overflow::logic::{MockClock, SCALE, SECONDS_PER_YEAR};
+use secure_overflow::{Pool, StakingAccount};
+
+const T0: i64 = 1_700_000_000;
+const YEAR: i64 = SECONDS_PER_YEAR as i64;
+const STAKE: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Pool paying 100% APY since `T0`
+fn pool() -> Pool {
+    Pool { reward_rate: SCALE, rate_updated_at: T0, ..common::overflow_pool(Pubkey::new_unique()) }
+}
+
+/// `STAKE` staked at `T0` with an uncapped pool balance
+fn staking() -> StakingAccount {
+    StakingAccount {
+        pool_balance: u64::MAX,
+        ..common::overflow_stake(Pubkey::new_unique(), Pubkey::new_unique(), STAKE, T0)
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[test]
+fn one_second_accruals_sum_to_one_accrual() {
+    let pool = pool();
+    let mut once = staking();
+    let mut often = staking();
+
+    once.accrue_rewards(&pool, &MockClock(T0 + 1_000)).unwrap();
+
+    // STAKE / YEAR ≈ 0.03 per second rounds down to 0; only the carried
+    // remainder makes an occasional second pay 1
+    for now in T0 + 1..=T0 + 1_000 {
+        let accrual = often.accrue_rewards(&pool, &MockClock(now)).unwrap();
+        assert!(accrual.rewards <= 1);
+    }
+
+    assert_eq!(once.pending_rewards, 31);
+    assert_eq!(often.pending_rewards, once.pending_rewards);
+    assert_eq!(often.accumulated_remainder, once.accumulated_remainder);
+}
+
+#[test]
+fn irregular_accruals_sum_to_one_accrual() {
+    let pool = pool();
+    let mut once = staking();
+    let mut often = staking();
+
+    once.accrue_rewards(&pool, &MockClock(T0 + YEAR)).unwrap();
+
+    // Deterministic but uneven gaps: 1s, 2s, ... wrapping at 9_973s
+    let mut now = T0;
+    let mut gap = 1;
+    while now < T0 + YEAR {
+        now = (now + gap).min(T0 + YEAR);
+        often.accrue_rewards(&pool, &MockClock(now)).unwrap();
+        gap = gap % 9_973 + 1;
+    }
+
+    assert_eq!(often.pending_rewards, once.pending_rewards);
+    assert_eq!(often.accumulated_remainder, once.accumulated_remainder);
+}
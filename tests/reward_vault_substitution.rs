@@ -0,0 +1,186 @@
+//! # Reward Vault Substitution Tests
+//!
+//! Demonstrates `has_one = reward_vault` on `secure_matching::ClaimRewards`
+//! in `solana-program-test`. The claim succeeds with the vault recorded in
+//! `pool.reward_vault` and fails with `InvalidRewardVault` for any other
+//! token account of the reward mint, even one owned by the pool PDA. The
+//! failures are decoded both from the custom error code and from Anchor's
+//! program log, which names the constraint's account.
+//!
+//! ```bash
+//! cargo test --test reward_vault_substitution
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const FUNDING: u64 = 10_000;
+const PENDING: u64 = 2_500;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    staker: Keypair,
+    pool: Pubkey,
+    reward_vault: Pubkey,
+    attacker_vault: Pubkey,
+    pool_side_account: Pubkey,
+    staking_account: Pubkey,
+    staker_rewards: Pubkey,
+}
+
+/// Pool whose recorded vault holds `FUNDING`, a staker owed `PENDING`, and
+/// two look-alike reward-mint accounts holding `FUNDING`: one owned by an
+/// attacker, one owned by the pool PDA but not recorded as its vault
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let token_mint = Pubkey::new_unique();
+    let reward_mint = Pubkey::new_unique();
+    let staker = Keypair::new();
+    let attacker = Pubkey::new_unique();
+
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", token_mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, reward_mint, pool, FUNDING);
+    let attacker_vault = add_token_account(&mut program_test, reward_mint, attacker, FUNDING);
+    let pool_side_account = add_token_account(&mut program_test, reward_mint, pool, FUNDING);
+    add_anchor_account(
+        &mut program_test,
+        pool,
+        secure_matching::ID,
+        &secure_matching::Pool {
+            total_rewards_funded: FUNDING,
+            ..common::matching_pool(Pubkey::new_unique(), token_mint, reward_mint, reward_vault, bump)
+        },
+    );
+
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", staker.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    add_anchor_account(
+        &mut program_test,
+        staking_account,
+        secure_matching::ID,
+        &secure_matching::StakingAccount {
+            pending_rewards: PENDING,
+            ..common::matching_stake(staker.pubkey(), pool, 0, bump)
+        },
+    );
+    let staker_rewards = add_token_account(&mut program_test, reward_mint, staker.pubkey(), 0);
+
+    let env = TestEnv::start(program_test).await;
+    Setup {
+        env,
+        staker,
+        pool,
+        reward_vault,
+        attacker_vault,
+        pool_side_account,
+        staking_account,
+        staker_rewards,
+    }
+}
+
+/// Claim for the staker with `reward_vault` in the vault slot, returning
+/// the result and the program log
+async fn claim(setup: &mut Setup, reward_vault: Pubkey) -> (Result<(), TransactionError>, Vec<String>) {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ClaimRewards {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            pool: setup.pool,
+            reward_vault,
+            user_reward_account: setup.staker_rewards,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::ClaimRewards {}.data(),
+    };
+    setup.env.send_with_logs(&[ix], &[&setup.staker]).await
+}
+
+/// `(account, error name)` from Anchor's
+/// "AnchorError caused by account: X. Error Code: Y. ..." log line
+fn anchor_error(logs: &[String]) -> Option<(String, String)> {
+    logs.iter().find_map(|line| {
+        let rest = line.split("AnchorError caused by account: ").nth(1)?;
+        let (account, rest) = rest.split_once(". Error Code: ")?;
+        let (name, _) = rest.split_once('.')?;
+        Some((account.to_string(), name.to_string()))
+    })
+}
+
+async fn pending_rewards(setup: &mut Setup) -> u64 {
+    let staking_account: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    staking_account.pending_rewards
+}
+
+/// The substitution failed on `pool`'s `has_one` and moved nothing
+async fn assert_rejected(setup: &mut Setup, substitute: Pubkey) {
+    let (result, logs) = claim(setup, substitute).await;
+
+    assert_eq!(result.unwrap_err(), custom(secure_matching::ErrorCode::InvalidRewardVault));
+    assert_eq!(
+        anchor_error(&logs),
+        Some(("pool".to_string(), "InvalidRewardVault".to_string())),
+        "logs: {:#?}",
+        logs
+    );
+
+    assert_eq!(pending_rewards(setup).await, PENDING);
+    assert_eq!(setup.env.token_balance(substitute).await, FUNDING);
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, FUNDING);
+    assert_eq!(setup.env.token_balance(setup.staker_rewards).await, 0);
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn recorded_reward_vault_pays_the_claim() {
+    let mut setup = setup().await;
+
+    let reward_vault = setup.reward_vault;
+    let (result, _) = claim(&mut setup, reward_vault).await;
+    result.unwrap();
+
+    // Nothing staked and nothing left to claim: the claim closed the account
+    assert!(setup.env.account(setup.staking_account).await.is_none());
+    assert_eq!(setup.env.token_balance(setup.staker_rewards).await, PENDING);
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, FUNDING - PENDING);
+}
+
+#[tokio::test]
+async fn attacker_owned_account_as_reward_vault_is_rejected() {
+    let mut setup = setup().await;
+
+    // Same mint and balance as the real vault; only the address differs
+    let attacker_vault = setup.attacker_vault;
+    assert_rejected(&mut setup, attacker_vault).await;
+}
+
+#[tokio::test]
+async fn pool_owned_account_that_is_not_the_vault_is_rejected() {
+    let mut setup = setup().await;
+
+    // An owner == pool check alone would accept this one
+    let pool_side_account = setup.pool_side_account;
+    assert_rejected(&mut setup, pool_side_account).await;
+}
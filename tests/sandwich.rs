@@ -0,0 +1,173 @@
+//! # Front-Running / Sandwich Tests
+//!
+//! Multi-step `solana-program-test` scenario for `secure_cpi::swap_tokens`:
+//! an attacker's large swap lands before the victim's swap and moves the
+//! price. Shows why `min_amount_out` matters.
+//!
+//! ```bash
+//! cargo test --test sandwich
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+const RESERVE: u64 = 1_000_000;
+const ATTACKER_IN: u64 = 500_000;
+const VICTIM_IN: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Market {
+    pool: Pubkey,
+    pool_token_in: Pubkey,
+    pool_token_out: Pubkey,
+    mint_in: Pubkey,
+    mint_out: Pubkey,
+}
+
+struct Trader {
+    keypair: Keypair,
+    token_in: Pubkey,
+    token_out: Pubkey,
+}
+
+fn add_mint(program_test: &mut ProgramTest) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    add_packed(program_test, mint, Mint { supply: u64::MAX, ..common::mint(None) });
+    mint
+}
+
+fn add_trader(program_test: &mut ProgramTest, market: &Market, balance: u64) -> Trader {
+    let keypair = Keypair::new();
+    let token_in = add_token_account(program_test, market.mint_in, keypair.pubkey(), balance);
+    let token_out = add_token_account(program_test, market.mint_out, keypair.pubkey(), 0);
+    Trader { keypair, token_in, token_out }
+}
+
+/// Pool PDA with 1M / 1M reserves, backed by matching token balances
+fn add_market(program_test: &mut ProgramTest) -> Market {
+    let mint_in = add_mint(program_test);
+    let mint_out = add_mint(program_test);
+    let (pool, bump) = Pubkey::find_program_address(
+        &[b"pool", mint_in.as_ref(), mint_out.as_ref()],
+        &secure_cpi::ID,
+    );
+
+    let state = secure_cpi::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        fee_bps: 0,
+        ..common::cpi_pool(Pubkey::new_unique(), mint_in, mint_out, bump)
+    };
+    add_anchor_account(program_test, pool, secure_cpi::ID, &state);
+
+    Market {
+        pool,
+        pool_token_in: add_token_account(program_test, mint_in, pool, RESERVE),
+        pool_token_out: add_token_account(program_test, mint_out, pool, RESERVE),
+        mint_in,
+        mint_out,
+    }
+}
+
+fn swap_ix(market: &Market, trader: &Trader, amount_in: u64, min_amount_out: u64) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SwapTokens {
+            user: trader.keypair.pubkey(),
+            user_token_in: trader.token_in,
+            user_token_out: trader.token_out,
+            pool: market.pool,
+            pool_token_in: market.pool_token_in,
+            pool_token_out: market.pool_token_out,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::SwapTokens { amount_in, min_amount_out, reserve_tolerance: None }.data(),
+    }
+}
+
+async fn swap(
+    env: &mut TestEnv,
+    market: &Market,
+    trader: &Trader,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<(), TransactionError> {
+    let ix = swap_ix(market, trader, amount_in, min_amount_out);
+    env.send(&[ix], &[&trader.keypair]).await
+}
+
+async fn setup() -> (TestEnv, Market, Trader, Trader) {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let market = add_market(&mut program_test);
+    let attacker = add_trader(&mut program_test, &market, ATTACKER_IN);
+    let victim = add_trader(&mut program_test, &market, VICTIM_IN);
+    (TestEnv::start(program_test).await, market, attacker, victim)
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn victim_without_min_out_is_sandwiched() {
+    let (mut env, market, attacker, victim) = setup().await;
+
+    // Victim quotes against the reserves they see
+    let quoted = env.fetch::<secure_cpi::Pool>(market.pool).await.quote(VICTIM_IN).unwrap();
+    assert_eq!(quoted, 9_900);
+
+    // Attacker's large swap lands first and moves the price
+    swap(&mut env, &market, &attacker, ATTACKER_IN, 1).await.unwrap();
+
+    // ❌ Victim accepts any output: secure_cpi rejects min_amount_out = 0,
+    // so 1 is the loosest bound it allows
+    swap(&mut env, &market, &victim, VICTIM_IN, 1).await.unwrap();
+
+    let received = env.token_balance(victim.token_out).await;
+    assert_eq!(received, 4_415);
+    assert!(received < quoted / 2);
+}
+
+#[tokio::test]
+async fn victim_with_tight_min_out_reverts() {
+    let (mut env, market, attacker, victim) = setup().await;
+
+    let quoted = env.fetch::<secure_cpi::Pool>(market.pool).await.quote(VICTIM_IN).unwrap();
+    let min_amount_out = quoted - quoted / 100; // 1% tolerance
+
+    swap(&mut env, &market, &attacker, ATTACKER_IN, 1).await.unwrap();
+
+    // ✅ Price moved past the tolerance: the swap reverts
+    let err = swap(&mut env, &market, &victim, VICTIM_IN, min_amount_out).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::common_errors::CommonError::SlippageExceeded));
+
+    // Victim keeps their input tokens
+    assert_eq!(env.token_balance(victim.token_in).await, VICTIM_IN);
+    assert_eq!(env.token_balance(victim.token_out).await, 0);
+}
+
+#[tokio::test]
+async fn victim_with_tight_min_out_fills_when_not_front_run() {
+    let (mut env, market, _attacker, victim) = setup().await;
+
+    let quoted = env.fetch::<secure_cpi::Pool>(market.pool).await.quote(VICTIM_IN).unwrap();
+    let min_amount_out = quoted - quoted / 100;
+
+    swap(&mut env, &market, &victim, VICTIM_IN, min_amount_out).await.unwrap();
+
+    assert_eq!(env.token_balance(victim.token_out).await, quoted);
+}
@@ -0,0 +1,201 @@
+//! # Session Key Tests
+//!
+//! `solana-program-test` scenarios for `secure_signer`'s session keys. The
+//! authority delegates a `LIMIT` allowance to a session key for one hour;
+//! the session key then withdraws on its own signature until the allowance
+//! runs out or the hour passes. The `Clock` sysvar is moved forward
+//! directly instead of waiting.
+//!
+//! ```bash
+//! cargo test --test session_key
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use secure_signer::ErrorCode;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const DEPOSIT: u64 = 1_000;
+const LIMIT: u64 = 300;
+const SESSION_LENGTH: i64 = 60 * 60;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    session_key: Keypair,
+    vault: Pubkey,
+    session: Pubkey,
+}
+
+/// A vault holding `DEPOSIT` with a `LIMIT` session for `session_key`
+/// expiring `SESSION_LENGTH` from now
+async fn setup() -> Setup {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let session_key = Keypair::new();
+    let vault = Keypair::new();
+    let (session, _) = Pubkey::find_program_address(
+        &[b"session", vault.pubkey().as_ref(), session_key.pubkey().as_ref()],
+        &secure_signer::ID,
+    );
+
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount: DEPOSIT }.data(),
+    };
+    env.send(&[init, deposit], &[&vault, &authority]).await.unwrap();
+
+    let expiry = env.unix_timestamp().await + SESSION_LENGTH;
+    let create = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::CreateSession {
+            vault: vault.pubkey(),
+            session,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::CreateSession {
+            session_key: session_key.pubkey(),
+            limit: LIMIT,
+            expiry,
+        }
+        .data(),
+    };
+    env.send(&[create], &[&authority]).await.unwrap();
+
+    Setup { env, authority, session_key, vault: vault.pubkey(), session }
+}
+
+async fn withdraw_via_session(setup: &mut Setup, amount: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::WithdrawViaSession {
+            vault: setup.vault,
+            session: setup.session,
+            session_key: setup.session_key.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::WithdrawViaSession { amount }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.session_key]).await
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_signer::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+async fn session_state(setup: &mut Setup) -> secure_signer::SessionKey {
+    setup.env.fetch(setup.session).await
+}
+
+// ============================================================================
+// LIMIT
+// ============================================================================
+
+#[tokio::test]
+async fn session_withdraws_without_the_authority() {
+    let mut setup = setup().await;
+
+    withdraw_via_session(&mut setup, 100).await.unwrap();
+
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - 100);
+    assert_eq!(session_state(&mut setup).await.spent, 100);
+}
+
+#[tokio::test]
+async fn session_limit_is_cumulative() {
+    let mut setup = setup().await;
+
+    withdraw_via_session(&mut setup, 200).await.unwrap();
+    withdraw_via_session(&mut setup, LIMIT - 200).await.unwrap();
+
+    // Exhausted: even the smallest withdrawal is over the cap
+    let err = withdraw_via_session(&mut setup, 1).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::SessionLimitExceeded));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - LIMIT);
+    assert_eq!(session_state(&mut setup).await.spent, LIMIT);
+}
+
+#[tokio::test]
+async fn single_withdrawal_over_the_limit_is_rejected() {
+    let mut setup = setup().await;
+
+    let err = withdraw_via_session(&mut setup, LIMIT + 1).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::SessionLimitExceeded));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
+
+// ============================================================================
+// EXPIRY
+// ============================================================================
+
+#[tokio::test]
+async fn expired_session_is_rejected() {
+    let mut setup = setup().await;
+    withdraw_via_session(&mut setup, 100).await.unwrap();
+
+    setup.env.advance_clock(SESSION_LENGTH).await;
+    let err = withdraw_via_session(&mut setup, 100).await.unwrap_err();
+
+    assert_eq!(err, custom(ErrorCode::SessionExpired));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - 100);
+}
+
+// ============================================================================
+// ACCESS CONTROL
+// ============================================================================
+
+#[tokio::test]
+async fn revoked_session_cannot_withdraw() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let revoke = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::RevokeSession {
+            vault: setup.vault,
+            session: setup.session,
+            authority: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::RevokeSession {}.data(),
+    };
+    setup.env.send(&[revoke], &[&authority]).await.unwrap();
+
+    let err = withdraw_via_session(&mut setup, 100).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::AccountNotInitialized));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
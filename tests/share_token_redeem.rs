@@ -0,0 +1,140 @@
+//! # Share Token Redeem Tests
+//!
+//! `solana-program-test` scenarios for `secure_inflation::redeem`. Each one
+//! starts from a vault holding `VAULT_TOKENS` underlying against
+//! `TOTAL_SHARES`, of which the user holds `USER_SHARES` as share tokens.
+//! Redeeming must burn the shares (lowering the share mint's supply) and
+//! pay exactly the pro-rata underlying.
+//!
+//! ```bash
+//! cargo test --test share_token_redeem
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_token::state::Mint;
+
+const VAULT_TOKENS: u64 = 4_000;
+const TOTAL_SHARES: u64 = 2_000;
+const USER_SHARES: u64 = 500;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    user: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    share_mint: Pubkey,
+    user_share_tokens: Pubkey,
+    user_tokens: Pubkey,
+}
+
+/// Vault with `VAULT_TOKENS` underlying and `TOTAL_SHARES` shares, the user
+/// holding `USER_SHARES` of them as share tokens
+async fn setup(locked: bool) -> Setup {
+    let id = secure_inflation::ID;
+    let mut program_test = ProgramTest::new("secure_inflation", id, processor!(secure_inflation::entry));
+
+    let mint = Pubkey::new_unique();
+    let share_mint = Pubkey::new_unique();
+    let user = Keypair::new();
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", mint.as_ref()], &id);
+
+    add_packed(&mut program_test, share_mint, Mint { supply: USER_SHARES, ..common::mint(Some(vault)) });
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, VAULT_TOKENS);
+    let user_share_tokens = add_token_account(&mut program_test, share_mint, user.pubkey(), USER_SHARES);
+    let user_tokens = add_token_account(&mut program_test, mint, user.pubkey(), 0);
+
+    let state = secure_inflation::Vault { mint, vault_tokens, share_mint, total_shares: TOTAL_SHARES, locked, bump };
+    add_anchor_account(&mut program_test, vault, id, &state);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, user, vault, vault_tokens, share_mint, user_share_tokens, user_tokens }
+}
+
+fn redeem_ix(setup: &Setup, shares: u64) -> Instruction {
+    Instruction {
+        program_id: secure_inflation::ID,
+        accounts: secure_inflation::accounts::Redeem {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            share_mint: setup.share_mint,
+            user_share_tokens: setup.user_share_tokens,
+            user_tokens: setup.user_tokens,
+            user: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_inflation::instruction::Redeem { shares }.data(),
+    }
+}
+
+async fn share_supply(setup: &mut Setup) -> u64 {
+    let account = setup.env.account(setup.share_mint).await.unwrap();
+    Mint::unpack(&account.data).unwrap().supply
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_inflation::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn redeem_burns_shares_and_pays_pro_rata() {
+    let mut setup = setup(false).await;
+
+    let ix = redeem_ix(&setup, 200);
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+
+    // 200 / 2_000 of 4_000 underlying
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, 400);
+    assert_eq!(setup.env.token_balance(setup.vault_tokens).await, VAULT_TOKENS - 400);
+
+    // The shares are gone from the mint, not just from the user
+    assert_eq!(share_supply(&mut setup).await, USER_SHARES - 200);
+    assert_eq!(setup.env.token_balance(setup.user_share_tokens).await, USER_SHARES - 200);
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.total_shares, TOTAL_SHARES - 200);
+    assert!(!vault.locked);
+}
+
+#[tokio::test]
+async fn redeem_more_than_share_balance_fails() {
+    let mut setup = setup(false).await;
+
+    let ix = redeem_ix(&setup, USER_SHARES + 1);
+    let err = setup.env.send(&[ix], &[&setup.user]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_inflation::ErrorCode::InsufficientShareBalance));
+    assert_eq!(share_supply(&mut setup).await, USER_SHARES);
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, 0);
+}
+
+#[tokio::test]
+async fn redeem_while_locked_fails() {
+    // A vault left locked mid-redeem, as a re-entrant call would find it
+    let mut setup = setup(true).await;
+
+    let ix = redeem_ix(&setup, 200);
+    let err = setup.env.send(&[ix], &[&setup.user]).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_inflation::ErrorCode::ReentrancyDetected));
+    assert_eq!(share_supply(&mut setup).await, USER_SHARES);
+    assert_eq!(setup.env.token_balance(setup.vault_tokens).await, VAULT_TOKENS);
+}
@@ -0,0 +1,120 @@
+//! # Signed Duration Tests
+//!
+//! `solana-program-test` scenarios for a `start_time` ahead of the clock.
+//! `vulnerable_signed` takes it from the caller, casts the negative
+//! duration to `u64` and books rewards for ~584 billion years;
+//! `secure_signed` fails `calculate_rewards` with `NegativeDuration` for
+//! the same account state and leaves `pending_rewards` untouched.
+//!
+//! ```bash
+//! cargo test --test signed
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const NOW: i64 = 1_700_000_000;
+/// One hour ahead of `NOW`
+const FUTURE: i64 = NOW + 3_600;
+const AMOUNT: u64 = 1_000_000;
+/// 10% APY, as both programs set it on `stake`
+const RATE: u64 = 100_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Both programs, plus `extra` accounts, with the clock at `NOW`
+async fn setup(extra: impl FnOnce(&mut ProgramTest)) -> TestEnv {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("vulnerable_signed", vulnerable_signed::ID, processor!(vulnerable_signed::entry));
+    program_test.add_program("secure_signed", secure_signed::ID, processor!(secure_signed::entry));
+    extra(&mut program_test);
+
+    let mut env = TestEnv::start(program_test).await;
+    env.set_unix_timestamp(NOW).await;
+    env
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_future_start_time_pays_a_wrapped_duration() {
+    let mut env = setup(|_| {}).await;
+    let attacker = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let staking = Keypair::new();
+
+    // ❌ The caller picks a start one hour in the future
+    let stake = Instruction {
+        program_id: vulnerable_signed::ID,
+        accounts: vulnerable_signed::accounts::Stake {
+            staking: staking.pubkey(),
+            owner: attacker.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_signed::instruction::Stake { amount: AMOUNT, start_time: FUTURE }.data(),
+    };
+    env.send(&[stake], &[&attacker, &staking]).await.unwrap();
+
+    let calculate = Instruction {
+        program_id: vulnerable_signed::ID,
+        accounts: vulnerable_signed::accounts::CalculateRewards { staking: staking.pubkey() }.to_account_metas(None),
+        data: vulnerable_signed::instruction::CalculateRewards {}.data(),
+    };
+    env.send(&[calculate], &[]).await.unwrap();
+
+    // -3600 as u64 = u64::MAX - 3599 seconds staked
+    let time_staked = (NOW - FUTURE) as u64;
+    assert_eq!(time_staked, u64::MAX - 3_599);
+    let expected = AMOUNT as u128 * RATE as u128 * time_staked as u128 / 1_000_000 / (365 * 24 * 60 * 60);
+
+    let state: vulnerable_signed::StakingAccount = env.fetch(staking.pubkey()).await;
+    assert_eq!(state.pending_rewards as u128, expected);
+    assert_eq!(state.pending_rewards, 58_494_241_735_507_191);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_future_start_time_fails_with_negative_duration() {
+    let owner = Keypair::new();
+    let staking = Pubkey::new_unique();
+    // `stake` can't write a future start, so preload one, as a migrated
+    // account might hold
+    let state = secure_signed::StakingAccount {
+        owner: owner.pubkey(),
+        amount: AMOUNT,
+        rate: RATE,
+        start_time: FUTURE,
+        pending_rewards: 0,
+    };
+    let mut env = setup(|program_test| add_anchor_account(program_test, staking, secure_signed::ID, &state)).await;
+
+    let calculate = Instruction {
+        program_id: secure_signed::ID,
+        accounts: secure_signed::accounts::CalculateRewards { staking, owner: owner.pubkey() }
+            .to_account_metas(None),
+        data: secure_signed::instruction::CalculateRewards {}.data(),
+    };
+    let err = env.send(&[calculate], &[&owner]).await.unwrap_err();
+
+    // ✅ Rejected before any reward is computed
+    assert_eq!(err, custom(secure_signed::ErrorCode::NegativeDuration));
+    let state: secure_signed::StakingAccount = env.fetch(staking).await;
+    assert_eq!(state.pending_rewards, 0);
+}
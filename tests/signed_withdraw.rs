@@ -0,0 +1,224 @@
+//! # Signed Withdrawal Tests
+//!
+//! `solana-program-test` scenarios for `secure_signer::withdraw_with_signature`.
+//! The vault authority signs `(vault, amount, expiry, nonce)` off-chain; a
+//! separate submitter sends an ed25519 program instruction carrying that
+//! signature followed by the withdrawal, in the same transaction.
+//!
+//! ```bash
+//! cargo test --test signed_withdraw
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, custom_at, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    ed25519_program,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::TransactionError,
+};
+
+const DEPOSIT: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+}
+
+/// A vault holding `DEPOSIT`, owned by `authority`. The test payer submits
+/// every withdrawal; the authority never signs a transaction after setup.
+async fn setup() -> Setup {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+
+    let vault = Keypair::new();
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount: DEPOSIT }.data(),
+    };
+    env.send(&[init, deposit], &[&vault, &authority]).await.unwrap();
+
+    Setup { env, authority, vault: vault.pubkey() }
+}
+
+/// Ed25519 program instruction with one signature and all data inline:
+/// header, offsets, then public key, signature and message
+fn ed25519_ix(signer: &Keypair, message: &[u8]) -> Instruction {
+    const HEADER: u16 = 2 + 14;
+    let public_key_offset = HEADER;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let this_instruction = u16::MAX;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        this_instruction,
+        public_key_offset,
+        this_instruction,
+        message_offset,
+        message.len() as u16,
+        this_instruction,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signer.sign_message(message).as_ref());
+    data.extend_from_slice(message);
+
+    Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+}
+
+fn withdraw_ix(setup: &Setup, amount: u64, expiry: i64, nonce: u64) -> Instruction {
+    Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::WithdrawWithSignature {
+            vault: setup.vault,
+            submitter: setup.env.payer(),
+            instructions: sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::WithdrawWithSignature { amount, expiry, nonce }.data(),
+    }
+}
+
+/// `signer` authorizes `(amount, expiry, nonce)`; the withdrawal asks for the same
+fn signed_withdraw(setup: &Setup, signer: &Keypair, amount: u64, expiry: i64, nonce: u64) -> [Instruction; 2] {
+    let message = secure_signer::withdrawal_message(&setup.vault, amount, expiry, nonce);
+    [ed25519_ix(signer, &message), withdraw_ix(setup, amount, expiry, nonce)]
+}
+
+/// The payer submits; only the ed25519 instruction carries the authority's signature
+async fn submit(setup: &mut Setup, ixs: &[Instruction]) -> Result<(), TransactionError> {
+    setup.env.send(ixs, &[]).await
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_signer::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+/// The withdrawal is the second instruction, after the ed25519 check
+fn withdraw_error(code: secure_signer::ErrorCode) -> TransactionError {
+    custom_at(1, code)
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn authority_signature_authorizes_withdrawal() {
+    let mut setup = setup().await;
+
+    let ixs = signed_withdraw(&setup, &setup.authority, 400, i64::MAX, 1);
+    submit(&mut setup, &ixs).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.balance, DEPOSIT - 400);
+    assert_eq!(vault.total_withdrawn, 400);
+    assert_eq!(vault.nonce, 1);
+
+    // Nonces may skip ahead
+    let ixs = signed_withdraw(&setup, &setup.authority, 100, i64::MAX, 5);
+    submit(&mut setup, &ixs).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.nonce, 5);
+}
+
+#[tokio::test]
+async fn replayed_nonce_is_rejected() {
+    let mut setup = setup().await;
+
+    let ixs = signed_withdraw(&setup, &setup.authority, 400, i64::MAX, 1);
+    submit(&mut setup, &ixs).await.unwrap();
+
+    let err = submit(&mut setup, &ixs).await.unwrap_err();
+    assert_eq!(err, withdraw_error(secure_signer::ErrorCode::BadNonce));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - 400);
+}
+
+#[tokio::test]
+async fn expired_authorization_is_rejected() {
+    let mut setup = setup().await;
+
+    let ixs = signed_withdraw(&setup, &setup.authority, 400, 0, 1);
+    let err = submit(&mut setup, &ixs).await.unwrap_err();
+    assert_eq!(err, withdraw_error(secure_signer::ErrorCode::SignatureExpired));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
+
+#[tokio::test]
+async fn authorization_expires_at_its_expiry() {
+    let mut setup = setup().await;
+    let now = 1_700_000_000;
+    setup.env.set_unix_timestamp(now).await;
+
+    // Usable up to the second before `expiry`, like a session
+    let ixs = signed_withdraw(&setup, &setup.authority, 400, now, 1);
+    let err = submit(&mut setup, &ixs).await.unwrap_err();
+    assert_eq!(err, withdraw_error(secure_signer::ErrorCode::SignatureExpired));
+
+    let ixs = signed_withdraw(&setup, &setup.authority, 400, now + 1, 1);
+    submit(&mut setup, &ixs).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT - 400);
+}
+
+#[tokio::test]
+async fn signature_from_another_key_is_rejected() {
+    let mut setup = setup().await;
+
+    // A perfectly valid ed25519 signature, just not the authority's
+    let attacker = Keypair::new();
+    let ixs = signed_withdraw(&setup, &attacker, DEPOSIT, i64::MAX, 1);
+    let err = submit(&mut setup, &ixs).await.unwrap_err();
+    assert_eq!(err, withdraw_error(secure_signer::ErrorCode::InvalidSignature));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
+
+#[tokio::test]
+async fn amount_must_match_signed_message() {
+    let mut setup = setup().await;
+
+    // Authority signed for 1, submitter asks for everything
+    let message = secure_signer::withdrawal_message(&setup.vault, 1, i64::MAX, 1);
+    let ixs = [ed25519_ix(&setup.authority, &message), withdraw_ix(&setup, DEPOSIT, i64::MAX, 1)];
+    let err = submit(&mut setup, &ixs).await.unwrap_err();
+    assert_eq!(err, withdraw_error(secure_signer::ErrorCode::InvalidSignature));
+    assert_eq!(vault_state(&mut setup).await.balance, DEPOSIT);
+}
+
+#[tokio::test]
+async fn missing_ed25519_instruction_is_rejected() {
+    let mut setup = setup().await;
+
+    let ix = withdraw_ix(&setup, DEPOSIT, i64::MAX, 1);
+    let err = submit(&mut setup, &[ix]).await.unwrap_err();
+    assert_eq!(err, custom(secure_signer::ErrorCode::InvalidSignature));
+}
@@ -0,0 +1,120 @@
+//! # Sponsored Vault Creation Tests
+//!
+//! `solana-program-test` scenarios for `secure_pda::create_sponsored_vault`.
+//! A sponsor pays the rent for a vault owned by an authority holding no
+//! lamports at all. The vault must record the authority, and only the
+//! sponsor's balance may go down.
+//!
+//! ```bash
+//! cargo test --test sponsored_vault
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, Space, ToAccountMetas};
+use common::{add_account, add_funded_keypair, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const NAME: &str = "savings";
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    /// Pays fees and rent
+    sponsor: Keypair,
+    /// Owns the registry, has no lamports
+    authority: Keypair,
+    registry: Pubkey,
+    vault: Pubkey,
+}
+
+/// Empty registry for an unfunded `authority`, and a funded sponsor
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+
+    let authority = Keypair::new();
+    let (registry, bump) = Pubkey::find_program_address(&[b"registry", authority.pubkey().as_ref()], &secure_pda::ID);
+    let mut data = common::serialize(&secure_pda::VaultRegistry { authority: authority.pubkey(), names: vec![], bump });
+    // Sized for a full registry, as `initialize_registry` would allocate
+    data.resize(8 + secure_pda::VaultRegistry::INIT_SPACE, 0);
+    add_account(&mut program_test, registry, secure_pda::ID, data);
+
+    let sponsor = add_funded_keypair(&mut program_test, LAMPORTS_PER_SOL);
+
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault", authority.pubkey().as_ref(), NAME.as_bytes()],
+        &secure_pda::ID,
+    );
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, sponsor, authority, registry, vault }
+}
+
+fn create_sponsored_vault_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::CreateSponsoredVault {
+            vault: setup.vault,
+            registry: setup.registry,
+            authority: setup.authority.pubkey(),
+            payer: setup.sponsor.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::CreateSponsoredVault { vault_name: NAME.to_string() }.data(),
+    }
+}
+
+/// The sponsor pays the fee, so it is always a signer
+async fn send(setup: &mut Setup, ix: Instruction, signers: &[&Keypair]) -> Result<(), TransactionError> {
+    setup.env.send_paid_by(&[ix], &setup.sponsor, signers).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn sponsor_pays_rent_and_authority_owns_the_vault() {
+    let mut setup = setup().await;
+    let (sponsor, authority) = (setup.sponsor.pubkey(), setup.authority.insecure_clone());
+    let before = setup.env.lamports(sponsor).await;
+
+    let ix = create_sponsored_vault_ix(&setup);
+    send(&mut setup, ix, &[&authority]).await.unwrap();
+
+    let vault: secure_pda::Vault = setup.env.fetch(setup.vault).await;
+    assert_eq!(vault.authority, authority.pubkey());
+    assert_ne!(vault.authority, sponsor);
+    assert_eq!(vault.name, NAME);
+
+    // Rent came out of the sponsor; the authority still holds nothing
+    let rent = Rent::default().minimum_balance(8 + secure_pda::Vault::INIT_SPACE);
+    assert_eq!(setup.env.lamports(setup.vault).await, rent);
+    assert!(before - setup.env.lamports(sponsor).await >= rent);
+    assert_eq!(setup.env.lamports(authority.pubkey()).await, 0);
+}
+
+#[tokio::test]
+async fn sponsor_cannot_create_a_vault_without_the_authority() {
+    let mut setup = setup().await;
+    // Griefer pays and names the victim as authority, but the victim never signs
+    let mut ix = create_sponsored_vault_ix(&setup);
+    ix.accounts[2].is_signer = false;
+    let err = send(&mut setup, ix, &[]).await.unwrap_err();
+
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::AccountNotSigner));
+    assert!(setup.env.account(setup.vault).await.is_none());
+}
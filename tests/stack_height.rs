@@ -0,0 +1,214 @@
+//! # Stack Height Tests
+//!
+//! Nested-CPI ownership theft against `vulnerable_stack_height` and
+//! `secure_stack_height`. A proxy program, standing in for the attacker's,
+//! forwards the victim's signer privilege into `transfer_authority` with
+//! the attacker as the new authority. Runs in `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test stack_height
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program::invoke,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+// ============================================================================
+// PROXY PROGRAM
+// ============================================================================
+
+/// Stand-in for the attacker's program: invokes the program passed as the
+/// first account with the remaining accounts, flags and data unchanged
+fn proxy_entry(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (target, rest) = accounts.split_first().unwrap();
+    let ix = Instruction {
+        program_id: *target.key,
+        accounts: rest
+            .iter()
+            .map(|a| AccountMeta { pubkey: *a.key, is_signer: a.is_signer, is_writable: a.is_writable })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke(&ix, accounts)
+}
+
+/// Wrap `ix` so it reaches its program through the proxy
+fn through_proxy(proxy: Pubkey, ix: Instruction) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts);
+    Instruction { program_id: proxy, accounts, data: ix.data }
+}
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    victim: Keypair,
+    attacker: Pubkey,
+    proxy: Pubkey,
+}
+
+async fn setup() -> Setup {
+    let proxy = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_stack_height",
+        vulnerable_stack_height::ID,
+        processor!(vulnerable_stack_height::entry),
+    );
+    program_test.add_program(
+        "secure_stack_height",
+        secure_stack_height::ID,
+        processor!(secure_stack_height::entry),
+    );
+    program_test.add_program("proxy", proxy, processor!(proxy_entry));
+    let mut env = TestEnv::start(program_test).await;
+    let victim = env.funded_keypair(LAMPORTS_PER_SOL).await;
+
+    Setup { env, victim, attacker: Pubkey::new_unique(), proxy }
+}
+
+/// Send `ix` signed by the victim
+async fn send_as_victim(setup: &mut Setup, ix: Instruction) -> Result<(), TransactionError> {
+    setup.env.send(&[ix], &[&setup.victim]).await
+}
+
+fn vault_pda(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", authority.as_ref()], program_id).0
+}
+
+// ============================================================================
+// VULNERABLE PROGRAM
+// ============================================================================
+
+async fn vulnerable_vault(setup: &mut Setup) -> Pubkey {
+    let victim = setup.victim.pubkey();
+    let vault = vault_pda(&vulnerable_stack_height::ID, &victim);
+    let init = Instruction {
+        program_id: vulnerable_stack_height::ID,
+        accounts: vulnerable_stack_height::accounts::Initialize {
+            vault,
+            authority: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_stack_height::instruction::Initialize {}.data(),
+    };
+    send_as_victim(setup, init).await.unwrap();
+    vault
+}
+
+async fn vulnerable_authority(setup: &mut Setup, vault: Pubkey) -> Pubkey {
+    setup.env.fetch::<vulnerable_stack_height::Vault>(vault).await.authority
+}
+
+#[tokio::test]
+async fn vulnerable_transfer_through_proxy_gives_the_vault_away() {
+    let mut setup = setup().await;
+    let vault = vulnerable_vault(&mut setup).await;
+
+    let transfer = Instruction {
+        program_id: vulnerable_stack_height::ID,
+        accounts: vulnerable_stack_height::accounts::TransferAuthority { vault, authority: setup.victim.pubkey() }
+            .to_account_metas(None),
+        data: vulnerable_stack_height::instruction::TransferAuthority { new_authority: setup.attacker }.data(),
+    };
+
+    // The victim only "called" the proxy
+    let ix = through_proxy(setup.proxy, transfer);
+    send_as_victim(&mut setup, ix).await.unwrap();
+
+    assert_eq!(vulnerable_authority(&mut setup, vault).await, setup.attacker);
+}
+
+// ============================================================================
+// SECURE PROGRAM
+// ============================================================================
+
+async fn secure_vault(setup: &mut Setup) -> Pubkey {
+    let victim = setup.victim.pubkey();
+    let vault = vault_pda(&secure_stack_height::ID, &victim);
+    let init = Instruction {
+        program_id: secure_stack_height::ID,
+        accounts: secure_stack_height::accounts::Initialize {
+            vault,
+            authority: victim,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_stack_height::instruction::Initialize {}.data(),
+    };
+    send_as_victim(setup, init).await.unwrap();
+    vault
+}
+
+fn secure_transfer_ix(setup: &Setup, vault: Pubkey, new_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_stack_height::ID,
+        accounts: secure_stack_height::accounts::TransferAuthority { vault, authority: setup.victim.pubkey() }
+            .to_account_metas(None),
+        data: secure_stack_height::instruction::TransferAuthority { new_authority }.data(),
+    }
+}
+
+async fn secure_authority(setup: &mut Setup, vault: Pubkey) -> Pubkey {
+    setup.env.fetch::<secure_stack_height::Vault>(vault).await.authority
+}
+
+fn unexpected_stack_height() -> TransactionError {
+    custom(secure_stack_height::ErrorCode::UnexpectedStackHeight)
+}
+
+#[tokio::test]
+async fn secure_transfer_through_proxy_is_rejected() {
+    let mut setup = setup().await;
+    let vault = secure_vault(&mut setup).await;
+
+    let transfer = secure_transfer_ix(&setup, vault, setup.attacker);
+    let ix = through_proxy(setup.proxy, transfer);
+    let err = send_as_victim(&mut setup, ix).await.unwrap_err();
+
+    assert_eq!(err, unexpected_stack_height());
+    assert_eq!(secure_authority(&mut setup, vault).await, setup.victim.pubkey());
+}
+
+#[tokio::test]
+async fn secure_transfer_nested_two_proxies_deep_is_rejected() {
+    let mut setup = setup().await;
+    let vault = secure_vault(&mut setup).await;
+
+    // Stack height 3: proxy → proxy → transfer_authority
+    let transfer = secure_transfer_ix(&setup, vault, setup.attacker);
+    let nested = through_proxy(setup.proxy, through_proxy(setup.proxy, transfer));
+    let err = send_as_victim(&mut setup, nested).await.unwrap_err();
+
+    assert_eq!(err, unexpected_stack_height());
+    assert_eq!(secure_authority(&mut setup, vault).await, setup.victim.pubkey());
+}
+
+#[tokio::test]
+async fn secure_direct_transfer_succeeds() {
+    let mut setup = setup().await;
+    let vault = secure_vault(&mut setup).await;
+    let successor = Pubkey::new_unique();
+
+    let transfer = secure_transfer_ix(&setup, vault, successor);
+    send_as_victim(&mut setup, transfer).await.unwrap();
+
+    assert_eq!(secure_authority(&mut setup, vault).await, successor);
+}
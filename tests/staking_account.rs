@@ -0,0 +1,333 @@
+//! # Staking Account PDA Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::create_staking_account`,
+//! the `["staking", user, pool]` seeds check on `stake`, and closing the
+//! account once it is empty: automatically on a full `unstake`,
+//! `unstake_with_penalty` or `emergency_unstake`, or through
+//! `close_staking_account`, which fails with `AccountNotEmpty` otherwise.
+//!
+//! ```bash
+//! cargo test --test staking_account
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_funded_keypair, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const BALANCE: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Pool PDA for `mint` and its empty reward vault, paying in `mint` too
+fn add_pool(program_test: &mut ProgramTest, mint: Pubkey) -> (Pubkey, Pubkey) {
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(program_test, mint, pool, 0);
+    let pool_state = common::matching_pool(Pubkey::new_unique(), mint, mint, reward_vault, bump);
+    add_anchor_account(program_test, pool, secure_matching::ID, &pool_state);
+    (pool, reward_vault)
+}
+
+fn staking_pda(user: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"staking", user.as_ref(), pool.as_ref()], &secure_matching::ID)
+}
+
+struct Setup {
+    env: TestEnv,
+    user: Keypair,
+    pools: [Pubkey; 2],
+    /// Reward vault of `pools[0]`
+    reward_vault: Pubkey,
+    user_tokens: Pubkey,
+    pool_tokens: Pubkey,
+}
+
+/// Two pools for the same user; the user pays fees and rent
+async fn setup(extra: impl FnOnce(&mut ProgramTest, &Keypair, Pubkey)) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let (pool, reward_vault) = add_pool(&mut program_test, mint);
+    let pools = [pool, add_pool(&mut program_test, Pubkey::new_unique()).0];
+
+    let user = add_funded_keypair(&mut program_test, 1_000_000_000);
+    let user_tokens = add_token_account(&mut program_test, mint, user.pubkey(), BALANCE);
+    let pool_tokens = add_token_account(&mut program_test, mint, pools[0], 0);
+    extra(&mut program_test, &user, pools[0]);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, user, pools, reward_vault, user_tokens, pool_tokens }
+}
+
+/// The user pays the fee and is the only signer
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), TransactionError> {
+    setup.env.send_paid_by(&[ix], &setup.user, &[]).await
+}
+
+fn create_ix(user: Pubkey, pool: Pubkey, staking_account: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::CreateStakingAccount {
+            user,
+            staking_account,
+            pool,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::CreateStakingAccount {}.data(),
+    }
+}
+
+fn stake_ix(setup: &Setup, staking_account: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::Stake {
+            user: setup.user.pubkey(),
+            staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pools[0],
+            owner: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::Stake { amount, lock_duration: 0 }.data(),
+    }
+}
+
+fn unstake_ix(setup: &Setup, staking_account: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::Unstake {
+            user: setup.user.pubkey(),
+            staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pools[0],
+            owner: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::Unstake { amount }.data(),
+    }
+}
+
+fn unstake_with_penalty_ix(setup: &Setup, staking_account: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::UnstakeWithPenalty {
+            user: setup.user.pubkey(),
+            staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pools[0],
+            reward_vault: setup.reward_vault,
+            owner: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::UnstakeWithPenalty { amount }.data(),
+    }
+}
+
+fn emergency_unstake_ix(setup: &Setup, staking_account: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::EmergencyUnstake {
+            user: setup.user.pubkey(),
+            staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pools[0],
+            owner: setup.user.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::EmergencyUnstake {}.data(),
+    }
+}
+
+fn close_ix(user: Pubkey, pool: Pubkey, staking_account: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::CloseStakingAccount { user, staking_account, pool }
+            .to_account_metas(None),
+        data: secure_matching::instruction::CloseStakingAccount {}.data(),
+    }
+}
+
+/// Lamports `ix` costs the user in fees, so rent refunds can be checked exactly
+async fn fee_for(setup: &mut Setup, ix: &Instruction) -> u64 {
+    let message = Message::new(&[ix.clone()], Some(&setup.user.pubkey()));
+    setup.env.banks().get_fee_for_message(message).await.unwrap().unwrap()
+}
+
+async fn staking_state(setup: &mut Setup, address: Pubkey) -> secure_matching::StakingAccount {
+    setup.env.fetch(address).await
+}
+
+/// Stake `BALANCE`, leave through `exit_ix`, and check the emptied account
+/// was closed with its rent back to the user
+async fn assert_full_exit_closes(exit_ix: fn(&Setup, Pubkey) -> Instruction) {
+    let mut setup = setup(|_, _, _| {}).await;
+    let (user, pool) = (setup.user.pubkey(), setup.pools[0]);
+    let (address, _) = staking_pda(&user, &pool);
+
+    send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+    let ix = stake_ix(&setup, address, BALANCE);
+    send(&mut setup, ix).await.unwrap();
+
+    let rent = setup.env.lamports(address).await;
+    let before = setup.env.lamports(user).await;
+    let ix = exit_ix(&setup, address);
+    let fee = fee_for(&mut setup, &ix).await;
+    send(&mut setup, ix).await.unwrap();
+
+    // ✅ No stake and no pending rewards (the pool emits nothing): closed
+    assert!(setup.env.account(address).await.is_none());
+    assert_eq!(setup.env.lamports(user).await, before + rent - fee);
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, BALANCE);
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn staking_account_is_created_at_the_user_pool_pda() {
+    let mut setup = setup(|_, _, _| {}).await;
+    let user = setup.user.pubkey();
+
+    for pool in setup.pools {
+        let (address, bump) = staking_pda(&user, &pool);
+        send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+
+        let state = staking_state(&mut setup, address).await;
+        assert_eq!(state.owner, user);
+        assert_eq!(state.pool, pool);
+        assert_eq!(state.amount, 0);
+        assert_eq!(state.pending_rewards, 0);
+        assert_eq!(state.bump, bump);
+        assert_eq!(state.version, secure_matching::CURRENT_VERSION);
+    }
+
+    // Same user, different pool → different account
+    assert_ne!(staking_pda(&user, &setup.pools[0]).0, staking_pda(&user, &setup.pools[1]).0);
+}
+
+#[tokio::test]
+async fn staking_account_cannot_be_created_twice() {
+    let mut setup = setup(|_, _, _| {}).await;
+    let (user, pool) = (setup.user.pubkey(), setup.pools[0]);
+    let (address, _) = staking_pda(&user, &pool);
+
+    send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+    assert!(send(&mut setup, create_ix(user, pool, address)).await.is_err());
+}
+
+#[tokio::test]
+async fn staking_account_at_any_other_address_is_rejected() {
+    let fake = Pubkey::new_unique();
+    // Correct owner, pool and bump, wrong address
+    let mut setup = setup(|program_test, user, pool| {
+        let state = secure_matching::StakingAccount {
+            pending_rewards: 1_000_000,
+            ..common::matching_stake(user.pubkey(), pool, 0, staking_pda(&user.pubkey(), &pool).1)
+        };
+        add_anchor_account(program_test, fake, secure_matching::ID, &state);
+    })
+    .await;
+
+    let ix = stake_ix(&setup, fake, BALANCE);
+    let err = send(&mut setup, ix).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+}
+
+#[tokio::test]
+async fn stake_accepts_the_pda_staking_account() {
+    let mut setup = setup(|_, _, _| {}).await;
+    let (user, pool) = (setup.user.pubkey(), setup.pools[0]);
+    let (address, _) = staking_pda(&user, &pool);
+
+    send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+    let ix = stake_ix(&setup, address, BALANCE);
+    send(&mut setup, ix).await.unwrap();
+
+    assert_eq!(staking_state(&mut setup, address).await.amount, BALANCE);
+}
+
+#[tokio::test]
+async fn full_unstake_closes_the_account_and_refunds_rent() {
+    assert_full_exit_closes(|setup, address| unstake_ix(setup, address, BALANCE)).await;
+}
+
+#[tokio::test]
+async fn full_penalty_unstake_closes_the_account_and_refunds_rent() {
+    // Past `min_stake_duration` (0 here), so no penalty is taken
+    assert_full_exit_closes(|setup, address| unstake_with_penalty_ix(setup, address, BALANCE)).await;
+}
+
+#[tokio::test]
+async fn emergency_unstake_closes_the_account_and_refunds_rent() {
+    assert_full_exit_closes(emergency_unstake_ix).await;
+}
+
+#[tokio::test]
+async fn partial_unstake_keeps_the_account() {
+    let mut setup = setup(|_, _, _| {}).await;
+    let (user, pool) = (setup.user.pubkey(), setup.pools[0]);
+    let (address, _) = staking_pda(&user, &pool);
+
+    send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+    let ix = stake_ix(&setup, address, BALANCE);
+    send(&mut setup, ix).await.unwrap();
+    let ix = unstake_ix(&setup, address, BALANCE - 1);
+    send(&mut setup, ix).await.unwrap();
+
+    assert_eq!(staking_state(&mut setup, address).await.amount, 1);
+}
+
+#[tokio::test]
+async fn unused_account_can_be_closed() {
+    let mut setup = setup(|_, _, _| {}).await;
+    let (user, pool) = (setup.user.pubkey(), setup.pools[0]);
+    let (address, _) = staking_pda(&user, &pool);
+
+    send(&mut setup, create_ix(user, pool, address)).await.unwrap();
+    send(&mut setup, close_ix(user, pool, address)).await.unwrap();
+
+    assert!(setup.env.account(address).await.is_none());
+}
+
+#[tokio::test]
+async fn account_with_pending_rewards_cannot_be_closed() {
+    let (mut user_key, mut pool_key) = (Pubkey::default(), Pubkey::default());
+    // Nothing staked, but rewards still to claim
+    let mut setup = setup(|program_test, user, pool| {
+        let (address, bump) = staking_pda(&user.pubkey(), &pool);
+        let state = secure_matching::StakingAccount {
+            pending_rewards: 1,
+            ..common::matching_stake(user.pubkey(), pool, 0, bump)
+        };
+        add_anchor_account(program_test, address, secure_matching::ID, &state);
+        (user_key, pool_key) = (user.pubkey(), pool);
+    })
+    .await;
+    let (address, _) = staking_pda(&user_key, &pool_key);
+
+    let err = send(&mut setup, close_ix(user_key, pool_key, address)).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::AccountNotEmpty));
+    assert_eq!(staking_state(&mut setup, address).await.pending_rewards, 1);
+}
@@ -0,0 +1,117 @@
+//! # Structured Log Tests
+//!
+//! Runs initialize → deposit → withdraw against `secure_signer` in
+//! `solana-program-test` and parses the `Program log: evt=...` lines that
+//! `log_event!` writes, checking each handler logs its keys in the
+//! `key=value` format monitoring tools split on.
+//!
+//! ```bash
+//! cargo test --test structured_logs
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::TestEnv;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use std::collections::HashMap;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Send `ixs` and return the transaction's log lines
+async fn send(env: &mut TestEnv, ixs: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+    let (result, logs) = env.send_with_logs(ixs, signers).await;
+    result.unwrap();
+    logs
+}
+
+/// Every structured line in `logs`, as its `key=value` pairs
+fn structured(logs: &[String]) -> Vec<HashMap<String, String>> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program log: "))
+        .filter(|line| line.starts_with("evt="))
+        .map(|line| {
+            line.split(' ')
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').expect("every field is key=value");
+                    (key.to_string(), value.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The single structured line in `logs`, which must be for `evt`
+fn only_event(logs: &[String], evt: &str) -> HashMap<String, String> {
+    let mut lines = structured(logs);
+    assert_eq!(lines.len(), 1, "expected one structured line in {logs:#?}");
+    let line = lines.remove(0);
+    assert_eq!(line["evt"], evt);
+    line
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn withdraw_logs_structured_keys() {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+    let payer = env.payer();
+
+    let vault = Keypair::new();
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let logs = send(&mut env, &[init], &[&vault]).await;
+    let line = only_event(&logs, "initialize");
+    assert_eq!(line["vault"], vault.pubkey().to_string());
+    assert_eq!(line["authority"], payer.to_string());
+
+    let deposit = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit {
+            vault: vault.pubkey(),
+            depositor: payer,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount: 1_000 }.data(),
+    };
+    let logs = send(&mut env, &[deposit], &[]).await;
+    let line = only_event(&logs, "deposit");
+    assert_eq!((line["amount"].as_str(), line["balance"].as_str()), ("1000", "1000"));
+
+    let withdraw = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Withdraw {
+            vault: vault.pubkey(),
+            authority: payer,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Withdraw { amount: 400 }.data(),
+    };
+    let logs = send(&mut env, &[withdraw], &[]).await;
+    let line = only_event(&logs, "withdraw");
+
+    let mut keys: Vec<_> = line.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["amount", "balance", "evt", "vault"]);
+    assert_eq!(line["vault"], vault.pubkey().to_string());
+    assert_eq!(line["amount"], "400");
+    assert_eq!(line["balance"], "600");
+}
@@ -0,0 +1,114 @@
+//! # Sub-Vault Tests
+//!
+//! `solana-program-test` scenarios for `secure_pda::create_subvault`. Each
+//! sub-vault lives at ["subvault", parent_vault, sub_name]: two under one
+//! parent get their own addresses, and neither aliases the top-level vault
+//! whose name is the parent's and the sub-vault's run together. A parent
+//! that doesn't belong to the signer fails with `ParentMismatch`.
+//!
+//! ```bash
+//! cargo test --test subvault
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_funded_keypair, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const PARENT: &str = "savings";
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    parent: Pubkey,
+}
+
+/// A `PARENT` vault at the authority's PDA, recording `recorded_authority`
+/// (the authority itself unless set otherwise)
+async fn setup(recorded_authority: Option<Pubkey>) -> Setup {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+
+    let authority = add_funded_keypair(&mut program_test, LAMPORTS_PER_SOL);
+    let (parent, bump) = vault_address(&authority.pubkey(), PARENT);
+    let state = common::pda_vault(recorded_authority.unwrap_or(authority.pubkey()), PARENT, 0, bump);
+    add_anchor_account(&mut program_test, parent, secure_pda::ID, &state);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, parent }
+}
+
+fn vault_address(authority: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", authority.as_ref(), name.as_bytes()], &secure_pda::ID)
+}
+
+fn subvault_address(parent: &Pubkey, name: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"subvault", parent.as_ref(), name.as_bytes()], &secure_pda::ID).0
+}
+
+async fn create_subvault(setup: &mut Setup, name: &str) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::CreateSubvault {
+            parent_vault: setup.parent,
+            subvault: subvault_address(&setup.parent, name),
+            authority: setup.authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::CreateSubvault { parent_name: PARENT.to_string(), sub_name: name.to_string() }
+            .data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn two_subvaults_get_distinct_addresses() {
+    let mut setup = setup(None).await;
+
+    create_subvault(&mut setup, "travel").await.unwrap();
+    create_subvault(&mut setup, "rent").await.unwrap();
+
+    let travel = subvault_address(&setup.parent, "travel");
+    let rent = subvault_address(&setup.parent, "rent");
+    assert_ne!(travel, rent);
+    for (address, name) in [(travel, "travel"), (rent, "rent")] {
+        let state: secure_pda::SubVault = setup.env.fetch(address).await;
+        assert_eq!(state.name, name);
+        assert_eq!(state.parent, setup.parent);
+        assert_eq!(state.authority, setup.authority.pubkey());
+    }
+
+    // ✅ No top-level vault can be named onto a sub-vault's address
+    let (joined, _) = vault_address(&setup.authority.pubkey(), "savingstravel");
+    assert_ne!(joined, travel);
+}
+
+#[tokio::test]
+async fn parent_of_another_authority_fails_with_parent_mismatch() {
+    // The parent sits at the signer's PDA but records someone else, so the
+    // seeds pass and only `has_one` can catch it
+    let mut setup = setup(Some(Pubkey::new_unique())).await;
+
+    let err = create_subvault(&mut setup, "travel").await.unwrap_err();
+
+    assert_eq!(err, custom(secure_pda::ErrorCode::ParentMismatch));
+    let travel = subvault_address(&setup.parent, "travel");
+    assert!(setup.env.account(travel).await.is_none());
+}
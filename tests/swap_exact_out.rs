@@ -0,0 +1,183 @@
+//! # Exact-Output Swap Tests
+//!
+//! Plain `#[test]`s check `Pool::quote_exact_out` against `quote`, fee
+//! included: the quoted input is the least that buys the output. The
+//! `solana-program-test` scenarios run `secure_cpi::swap_exact_out` and
+//! `swap_tokens` for the same trade and compare the pools afterwards, then
+//! check `ExcessiveInputAmount` and `InsufficientLiquidity`.
+//!
+//! ```bash
+//! cargo test --test swap_exact_out
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use secure_cpi::{CurveType, ErrorCode};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const RESERVE: u64 = 1_000_000;
+const FEE_BPS: u16 = 30;
+const AMOUNT_OUT: u64 = 9_000;
+const BALANCE: u64 = 100_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn pool_state(mints: (Pubkey, Pubkey), bump: u8) -> secure_cpi::Pool {
+    secure_cpi::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        fee_bps: FEE_BPS,
+        ..common::cpi_pool(Pubkey::new_unique(), mints, bump)
+    }
+}
+
+struct Setup {
+    env: TestEnv,
+    trader: Keypair,
+    accounts: secure_cpi::accounts::SwapTokens,
+}
+
+/// 1M / 1M pool charging `FEE_BPS`, and a trader holding `BALANCE` in
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mints = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, bump) =
+        Pubkey::find_program_address(&[b"pool", mints.0.as_ref(), mints.1.as_ref()], &secure_cpi::ID);
+
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &pool_state(mints, bump));
+
+    let trader = Keypair::new();
+    let accounts = secure_cpi::accounts::SwapTokens {
+        user: trader.pubkey(),
+        user_token_in: add_token_account(&mut program_test, mints.0, trader.pubkey(), BALANCE),
+        user_token_out: add_token_account(&mut program_test, mints.1, trader.pubkey(), 0),
+        pool,
+        pool_token_in: add_token_account(&mut program_test, mints.0, pool, RESERVE),
+        pool_token_out: add_token_account(&mut program_test, mints.1, pool, RESERVE),
+        token_program: spl_token::ID,
+    };
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, trader, accounts }
+}
+
+async fn send(setup: &mut Setup, data: Vec<u8>) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: setup.accounts.to_account_metas(None),
+        data,
+    };
+    setup.env.send(&[ix], &[&setup.trader]).await
+}
+
+async fn exact_out(setup: &mut Setup, amount_out: u64, max_amount_in: u64) -> Result<(), TransactionError> {
+    send(setup, secure_cpi::instruction::SwapExactOut { amount_out, max_amount_in }.data()).await
+}
+
+async fn exact_in(setup: &mut Setup, amount_in: u64) -> Result<(), TransactionError> {
+    send(setup, secure_cpi::instruction::SwapTokens { amount_in, min_amount_out: 1, reserve_tolerance: None }.data())
+        .await
+}
+
+async fn fetch_pool(setup: &mut Setup) -> secure_cpi::Pool {
+    setup.env.fetch(setup.accounts.pool).await
+}
+
+// ============================================================================
+// QUOTE_EXACT_OUT
+// ============================================================================
+
+#[test]
+fn exact_out_quote_is_least_exact_in_amount() {
+    let mints = (Pubkey::new_unique(), Pubkey::new_unique());
+    for fee_bps in [0, FEE_BPS, secure_cpi::MAX_FEE_BPS] {
+        let pool = secure_cpi::Pool { fee_bps, ..pool_state(mints, 255) };
+        for amount_out in [1, 2, 999, AMOUNT_OUT, RESERVE / 2, RESERVE - 1] {
+            let amount_in = pool.quote_exact_out(amount_out).unwrap();
+            assert!(pool.quote(amount_in).unwrap() >= amount_out);
+            assert!(pool.quote(amount_in - 1).unwrap() < amount_out);
+        }
+    }
+}
+
+#[test]
+fn exact_out_quote_rejects_what_the_pool_cannot_supply() {
+    let pool = pool_state((Pubkey::new_unique(), Pubkey::new_unique()), 255);
+    for amount_out in [RESERVE, RESERVE + 1, u64::MAX] {
+        assert_eq!(
+            pool.quote_exact_out(amount_out).unwrap_err(),
+            ErrorCode::InsufficientLiquidity.into()
+        );
+    }
+}
+
+#[test]
+fn exact_out_quote_rejects_stable_swap() {
+    let pool = secure_cpi::Pool {
+        curve: CurveType::StableSwap,
+        amp: 100,
+        ..pool_state((Pubkey::new_unique(), Pubkey::new_unique()), 255)
+    };
+    assert_eq!(pool.quote_exact_out(AMOUNT_OUT).unwrap_err(), ErrorCode::UnsupportedCurve.into());
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn exact_out_and_exact_in_agree_on_the_same_trade() {
+    let amount_in = pool_state((Pubkey::new_unique(), Pubkey::new_unique()), 255)
+        .quote_exact_out(AMOUNT_OUT)
+        .unwrap();
+
+    let mut out_setup = setup().await;
+    exact_out(&mut out_setup, AMOUNT_OUT, amount_in).await.unwrap();
+    let user_token_out = out_setup.accounts.user_token_out;
+    let user_token_in = out_setup.accounts.user_token_in;
+    assert_eq!(out_setup.env.token_balance(user_token_out).await, AMOUNT_OUT);
+    assert_eq!(out_setup.env.token_balance(user_token_in).await, BALANCE - amount_in);
+
+    let mut in_setup = setup().await;
+    exact_in(&mut in_setup, amount_in).await.unwrap();
+    let user_token_out = in_setup.accounts.user_token_out;
+    assert_eq!(in_setup.env.token_balance(user_token_out).await, AMOUNT_OUT);
+
+    // Same input, same fee, same output: the pools end up identical
+    let (after_out, after_in) = (fetch_pool(&mut out_setup).await, fetch_pool(&mut in_setup).await);
+    assert_eq!(after_out.reserve_in, after_in.reserve_in);
+    assert_eq!(after_out.reserve_out, after_in.reserve_out);
+    assert_eq!(after_out.fees_collected, after_in.fees_collected);
+    assert_eq!(after_out.total_volume, after_in.total_volume);
+}
+
+#[tokio::test]
+async fn exact_out_over_max_input_fails() {
+    let mut setup = setup().await;
+    let amount_in = fetch_pool(&mut setup).await.quote_exact_out(AMOUNT_OUT).unwrap();
+
+    let err = exact_out(&mut setup, AMOUNT_OUT, amount_in - 1).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::ExcessiveInputAmount));
+
+    let user_token_in = setup.accounts.user_token_in;
+    assert_eq!(setup.env.token_balance(user_token_in).await, BALANCE);
+}
+
+#[tokio::test]
+async fn exact_out_beyond_reserves_fails() {
+    let mut setup = setup().await;
+
+    let err = exact_out(&mut setup, RESERVE, u64::MAX).await.unwrap_err();
+    assert_eq!(err, custom(ErrorCode::InsufficientLiquidity));
+    assert_eq!(fetch_pool(&mut setup).await.reserve_out, RESERVE);
+}
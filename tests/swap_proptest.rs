@@ -0,0 +1,83 @@
+//! # Swap Math Property Tests
+//!
+//! Property-based tests for `logic::swap_output` (via `secure_cpi::logic`).
+//!
+//! ```bash
+//! cargo test --test swap_proptest
+//! ```
+
+use proptest::prelude::*;
+use secure_cpi::logic::swap_output;
+
+proptest! {
+    /// Output can never exceed what the pool holds
+    #[test]
+    fn output_never_exceeds_reserve_out(
+        amount_in in 1..=u64::MAX,
+        reserve_in in 0..=u64::MAX,
+        reserve_out in 0..=u64::MAX,
+    ) {
+        let amount_out = swap_output(amount_in, reserve_in, reserve_out).unwrap();
+        prop_assert!(amount_out <= reserve_out);
+    }
+
+    /// k' >= k: rounding always favors the pool, never the trader
+    #[test]
+    fn constant_product_never_decreases(
+        amount_in in 1..=u64::MAX,
+        reserve_in in 0..=u64::MAX,
+        reserve_out in 0..=u64::MAX,
+    ) {
+        let amount_out = swap_output(amount_in, reserve_in, reserve_out).unwrap();
+
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = (reserve_in as u128 + amount_in as u128)
+            .checked_mul((reserve_out - amount_out) as u128);
+
+        // k_after only overflows u128 when it is larger than any u128 k_before
+        if let Some(k_after) = k_after {
+            prop_assert!(k_after >= k_before);
+        }
+    }
+
+    /// A larger input never yields a smaller output
+    #[test]
+    fn output_is_monotonic_in_amount_in(
+        amount_in in 1..u64::MAX,
+        extra in 1..=u64::MAX,
+        reserve_in in 0..=u64::MAX,
+        reserve_out in 0..=u64::MAX,
+    ) {
+        let larger_in = amount_in.saturating_add(extra);
+
+        let smaller = swap_output(amount_in, reserve_in, reserve_out).unwrap();
+        let larger = swap_output(larger_in, reserve_in, reserve_out).unwrap();
+        prop_assert!(larger >= smaller);
+    }
+}
+
+#[test]
+fn output_too_large_boundary_is_never_reached() {
+    // amount_out = amount_in * reserve_out / (reserve_in + amount_in) <= reserve_out,
+    // so even at the extremes the u128 result always fits back into a u64.
+    let cases = [
+        (u64::MAX, 0, u64::MAX),
+        (u64::MAX, u64::MAX, u64::MAX),
+        (u64::MAX, 1, u64::MAX),
+        (1, 0, u64::MAX),
+    ];
+
+    for (amount_in, reserve_in, reserve_out) in cases {
+        let amount_out = swap_output(amount_in, reserve_in, reserve_out).unwrap();
+        assert!(amount_out <= reserve_out);
+    }
+
+    // Empty input side: the whole output reserve is quoted, nothing more
+    assert_eq!(swap_output(u64::MAX, 0, u64::MAX).unwrap(), u64::MAX);
+}
+
+#[test]
+fn zero_input_against_empty_pool_is_rejected() {
+    // 0 * reserve_out / (0 + 0) divides by zero
+    assert!(swap_output(0, 0, 1_000).is_err());
+}
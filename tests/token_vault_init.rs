@@ -0,0 +1,143 @@
+//! # Token Vault Initialization Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::initialize_token_vault`.
+//! The vault PDA's token account is created first, either empty or already
+//! holding tokens, and the vault's recorded `balance` must start at what it
+//! actually holds.
+//!
+//! ```bash
+//! cargo test --test token_vault_init
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_funded_keypair, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+const PREFUNDED: u64 = 5_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+}
+
+/// Token account holding `amount` and owned by `owner`, for a vault PDA
+/// that does not exist yet. The vault authority pays for everything.
+async fn setup_with_owner(amount: u64, owner: impl FnOnce(Pubkey) -> Pubkey) -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let authority = add_funded_keypair(&mut program_test, 1_000_000_000);
+
+    let (vault, _) = Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref()], &secure_cpi::ID);
+    let vault_tokens = add_token_account(&mut program_test, Pubkey::new_unique(), owner(vault), amount);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, vault, vault_tokens }
+}
+
+/// Token account owned by the vault PDA
+async fn setup(amount: u64) -> Setup {
+    setup_with_owner(amount, |vault| vault).await
+}
+
+async fn send(setup: &mut Setup, ix: Instruction) -> Result<(), TransactionError> {
+    setup.env.send_paid_by(&[ix], &setup.authority, &[]).await
+}
+
+async fn initialize(setup: &mut Setup, allow_prefunded: bool) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::InitializeTokenVault {
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            authority: setup.authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::InitializeTokenVault { allow_prefunded }.data(),
+    };
+    send(setup, ix).await
+}
+
+async fn reconcile(setup: &mut Setup) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Reconcile {
+            authority: setup.authority.pubkey(),
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Reconcile { credit_surplus: false }.data(),
+    };
+    send(setup, ix).await
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_cpi::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn empty_token_account_starts_at_zero() {
+    let mut setup = setup(0).await;
+
+    initialize(&mut setup, false).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.authority, setup.authority.pubkey());
+    assert_eq!((vault.balance, vault.surplus, vault.total_deposited), (0, 0, 0));
+    assert!(!vault.locked);
+}
+
+#[tokio::test]
+async fn prefunded_token_account_is_rejected_by_default() {
+    let mut setup = setup(PREFUNDED).await;
+
+    let err = initialize(&mut setup, false).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_cpi::ErrorCode::PrefundedVault));
+    // Nothing was created
+    assert!(setup.env.account(setup.vault).await.is_none());
+}
+
+#[tokio::test]
+async fn prefunded_token_account_is_booked_when_allowed() {
+    let mut setup = setup(PREFUNDED).await;
+
+    initialize(&mut setup, true).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert_eq!(vault.balance, PREFUNDED);
+    // Not a deposit: the audit counters stay at zero
+    assert_eq!((vault.total_deposited, vault.deposit_count), (0, 0));
+
+    // Recorded and actual balances agree from the start
+    reconcile(&mut setup).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.surplus, 0);
+}
+
+#[tokio::test]
+async fn token_account_of_another_owner_is_rejected() {
+    let mut setup = setup_with_owner(0, |_| Pubkey::new_unique()).await;
+
+    let err = initialize(&mut setup, false).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_cpi::common_errors::CommonError::InvalidOwner));
+}
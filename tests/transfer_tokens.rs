@@ -0,0 +1,238 @@
+//! # Transfer Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::transfer_tokens`:
+//! a normal transfer emits `TransferExecuted`, and the same token account
+//! passed as both `from_account` and `to_account` is rejected with
+//! `SelfTransfer` and emits nothing. A frozen account on either side fails
+//! with `TokenAccountFrozen` before the token CPI. `transfer_as_delegate`
+//! spends an `approve_delegate` allowance and nothing beyond it; expiry is
+//! covered in `delegate_expiry.rs`.
+//!
+//! ```bash
+//! cargo test --test transfer_tokens
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_packed, add_token_account, custom, events, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+use spl_token::state::{Account as TokenAccount, AccountState};
+
+const BALANCE: u64 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// As `add_token_account`, frozen by the mint
+fn add_frozen_token_account(program_test: &mut ProgramTest, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let state = TokenAccount { state: AccountState::Frozen, ..common::token_account(mint, owner, amount) };
+    add_packed(program_test, address, state);
+    address
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    /// Not yet approved on anything
+    delegate: Keypair,
+    source: Pubkey,
+    destination: Pubkey,
+    /// Holds `BALANCE` for `authority`, frozen by the mint
+    frozen_source: Pubkey,
+    /// Empty, frozen by the mint
+    frozen_destination: Pubkey,
+}
+
+/// `source` holds `BALANCE` for `authority`; `destination` is empty.
+/// Each has a frozen twin.
+async fn setup() -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let source = add_token_account(&mut program_test, mint, authority.pubkey(), BALANCE);
+    let destination = add_token_account(&mut program_test, mint, Pubkey::new_unique(), 0);
+    let frozen_source = add_frozen_token_account(&mut program_test, mint, authority.pubkey(), BALANCE);
+    let frozen_destination = add_frozen_token_account(&mut program_test, mint, Pubkey::new_unique(), 0);
+
+    Setup {
+        env: TestEnv::start(program_test).await,
+        authority,
+        delegate: Keypair::new(),
+        source,
+        destination,
+        frozen_source,
+        frozen_destination,
+    }
+}
+
+fn transfer_ix(setup: &Setup, from_account: Pubkey, to_account: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::TransferTokens {
+            from_account,
+            to_account,
+            authority: setup.authority.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::TransferTokens { amount }.data(),
+    }
+}
+
+fn delegation(setup: &Setup) -> Pubkey {
+    Pubkey::find_program_address(&[b"delegation", setup.source.as_ref()], &secure_matching::ID).0
+}
+
+fn delegate_transfer_ix(setup: &Setup, signer: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::TransferAsDelegate {
+            from_account: setup.source,
+            to_account: setup.destination,
+            delegation: delegation(setup),
+            authority: signer,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::TransferAsDelegate { amount }.data(),
+    }
+}
+
+/// Owner approves `setup.delegate` to spend `amount` from `source`, with
+/// no practical expiry
+async fn approve(setup: &mut Setup, amount: u64) {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::ApproveDelegate {
+            owner: setup.authority.pubkey(),
+            token_account: setup.source,
+            delegate: setup.delegate.pubkey(),
+            delegation: delegation(setup),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::ApproveDelegate { amount, delegate_expiry: i64::MAX }.data(),
+    };
+    let authority = setup.authority.insecure_clone();
+    send(setup, ix, &authority).await.0.unwrap();
+}
+
+/// Send `ix` signed by `signer` and return its result with the transaction's log lines
+async fn send(setup: &mut Setup, ix: Instruction, signer: &Keypair) -> (Result<(), TransactionError>, Vec<String>) {
+    setup.env.send_with_logs(&[ix], &[signer]).await
+}
+
+async fn token_account(env: &mut TestEnv, address: Pubkey) -> TokenAccount {
+    TokenAccount::unpack(&env.account(address).await.unwrap().data).unwrap()
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn transfer_between_distinct_accounts_emits_event() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let ix = transfer_ix(&setup, setup.source, setup.destination, 400);
+    let (result, logs) = send(&mut setup, ix, &authority).await;
+    result.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE - 400);
+    assert_eq!(setup.env.token_balance(setup.destination).await, 400);
+
+    let transfers = events::<secure_matching::TransferExecuted>(&logs);
+    assert_eq!(transfers.len(), 1);
+    assert_eq!((transfers[0].from, transfers[0].to), (setup.source, setup.destination));
+    assert_eq!(transfers[0].amount, 400);
+}
+
+#[tokio::test]
+async fn self_transfer_is_rejected_without_event() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    // Same account aliased as from_account and to_account
+    let ix = transfer_ix(&setup, setup.source, setup.source, 400);
+    let (result, logs) = send(&mut setup, ix, &authority).await;
+
+    assert_eq!(result.unwrap_err(), custom(secure_matching::ErrorCode::SelfTransfer));
+    assert!(events::<secure_matching::TransferExecuted>(&logs).is_empty());
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
+
+#[tokio::test]
+async fn frozen_accounts_fail_with_token_account_frozen() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let frozen = || custom(secure_matching::ErrorCode::TokenAccountFrozen);
+
+    let ix = transfer_ix(&setup, setup.frozen_source, setup.destination, 400);
+    let (result, _) = send(&mut setup, ix, &authority).await;
+    assert_eq!(result.unwrap_err(), frozen());
+
+    let ix = transfer_ix(&setup, setup.source, setup.frozen_destination, 400);
+    let (result, _) = send(&mut setup, ix, &authority).await;
+    assert_eq!(result.unwrap_err(), frozen());
+
+    assert_eq!(setup.env.token_balance(setup.frozen_source).await, BALANCE);
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
+
+#[tokio::test]
+async fn approved_delegate_transfers_within_allowance() {
+    let mut setup = setup().await;
+    approve(&mut setup, 500).await;
+    let delegate = setup.delegate.insecure_clone();
+
+    let ix = delegate_transfer_ix(&setup, delegate.pubkey(), 300);
+    let (result, logs) = send(&mut setup, ix, &delegate).await;
+    result.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE - 300);
+    assert_eq!(setup.env.token_balance(setup.destination).await, 300);
+    // The token program spends down the allowance
+    assert_eq!(token_account(&mut setup.env, setup.source).await.delegated_amount, 200);
+
+    let transfers = events::<secure_matching::TransferExecuted>(&logs);
+    assert_eq!(transfers[0].authority, delegate.pubkey());
+}
+
+#[tokio::test]
+async fn delegate_cannot_exceed_allowance() {
+    let mut setup = setup().await;
+    approve(&mut setup, 500).await;
+    let delegate = setup.delegate.insecure_clone();
+
+    let ix = delegate_transfer_ix(&setup, delegate.pubkey(), 501);
+    let (result, _) = send(&mut setup, ix, &delegate).await;
+    assert_eq!(result.unwrap_err(), custom(secure_matching::ErrorCode::InsufficientDelegation));
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
+
+#[tokio::test]
+async fn non_delegate_signer_is_rejected() {
+    let mut setup = setup().await;
+    approve(&mut setup, 500).await;
+    let stranger = Keypair::new();
+
+    let ix = delegate_transfer_ix(&setup, stranger.pubkey(), 100);
+    let (result, _) = send(&mut setup, ix, &stranger).await;
+    assert_eq!(result.unwrap_err(), custom(secure_matching::ErrorCode::NotDelegate));
+    assert_eq!(setup.env.token_balance(setup.source).await, BALANCE);
+}
@@ -0,0 +1,225 @@
+//! # TWAP Accumulator Tests
+//!
+//! Plain `#[test]`s for the `secure_cpi` price accumulator: `Pool::update_twap`
+//! over a sequence of simulated swaps, and the wrapping math in `logic.rs`
+//! that makes overflow of `price_cumulative` harmless. `solana-program-test`
+//! scenarios check that `consult_twap` fails with `StaleData` once the
+//! snapshot is older than `MAX_TWAP_AGE`, until `restart_twap` renews it.
+//!
+//! ```bash
+//! cargo test --test twap
+//! ```
+
+mod common;
+
+use anchor_lang::{prelude::*, InstructionData, ToAccountMetas};
+use common::{add_anchor_account, custom, events, TestEnv};
+use secure_cpi::logic::{accumulate_price, spot_price_q64, twap_q64, LogicError};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+/// 1.0 in Q64.64
+const ONE: u128 = 1 << 64;
+
+fn pool(reserve_in: u64, reserve_out: u64, now: i64) -> secure_cpi::Pool {
+    secure_cpi::Pool {
+        reserve_in,
+        reserve_out,
+        fee_bps: 0,
+        last_twap_update: now,
+        ..common::cpi_pool(Pubkey::new_unique(), (Pubkey::new_unique(), Pubkey::new_unique()), 255)
+    }
+}
+
+/// What a swap does to the accumulator: advance at the old price, then move reserves
+fn swap(pool: &mut secure_cpi::Pool, now: i64, reserve_in: u64, reserve_out: u64) {
+    pool.update_twap(now).unwrap();
+    pool.reserve_in = reserve_in;
+    pool.reserve_out = reserve_out;
+}
+
+// ============================================================================
+// ACCUMULATOR OVER SWAPS
+// ============================================================================
+
+#[test]
+fn twap_weights_each_price_by_how_long_it_held() {
+    let mut pool = pool(1_000_000, 1_000_000, 0);
+    let start = pool.price_cumulative;
+
+    // 1.0 for 100s, 0.5 for 200s, 1.0 for 100s
+    swap(&mut pool, 100, 2_000_000, 1_000_000);
+    swap(&mut pool, 300, 1_000_000, 1_000_000);
+    let end = pool.cumulative_price_at(400).unwrap();
+
+    // (100 * 1.0 + 200 * 0.5 + 100 * 1.0) / 400 = 0.75
+    assert_eq!(twap_q64(start, end, 400).unwrap(), ONE * 3 / 4);
+}
+
+#[test]
+fn same_second_swaps_add_nothing() {
+    let mut pool = pool(1_000_000, 1_000_000, 0);
+    swap(&mut pool, 100, 1_000_000, 1_000_000);
+    let before = pool.price_cumulative;
+
+    // Push the price 10x and back within one timestamp
+    swap(&mut pool, 100, 100_000, 1_000_000);
+    swap(&mut pool, 100, 1_000_000, 1_000_000);
+
+    assert_eq!(pool.price_cumulative, before);
+    assert_eq!(twap_q64(0, pool.cumulative_price_at(200).unwrap(), 200).unwrap(), ONE);
+}
+
+#[test]
+fn cumulative_price_at_reads_without_writing() {
+    let pool = pool(1_000_000, 2_000_000, 10);
+
+    assert_eq!(pool.cumulative_price_at(20).unwrap(), ONE * 2 * 10);
+    assert_eq!(pool.price_cumulative, 0);
+    assert_eq!(pool.last_twap_update, 10);
+}
+
+#[test]
+fn empty_pool_accumulates_nothing() {
+    let mut pool = pool(0, 0, 0);
+    pool.update_twap(1_000).unwrap();
+
+    assert_eq!(pool.price_cumulative, 0);
+    assert_eq!(pool.last_twap_update, 1_000);
+}
+
+#[test]
+fn clock_going_backwards_is_rejected() {
+    let mut pool = pool(1_000_000, 1_000_000, 100);
+    assert!(pool.update_twap(99).is_err());
+    assert_eq!(pool.last_twap_update, 100);
+}
+
+// ============================================================================
+// WRAPPING MATH
+// ============================================================================
+
+#[test]
+fn twap_is_exact_across_accumulator_overflow() {
+    // 10s short of wrapping at a price of 1.0
+    let start = u128::MAX - 10 * ONE + 1;
+    let end = accumulate_price(start, ONE * 3 / 2, 100);
+
+    assert!(end < start);
+    assert_eq!(twap_q64(start, end, 100).unwrap(), ONE * 3 / 2);
+}
+
+#[test]
+fn spot_price_fits_for_any_u64_reserves() {
+    assert_eq!(spot_price_q64(1, u64::MAX).unwrap(), (u64::MAX as u128) << 64);
+    assert_eq!(spot_price_q64(u64::MAX, 1).unwrap(), 1);
+    assert!(spot_price_q64(0, 1).is_err());
+}
+
+#[test]
+fn twap_rejects_empty_window() {
+    assert!(twap_q64(0, ONE, 0).is_err());
+}
+
+// ============================================================================
+// STALENESS
+// ============================================================================
+
+/// Consulting period used by the scenarios
+const PERIOD: i64 = 60;
+
+struct Setup {
+    env: TestEnv,
+    owner: Keypair,
+    pool: Pubkey,
+    snapshot: Pubkey,
+}
+
+/// A 1M/1M pool and a snapshot the owner has just taken of it
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mints = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (pool, bump) =
+        Pubkey::find_program_address(&[b"pool", mints.0.as_ref(), mints.1.as_ref()], &secure_cpi::ID);
+    add_anchor_account(&mut program_test, pool, secure_cpi::ID, &common::cpi_pool(Pubkey::new_unique(), mints, bump));
+
+    let mut env = TestEnv::start(program_test).await;
+    let owner = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let (snapshot, _) =
+        Pubkey::find_program_address(&[b"twap", pool.as_ref(), owner.pubkey().as_ref()], &secure_cpi::ID);
+
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SnapshotTwap {
+            owner: owner.pubkey(),
+            snapshot,
+            pool,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::SnapshotTwap {}.data(),
+    };
+    env.send(&[ix], &[&owner]).await.unwrap();
+    Setup { env, owner, pool, snapshot }
+}
+
+fn window_accounts(setup: &Setup) -> Vec<AccountMeta> {
+    secure_cpi::accounts::ConsultTwap { owner: setup.owner.pubkey(), snapshot: setup.snapshot, pool: setup.pool }
+        .to_account_metas(None)
+}
+
+fn consult_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: window_accounts(setup),
+        data: secure_cpi::instruction::ConsultTwap { period: PERIOD }.data(),
+    }
+}
+
+fn restart_ix(setup: &Setup) -> Instruction {
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts: window_accounts(setup),
+        data: secure_cpi::instruction::RestartTwap {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn snapshot_at_max_age_is_consulted() {
+    let mut setup = setup().await;
+    setup.env.advance_clock(secure_cpi::MAX_TWAP_AGE).await;
+
+    let ix = consult_ix(&setup);
+    let (result, logs) = setup.env.send_with_logs(&[ix], &[&setup.owner]).await;
+    result.unwrap();
+
+    let consulted = events::<secure_cpi::TwapConsulted>(&logs);
+    assert_eq!(consulted.len(), 1);
+    assert_eq!((consulted[0].price_q64, consulted[0].window), (ONE, secure_cpi::MAX_TWAP_AGE));
+}
+
+#[tokio::test]
+async fn snapshot_past_max_age_fails_until_restarted() {
+    let mut setup = setup().await;
+    setup.env.advance_clock(secure_cpi::MAX_TWAP_AGE + 1).await;
+
+    // ✅ A day-old window says little about the current price
+    let ix = consult_ix(&setup);
+    let err = setup.env.send(&[ix], &[&setup.owner]).await.unwrap_err();
+    assert_eq!(err, custom(LogicError::StaleData));
+
+    let ix = restart_ix(&setup);
+    setup.env.send(&[ix], &[&setup.owner]).await.unwrap();
+    let now = setup.env.unix_timestamp().await;
+    let state: secure_cpi::TwapSnapshot = setup.env.fetch(setup.snapshot).await;
+    assert_eq!(state.timestamp, now);
+
+    setup.env.advance_clock(PERIOD).await;
+    let ix = consult_ix(&setup);
+    setup.env.send(&[ix], &[&setup.owner]).await.unwrap();
+}
@@ -0,0 +1,207 @@
+//! # Unstake Tests
+//!
+//! `solana-program-test` scenarios for `secure_matching::unstake` and
+//! `unstake_with_penalty`. After `min_stake_duration` the pool PDA signs the
+//! transfer from `pool_tokens` back to the staker's `user_tokens`, for part
+//! of the stake or all of it; a full exit also closes the staking account.
+//! Inside the lock `unstake` fails with `StakeLocked` and nothing moves,
+//! while `unstake_with_penalty` routes `early_exit_penalty_bps` of the
+//! amount to the reward vault and pays out the rest.
+//!
+//! ```bash
+//! cargo test --test unstake
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, events, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const STAKED: u64 = 1_000;
+/// When the stake was made
+const T0: i64 = 1_700_000_000;
+const LOCK: i64 = 24 * 60 * 60;
+/// 10% early exit penalty
+const PENALTY_BPS: u16 = 1_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    staker: Keypair,
+    pool: Pubkey,
+    staking_account: Pubkey,
+    user_tokens: Pubkey,
+    pool_tokens: Pubkey,
+    reward_vault: Pubkey,
+}
+
+/// `STAKED` staked at `T0` in a pool with a `LOCK`-second minimum, a
+/// `PENALTY_BPS` early exit penalty and no emissions, with the clock at `now`
+async fn setup(now: i64) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    let staker = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let reward_vault = add_token_account(&mut program_test, mint, pool, 0);
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, STAKED);
+    let user_tokens = add_token_account(&mut program_test, mint, staker.pubkey(), 0);
+
+    let pool_state = secure_matching::Pool {
+        total_staked: STAKED,
+        min_stake_duration: LOCK,
+        early_exit_penalty_bps: PENALTY_BPS,
+        last_reward_time: T0,
+        ..common::matching_pool(Pubkey::new_unique(), mint, mint, reward_vault, bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_matching::ID, &pool_state);
+
+    let (staking_account, bump) = Pubkey::find_program_address(
+        &[b"staking", staker.pubkey().as_ref(), pool.as_ref()],
+        &secure_matching::ID,
+    );
+    let staking_state = secure_matching::StakingAccount {
+        last_stake_time: T0,
+        ..common::matching_stake(staker.pubkey(), pool, STAKED, bump)
+    };
+    add_anchor_account(&mut program_test, staking_account, secure_matching::ID, &staking_state);
+
+    let mut env = TestEnv::start(program_test).await;
+    env.set_unix_timestamp(now).await;
+    Setup { env, staker, pool, staking_account, user_tokens, pool_tokens, reward_vault }
+}
+
+async fn unstake(setup: &mut Setup, amount: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::Unstake {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pool,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::Unstake { amount }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.staker]).await
+}
+
+async fn unstake_with_penalty(setup: &mut Setup, amount: u64) -> (Result<(), TransactionError>, Vec<String>) {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::UnstakeWithPenalty {
+            user: setup.staker.pubkey(),
+            staking_account: setup.staking_account,
+            user_tokens: setup.user_tokens,
+            pool_tokens: setup.pool_tokens,
+            pool: setup.pool,
+            reward_vault: setup.reward_vault,
+            owner: setup.staker.pubkey(),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::UnstakeWithPenalty { amount }.data(),
+    };
+    setup.env.send_with_logs(&[ix], &[&setup.staker]).await
+}
+
+// ============================================================================
+// UNSTAKE
+// ============================================================================
+
+#[tokio::test]
+async fn partial_unstake_after_lock_returns_that_amount() {
+    let mut setup = setup(T0 + LOCK).await;
+
+    unstake(&mut setup, 400).await.unwrap();
+
+    // ✅ Signed by the pool PDA: pool_tokens has no other authority
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, 400);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, STAKED - 400);
+
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(staking.amount, STAKED - 400);
+    let pool: secure_matching::Pool = setup.env.fetch(setup.pool).await;
+    assert_eq!(pool.total_staked, STAKED - 400);
+}
+
+#[tokio::test]
+async fn full_unstake_after_lock_returns_everything() {
+    let mut setup = setup(T0 + LOCK).await;
+
+    unstake(&mut setup, STAKED).await.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, STAKED);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, 0);
+    let pool: secure_matching::Pool = setup.env.fetch(setup.pool).await;
+    assert_eq!(pool.total_staked, 0);
+    // Nothing staked and nothing pending: the account is closed
+    assert!(setup.env.account(setup.staking_account).await.is_none());
+}
+
+#[tokio::test]
+async fn unstake_inside_lock_fails_with_stake_locked() {
+    let mut setup = setup(T0 + LOCK - 1).await;
+
+    let err = unstake(&mut setup, STAKED).await.unwrap_err();
+
+    assert_eq!(err, custom(secure_matching::ErrorCode::StakeLocked));
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, 0);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, STAKED);
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(staking.amount, STAKED);
+}
+
+// ============================================================================
+// UNSTAKE WITH PENALTY
+// ============================================================================
+
+#[tokio::test]
+async fn penalty_unstake_inside_lock_pays_the_reward_vault() {
+    let mut setup = setup(T0 + LOCK - 1).await;
+
+    let (result, logs) = unstake_with_penalty(&mut setup, STAKED).await;
+    result.unwrap();
+
+    // ✅ 10% to the reward vault, the rest to the staker
+    let penalty = STAKED / 10;
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, penalty);
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, STAKED - penalty);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, 0);
+
+    let applied = events::<secure_matching::PenaltyApplied>(&logs);
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].staking_account, setup.staking_account);
+    assert_eq!(applied[0].user, setup.staker.pubkey());
+    assert_eq!((applied[0].amount, applied[0].penalty), (STAKED, penalty));
+}
+
+#[tokio::test]
+async fn penalty_unstake_after_lock_pays_no_penalty() {
+    let mut setup = setup(T0 + LOCK).await;
+
+    let (result, logs) = unstake_with_penalty(&mut setup, 400).await;
+    result.unwrap();
+
+    assert_eq!(setup.env.token_balance(setup.reward_vault).await, 0);
+    assert_eq!(setup.env.token_balance(setup.user_tokens).await, 400);
+    assert_eq!(setup.env.token_balance(setup.pool_tokens).await, STAKED - 400);
+    assert!(events::<secure_matching::PenaltyApplied>(&logs).is_empty());
+
+    let staking: secure_matching::StakingAccount = setup.env.fetch(setup.staking_account).await;
+    assert_eq!(staking.amount, STAKED - 400);
+}
@@ -0,0 +1,183 @@
+//! # Per-User Deposit Cap Tests
+//!
+//! `solana-program-test` scenarios for `Pool::per_user_cap` in
+//! `secure_matching::deposit_to_pool`. Two users share a pool: one hitting
+//! their own cap must not stop the other, and the global
+//! `max_total_deposits` still applies on top.
+//!
+//! ```bash
+//! cargo test --test user_cap
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_packed, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use spl_token::state::Mint;
+
+const BALANCE: u64 = 1_000;
+const USER_CAP: u64 = 400;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Depositor {
+    keypair: Keypair,
+    staking_account: Pubkey,
+    tokens: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    mint: Pubkey,
+    pool: Pubkey,
+    pool_tokens: Pubkey,
+    depositors: [Depositor; 2],
+}
+
+/// Empty pool capped at `max_total_deposits` overall and `USER_CAP` per
+/// user, and two users holding `BALANCE` each
+async fn setup(max_total_deposits: u64) -> Setup {
+    let mut program_test =
+        ProgramTest::new("secure_matching", secure_matching::ID, processor!(secure_matching::entry));
+
+    let mint = Pubkey::new_unique();
+    add_packed(&mut program_test, mint, Mint { supply: u64::MAX, ..common::mint(None) });
+
+    let authority = Keypair::new();
+    let (pool, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &secure_matching::ID);
+    let pool_state = secure_matching::Pool {
+        max_total_deposits,
+        per_user_cap: USER_CAP,
+        ..common::matching_pool(authority.pubkey(), mint, mint, Pubkey::new_unique(), bump)
+    };
+    add_anchor_account(&mut program_test, pool, secure_matching::ID, &pool_state);
+    let pool_tokens = add_token_account(&mut program_test, mint, pool, 0);
+
+    let depositors = [(); 2].map(|_| {
+        let keypair = Keypair::new();
+        let (staking_account, bump) = Pubkey::find_program_address(
+            &[b"staking", keypair.pubkey().as_ref(), pool.as_ref()],
+            &secure_matching::ID,
+        );
+        let state = common::matching_stake(keypair.pubkey(), pool, 0, bump);
+        add_anchor_account(&mut program_test, staking_account, secure_matching::ID, &state);
+        let tokens = add_token_account(&mut program_test, mint, keypair.pubkey(), BALANCE);
+        Depositor { keypair, staking_account, tokens }
+    });
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, mint, pool, pool_tokens, depositors }
+}
+
+async fn deposit(setup: &mut Setup, depositor: usize, amount: u64) -> Result<(), TransactionError> {
+    let user = &setup.depositors[depositor].keypair;
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::DepositToPool {
+            user: user.pubkey(),
+            user_tokens: setup.depositors[depositor].tokens,
+            pool_tokens: setup.pool_tokens,
+            token_mint: setup.mint,
+            pool: setup.pool,
+            staking_account: setup.depositors[depositor].staking_account,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::DepositToPool { amount }.data(),
+    };
+    setup.env.send(&[ix], &[user]).await
+}
+
+async fn set_user_cap(setup: &mut Setup, signer: &Keypair, per_user_cap: u64) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_matching::ID,
+        accounts: secure_matching::accounts::SetCap {
+            pool: setup.pool,
+            authority: signer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_matching::instruction::SetUserCap { per_user_cap }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn pool_state(setup: &mut Setup) -> secure_matching::Pool {
+    setup.env.fetch(setup.pool).await
+}
+
+async fn user_total(setup: &mut Setup, depositor: usize) -> u64 {
+    let address = setup.depositors[depositor].staking_account;
+    setup.env.fetch::<secure_matching::StakingAccount>(address).await.total_deposited
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn capped_user_does_not_block_another() {
+    let mut setup = setup(u64::MAX).await;
+
+    // Up to exactly the cap, across two deposits
+    deposit(&mut setup, 0, USER_CAP - 100).await.unwrap();
+    deposit(&mut setup, 0, 100).await.unwrap();
+    assert_eq!(user_total(&mut setup, 0).await, USER_CAP);
+
+    // ❌ One more token is over user 0's cap
+    let err = deposit(&mut setup, 0, 1).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::UserCapExceeded));
+
+    // ✅ User 1 has their own allowance
+    deposit(&mut setup, 1, USER_CAP).await.unwrap();
+    assert_eq!(user_total(&mut setup, 1).await, USER_CAP);
+    assert_eq!(pool_state(&mut setup).await.total_deposits, 2 * USER_CAP);
+}
+
+#[tokio::test]
+async fn global_cap_still_applies_below_the_user_cap() {
+    // Room for one full user allowance plus half of another
+    let mut setup = setup(USER_CAP + USER_CAP / 2).await;
+
+    deposit(&mut setup, 0, USER_CAP).await.unwrap();
+
+    // User 1 is within their own cap, but the pool is not
+    let err = deposit(&mut setup, 1, USER_CAP).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::ErrorCode::DepositCapExceeded));
+    assert_eq!(user_total(&mut setup, 1).await, 0);
+
+    deposit(&mut setup, 1, USER_CAP / 2).await.unwrap();
+    assert_eq!(pool_state(&mut setup).await.total_deposits, USER_CAP + USER_CAP / 2);
+}
+
+#[tokio::test]
+async fn authority_can_raise_the_user_cap() {
+    let mut setup = setup(u64::MAX).await;
+    let authority = setup.authority.insecure_clone();
+
+    deposit(&mut setup, 0, USER_CAP).await.unwrap();
+    set_user_cap(&mut setup, &authority, 2 * USER_CAP).await.unwrap();
+    deposit(&mut setup, 0, USER_CAP).await.unwrap();
+
+    assert_eq!(user_total(&mut setup, 0).await, 2 * USER_CAP);
+    assert_eq!(pool_state(&mut setup).await.per_user_cap, 2 * USER_CAP);
+}
+
+#[tokio::test]
+async fn non_authority_cannot_set_user_cap() {
+    let mut setup = setup(u64::MAX).await;
+    let attacker = Keypair::new();
+
+    let err = set_user_cap(&mut setup, &attacker, u64::MAX).await.unwrap_err();
+    assert_eq!(err, custom(secure_matching::common_errors::CommonError::Unauthorized));
+    assert_eq!(pool_state(&mut setup).await.per_user_cap, USER_CAP);
+}
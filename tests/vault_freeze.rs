@@ -0,0 +1,201 @@
+//! # Vault Freeze Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::freeze_vault` and
+//! `unfreeze_vault`: withdrawals fail with `VaultFrozen` while frozen,
+//! deposits follow the freeze's `allow_deposits` policy, and only the vault
+//! authority can freeze or unfreeze.
+//!
+//! ```bash
+//! cargo test --test vault_freeze
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, add_token_account, custom, TestEnv};
+use secure_cpi::FREEZE_REASON_INCIDENT;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const BALANCE: u64 = 1_000;
+const AMOUNT: u64 = 100;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    /// The authority's own token account, holding `BALANCE`
+    user_tokens: Pubkey,
+}
+
+/// Unfrozen vault recording and holding `BALANCE`
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mint = Pubkey::new_unique();
+    let authority = Keypair::new();
+
+    let (vault, bump) = Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref()], &secure_cpi::ID);
+    let state = secure_cpi::Vault { deposit_count: 1, ..common::cpi_vault(authority.pubkey(), BALANCE, bump) };
+    add_anchor_account(&mut program_test, vault, secure_cpi::ID, &state);
+    let vault_tokens = add_token_account(&mut program_test, mint, vault, BALANCE);
+    let user_tokens = add_token_account(&mut program_test, mint, authority.pubkey(), BALANCE);
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, vault, vault_tokens, user_tokens }
+}
+
+fn set_freeze_accounts(setup: &Setup, signer: &Keypair) -> Vec<AccountMeta> {
+    secure_cpi::accounts::SetVaultFreeze { authority: signer.pubkey(), vault: setup.vault }.to_account_metas(None)
+}
+
+async fn freeze(setup: &mut Setup, signer: &Keypair, reason: u8, allow_deposits: bool) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: set_freeze_accounts(setup, signer),
+        data: secure_cpi::instruction::FreezeVault { reason, allow_deposits }.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn unfreeze(setup: &mut Setup, signer: &Keypair) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: set_freeze_accounts(setup, signer),
+        data: secure_cpi::instruction::UnfreezeVault {}.data(),
+    };
+    setup.env.send(&[ix], &[signer]).await
+}
+
+async fn deposit(setup: &mut Setup) -> Result<(), TransactionError> {
+    let authority = &setup.authority;
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Deposit {
+            user: authority.pubkey(),
+            user_tokens: setup.user_tokens,
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Deposit { amount: AMOUNT, idempotency_key: None }.data(),
+    };
+    setup.env.send(&[ix], &[authority]).await
+}
+
+async fn withdraw(setup: &mut Setup) -> Result<(), TransactionError> {
+    let authority = &setup.authority;
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::Withdraw {
+            authority: authority.pubkey(),
+            user_tokens: setup.user_tokens,
+            vault: setup.vault,
+            vault_tokens: setup.vault_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::Withdraw { amount: AMOUNT }.data(),
+    };
+    setup.env.send(&[ix], &[authority]).await
+}
+
+async fn vault_state(setup: &mut Setup) -> secure_cpi::Vault {
+    setup.env.fetch(setup.vault).await
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn frozen_vault_rejects_withdrawals() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    freeze(&mut setup, &authority, FREEZE_REASON_INCIDENT, true).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert!(vault.frozen);
+    assert_eq!(vault.freeze_reason, FREEZE_REASON_INCIDENT);
+
+    // ✅ Even the authority cannot move funds out while frozen
+    let err = withdraw(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::VaultFrozen));
+    assert_eq!(vault_state(&mut setup).await.balance, BALANCE);
+}
+
+#[tokio::test]
+async fn deposits_follow_the_freeze_policy() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    // Deposits allowed: funds keep coming in
+    freeze(&mut setup, &authority, FREEZE_REASON_INCIDENT, true).await.unwrap();
+    deposit(&mut setup).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.balance, BALANCE + AMOUNT);
+
+    // Re-freezing replaces the policy: now deposits are refused too
+    freeze(&mut setup, &authority, FREEZE_REASON_INCIDENT, false).await.unwrap();
+    let err = deposit(&mut setup).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::VaultFrozen));
+    assert_eq!(vault_state(&mut setup).await.balance, BALANCE + AMOUNT);
+}
+
+#[tokio::test]
+async fn unfreeze_restores_withdrawals_and_clears_the_reason() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    freeze(&mut setup, &authority, FREEZE_REASON_INCIDENT, false).await.unwrap();
+    unfreeze(&mut setup, &authority).await.unwrap();
+
+    let vault = vault_state(&mut setup).await;
+    assert!(!vault.frozen);
+    assert_eq!(vault.freeze_reason, 0);
+    assert_eq!(vault.sequence, 2);
+
+    withdraw(&mut setup).await.unwrap();
+    assert_eq!(vault_state(&mut setup).await.balance, BALANCE - AMOUNT);
+
+    // Nothing left to lift
+    let err = unfreeze(&mut setup, &authority).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::VaultNotFrozen));
+}
+
+#[tokio::test]
+async fn zero_reason_is_rejected() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+
+    let err = freeze(&mut setup, &authority, 0, false).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::InvalidFreezeReason));
+    assert!(!vault_state(&mut setup).await.frozen);
+}
+
+#[tokio::test]
+async fn only_the_authority_can_freeze_or_unfreeze() {
+    let mut setup = setup().await;
+    let authority = setup.authority.insecure_clone();
+    let attacker = Keypair::new();
+
+    // ❌ The vault PDA is derived from the signer, so an outsider's key
+    // points at a different address and fails the seeds check
+    let err = freeze(&mut setup, &attacker, FREEZE_REASON_INCIDENT, false).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+
+    freeze(&mut setup, &authority, FREEZE_REASON_INCIDENT, false).await.unwrap();
+    let err = unfreeze(&mut setup, &attacker).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+    assert!(vault_state(&mut setup).await.frozen);
+}
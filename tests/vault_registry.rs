@@ -0,0 +1,151 @@
+//! # Vault Registry Tests
+//!
+//! `solana-program-test` scenarios for the `secure_pda` per-authority
+//! `VaultRegistry`. Creating a vault adds its name and closing it removes
+//! the name again. A name already listed fails with `DuplicateVaultName`,
+//! and once 20 names are listed the 21st fails with `RegistryFull`.
+//!
+//! ```bash
+//! cargo test --test vault_registry
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, Space, ToAccountMetas};
+use common::{add_account, add_funded_keypair, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+
+/// `MAX_REGISTRY_ENTRIES` in `secure_pda`
+const MAX_ENTRIES: usize = 20;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    registry: Pubkey,
+}
+
+/// Registry for a funded authority, listing `names`. `None` leaves the
+/// registry to `initialize_registry`.
+async fn setup(names: Option<Vec<String>>) -> Setup {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+
+    let authority = add_funded_keypair(&mut program_test, LAMPORTS_PER_SOL);
+    let (registry, bump) = Pubkey::find_program_address(&[b"registry", authority.pubkey().as_ref()], &secure_pda::ID);
+    if let Some(names) = names {
+        let mut data = common::serialize(&secure_pda::VaultRegistry { authority: authority.pubkey(), names, bump });
+        data.resize(8 + secure_pda::VaultRegistry::INIT_SPACE, 0);
+        add_account(&mut program_test, registry, secure_pda::ID, data);
+    }
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, registry }
+}
+
+fn vault_address(authority: &Pubkey, name: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", authority.as_ref(), name.as_bytes()], &secure_pda::ID).0
+}
+
+async fn create_vault(setup: &mut Setup, name: &str) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::CreateVault {
+            vault: vault_address(&setup.authority.pubkey(), name),
+            registry: setup.registry,
+            authority: setup.authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::CreateVault { vault_name: name.to_string() }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+async fn close_vault(setup: &mut Setup, name: &str) -> Result<(), TransactionError> {
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::CloseVault {
+            vault: vault_address(&setup.authority.pubkey(), name),
+            registry: setup.registry,
+            authority: setup.authority.pubkey(),
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::CloseVault {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await
+}
+
+async fn names(setup: &mut Setup) -> Vec<String> {
+    let registry: secure_pda::VaultRegistry = setup.env.fetch(setup.registry).await;
+    registry.names
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn create_and_close_round_trip_the_registry() {
+    let mut setup = setup(None).await;
+
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::InitializeRegistry {
+            registry: setup.registry,
+            authority: setup.authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_pda::instruction::InitializeRegistry {}.data(),
+    };
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+    assert!(names(&mut setup).await.is_empty());
+
+    create_vault(&mut setup, "savings").await.unwrap();
+    create_vault(&mut setup, "checking").await.unwrap();
+    assert_eq!(names(&mut setup).await, vec!["savings", "checking"]);
+
+    close_vault(&mut setup, "savings").await.unwrap();
+    assert_eq!(names(&mut setup).await, vec!["checking"]);
+
+    // The name is free again once its vault is closed
+    create_vault(&mut setup, "savings").await.unwrap();
+    assert_eq!(names(&mut setup).await, vec!["checking", "savings"]);
+}
+
+#[tokio::test]
+async fn listed_name_fails_with_duplicate_vault_name() {
+    // Listed, though no vault exists at its address to collide with first
+    let mut setup = setup(Some(vec!["savings".to_string()])).await;
+
+    let err = create_vault(&mut setup, "savings").await.unwrap_err();
+
+    assert_eq!(err, custom(secure_pda::ErrorCode::DuplicateVaultName));
+    assert_eq!(names(&mut setup).await, vec!["savings"]);
+    let vault = vault_address(&setup.authority.pubkey(), "savings");
+    assert!(setup.env.account(vault).await.is_none());
+}
+
+#[tokio::test]
+async fn twenty_first_name_fails_with_registry_full() {
+    let full: Vec<String> = (0..MAX_ENTRIES).map(|i| format!("vault-{i}")).collect();
+    let mut setup = setup(Some(full.clone())).await;
+
+    let err = create_vault(&mut setup, "vault-20").await.unwrap_err();
+
+    assert_eq!(err, custom(secure_pda::ErrorCode::RegistryFull));
+    assert_eq!(names(&mut setup).await, full);
+    let vault = vault_address(&setup.authority.pubkey(), "vault-20");
+    assert!(setup.env.account(vault).await.is_none());
+}
@@ -0,0 +1,140 @@
+//! # CPI Whitelist Tests
+//!
+//! `solana-program-test` scenarios for `secure_cpi::invoke_whitelisted`:
+//! the admin adds a program, a caller invokes it, the admin removes it, and
+//! the same invocation is then rejected with `ProgramNotWhitelisted`.
+//!
+//! The System program is the CPI target: a lamport transfer signed by the
+//! caller is easy to observe and needs no extra program to be loaded.
+//!
+//! ```bash
+//! cargo test --test whitelist
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+};
+
+const TRANSFER: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn whitelist_address(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"whitelist", authority.as_ref()], &secure_cpi::ID).0
+}
+
+fn update_ix(authority: &Pubkey, program_id: Pubkey, add: bool) -> Instruction {
+    let accounts = secure_cpi::accounts::UpdateWhitelist {
+        whitelist: whitelist_address(authority),
+        authority: *authority,
+    }
+    .to_account_metas(None);
+    let data = if add {
+        secure_cpi::instruction::AddProgram { program_id }.data()
+    } else {
+        secure_cpi::instruction::RemoveProgram { program_id }.data()
+    };
+    Instruction { program_id: secure_cpi::ID, accounts, data }
+}
+
+/// `invoke_whitelisted` wrapping a System transfer of `TRANSFER` lamports
+fn invoke_transfer_ix(authority: &Pubkey, caller: &Pubkey, recipient: &Pubkey) -> Instruction {
+    let transfer = system_instruction::transfer(caller, recipient, TRANSFER);
+
+    let mut accounts = secure_cpi::accounts::InvokeWhitelisted {
+        caller: *caller,
+        whitelist: whitelist_address(authority),
+        target_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend([
+        AccountMeta::new(*caller, true),
+        AccountMeta::new(*recipient, false),
+    ]);
+
+    Instruction {
+        program_id: secure_cpi::ID,
+        accounts,
+        data: secure_cpi::instruction::InvokeWhitelisted { data: transfer.data }.data(),
+    }
+}
+
+/// The payer is the whitelist admin
+async fn setup() -> (TestEnv, Keypair) {
+    let program_test = ProgramTest::new("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    let mut env = TestEnv::start(program_test).await;
+    let payer = env.payer();
+
+    let attacker = Keypair::new();
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::InitializeWhitelist {
+            whitelist: whitelist_address(&payer),
+            authority: payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::InitializeWhitelist {}.data(),
+    };
+    env.send(&[ix], &[]).await.unwrap();
+
+    (env, attacker)
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn add_invoke_remove_then_invoke_fails() {
+    let (mut env, _) = setup().await;
+    let authority = env.payer();
+    let recipient = Pubkey::new_unique();
+
+    // ✅ Admin whitelists the System program
+    env.send(&[update_ix(&authority, system_program::ID, true)], &[]).await.unwrap();
+
+    // ✅ Whitelisted target: the CPI runs
+    env.send(&[invoke_transfer_ix(&authority, &authority, &recipient)], &[]).await.unwrap();
+    assert_eq!(env.lamports(recipient).await, TRANSFER);
+
+    // ✅ Admin removes it again
+    env.send(&[update_ix(&authority, system_program::ID, false)], &[]).await.unwrap();
+
+    // ❌ Same invocation is now rejected before any CPI happens
+    let err = env.send(&[invoke_transfer_ix(&authority, &authority, &recipient)], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ProgramNotWhitelisted));
+    assert_eq!(env.lamports(recipient).await, TRANSFER);
+}
+
+#[tokio::test]
+async fn invoke_fails_when_never_whitelisted() {
+    let (mut env, _) = setup().await;
+    let authority = env.payer();
+
+    let ix = invoke_transfer_ix(&authority, &authority, &Pubkey::new_unique());
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_cpi::ErrorCode::ProgramNotWhitelisted));
+}
+
+#[tokio::test]
+async fn non_admin_cannot_add_program() {
+    let (mut env, attacker) = setup().await;
+
+    // ❌ Attacker signs for the admin's whitelist: the PDA seeds use the
+    // signer's key, so the derived address doesn't match
+    let mut ix = update_ix(&attacker.pubkey(), system_program::ID, true);
+    ix.accounts[0].pubkey = whitelist_address(&env.payer());
+
+    assert!(env.send(&[ix], &[&attacker]).await.is_err());
+}
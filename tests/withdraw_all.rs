@@ -0,0 +1,100 @@
+//! # Withdraw All Tests
+//!
+//! `solana-program-test` scenarios for `withdraw_all` in `secure_pda` and
+//! `secure_signer`. The amount is read from the vault, so the
+//! `WithdrawalMade` event must report the whole prior balance and leave the
+//! vault at 0.
+//!
+//! ```bash
+//! cargo test --test withdraw_all
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{add_anchor_account, events, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const BALANCE: u64 = 1_000;
+
+// ============================================================================
+// SECURE PDA
+// ============================================================================
+
+#[tokio::test]
+async fn pda_withdraw_all_reports_the_prior_balance() {
+    let mut program_test = ProgramTest::new("secure_pda", secure_pda::ID, processor!(secure_pda::entry));
+    let authority = Keypair::new();
+    let (vault, bump) =
+        Pubkey::find_program_address(&[b"vault", authority.pubkey().as_ref(), b"savings"], &secure_pda::ID);
+    let state = common::pda_vault(authority.pubkey(), "savings", BALANCE, bump);
+    add_anchor_account(&mut program_test, vault, secure_pda::ID, &state);
+    let mut env = TestEnv::start(program_test).await;
+
+    let ix = Instruction {
+        program_id: secure_pda::ID,
+        accounts: secure_pda::accounts::Withdraw { vault, authority: authority.pubkey() }.to_account_metas(None),
+        data: secure_pda::instruction::WithdrawAll {}.data(),
+    };
+    let (result, logs) = env.send_with_logs(&[ix], &[&authority]).await;
+    result.unwrap();
+
+    let withdrawals = events::<secure_pda::WithdrawalMade>(&logs);
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!((withdrawals[0].amount, withdrawals[0].remaining_balance), (BALANCE, 0));
+    let state: secure_pda::Vault = env.fetch(vault).await;
+    assert_eq!(state.balance, 0);
+}
+
+// ============================================================================
+// SECURE SIGNER
+// ============================================================================
+
+#[tokio::test]
+async fn signer_withdraw_all_reports_the_prior_balance() {
+    let program_test = ProgramTest::new("secure_signer", secure_signer::ID, processor!(secure_signer::entry));
+    let mut env = TestEnv::start(program_test).await;
+    let authority = env.funded_keypair(LAMPORTS_PER_SOL).await;
+    let vault = Keypair::new();
+
+    let init = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Initialize {
+            vault: vault.pubkey(),
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_signer::instruction::Initialize {}.data(),
+    };
+    let deposit = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Deposit { vault: vault.pubkey(), depositor: authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_signer::instruction::Deposit { amount: BALANCE }.data(),
+    };
+    env.send(&[init, deposit], &[&vault, &authority]).await.unwrap();
+
+    let ix = Instruction {
+        program_id: secure_signer::ID,
+        accounts: secure_signer::accounts::Withdraw { vault: vault.pubkey(), authority: authority.pubkey() }
+            .to_account_metas(None),
+        data: secure_signer::instruction::WithdrawAll {}.data(),
+    };
+    let (result, logs) = env.send_with_logs(&[ix], &[&authority]).await;
+    result.unwrap();
+
+    let withdrawals = events::<secure_signer::WithdrawalMade>(&logs);
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!((withdrawals[0].amount, withdrawals[0].remaining_balance), (BALANCE, 0));
+    let state: secure_signer::Vault = env.fetch(vault.pubkey()).await;
+    assert_eq!(state.balance, 0);
+    assert_eq!(state.total_withdrawn, BALANCE);
+}
@@ -0,0 +1,161 @@
+//! # Writability Tests
+//!
+//! Passes the record account read-only to `vulnerable_writable::update` and
+//! `secure_writable::update`. Both mutations fail, but the vulnerable handler
+//! runs to completion and is stopped by the runtime, while the secure one is
+//! rejected by Anchor's `mut` check before it runs. Runs in
+//! `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test writable
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    system_program,
+    transaction::TransactionError,
+};
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+async fn setup() -> TestEnv {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("vulnerable_writable", vulnerable_writable::ID, processor!(vulnerable_writable::entry));
+    program_test.add_program("secure_writable", secure_writable::ID, processor!(secure_writable::entry));
+    TestEnv::start(program_test).await
+}
+
+fn record_pda(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"record", authority.as_ref()], program_id).0
+}
+
+/// Downgrade `address` to read-only in `accounts`
+fn readonly(mut accounts: Vec<AccountMeta>, address: Pubkey) -> Vec<AccountMeta> {
+    for meta in accounts.iter_mut().filter(|m| m.pubkey == address) {
+        meta.is_writable = false;
+    }
+    accounts
+}
+
+// ============================================================================
+// VULNERABLE PROGRAM
+// ============================================================================
+
+async fn vulnerable_record(env: &mut TestEnv) -> Pubkey {
+    let authority = env.payer();
+    let record = record_pda(&vulnerable_writable::ID, &authority);
+    let ix = Instruction {
+        program_id: vulnerable_writable::ID,
+        accounts: vulnerable_writable::accounts::Initialize { record, authority, system_program: system_program::ID }
+            .to_account_metas(None),
+        data: vulnerable_writable::instruction::Initialize {}.data(),
+    };
+    env.send(&[ix], &[]).await.unwrap();
+    record
+}
+
+async fn vulnerable_value(env: &mut TestEnv, record: Pubkey) -> u64 {
+    env.fetch::<vulnerable_writable::Record>(record).await.value
+}
+
+#[tokio::test]
+async fn vulnerable_update_on_readonly_record_is_only_caught_by_runtime() {
+    let mut env = setup().await;
+    let record = vulnerable_record(&mut env).await;
+
+    // `Update` has no `mut`, so its metas are read-only already
+    let accounts = vulnerable_writable::accounts::Update { record, authority: env.payer() }
+        .to_account_metas(None);
+    assert!(!accounts[0].is_writable);
+    let ix = Instruction {
+        program_id: vulnerable_writable::ID,
+        accounts,
+        data: vulnerable_writable::instruction::Update { value: 42 }.data(),
+    };
+
+    // The handler returned Ok; the runtime rejected the write afterwards
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::ReadonlyDataModified));
+    assert_eq!(vulnerable_value(&mut env, record).await, 0);
+}
+
+// ============================================================================
+// SECURE PROGRAM
+// ============================================================================
+
+async fn secure_record(env: &mut TestEnv) -> Pubkey {
+    let authority = env.payer();
+    let record = record_pda(&secure_writable::ID, &authority);
+    let ix = Instruction {
+        program_id: secure_writable::ID,
+        accounts: secure_writable::accounts::Initialize { record, authority, system_program: system_program::ID }
+            .to_account_metas(None),
+        data: secure_writable::instruction::Initialize {}.data(),
+    };
+    env.send(&[ix], &[]).await.unwrap();
+    record
+}
+
+async fn secure_value(env: &mut TestEnv, record: Pubkey) -> u64 {
+    env.fetch::<secure_writable::Record>(record).await.value
+}
+
+fn secure_update_ix(env: &TestEnv, record: Pubkey, value: u64) -> Instruction {
+    Instruction {
+        program_id: secure_writable::ID,
+        accounts: secure_writable::accounts::Update { record, authority: env.payer() }
+            .to_account_metas(None),
+        data: secure_writable::instruction::Update { value }.data(),
+    }
+}
+
+#[tokio::test]
+async fn secure_update_on_readonly_record_fails_constraint_mut() {
+    let mut env = setup().await;
+    let record = secure_record(&mut env).await;
+
+    let mut ix = secure_update_ix(&env, record, 42);
+    ix.accounts = readonly(ix.accounts, record);
+
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintMut));
+    assert_eq!(secure_value(&mut env, record).await, 0);
+}
+
+#[tokio::test]
+async fn secure_update_on_writable_record_succeeds() {
+    let mut env = setup().await;
+    let record = secure_record(&mut env).await;
+
+    let ix = secure_update_ix(&env, record, 42);
+    env.send(&[ix], &[]).await.unwrap();
+
+    assert_eq!(secure_value(&mut env, record).await, 42);
+}
+
+#[tokio::test]
+async fn secure_update_many_rejects_readonly_remaining_account() {
+    let mut env = setup().await;
+    let record = secure_record(&mut env).await;
+
+    let mut accounts = secure_writable::accounts::UpdateMany { authority: env.payer() }
+        .to_account_metas(None);
+    accounts.push(AccountMeta::new_readonly(record, false));
+    let ix = Instruction {
+        program_id: secure_writable::ID,
+        accounts,
+        data: secure_writable::instruction::UpdateMany { value: 42 }.data(),
+    };
+
+    let err = env.send(&[ix], &[]).await.unwrap_err();
+    assert_eq!(err, custom(secure_writable::ErrorCode::AccountNotWritable));
+    assert_eq!(secure_value(&mut env, record).await, 0);
+}
@@ -0,0 +1,201 @@
+//! # Wrong Bump Tests
+//!
+//! Plain `#[test]`s showing that a hardcoded bump may not be a PDA at all,
+//! or may be a valid but non-canonical one, followed by
+//! `solana-program-test` scenarios: `vulnerable_wrong_bump::withdraw`
+//! fails for an authority whose canonical bump is below 255, while
+//! `secure_wrong_bump::withdraw` works for any authority.
+//!
+//! ```bash
+//! cargo test --test wrong_bump
+//! ```
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::{custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const TREASURY: u64 = 10_000_000;
+const AMOUNT: u64 = 1_000_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+fn treasury(authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury", authority.as_ref()], program_id)
+}
+
+/// A fresh authority whose canonical treasury bump in `program_id`
+/// satisfies `keep`
+fn authority_where(program_id: &Pubkey, keep: impl Fn(&Pubkey, u8) -> bool) -> Keypair {
+    loop {
+        let authority = Keypair::new();
+        let (_, bump) = treasury(&authority.pubkey(), program_id);
+        if keep(&authority.pubkey(), bump) {
+            return authority;
+        }
+    }
+}
+
+/// Both programs, with a `TREASURY`-lamport treasury at the canonical
+/// address for each of `authorities` in each program
+async fn setup(authorities: &[&Keypair]) -> TestEnv {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "vulnerable_wrong_bump",
+        vulnerable_wrong_bump::ID,
+        processor!(vulnerable_wrong_bump::entry),
+    );
+    program_test.add_program("secure_wrong_bump", secure_wrong_bump::ID, processor!(secure_wrong_bump::entry));
+
+    for authority in authorities {
+        for program_id in [vulnerable_wrong_bump::ID, secure_wrong_bump::ID] {
+            let (address, _) = treasury(&authority.pubkey(), &program_id);
+            program_test.add_account(
+                address,
+                Account { lamports: TREASURY, data: vec![], owner: system_program::ID, executable: false, rent_epoch: 0 },
+            );
+        }
+        program_test.add_account(
+            authority.pubkey(),
+            Account { lamports: TREASURY, data: vec![], owner: system_program::ID, executable: false, rent_epoch: 0 },
+        );
+    }
+
+    TestEnv::start(program_test).await
+}
+
+fn vulnerable_withdraw_ix(treasury: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: vulnerable_wrong_bump::ID,
+        accounts: vulnerable_wrong_bump::accounts::Withdraw {
+            treasury,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: vulnerable_wrong_bump::instruction::Withdraw { amount: AMOUNT }.data(),
+    }
+}
+
+fn secure_withdraw_ix(treasury: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: secure_wrong_bump::ID,
+        accounts: secure_wrong_bump::accounts::Withdraw {
+            treasury,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: secure_wrong_bump::instruction::Withdraw { amount: AMOUNT }.data(),
+    }
+}
+
+// ============================================================================
+// DERIVATION
+// ============================================================================
+
+#[test]
+fn canonical_bump_is_what_find_program_address_returns() {
+    let authority = Pubkey::new_unique();
+    let (address, bump) = treasury(&authority, &secure_wrong_bump::ID);
+
+    // ✅ The bump Anchor puts in ctx.bumps re-derives the same address
+    let derived =
+        Pubkey::create_program_address(&[b"treasury", authority.as_ref(), &[bump]], &secure_wrong_bump::ID).unwrap();
+    assert_eq!(derived, address);
+}
+
+#[test]
+fn hardcoded_255_is_not_a_pda_when_the_canonical_bump_is_lower() {
+    let authority = authority_where(&vulnerable_wrong_bump::ID, |_, bump| bump < 255).pubkey();
+
+    // ❌ What vulnerable_wrong_bump::withdraw computes: on the curve, no PDA
+    let result = Pubkey::create_program_address(&[b"treasury", authority.as_ref(), &[255]], &vulnerable_wrong_bump::ID);
+    assert!(result.is_err());
+}
+
+#[test]
+fn hardcoded_lower_bump_can_derive_a_non_canonical_address() {
+    let program_id = vulnerable_wrong_bump::ID;
+    let at = |authority: &Pubkey, bump: u8| {
+        Pubkey::create_program_address(&[b"treasury", authority.as_ref(), &[bump]], &program_id)
+    };
+    // Both 255 and 254 are off the curve: 255 is canonical, 254 is not
+    let authority = authority_where(&program_id, |key, bump| bump == 255 && at(key, 254).is_ok()).pubkey();
+
+    let (canonical, _) = treasury(&authority, &program_id);
+    let non_canonical = at(&authority, 254).unwrap();
+
+    // ❌ A second valid PDA for the same seeds
+    assert_ne!(non_canonical, canonical);
+}
+
+// ============================================================================
+// VULNERABLE
+// ============================================================================
+
+#[tokio::test]
+async fn vulnerable_withdraw_fails_when_the_canonical_bump_is_below_255() {
+    let unlucky = authority_where(&vulnerable_wrong_bump::ID, |_, bump| bump < 255);
+    let mut env = setup(&[&unlucky]).await;
+    let (address, _) = treasury(&unlucky.pubkey(), &vulnerable_wrong_bump::ID);
+
+    let ix = vulnerable_withdraw_ix(address, unlucky.pubkey());
+    let err = env.send(&[ix], &[&unlucky]).await.unwrap_err();
+
+    assert_eq!(err, custom(vulnerable_wrong_bump::ErrorCode::PdaNotFound));
+    // The lamports are still there, and always will be
+    assert_eq!(env.lamports(address).await, TREASURY);
+}
+
+#[tokio::test]
+async fn vulnerable_withdraw_works_only_when_255_is_canonical() {
+    let lucky = authority_where(&vulnerable_wrong_bump::ID, |_, bump| bump == 255);
+    let mut env = setup(&[&lucky]).await;
+    let (address, _) = treasury(&lucky.pubkey(), &vulnerable_wrong_bump::ID);
+
+    let ix = vulnerable_withdraw_ix(address, lucky.pubkey());
+    env.send(&[ix], &[&lucky]).await.unwrap();
+
+    assert_eq!(env.lamports(address).await, TREASURY - AMOUNT);
+}
+
+// ============================================================================
+// SECURE
+// ============================================================================
+
+#[tokio::test]
+async fn secure_withdraw_signs_with_the_canonical_bump() {
+    let unlucky = authority_where(&secure_wrong_bump::ID, |_, bump| bump < 255);
+    let mut env = setup(&[&unlucky]).await;
+    let (address, _) = treasury(&unlucky.pubkey(), &secure_wrong_bump::ID);
+
+    let ix = secure_withdraw_ix(address, unlucky.pubkey());
+    env.send(&[ix], &[&unlucky]).await.unwrap();
+
+    assert_eq!(env.lamports(address).await, TREASURY - AMOUNT);
+}
+
+#[tokio::test]
+async fn secure_withdraw_rejects_another_authoritys_treasury() {
+    let victim = Keypair::new();
+    let attacker = Keypair::new();
+    let mut env = setup(&[&victim, &attacker]).await;
+    let (address, _) = treasury(&victim.pubkey(), &secure_wrong_bump::ID);
+
+    let ix = secure_withdraw_ix(address, attacker.pubkey());
+    let err = env.send(&[ix], &[&attacker]).await.unwrap_err();
+
+    assert_eq!(err, custom(anchor_lang::error::ErrorCode::ConstraintSeeds));
+    assert_eq!(env.lamports(address).await, TREASURY);
+}
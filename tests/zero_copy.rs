@@ -0,0 +1,211 @@
+//! # Zero-Copy Pool Tests
+//!
+//! Runs the same swap against `secure_cpi` (`Account<Pool>`) and
+//! `secure_zero_copy` (`AccountLoader<BigPool>`) with identical reserves and
+//! fee, and checks both produce the same output and resulting state. Also
+//! writes a reward tier at the far end of the zero-copy array. Runs in
+//! `solana-program-test`.
+//!
+//! ```bash
+//! cargo test --test zero_copy
+//! ```
+
+mod common;
+
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use bytemuck::Zeroable;
+use common::{add_account, add_anchor_account, add_token_account, custom, TestEnv};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const RESERVE: u64 = 1_000_000;
+const FEE_BPS: u16 = 30;
+const SWAP_IN: u64 = 10_000;
+
+// ============================================================================
+// SETUP HELPERS
+// ============================================================================
+
+/// Token accounts around one pool
+struct Side {
+    pool: Pubkey,
+    pool_token_in: Pubkey,
+    pool_token_out: Pubkey,
+    user_in: Pubkey,
+    user_out: Pubkey,
+}
+
+struct Setup {
+    env: TestEnv,
+    authority: Keypair,
+    user: Keypair,
+    cpi: Side,
+    zero_copy: Side,
+}
+
+fn add_side(program_test: &mut ProgramTest, pool: Pubkey, mint_in: Pubkey, mint_out: Pubkey, user: Pubkey) -> Side {
+    Side {
+        pool,
+        pool_token_in: add_token_account(program_test, mint_in, pool, RESERVE),
+        pool_token_out: add_token_account(program_test, mint_out, pool, RESERVE),
+        user_in: add_token_account(program_test, mint_in, user, SWAP_IN),
+        user_out: add_token_account(program_test, mint_out, user, 0),
+    }
+}
+
+/// `RESERVE` / `RESERVE` pools at `FEE_BPS` in both programs, over the same
+/// mint pair, and a user holding `SWAP_IN` for each
+async fn setup() -> Setup {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program("secure_cpi", secure_cpi::ID, processor!(secure_cpi::entry));
+    program_test.add_program("secure_zero_copy", secure_zero_copy::ID, processor!(secure_zero_copy::entry));
+
+    let mint_in = Pubkey::new_unique();
+    let mint_out = Pubkey::new_unique();
+    let authority = Keypair::new();
+    let user = Keypair::new();
+
+    let (cpi_pool, bump) =
+        Pubkey::find_program_address(&[b"pool", mint_in.as_ref(), mint_out.as_ref()], &secure_cpi::ID);
+    let state = secure_cpi::Pool {
+        reserve_in: RESERVE,
+        reserve_out: RESERVE,
+        fee_bps: FEE_BPS,
+        ..common::cpi_pool(authority.pubkey(), (mint_in, mint_out), bump)
+    };
+    add_anchor_account(&mut program_test, cpi_pool, secure_cpi::ID, &state);
+
+    let (zero_copy_pool, bump) = Pubkey::find_program_address(
+        &[b"big_pool", mint_in.as_ref(), mint_out.as_ref()],
+        &secure_zero_copy::ID,
+    );
+    let mut state = secure_zero_copy::BigPool::zeroed();
+    state.authority = authority.pubkey();
+    state.token_in_mint = mint_in;
+    state.token_out_mint = mint_out;
+    state.reserve_in = RESERVE;
+    state.reserve_out = RESERVE;
+    state.fee_bps = FEE_BPS;
+    state.bump = bump;
+    let mut data = secure_zero_copy::BigPool::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&state));
+    add_account(&mut program_test, zero_copy_pool, secure_zero_copy::ID, data);
+
+    let cpi = add_side(&mut program_test, cpi_pool, mint_in, mint_out, user.pubkey());
+    let zero_copy = add_side(&mut program_test, zero_copy_pool, mint_in, mint_out, user.pubkey());
+
+    let env = TestEnv::start(program_test).await;
+    Setup { env, authority, user, cpi, zero_copy }
+}
+
+async fn big_pool(setup: &mut Setup) -> secure_zero_copy::BigPool {
+    let account = setup.env.account(setup.zero_copy.pool).await.unwrap();
+    assert_eq!(&account.data[..8], secure_zero_copy::BigPool::DISCRIMINATOR);
+    *bytemuck::from_bytes(&account.data[8..])
+}
+
+fn set_tier_ix(setup: &Setup, authority: Pubkey, index: u8) -> Instruction {
+    Instruction {
+        program_id: secure_zero_copy::ID,
+        accounts: secure_zero_copy::accounts::SetTier { pool: setup.zero_copy.pool, authority }
+            .to_account_metas(None),
+        data: secure_zero_copy::instruction::SetTier { index, min_volume: 5_000_000, bonus_bps: 250 }.data(),
+    }
+}
+
+// ============================================================================
+// SCENARIOS
+// ============================================================================
+
+#[tokio::test]
+async fn zero_copy_swap_matches_account_swap() {
+    let mut setup = setup().await;
+
+    let ix = Instruction {
+        program_id: secure_cpi::ID,
+        accounts: secure_cpi::accounts::SwapTokens {
+            user: setup.user.pubkey(),
+            user_token_in: setup.cpi.user_in,
+            user_token_out: setup.cpi.user_out,
+            pool: setup.cpi.pool,
+            pool_token_in: setup.cpi.pool_token_in,
+            pool_token_out: setup.cpi.pool_token_out,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_cpi::instruction::SwapTokens { amount_in: SWAP_IN, min_amount_out: 1, reserve_tolerance: None }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+
+    let ix = Instruction {
+        program_id: secure_zero_copy::ID,
+        accounts: secure_zero_copy::accounts::SwapTokens {
+            user: setup.user.pubkey(),
+            user_token_in: setup.zero_copy.user_in,
+            user_token_out: setup.zero_copy.user_out,
+            pool: setup.zero_copy.pool,
+            pool_token_in: setup.zero_copy.pool_token_in,
+            pool_token_out: setup.zero_copy.pool_token_out,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: secure_zero_copy::instruction::SwapTokens { amount_in: SWAP_IN, min_amount_out: 1 }.data(),
+    };
+    setup.env.send(&[ix], &[&setup.user]).await.unwrap();
+
+    // Same tokens out
+    let cpi_out = setup.env.token_balance(setup.cpi.user_out).await;
+    let zero_copy_out = setup.env.token_balance(setup.zero_copy.user_out).await;
+    assert!(cpi_out > 0);
+    assert_eq!(zero_copy_out, cpi_out);
+
+    // Same pool state
+    let cpi_pool: secure_cpi::Pool = setup.env.fetch(setup.cpi.pool).await;
+    let zero_copy_pool = big_pool(&mut setup).await;
+    assert_eq!(
+        (zero_copy_pool.reserve_in, zero_copy_pool.reserve_out),
+        (cpi_pool.reserve_in, cpi_pool.reserve_out)
+    );
+    assert_eq!(zero_copy_pool.fees_collected, cpi_pool.fees_collected);
+    assert_eq!(zero_copy_pool.total_volume, SWAP_IN);
+
+    // Reserves + fees still fully backed by the pool's token accounts
+    assert_eq!(
+        setup.env.token_balance(setup.zero_copy.pool_token_in).await,
+        zero_copy_pool.reserve_in + zero_copy_pool.fees_collected
+    );
+    assert_eq!(setup.env.token_balance(setup.zero_copy.pool_token_out).await, zero_copy_pool.reserve_out);
+}
+
+#[tokio::test]
+async fn authority_sets_last_tier_in_place() {
+    let mut setup = setup().await;
+    let last = (secure_zero_copy::MAX_TIERS - 1) as u8;
+
+    let ix = set_tier_ix(&setup, setup.authority.pubkey(), last);
+    setup.env.send(&[ix], &[&setup.authority]).await.unwrap();
+
+    let pool = big_pool(&mut setup).await;
+    let tier = pool.tiers[last as usize];
+    assert_eq!((tier.min_volume, tier.bonus_bps), (5_000_000, 250));
+    // Neighbouring fields untouched
+    assert_eq!(pool.tiers[last as usize - 1].min_volume, 0);
+    assert_eq!((pool.reserve_in, pool.reserve_out), (RESERVE, RESERVE));
+}
+
+#[tokio::test]
+async fn tier_out_of_range_and_non_authority_are_rejected() {
+    let mut setup = setup().await;
+
+    let ix = set_tier_ix(&setup, setup.authority.pubkey(), secure_zero_copy::MAX_TIERS as u8);
+    let err = setup.env.send(&[ix], &[&setup.authority]).await.unwrap_err();
+    assert_eq!(err, custom(secure_zero_copy::ErrorCode::TierOutOfRange));
+
+    let ix = set_tier_ix(&setup, setup.user.pubkey(), 0);
+    let err = setup.env.send(&[ix], &[&setup.user]).await.unwrap_err();
+    assert_eq!(err, custom(secure_zero_copy::common_errors::CommonError::Unauthorized));
+}
@@ -0,0 +1,142 @@
+//! # Vulnerable Account Owner Example
+//!
+//! This program demonstrates a CRITICAL vulnerability: missing program ownership verification.
+//!
+//! ## Vulnerability
+//! The `pool` account is taken as a raw `AccountInfo` and deserialized by hand.
+//! Nothing checks that `pool.owner == program_id`, so the program happily reads
+//! data from an account that some OTHER program created and controls.
+//!
+//! ## Attack Vector
+//! 1. Attacker deploys their own program (or reuses any program that lets them write bytes)
+//! 2. Attacker creates an account owned by that program with the `Pool` layout
+//! 3. Attacker writes themselves in as `authority` with a huge `total_deposits`
+//! 4. Attacker passes the fake account as `pool` to `withdraw_from_pool`
+//! 5. The data deserializes cleanly and every field check passes
+//!
+//! ## Impact
+//! - Fake account injection
+//! - Attacker-chosen state is trusted as program state
+//! - Unauthorized withdrawals from the real pool token account
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("Vuln222222222222222222222222222222222222222");
+
+#[program]
+pub mod vulnerable_account_owner {
+    use super::*;
+
+    /// Initialize a pool owned by this program
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.total_deposits = 0;
+
+        msg!("Pool initialized for authority: {}", pool.authority);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Withdraw using a pool read from an unchecked AccountInfo
+    ///
+    /// This function is CRITICALLY VULNERABLE because:
+    /// 1. `pool` is an `AccountInfo`, so Anchor performs NO owner check
+    /// 2. `try_deserialize` only checks the 8-byte discriminator, which is public
+    /// 3. An attacker-owned account with the right bytes is indistinguishable
+    ///
+    /// An attacker can:
+    /// - Create an account owned by their own program with the `Pool` layout
+    /// - Set `authority` to themselves and `total_deposits` to anything
+    /// - Pass it here and pass every "check" below
+    pub fn withdraw_from_pool(ctx: Context<WithdrawFromPool>, amount: u64) -> Result<()> {
+        // ❌ VULNERABLE: No check that ctx.accounts.pool.owner == program_id!
+        // The discriminator is derived from the struct name, anyone can write it
+        let data = ctx.accounts.pool.try_borrow_data()?;
+        let pool = Pool::try_deserialize(&mut &data[..])?;
+
+        // ❌ THESE CHECKS ARE USELESS - the attacker wrote these fields
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            pool.authority,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            pool.total_deposits >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        msg!("Withdrew {} from pool", amount);
+
+        // In real code, a CPI transfer out of the pool token account would happen here
+        // But the pool state it relies on was never verified!
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromPool<'info> {
+    // ❌ VULNERABLE: AccountInfo skips the owner check entirely
+    // Any account owned by any program can be passed here
+    /// CHECK: This SHOULD be verified as owned by this program but ISN'T
+    pub pool: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    /// The authority allowed to withdraw from the pool
+    pub authority: Pubkey,
+    /// Total tokens deposited into the pool
+    pub total_deposits: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// FAKE ACCOUNT INJECTION:
+// -----------------------
+// 1. Attacker deploys FakePoolProgram with a single instruction that
+//    writes arbitrary bytes into an account it owns
+//
+// 2. Attacker builds fake pool data:
+//    - discriminator: sha256("account:Pool")[..8]  (public knowledge)
+//    - authority:     attacker's pubkey
+//    - total_deposits: u64::MAX
+//
+// 3. Attacker creates the account (owner = FakePoolProgram) and writes the data
+//
+// 4. Attacker calls withdraw_from_pool:
+//    - pool: fake account (owner != vulnerable_account_owner::ID)
+//    - authority: attacker (signs transaction)
+//
+// 5. try_deserialize succeeds, authority matches, total_deposits is "enough"
+// 6. Withdrawal proceeds against attacker-controlled state
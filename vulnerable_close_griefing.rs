@@ -0,0 +1,149 @@
+//! # Vulnerable Account Closing Example
+//!
+//! This program demonstrates a MEDIUM severity vulnerability: a close
+//! instruction that never checks who is closing.
+//!
+//! The secure counterpart is `secure_pda::close_vault`, which already binds
+//! the vault to the signing authority through its seeds and `has_one`.
+//!
+//! ## Vulnerability
+//! `close_vault` checks that the vault is a real PDA of this program and
+//! that it is empty. It does not check that the signer is the vault's
+//! authority, and it sends the reclaimed rent to the signer.
+//!
+//! ## Attack Vector
+//! 1. Victim creates a vault; it sits empty between deposits
+//! 2. Attacker calls `close_vault` with the victim's vault and themselves
+//!    as `closer`
+//! 3. Seeds (derived from the stored authority) and the empty check pass
+//! 4. The vault is closed and its rent lamports go to the attacker
+//!
+//! ## Impact
+//! - Any empty vault can be deleted by anyone, at any time
+//! - The attacker is paid the victim's rent deposit for doing it
+//! - Anything keyed on the vault's existence (integrations, pending
+//!   deposits in the same slot, stored configuration) breaks until the
+//!   victim pays rent again to recreate it, and the attacker can repeat
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("VulnEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE");
+
+#[program]
+pub mod vulnerable_close_griefing {
+    use super::*;
+
+    /// Create a named vault PDA for `authority`
+    pub fn create_vault(ctx: Context<CreateVault>, name: String) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.balance = 0;
+        vault.name = name;
+        vault.bump = ctx.bumps.vault;
+
+        msg!("Created vault '{}'", vault.name);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Close an empty vault and reclaim its rent
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. `closer` is any signer
+    /// 2. No `has_one = authority` ties the vault to that signer
+    /// 3. `close = closer` pays the vault's rent to whoever called
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+
+        // The balance check makes this griefing rather than theft
+        require!(vault.balance == 0, ErrorCode::VaultNotEmpty);
+
+        // ❌ VULNERABLE: No check that closer == vault.authority
+
+        msg!("Closed vault '{}'", vault.name);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateVault<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    // ❌ VULNERABLE: Seeds come from the vault's own stored authority, so
+    // they prove the vault is genuine but say nothing about the signer
+    #[account(
+        mut,
+        seeds = [b"vault", vault.authority.as_ref(), vault.name.as_bytes()],
+        bump = vault.bump,
+        close = closer
+    )]
+    pub vault: Account<'info, Vault>,
+
+    // ❌ VULNERABLE: Any signer, and the rent recipient
+    #[account(mut)]
+    pub closer: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+    #[max_len(32)]
+    pub name: String,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Vault must be empty before closing")]
+    VaultNotEmpty,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Victim's vault: seeds ["vault", victim, "savings"], balance 0, rent R
+//
+//   attacker → close_vault
+//                vault  = victim's vault
+//                closer = attacker (signer)
+//
+//   seeds ["vault", vault.authority, vault.name] → matches   ✓
+//   balance == 0                                             ✓
+//   close = closer → R lamports to attacker, vault deleted
+//
+// ============================================================================
+// SECURITY ANALYSIS: secure_pda::close_vault
+// ============================================================================
+//
+// The same call against secure_pda fails twice over:
+//
+// 1. Seeds are ["vault", authority.key(), vault.name] where `authority` is
+//    the SIGNER, not the stored value. With the attacker signing, the
+//    derived address is not the victim's vault → ConstraintSeeds. This is
+//    the check that fires first.
+// 2. has_one = authority @ Unauthorized would reject it anyway if the
+//    seeds did not include the signer (e.g. a vault keyed by name only)
+// 3. close = authority sends rent to the vault's authority, so even a
+//    bypass would not pay the attacker
+//
+// The registry account carries the same ["registry", authority] binding.
@@ -15,6 +15,10 @@
 //! ## DO NOT USE IN PRODUCTION
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
 
 declare_id!("Vuln555555555555555555555555555555555555555");
 
@@ -83,8 +87,25 @@ pub mod vulnerable_cpi {
         // ❌ VULNERABLE: External call BEFORE state update!
         // Malicious callback can re-enter while state is stale
         
-        // Simulate external call (in real code, this would be CPI)
+        // ❌ VULNERABLE: Calls whatever program the user passes in,
+        // forwarding the remaining accounts and the deposit amount
         msg!("Making external call...");
+        let ix = Instruction {
+            program_id: ctx.accounts.callback_program.key(),
+            accounts: ctx
+                .remaining_accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: amount.to_le_bytes().to_vec(),
+        };
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.callback_program.to_account_info());
+        invoke(&ix, &account_infos)?;
         
         // If the external call triggers a callback that calls deposit again:
         // - vault.balance is still the OLD value
@@ -149,6 +170,10 @@ pub struct DepositWithCallback<'info> {
     
     #[account(mut)]
     pub vault: Account<'info, Vault>,
+    
+    // ❌ VULNERABLE: Any program can be passed as the callback
+    /// CHECK: Not verified
+    pub callback_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -212,6 +237,12 @@ pub enum ErrorCode {
 //    
 // 3. Repeat until drained
 //
+// NOTE: The Solana runtime rejects indirect reentrancy (A → B → A) with
+// ReentrancyNotAllowed, so this exact flow fails on-chain today. Only direct
+// self-recursion is allowed. Don't rely on that: the stale-state bug is still
+// there, and it's reachable via self-CPI or a future runtime change.
+// See tests/reentrancy.rs and tests/programs/reentrant_callback.
+//
 // AUTHORITY BYPASS:
 // -----------------
 // 1. Attacker finds pool with funds
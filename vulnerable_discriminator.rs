@@ -0,0 +1,199 @@
+//! # Vulnerable Discriminator Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: type confusion between
+//! account types with the same layout.
+//!
+//! ## Vulnerability
+//! `update_summary` walks `remaining_accounts` as raw `AccountInfo` and reads
+//! each pool's `reserve` straight from the account bytes. It checks the owner,
+//! but never checks the 8-byte discriminator, so ANY account this program owns
+//! is treated as a `Pool`.
+//!
+//! `Position { owner, amount }` has the same layout as `Pool { authority, reserve }`,
+//! and users choose their own `amount`.
+//!
+//! ## Attack Vector
+//! 1. Attacker opens a `Position` with `amount = 1_000_000_000_000`
+//! 2. Attacker calls `update_summary`, passing the real pools AND their position
+//! 3. The position passes the owner check (this program created it)
+//! 4. Its `amount` is read at the `reserve` offset and added to the total
+//! 5. `summary.total_reserves` is inflated by an attacker-chosen value
+//!
+//! ## Impact
+//! - Inflated TVL / reserve figures
+//! - Anything priced off `total_reserves` (shares, collateral limits) is wrong
+//! - Passing the same pool twice double counts it as well
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("Vuln999999999999999999999999999999999999999");
+
+/// Offset of `Pool::reserve` in account data: discriminator + authority
+const RESERVE_OFFSET: usize = 8 + 32;
+
+#[program]
+pub mod vulnerable_discriminator {
+    use super::*;
+
+    /// Initialize the reserve summary
+    pub fn initialize_summary(ctx: Context<InitializeSummary>) -> Result<()> {
+        let summary = &mut ctx.accounts.summary;
+        summary.authority = ctx.accounts.authority.key();
+        summary.total_reserves = 0;
+        Ok(())
+    }
+
+    /// Create a pool with an initial reserve
+    pub fn initialize_pool(ctx: Context<InitializePool>, reserve: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.reserve = reserve;
+
+        msg!("Pool initialized with reserve {}", reserve);
+        Ok(())
+    }
+
+    /// Open a user position; the user picks `amount`
+    pub fn open_position(ctx: Context<OpenPosition>, amount: u64) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.amount = amount;
+
+        msg!("Position opened with amount {}", amount);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Sum pool reserves passed as remaining accounts
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. Only the owner is checked, not the account TYPE
+    /// 2. Any same-sized account this program owns is read as a `Pool`
+    /// 3. The same pool can be passed more than once
+    pub fn update_summary(ctx: Context<UpdateSummary>) -> Result<()> {
+        let mut total: u64 = 0;
+
+        for info in ctx.remaining_accounts.iter() {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidAccountOwner);
+
+            // ❌ VULNERABLE: No discriminator check!
+            // A Position's `amount` sits at the same offset as a Pool's `reserve`
+            let data = info.try_borrow_data()?;
+            let reserve = data
+                .get(RESERVE_OFFSET..RESERVE_OFFSET + 8)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or(ErrorCode::InvalidAccountData)?;
+
+            total = total.checked_add(reserve).ok_or(ErrorCode::Overflow)?;
+        }
+
+        ctx.accounts.summary.total_reserves = total;
+
+        msg!("Total reserves: {}", total);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeSummary<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Summary::INIT_SPACE
+    )]
+    pub summary: Account<'info, Summary>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSummary<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub summary: Account<'info, Summary>,
+    pub authority: Signer<'info>,
+    // Pools are passed as remaining accounts
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Summary {
+    pub authority: Pubkey,
+    pub total_reserves: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub reserve: u64,
+}
+
+/// Same layout as `Pool`, different meaning
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Account is not owned by this program")]
+    InvalidAccountOwner,
+    #[msg("Account data too short")]
+    InvalidAccountData,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Unauthorized")]
+    Unauthorized,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Pools: A (reserve 1_000), B (reserve 2_000)
+// Attacker: open_position(1_000_000_000_000) → Position P
+//
+// Honest call:    update_summary([A, B])        → total_reserves = 3_000
+// Attacker call:  update_summary([A, B, P])     → total_reserves = 1_000_000_003_000
+// Also possible:  update_summary([B, B, B])     → total_reserves = 6_000
+//
+// Account bytes, both 48 long:
+//   Pool:     [disc("Pool")    ][authority: 32][reserve: 8]
+//   Position: [disc("Position")][owner:     32][amount:  8]
+//                ^ only difference, and it is never read
@@ -0,0 +1,286 @@
+//! # Vulnerable Donation Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: share math and
+//! the quoted share price read the vault's live token balance, which anyone
+//! can raise with a plain SPL transfer.
+//!
+//! ## Vulnerability
+//! `deposit`, `withdraw` and `share_price` all use `vault_tokens.amount` as
+//! the vault's assets. A transfer straight to `vault_tokens` (a "donation")
+//! never goes through `deposit`, mints no shares, and still counts.
+//!
+//! ## Attack Vector
+//! 1. Attacker holds shares in the vault, or posts them as collateral in a
+//!    lending market that values them at `share_price`
+//! 2. Attacker transfers tokens directly to `vault_tokens`
+//! 3. `share_price` jumps in the same transaction; the collateral is now
+//!    "worth" more and the attacker borrows against it
+//! 4. Deposits that land after the donation mint fewer shares per token
+//!
+//! ## Impact
+//! - Any consumer of `share_price` can be moved atomically by whoever is
+//!   willing to lock up tokens for one transaction
+//! - Depositors are priced off a balance nobody deposited; combined with
+//!   rounding, see `vulnerable_inflation` for the zero-share case
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("VulnMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMMM");
+
+/// Fixed-point scale of `share_price` (1.0 = `SCALE`)
+pub const SCALE: u64 = 1_000_000;
+
+#[program]
+pub mod vulnerable_donation {
+    use super::*;
+
+    /// Create a vault and its token account for `mint`
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.total_shares = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Open an empty share position in `vault`
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.vault = ctx.accounts.vault.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Deposit tokens for shares priced off the live balance
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let total_shares = ctx.accounts.vault.total_shares;
+
+        // ❌ VULNERABLE: Donations are in here
+        let total_assets = ctx.accounts.vault_tokens.amount;
+
+        let shares = if total_shares == 0 {
+            amount
+        } else {
+            ((amount as u128)
+                .checked_mul(total_shares as u128)
+                .ok_or(ErrorCode::Overflow)?
+                / total_assets as u128) as u64
+        };
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        ctx.accounts.vault.total_shares = total_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.position.shares = ctx.accounts.position.shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Deposited {} tokens for {} shares", amount, shares);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Redeem `shares` for a pro-rata part of the live balance
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        require!(ctx.accounts.position.shares >= shares, ErrorCode::InsufficientShares);
+
+        let amount = ((shares as u128)
+            .checked_mul(ctx.accounts.vault_tokens.amount as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / ctx.accounts.vault.total_shares as u128) as u64;
+
+        let mint = ctx.accounts.vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        ctx.accounts.position.shares -= shares;
+        ctx.accounts.vault.total_shares -= shares;
+
+        msg!("Redeemed {} shares for {} tokens", shares, amount);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Tokens per share, scaled by `SCALE`, for integrators
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. It reads `vault_tokens.amount`, which anyone can raise
+    /// 2. Integrators call it in the same transaction the donation lands in
+    pub fn share_price(ctx: Context<SharePrice>) -> Result<u64> {
+        let total_shares = ctx.accounts.vault.total_shares;
+        if total_shares == 0 {
+            return Ok(SCALE);
+        }
+
+        // ❌ VULNERABLE: Live balance
+        let price = (ctx.accounts.vault_tokens.amount as u128)
+            .checked_mul(SCALE as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / total_shares as u128;
+
+        msg!("Share price {}", price);
+        Ok(price as u64)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, has_one = vault, constraint = position.owner == user.key())]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, has_one = vault, constraint = position.owner == user.key())]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SharePrice<'info> {
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub vault_tokens: Pubkey,
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Not enough shares")]
+    InsufficientShares,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Step                         vault_tokens   total_shares   share_price
+// ----                         ------------   ------------   -----------
+// Honest deposits                    10_000         10_000           1.0
+// Attacker donates 10_000            20_000         10_000           2.0
+// Victim deposit(10_000)             30_000         15_000           2.0
+//                              10_000 * 10_000 / 20_000 = 5_000 shares
+//
+// One transaction, one transfer, and every reader of share_price sees the
+// vault's shares worth double. A lending market pricing share collateral
+// this way lends against value that leaves with the attacker's withdraw.
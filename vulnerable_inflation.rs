@@ -0,0 +1,288 @@
+//! # Vulnerable Share Inflation Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: the first-depositor
+//! ("donation") inflation attack on a share-based vault.
+//!
+//! ## Vulnerability
+//! Shares are priced off the vault's live token balance:
+//! `shares = amount * total_shares / vault_tokens.amount`, rounding down.
+//! Anyone can raise `vault_tokens.amount` with a plain SPL transfer, without
+//! minting shares. An empty vault also accepts a 1-token first deposit.
+//!
+//! ## Attack Vector
+//! 1. Attacker is the first depositor and deposits 1 token → 1 share
+//! 2. Attacker transfers 10_000 tokens straight to `vault_tokens` (a "donation")
+//! 3. One share is now backed by 10_001 tokens
+//! 4. Victim deposits 10_000: `10_000 * 1 / 10_001 = 0` shares
+//! 5. The deposit succeeds, the victim owns nothing, and the attacker's
+//!    single share is worth 20_001 tokens
+//!
+//! ## Impact
+//! - Later depositors lose their entire deposit to rounding
+//! - Attacker front-runs each large deposit with a slightly larger donation
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("VulnAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+
+/// ❌ VULNERABLE: Shares for depositing `amount` into a vault holding
+/// `total_assets` tokens against `total_shares`
+///
+/// - `total_assets` is the raw token balance, so donations move the price
+/// - No minimum first deposit
+/// - Rounding to zero shares is accepted
+pub fn shares_for_deposit(amount: u64, total_assets: u64, total_shares: u64) -> Result<u64> {
+    if total_shares == 0 {
+        return Ok(amount);
+    }
+
+    let shares = (amount as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / total_assets as u128;
+
+    Ok(shares as u64)
+}
+
+#[program]
+pub mod vulnerable_inflation {
+    use super::*;
+
+    /// Create a vault and its token account for `mint`
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.mint = ctx.accounts.mint.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.total_shares = 0;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Open an empty share position in `vault`
+    pub fn open_position(ctx: Context<OpenPosition>) -> Result<()> {
+        let position = &mut ctx.accounts.position;
+        position.owner = ctx.accounts.owner.key();
+        position.vault = ctx.accounts.vault.key();
+        position.shares = 0;
+        position.bump = ctx.bumps.position;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Deposit tokens for shares
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. The share price reads `vault_tokens.amount`, which donations inflate
+    /// 2. A 1-token first deposit sets the price for everyone after
+    /// 3. A deposit that mints 0 shares still takes the tokens
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+
+        // ❌ VULNERABLE: Live balance, not recorded assets
+        let shares = shares_for_deposit(
+            amount,
+            ctx.accounts.vault_tokens.amount,
+            vault.total_shares,
+        )?;
+
+        // ❌ VULNERABLE: shares may be 0 here
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_tokens.to_account_info(),
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        ctx.accounts.vault.total_shares = ctx.accounts.vault.total_shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.position.shares = ctx.accounts.position.shares
+            .checked_add(shares)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(DepositMade {
+            vault: ctx.accounts.vault.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+        });
+
+        msg!("Deposited {} tokens for {} shares", amount, shares);
+        Ok(())
+    }
+
+    /// Redeem `shares` for their pro-rata part of the vault balance
+    pub fn withdraw(ctx: Context<Withdraw>, shares: u64) -> Result<()> {
+        require!(ctx.accounts.position.shares >= shares, ErrorCode::InsufficientShares);
+
+        let amount = ((shares as u128)
+            .checked_mul(ctx.accounts.vault_tokens.amount as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / ctx.accounts.vault.total_shares as u128) as u64;
+
+        let mint = ctx.accounts.vault.mint;
+        let seeds = &[b"vault".as_ref(), mint.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        ctx.accounts.position.shares -= shares;
+        ctx.accounts.vault.total_shares -= shares;
+
+        msg!("Redeemed {} shares for {} tokens", shares, amount);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = vault,
+        seeds = [b"vault_tokens", vault.key().as_ref()],
+        bump
+    )]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct OpenPosition<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Position::INIT_SPACE,
+        seeds = [b"position", vault.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, has_one = vault, constraint = position.owner == user.key())]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.vault_tokens)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, has_one = vault, constraint = position.owner == user.key())]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub vault_tokens: Pubkey,
+    pub total_shares: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub bump: u8,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Not enough shares")]
+    InsufficientShares,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Step                         vault_tokens   total_shares   shares minted
+// ----                         ------------   ------------   -------------
+// Attacker deposit(1)                     1              1               1
+// Attacker donates 10_000            10_001              1               -
+// Victim deposit(10_000)             20_001              1               0
+//                                                 10_000 * 1 / 10_001 = 0
+// Attacker withdraw(1)                    0              0   → 20_001 tokens
+//
+// Attacker cost: 10_001 tokens. Attacker profit: the victim's 10_000.
+// The victim's transaction succeeds; nothing on chain flags the loss.
@@ -0,0 +1,154 @@
+//! # Vulnerable Instruction Introspection Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: a sensitive
+//! instruction that can be invoked through CPI by any program the user
+//! happens to call ("CPI-in-the-middle").
+//!
+//! ## Vulnerability
+//! `withdraw` only checks that `authority` signed. Signer privileges carry
+//! through CPI: if the authority signs a transaction that calls some other
+//! program, that program may invoke `withdraw` with the authority as signer
+//! and any `destination` it likes.
+//!
+//! ## Attack Vector
+//! 1. Attacker deploys a program that looks harmless (an "airdrop claim")
+//! 2. Victim signs a transaction calling it, passing their own wallet
+//! 3. The attacker program CPIs `withdraw(vault.lamports - rent)` with the
+//!    victim as signer and the attacker's wallet as `destination`
+//! 4. Every check in `withdraw` passes; the vault is drained
+//!
+//! ## Impact
+//! - Any transaction the authority signs can empty the vault
+//! - The user interface shows a call to the attacker program, not to this one
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("VulnBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB");
+
+#[program]
+pub mod vulnerable_introspection {
+    use super::*;
+
+    /// Create the lamport vault PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// Deposit lamports into the vault
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.depositor.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        msg!("Deposited {} lamports", amount);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Withdraw lamports to `destination`
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. It never asks HOW it was invoked
+    /// 2. A CPI from any program carries the authority's signature
+    /// 3. That program chooses `destination`
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        // ❌ VULNERABLE: No check that this is a top-level instruction
+
+        let vault = ctx.accounts.vault.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(vault.data_len());
+        require!(
+            vault.lamports().saturating_sub(amount) >= rent_floor,
+            ErrorCode::InsufficientFunds
+        );
+
+        **vault.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.destination.try_borrow_mut_lamports()? += amount;
+
+        msg!("Withdrew {} lamports to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority
+    )]
+    pub vault: Account<'info, Vault>,
+
+    // Signed... but possibly for a different program
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Withdrawal would leave the vault below rent exemption")]
+    InsufficientFunds,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Transaction signed by the victim:
+//
+//   [0] attacker_program::claim_airdrop
+//         accounts: vulnerable_introspection, vault, victim (signer),
+//                   attacker_wallet
+//       └─ CPI vulnerable_introspection::withdraw(all)
+//            authority   = victim        (signer privilege inherited)
+//            destination = attacker_wallet
+//
+// has_one, seeds and Signer all pass. The wallet prompt only named
+// attacker_program, so the victim never saw a withdrawal.
@@ -10,11 +10,15 @@
 //! ## Attack Vectors
 //! 1. Pass victim's token account as source
 //! 2. Deposit worthless tokens, get valuable pool shares
-//! 3. Claim rewards from wrong vault
+//! 3. Claim rewards from wrong vault: a staking account from one pool,
+//!    paid out of another pool's reward vault
 //! 
 //! ## DO NOT USE IN PRODUCTION
 
 use anchor_lang::prelude::*;
+#[cfg(feature = "bench")]
+use anchor_lang::solana_program::log::sol_log_compute_units;
+use anchor_spl::token::{self, Token, Transfer};
 
 declare_id!("Vuln666666666666666666666666666666666666666");
 
@@ -33,14 +37,27 @@ pub mod vulnerable_matching {
         ctx: Context<TransferTokens>,
         amount: u64,
     ) -> Result<()> {
+        // Bench: everything before this line is account validation
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
+        
         // ❌ VULNERABLE: from_account might not belong to authority!
         // Attacker can pass any token account as source
         
+        emit!(TransferExecuted {
+            from: ctx.accounts.from_account.key(),
+            to: ctx.accounts.to_account.key(),
+            amount,
+            authority: ctx.accounts.authority.key(),
+        });
+        
         msg!("Transferring {} tokens", amount);
         
         // In real code, CPI transfer would happen here
         // But from_account ownership isn't verified!
         
+        #[cfg(feature = "bench")]
+        sol_log_compute_units();
         Ok(())
     }
 
@@ -52,6 +69,9 @@ pub mod vulnerable_matching {
     /// 3. Attacker deposits FAKE tokens (no mint check)
     /// 4. Attacker receives pool shares worth real USDC
     /// 5. Attacker redeems shares for real USDC
+    ///
+    /// Also credits the requested `amount`, not what arrived. With a
+    /// fee-on-transfer mint the pool records more than it received.
     pub fn deposit_to_pool(
         ctx: Context<DepositToPool>,
         amount: u64,
@@ -67,6 +87,13 @@ pub mod vulnerable_matching {
         // Calculate shares (simplified)
         let shares = amount;  // 1:1 for simplicity
         
+        emit!(DepositMade {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            shares,
+        });
+        
         msg!("Deposited {} tokens, received {} shares", amount, shares);
         
         // In real code, transfer and mint shares would happen
@@ -83,22 +110,54 @@ pub mod vulnerable_matching {
     /// 3. Sets pending_rewards to maximum
     /// 4. Calls claim with real pool's reward vault
     /// 5. Drains entire reward vault
+    ///
+    /// Cross-pool variant, with a legitimate staking account:
+    /// 1. Attacker earns rewards in pool A
+    /// 2. Calls claim with pool B and pool B's reward vault
+    /// 3. Pool B's PDA signs, so the transfer succeeds
+    /// 4. Pool A's rewards are paid out of pool B's stakers' vault
     pub fn claim_rewards(
         ctx: Context<ClaimRewards>,
     ) -> Result<()> {
-        let staking = &ctx.accounts.staking_account;
+        let staking = &mut ctx.accounts.staking_account;
         let rewards = staking.pending_rewards;
         
-        // ❌ VULNERABLE: reward_vault might not be the pool's vault!
-        // No verification of relationship between staking account and vault
+        // ❌ VULNERABLE: staking.pool is never compared with pool, and
+        // reward_vault is never compared with pool.reward_vault
         
         require!(rewards > 0, ErrorCode::NoRewards);
+        staking.pending_rewards = 0;
         
-        msg!("Claiming {} rewards", rewards);
+        // ❌ Signs for whichever pool was passed in
+        let pool = &ctx.accounts.pool;
+        let pool_seeds = &[
+            b"pool".as_ref(),
+            pool.token_mint.as_ref(),
+            &[pool.bump],
+        ];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.user_reward_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[&pool_seeds[..]],
+            ),
+            rewards,
+        )?;
         
-        // In real code, transfer from reward_vault would happen
-        // But vault relationship isn't verified!
+        // ❌ pool is whatever the (unverified) staking account claims
+        emit!(RewardsClaimed {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: staking.pool,
+            amount: rewards,
+        });
         
+        msg!("Claimed {} rewards", rewards);
         Ok(())
     }
 
@@ -115,6 +174,13 @@ pub mod vulnerable_matching {
         staking.amount = staking.amount.checked_add(amount)
             .ok_or(ErrorCode::Overflow)?;
         
+        emit!(Staked {
+            staking_account: staking.key(),
+            user: ctx.accounts.user.key(),
+            pool: ctx.accounts.pool.key(),
+            amount,
+        });
+        
         msg!("Staked {} tokens", amount);
         Ok(())
     }
@@ -163,6 +229,9 @@ pub struct ClaimRewards<'info> {
     #[account(mut)]
     pub staking_account: Account<'info, StakingAccount>,
     
+    // ❌ VULNERABLE: Any pool; not checked against staking_account.pool
+    pub pool: Account<'info, Pool>,
+    
     // ❌ VULNERABLE: No verification this is the correct reward vault
     /// CHECK: Should verify this is pool's reward vault
     #[account(mut)]
@@ -171,6 +240,8 @@ pub struct ClaimRewards<'info> {
     /// CHECK: User's reward account
     #[account(mut)]
     pub user_reward_account: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
@@ -192,6 +263,8 @@ pub struct Pool {
     pub total_deposits: u64,
     pub token_mint: Pubkey,
     pub reward_vault: Pubkey,
+    /// Canonical bump of `["pool", token_mint]`, which owns `reward_vault`
+    pub bump: u8,
 }
 
 #[account]
@@ -203,6 +276,38 @@ pub struct StakingAccount {
     pub pending_rewards: u64,
 }
 
+#[event]
+pub struct TransferExecuted {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DepositMade {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub staking_account: Pubkey,
+    pub user: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Overflow")]
@@ -248,3 +353,15 @@ pub enum ErrorCode {
 //    - reward_vault: real pool's reward vault
 // 3. No relationship verification!
 // 4. Entire reward vault drained to attacker
+//
+// CROSS-POOL REWARD DRAIN:
+// ------------------------
+// No fake account needed:
+// 1. Pools A and B pay rewards in the same mint
+// 2. Attacker stakes in A and earns pending_rewards honestly
+// 3. Attacker calls claim_rewards:
+//    - staking_account: their real pool A staking account
+//    - pool: pool B
+//    - reward_vault: pool B's reward vault
+// 4. staking_account.pool (A) is never compared with pool (B)
+// 5. Pool B's PDA signs the transfer: B's stakers pay A's rewards
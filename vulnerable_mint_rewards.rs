@@ -0,0 +1,205 @@
+//! # Vulnerable Mint Rewards Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: minting rewards
+//! from a mint the program does not control.
+//!
+//! ## Vulnerability
+//! `initialize_pool` accepts any mint as `reward_mint` and never looks at its
+//! `mint_authority`. `claim_rewards` then mints with whatever `mint_authority`
+//! signer the caller brings. The pool's accounting assumes it is the only
+//! issuer of the reward token; nothing makes that true.
+//!
+//! ## Attack Vector
+//! 1. Admin creates the reward mint, keeps `mint_authority` on their own key
+//!    and initializes the pool over it
+//! 2. Users stake and are credited rewards; `total_minted` tracks claims
+//! 3. Admin (or whoever steals the admin key) calls `spl_token::mint_to`
+//!    directly, outside the program, for any amount
+//! 4. Every claim also needs the admin key to co-sign, so claims stop the
+//!    day that key goes offline
+//!
+//! ## Impact
+//! - Reward supply is unbounded: `total_minted` no longer describes the
+//!   token, and holders are diluted at the key holder's discretion
+//! - Credited rewards are only as claimable as an off-chain key is available
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+
+declare_id!("VulnHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHHH");
+
+#[program]
+pub mod vulnerable_mint_rewards {
+    use super::*;
+
+    /// ❌ VULNERABLE: Create a reward pool over any mint
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.total_minted = 0;
+        pool.bump = ctx.bumps.pool;
+        Ok(())
+    }
+
+    /// Create `owner`'s reward account in `pool`
+    pub fn open_reward_account(ctx: Context<OpenRewardAccount>) -> Result<()> {
+        let rewards = &mut ctx.accounts.rewards;
+        rewards.owner = ctx.accounts.owner.key();
+        rewards.pool = ctx.accounts.pool.key();
+        rewards.pending = 0;
+        rewards.bump = ctx.bumps.rewards;
+        Ok(())
+    }
+
+    /// Credit `amount` of rewards to a user (pool authority only)
+    pub fn credit_rewards(ctx: Context<CreditRewards>, amount: u64) -> Result<()> {
+        let rewards = &mut ctx.accounts.rewards;
+        rewards.pending = rewards.pending
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Mint pending rewards with a caller-supplied authority
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let amount = ctx.accounts.rewards.pending;
+        require!(amount > 0, ErrorCode::NothingToClaim);
+
+        // ❌ VULNERABLE: Whoever signs as mint_authority is trusted; the
+        // program never checks it controls issuance of this mint
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_tokens.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token::mint_to(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        ctx.accounts.rewards.pending = 0;
+        let pool = &mut ctx.accounts.pool;
+        pool.total_minted = pool.total_minted
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Minted {} reward tokens", amount);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", reward_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    // ❌ VULNERABLE: mint_authority could be anyone
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRewardAccount<'info> {
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RewardAccount::INIT_SPACE,
+        seeds = [b"rewards", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub rewards: Account<'info, RewardAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreditRewards<'info> {
+    #[account(has_one = authority)]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(mut, has_one = pool)]
+    pub rewards: Account<'info, RewardAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut, has_one = reward_mint)]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(mut, has_one = pool, has_one = owner)]
+    pub rewards: Account<'info, RewardAccount>,
+
+    #[account(mut)]
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub user_tokens: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    // ❌ VULNERABLE: An external key, trusted because it signed
+    pub mint_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    /// Rewards minted through `claim_rewards`
+    pub total_minted: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardAccount {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    /// Credited and not yet minted
+    pub pending: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("No rewards to claim")]
+    NothingToClaim,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// reward_mint.mint_authority = admin key, pool.total_minted = 0
+//
+//   claim_rewards()  (user, admin co-signs)  supply = 100, total_minted = 100
+//   spl_token::mint_to(admin, 1_000_000)     supply = 1_000_100
+//                                            total_minted = 100
+//
+// The program recorded every token it minted and the supply still grew
+// 10_000x: it was never the only one able to mint.
@@ -33,6 +33,12 @@ pub mod vulnerable_overflow {
         let vault = &mut ctx.accounts.vault;
         vault.authority = ctx.accounts.authority.key();
         vault.balance = 0;
+        
+        emit!(VaultInitialized {
+            vault: vault.key(),
+            authority: vault.authority,
+        });
+        
         Ok(())
     }
 
@@ -50,6 +56,13 @@ pub mod vulnerable_overflow {
         // In release mode, this silently wraps instead of panicking
         vault.balance = vault.balance + amount;
         
+        emit!(DepositMade {
+            vault: vault.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+            new_balance: vault.balance,
+        });
+        
         msg!("Deposited {}. New balance: {}", amount, vault.balance);
         Ok(())
     }
@@ -68,6 +81,14 @@ pub mod vulnerable_overflow {
         // 100 - 200 = 18,446,744,073,709,551,515 (u64::MAX - 99)
         vault.balance = vault.balance - amount;
         
+        // remaining_balance reports the wrapped value as-is
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+        });
+        
         msg!("Withdrew {}. New balance: {}", amount, vault.balance);
         Ok(())
     }
@@ -92,6 +113,14 @@ pub mod vulnerable_overflow {
         let rewards = staking.amount * staking.rate * time_staked as u64;
         
         staking.pending_rewards = rewards;
+        
+        emit!(RewardsCalculated {
+            staking_account: staking.key(),
+            owner: staking.owner,
+            rewards,
+            time_staked: time_staked as u64,
+        });
+        
         msg!("Calculated rewards: {}", rewards);
         Ok(())
     }
@@ -113,6 +142,13 @@ pub mod vulnerable_overflow {
         pool.reserve_in = pool.reserve_in + amount_in;
         pool.reserve_out = pool.reserve_out - amount_out;
         
+        emit!(SwapExecuted {
+            pool: pool.key(),
+            user: ctx.accounts.user.key(),
+            amount_in,
+            amount_out,
+        });
+        
         msg!("Swapped {} for {}", amount_in, amount_out);
         Ok(())
     }
@@ -186,6 +222,44 @@ pub struct Pool {
     pub rate: u64,
 }
 
+#[event]
+pub struct VaultInitialized {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct WithdrawalMade {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+}
+
+#[event]
+pub struct RewardsCalculated {
+    pub staking_account: Pubkey,
+    pub owner: Pubkey,
+    pub rewards: u64,
+    pub time_staked: u64,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
 // ============================================================================
 // ATTACK DEMONSTRATIONS
 // ============================================================================
@@ -39,6 +39,12 @@ pub mod vulnerable_pda {
         vault.balance = 0;
         vault.name = vault_name.clone();
         
+        emit!(VaultCreated {
+            vault: vault.key(),
+            authority: vault.authority,
+            name: vault_name.clone(),
+        });
+        
         msg!("Created vault: {}", vault_name);
         Ok(())
     }
@@ -63,6 +69,13 @@ pub mod vulnerable_pda {
         vault.balance = vault.balance.checked_sub(amount)
             .ok_or(ErrorCode::InsufficientFunds)?;
         
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+        });
+        
         msg!("Withdrew {} from vault", amount);
         Ok(())
     }
@@ -75,6 +88,13 @@ pub mod vulnerable_pda {
         vault.balance = vault.balance.checked_add(amount)
             .ok_or(ErrorCode::Overflow)?;
         
+        emit!(DepositMade {
+            vault: vault.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+            new_balance: vault.balance,
+        });
+        
         msg!("Deposited {} to vault", amount);
         Ok(())
     }
@@ -128,6 +148,29 @@ pub struct Vault {
     pub name: String,
 }
 
+#[event]
+pub struct VaultCreated {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct WithdrawalMade {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Unauthorized")]
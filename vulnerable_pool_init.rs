@@ -0,0 +1,116 @@
+//! # Vulnerable Pool Initialization Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: a pool PDA
+//! seeded by a caller-chosen name, so whoever initializes a name first
+//! decides what it means.
+//!
+//! ## Vulnerability
+//! `initialize_pool` derives the pool from `["pool", name]` and takes the
+//! mints, the fee and the authority from whoever calls it. Nothing ties
+//! the name to the mints it advertises, and nothing stops anyone from
+//! initializing it.
+//!
+//! ## Attack Vector
+//! 1. The team announces a "USDC-SOL" pool; clients derive it from the name
+//! 2. Attacker sees the deployment transaction (or simply goes first)
+//! 3. Attacker calls `initialize_pool("USDC-SOL")` with a worthless mint of
+//!    their own as `token_mint`, a 100% fee, and themselves as authority
+//! 4. The team's `initialize_pool` fails: the address is already in use
+//!
+//! ## Impact
+//! - The address every client derives for "USDC-SOL" is the attacker's
+//! - Deposits priced against the fake mint, fees and admin powers go to
+//!   the attacker; the real pool can never exist at that address
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+declare_id!("VulnNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNNN");
+
+/// Longest pool name, in bytes
+pub const MAX_NAME_LEN: usize = 32;
+
+#[program]
+pub mod vulnerable_pool_init {
+    use super::*;
+
+    /// ❌ VULNERABLE: Create the pool called `name`
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. The address depends only on `name`, not on what the pool holds
+    /// 2. The first caller becomes `authority` and picks every parameter
+    /// 3. A later, legitimate call for the same name can only fail
+    pub fn initialize_pool(ctx: Context<InitializePool>, name: String, fee_bps: u16) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.name = name;
+        // ❌ VULNERABLE: Whatever mints and fee the caller brought
+        pool.token_mint = ctx.accounts.token_mint.key();
+        pool.reward_mint = ctx.accounts.reward_mint.key();
+        pool.fee_bps = fee_bps;
+        // ❌ VULNERABLE: First caller owns the pool
+        pool.authority = ctx.accounts.authority.key();
+        pool.bump = ctx.bumps.pool;
+
+        msg!("Initialized pool {}", pool.name);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct InitializePool<'info> {
+    // ❌ VULNERABLE: Seeded by a string anyone can claim
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", name.as_bytes()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub token_mint: Account<'info, Mint>,
+    pub reward_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    #[max_len(MAX_NAME_LEN)]
+    pub name: String,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Pool name is too long")]
+    NameTooLong,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Step                                   ["pool", "USDC-SOL"] holds
+// ----                                   --------------------------
+// Team announces "USDC-SOL"              (nothing yet)
+// Attacker: initialize_pool("USDC-SOL",  token_mint = FAKE
+//           fee_bps = 10_000)            authority = attacker, fee 100%
+// Team:     initialize_pool("USDC-SOL",  fails: address already in use
+//           fee_bps = 30)
+//
+// Clients that look the pool up by name find the attacker's. The name is
+// a label, and the program lets anyone write it on anything.
@@ -0,0 +1,128 @@
+//! # Vulnerable Program Owner Example
+//!
+//! This program demonstrates a CRITICAL vulnerability: trusting a vault
+//! because its `authority` field matches, without checking that this program
+//! owns the account.
+//!
+//! ## Vulnerability
+//! `withdraw` takes the vault as a raw `AccountInfo` and deserializes it by
+//! hand. `try_deserialize` checks the 8-byte discriminator, and the handler
+//! checks `vault.authority == signer`. Both are bytes in the account, and
+//! nothing checks which program wrote them.
+//!
+//! ## Attack Vector
+//! 1. Attacker copies a `Vault`'s bytes: discriminator, `authority`,
+//!    `balance`, `bump`
+//! 2. Attacker writes themselves in as `authority` and a large `balance`
+//! 3. Attacker creates the account under a program they control (any owner
+//!    other than this program)
+//! 4. Attacker passes the clone as `vault` to `withdraw` and signs
+//! 5. Discriminator, authority and balance all check out
+//!
+//! ## Impact
+//! - Withdrawals authorized against a balance this program never recorded
+//! - Every field check is satisfied by the attacker's own bytes
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("VulnGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGGG");
+
+#[program]
+pub mod vulnerable_program_owner {
+    use super::*;
+
+    /// Create the vault PDA for `authority` holding `balance`
+    pub fn initialize(ctx: Context<Initialize>, balance: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.balance = balance;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Withdraw against a vault read from an unchecked AccountInfo
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        // ❌ VULNERABLE: No check that ctx.accounts.vault.owner == program_id
+        let data = ctx.accounts.vault.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..])?;
+
+        // ❌ Matching authority proves nothing: the attacker wrote it
+        require_keys_eq!(
+            vault.authority,
+            ctx.accounts.authority.key(),
+            ErrorCode::Unauthorized
+        );
+        require!(vault.balance >= amount, ErrorCode::InsufficientFunds);
+
+        msg!("Withdrawal of {} approved against vault {}", amount, ctx.accounts.vault.key());
+
+        // In real code, a transfer out of the program's treasury would happen
+        // here, funded by a balance this program never recorded
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    // ❌ VULNERABLE: AccountInfo with no owner constraint
+    /// CHECK: This SHOULD be verified as owned by this program but ISN'T
+    pub vault: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub balance: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// CLONED VAULT:
+// -------------
+// Real vault (owner = vulnerable_program_owner::ID):
+//   discriminator | authority = victim   | balance = 100   | bump
+//
+// Clone (owner = any other program, e.g. the attacker's):
+//   discriminator | authority = attacker | balance = 10^18 | bump
+//
+//   withdraw(10^18) with vault = clone, authority = attacker
+//     try_deserialize                      ✓ discriminator is public
+//     vault.authority == attacker          ✓ attacker wrote it
+//     vault.balance >= 10^18               ✓ attacker wrote it
+//
+// The one property the clone cannot copy is its owner: only the owning
+// program can write an account's data, and this handler never looks.
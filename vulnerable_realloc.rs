@@ -0,0 +1,115 @@
+//! # Vulnerable Account Space Example
+//!
+//! This program demonstrates a MEDIUM severity vulnerability: an account
+//! holding a growing `Vec` is allocated a fixed size once and never resized.
+//!
+//! ## Vulnerability
+//! `VaultRegistry` is created with room for "a couple of names". Nothing
+//! compares the serialized size of `names` to the account's data length.
+//! `add_name` pushes unconditionally and relies on Anchor writing the
+//! account back on exit.
+//!
+//! ## Attack Vector
+//! 1. Authority (or anyone allowed to register) adds names
+//! 2. Once `names` serializes past the allocation, the push still succeeds
+//!    in memory: the handler is writing beyond the account conceptually
+//! 3. Anchor's exit serialization runs out of space and fails with
+//!    `AccountDidNotSerialize`
+//! 4. Every later `add_name` fails the same way; an attacker who can add
+//!    names can fill the registry on purpose
+//!
+//! ## Impact
+//! - The registry is bricked at a size chosen at deploy time
+//! - Any instruction that must append to it (and anything gated on that)
+//!   is permanently denied
+//! - Code that instead serializes by hand with an unchecked writer, or uses
+//!   zero-copy with an offset computed from `len`, corrupts or reads past
+//!   its own data rather than failing
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("VulnDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDDD");
+
+/// ❌ "Should be enough": room for two 28-byte names
+const REGISTRY_SPACE: usize = 8 + 32 + 1 + 4 + 64;
+
+#[program]
+pub mod vulnerable_realloc {
+    use super::*;
+
+    /// Create the registry PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.authority = ctx.accounts.authority.key();
+        registry.bump = ctx.bumps.registry;
+        registry.names = Vec::new();
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Append a name to the registry
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. The account was sized once, at `REGISTRY_SPACE`
+    /// 2. Nothing checks the new serialized size against it
+    /// 3. Nothing grows the account when it is needed
+    pub fn add_name(ctx: Context<AddName>, name: String) -> Result<()> {
+        // ❌ VULNERABLE: Push regardless of the space actually allocated
+        ctx.accounts.registry.names.push(name);
+
+        msg!("Registry now holds {} names", ctx.accounts.registry.names.len());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = REGISTRY_SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddName<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct VaultRegistry {
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub names: Vec<String>,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Allocated: 8 + 32 + 1 + 4 + 64 = 109 bytes
+//
+//   add_name("a" * 28)   names: 4 + 32        = 36  → 77 bytes   ✓
+//   add_name("b" * 28)   names: 4 + 32 * 2    = 68  → 109 bytes  ✓
+//   add_name("c")        names: 4 + 32 * 2 + 5 = 73 → 114 bytes
+//                        push succeeds, handler returns Ok
+//                        exit: serialize 114 into 109 → AccountDidNotSerialize
+//
+// From here on the registry can never grow again.
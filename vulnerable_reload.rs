@@ -0,0 +1,159 @@
+//! # Vulnerable Stale Account Read Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: reading a
+//! deserialized token account after a CPI has changed it.
+//!
+//! ## Vulnerability
+//! Anchor deserializes `vault_tokens` into a `TokenAccount` struct once,
+//! before the handler runs. `token::transfer` changes the account's data,
+//! not that struct. `withdraw` checks the reserve floor and records the
+//! remaining balance from `vault_tokens.amount` after the transfer, so it
+//! sees the balance from before the withdrawal.
+//!
+//! ## Attack Vector
+//! 1. Vault holds 1_000 with a `min_reserve` of 100
+//! 2. Authority (or a compromised authority key) withdraws 1_000
+//! 3. Post-transfer check reads the cached 1_000 >= 100 and passes
+//! 4. The vault is empty; `last_balance` says 1_000
+//!
+//! ## Impact
+//! - Post-conditions on balances never fire: reserves, collateral ratios
+//!   and "received at least" checks all compare the pre-CPI value
+//! - Recorded balances drift from the real ones, and every later decision
+//!   built on them inherits the error
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("VulnFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+
+#[program]
+pub mod vulnerable_reload {
+    use super::*;
+
+    /// Create the vault PDA for `authority` over an existing token account
+    pub fn initialize(ctx: Context<Initialize>, min_reserve: u64) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.vault_tokens = ctx.accounts.vault_tokens.key();
+        vault.min_reserve = min_reserve;
+        vault.last_balance = ctx.accounts.vault_tokens.amount;
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Withdraw, then check the reserve on stale data
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.vault_tokens.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let authority = ctx.accounts.authority.key();
+        let seeds = &[b"reserve_vault".as_ref(), authority.as_ref(), &[ctx.accounts.vault.bump]];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault_tokens.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        // ❌ VULNERABLE: Still the amount deserialized before the transfer
+        let remaining = ctx.accounts.vault_tokens.amount;
+        require!(
+            remaining >= ctx.accounts.vault.min_reserve,
+            ErrorCode::ReserveBreached
+        );
+        ctx.accounts.vault.last_balance = remaining;
+
+        msg!("Withdrew {}. Remaining: {}", amount, remaining);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReserveVault::INIT_SPACE,
+        seeds = [b"reserve_vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, ReserveVault>,
+
+    #[account(constraint = vault_tokens.owner == vault.key())]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"reserve_vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority,
+        has_one = vault_tokens
+    )]
+    pub vault: Account<'info, ReserveVault>,
+
+    #[account(mut)]
+    pub vault_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveVault {
+    pub authority: Pubkey,
+    pub vault_tokens: Pubkey,
+    /// Tokens `vault_tokens` must keep after any withdrawal
+    pub min_reserve: u64,
+    /// `vault_tokens.amount` as of the last instruction
+    pub last_balance: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Withdrawal would leave the vault below its reserve")]
+    ReserveBreached,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// vault_tokens: 1_000, min_reserve: 100
+//
+//   withdraw(1_000)
+//     Anchor deserializes vault_tokens     cached amount = 1_000
+//     token::transfer(1_000)               account data  = 0
+//     remaining = vault_tokens.amount      reads cached  = 1_000
+//     1_000 >= 100                         ✓ passes
+//     last_balance = 1_000
+//
+// The reserve floor never applied: the only balance the handler could see
+// was the one from before it moved any tokens.
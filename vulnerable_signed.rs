@@ -0,0 +1,138 @@
+//! # Vulnerable Signed Integer Example
+//!
+//! This program demonstrates a vulnerability from unchecked `i64` subtraction and sign casting.
+//!
+//! ## Vulnerability
+//! `Clock::unix_timestamp` is an `i64`. Subtracting a stored timestamp from it can
+//! produce a NEGATIVE duration, and casting a negative `i64` to `u64` does not fail:
+//! - `-1i64 as u64 = u64::MAX` (18,446,744,073,709,551,615)
+//! - `-3600i64 as u64 = u64::MAX - 3599`
+//!
+//! ## Attack Vectors
+//! 1. **Future start time**: Set `start_time` ahead of the clock, duration goes negative
+//! 2. **Sign cast**: Negative duration is reinterpreted as an enormous positive number
+//! 3. **Reward inflation**: Huge "time staked" multiplies into huge rewards
+//!
+//! ## Impact
+//! - Rewards for time that was never staked
+//! - Reward pool drained in a single call
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("Vuln777777777777777777777777777777777777777");
+
+/// Seconds in a (non-leap) year, used to annualize the reward rate
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[program]
+pub mod vulnerable_signed {
+    use super::*;
+
+    /// ❌ VULNERABLE: Stake with a caller-supplied start time
+    ///
+    /// Attack scenario:
+    /// 1. Attacker passes `start_time = now + 1`
+    /// 2. No check that start_time is not in the future
+    /// 3. Every later reward calculation sees a negative duration
+    pub fn stake(ctx: Context<Stake>, amount: u64, start_time: i64) -> Result<()> {
+        let staking = &mut ctx.accounts.staking;
+        staking.owner = ctx.accounts.owner.key();
+        staking.amount = amount;
+        staking.rate = 100_000; // 10% APY, scaled by 1_000_000
+
+        // ❌ VULNERABLE: start_time may be in the future
+        staking.start_time = start_time;
+
+        msg!("Staked {} starting at {}", amount, start_time);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Reward calculation with signed subtraction cast to u64
+    ///
+    /// Attack scenario:
+    /// 1. start_time = now + 1
+    /// 2. now - start_time = -1
+    /// 3. -1 as u64 = u64::MAX
+    /// 4. Rewards are computed as if the stake had existed for 584 billion years
+    pub fn calculate_rewards(ctx: Context<CalculateRewards>) -> Result<()> {
+        let staking = &mut ctx.accounts.staking;
+        let clock = Clock::get()?;
+
+        // ❌ VULNERABLE: i64 subtraction can go negative,
+        // and `as u64` silently reinterprets the sign bit
+        let time_staked = (clock.unix_timestamp - staking.start_time) as u64;
+
+        let rewards = (staking.amount as u128)
+            .checked_mul(staking.rate as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .checked_mul(time_staked as u128)
+            .ok_or(ErrorCode::Overflow)?
+            / 1_000_000
+            / SECONDS_PER_YEAR as u128;
+
+        // ❌ Truncating cast hides how absurd the value is
+        staking.pending_rewards = rewards as u64;
+
+        msg!("Calculated rewards: {} over {} seconds", staking.pending_rewards, time_staked);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakingAccount::INIT_SPACE
+    )]
+    pub staking: Account<'info, StakingAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CalculateRewards<'info> {
+    #[account(mut)]
+    pub staking: Account<'info, StakingAccount>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakingAccount {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub rate: u64,
+    pub start_time: i64,
+    pub pending_rewards: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Overflow")]
+    Overflow,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// NEGATIVE DURATION ATTACK:
+// -------------------------
+// Clock: unix_timestamp = 1_700_000_000
+//
+// Attacker calls: stake(1_000_000, 1_700_003_600)   // one hour in the future
+//
+// Attacker calls: calculate_rewards()
+// Calculation: 1_700_000_000 - 1_700_003_600 = -3600
+// Cast:        -3600 as u64 = 18,446,744,073,709,548,016
+//
+// rewards = 1_000_000 * 100_000 * 18,446,744,073,709,548,016
+//           / 1_000_000 / 31_536_000
+//         = 58,494,241,735,507,191
+//
+// A modest stake accrued ~5.8 * 10^16 in rewards for a duration that never happened.
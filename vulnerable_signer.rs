@@ -34,6 +34,11 @@ pub mod vulnerable_signer {
         vault.authority = ctx.accounts.authority.key();
         vault.balance = 0;
         
+        emit!(VaultInitialized {
+            vault: vault.key(),
+            authority: vault.authority,
+        });
+        
         msg!("Vault initialized for authority: {}", vault.authority);
         Ok(())
     }
@@ -46,6 +51,13 @@ pub mod vulnerable_signer {
         vault.balance = vault.balance.checked_add(amount)
             .ok_or(ErrorCode::Overflow)?;
         
+        emit!(DepositMade {
+            vault: vault.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount,
+            new_balance: vault.balance,
+        });
+        
         msg!("Deposited {} lamports. New balance: {}", amount, vault.balance);
         Ok(())
     }
@@ -76,6 +88,14 @@ pub mod vulnerable_signer {
         vault.balance = vault.balance.checked_sub(amount)
             .ok_or(ErrorCode::InsufficientFunds)?;
         
+        // The event looks exactly like a legitimate withdrawal
+        emit!(WithdrawalMade {
+            vault: vault.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining_balance: vault.balance,
+        });
+        
         msg!("Withdrew {} lamports. New balance: {}", amount, vault.balance);
         
         // In a real program, this would transfer SOL/tokens to the attacker
@@ -133,6 +153,28 @@ pub struct Vault {
     pub balance: u64,
 }
 
+#[event]
+pub struct VaultInitialized {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct DepositMade {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct WithdrawalMade {
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Unauthorized access attempt")]
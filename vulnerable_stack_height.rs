@@ -0,0 +1,102 @@
+//! # Vulnerable Stack Height Example
+//!
+//! This program demonstrates a HIGH severity vulnerability: an ownership
+//! transfer that any program can reach through a nested CPI.
+//!
+//! ## Vulnerability
+//! `transfer_authority` only checks that the current authority signed.
+//! Signer privileges carry through CPI, so any program the authority calls
+//! can invoke `transfer_authority` with the authority as signer and pick
+//! the new owner itself.
+//!
+//! ## Attack Vector
+//! 1. Attacker deploys a program that looks harmless (a "points claim")
+//! 2. Victim signs a transaction calling it
+//! 3. The attacker program CPIs `transfer_authority(attacker)` with the
+//!    victim's signature
+//! 4. `has_one` and `Signer` pass; the vault now belongs to the attacker
+//!
+//! ## Impact
+//! - Any transaction the authority signs can give the vault away
+//! - Nothing leaves the vault yet, so balance monitoring sees nothing; the
+//!   attacker withdraws later, at leisure
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("VulnKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKKK");
+
+#[program]
+pub mod vulnerable_stack_height {
+    use super::*;
+
+    /// Create the vault PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Hand the vault to `new_authority`
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. It never asks whether it was called directly or through CPI
+    /// 2. A CPI from any program carries the authority's signature
+    /// 3. That program chooses `new_authority`
+    pub fn transfer_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        // ❌ VULNERABLE: No stack height check
+
+        ctx.accounts.vault.authority = new_authority;
+
+        msg!("Vault authority is now {}", new_authority);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Vault::INIT_SPACE,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+
+    // Signed... but possibly for a different program
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Transaction signed by the victim:
+//
+//   [0] attacker_program::claim_points          stack height 1
+//         accounts: vulnerable_stack_height, vault, victim (signer)
+//       └─ CPI transfer_authority(attacker)     stack height 2
+//            authority = victim (signer privilege inherited)
+//
+// has_one and Signer pass. The wallet prompt only named attacker_program.
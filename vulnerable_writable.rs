@@ -0,0 +1,130 @@
+//! # Vulnerable Writability Example
+//!
+//! This program demonstrates a LOW severity weakness: a handler that mutates
+//! a raw `AccountInfo` without checking that the account was passed writable.
+//!
+//! ## Vulnerability
+//! `update` takes `record` as an unchecked `AccountInfo`, verifies owner,
+//! type and authority by hand, then writes through `try_borrow_mut_data`.
+//! It never looks at `record.is_writable`. Nothing in the borrow API does
+//! either: the borrow succeeds on a read-only account and the bytes change
+//! in the program's view.
+//!
+//! ## Attack Vector
+//! 1. Caller builds the instruction with `record` marked read-only
+//! 2. `update` runs to completion, logs success and "updates" the record
+//! 3. Only after the handler returns does the runtime compare the account
+//!    to its pre-instruction state and fail with `ReadonlyDataModified`
+//!
+//! ## Impact
+//! - The program is correct only because of the runtime's backstop, not
+//!   because of anything it checked
+//! - The failure arrives late and generic: every side effect the handler
+//!   planned (logs, CPIs to other programs, compute) ran first, and the
+//!   error says nothing about which account or why
+//! - The same blind spot in a handler that copies `is_writable` into CPI
+//!   account metas, or branches on a write "succeeding", turns into a real
+//!   logic bug
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+
+declare_id!("VulnCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCCC");
+
+#[program]
+pub mod vulnerable_writable {
+    use super::*;
+
+    /// Create the record PDA for `authority`
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let record = &mut ctx.accounts.record;
+        record.authority = ctx.accounts.authority.key();
+        record.value = 0;
+        record.bump = ctx.bumps.record;
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Overwrite the record's value
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. `record` is a raw `AccountInfo` with no `mut` constraint
+    /// 2. Owner, discriminator and authority are checked by hand
+    /// 3. `is_writable` is not
+    pub fn update(ctx: Context<Update>, value: u64) -> Result<()> {
+        let info = &ctx.accounts.record;
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidRecord);
+
+        let mut record = Record::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(record.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+        // ❌ VULNERABLE: Succeeds even if `record` was passed read-only
+        record.value = value;
+        let mut data = info.try_borrow_mut_data()?;
+        record.try_serialize(&mut &mut data[..])?;
+
+        msg!("Record updated to {}", value);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Record::INIT_SPACE,
+        seeds = [b"record", authority.key().as_ref()],
+        bump
+    )]
+    pub record: Account<'info, Record>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    /// CHECK: ❌ Owner and authority checked in the handler; writability never is
+    pub record: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Record {
+    pub authority: Pubkey,
+    pub value: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Record is not owned by this program")]
+    InvalidRecord,
+    #[msg("Signer is not the record authority")]
+    Unauthorized,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// update(42) with record passed read-only:
+//
+//   AccountMeta::new_readonly(record, false)
+//
+//   owner == crate::ID              ✓
+//   Record::try_deserialize         ✓
+//   authority matches               ✓
+//   try_borrow_mut_data             ✓   (RefCell borrow, no privilege check)
+//   "Record updated to 42"          logged
+//   handler returns Ok(())
+//   runtime: data changed on a read-only account → ReadonlyDataModified
+//
+// Nothing is persisted, but only because the runtime caught what the
+// program did not. Under direct account-data mapping the same write is a
+// memory access violation instead, an even less helpful error.
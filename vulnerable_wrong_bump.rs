@@ -0,0 +1,145 @@
+//! # Vulnerable PDA Bump Example
+//!
+//! This program demonstrates a MEDIUM severity vulnerability: re-deriving a
+//! PDA with a hardcoded bump instead of the canonical one.
+//!
+//! ## Vulnerability
+//! Deposits land in the treasury at Anchor's canonical address, but the
+//! bump is never stored. `withdraw` re-creates the signer seeds with a
+//! literal `255`, assuming every seed set's canonical bump is 255.
+//!
+//! ## Attack Vector
+//! There is no attacker; the program breaks on its own:
+//! 1. An authority whose canonical bump is below 255 deposits
+//! 2. `withdraw` calls `create_program_address` with bump 255
+//! 3. 255 is on the curve for those seeds, so no PDA exists
+//! 4. Every withdrawal fails and the lamports are stuck forever
+//!
+//! `find_program_address` tries 255 first and walks down, so 255 is the
+//! canonical bump for only about half of all seed sets.
+//!
+//! ## Impact
+//! - About half of all treasuries can never be withdrawn from
+//! - A different literal (say 254) is worse: where it happens to be a
+//!   valid bump but not the canonical one, the program signs for a second,
+//!   non-canonical address for the same seeds, breaking the "one account
+//!   per seed set" assumption every PDA check relies on
+//!
+//! ## DO NOT USE IN PRODUCTION
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("VulnJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJJ");
+
+#[program]
+pub mod vulnerable_wrong_bump {
+    use super::*;
+
+    /// Move lamports from `authority` into its treasury PDA
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            amount,
+        )?;
+
+        msg!("Deposited {} lamports", amount);
+        Ok(())
+    }
+
+    /// ❌ VULNERABLE: Withdraw, signing with a hardcoded bump
+    ///
+    /// This function is VULNERABLE because:
+    /// 1. The bump was never stored, so it has to be recomputed
+    /// 2. It is "recomputed" as the literal 255
+    /// 3. For any authority whose canonical bump is lower, no PDA exists
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+
+        // ❌ VULNERABLE: 255 is the canonical bump only some of the time
+        let seeds = &[b"treasury".as_ref(), authority.as_ref(), &[255]];
+        Pubkey::create_program_address(seeds, ctx.program_id).map_err(|_| ErrorCode::PdaNotFound)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: ctx.accounts.authority.to_account_info(),
+        };
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                &[seeds],
+            ),
+            amount,
+        )?;
+
+        msg!("Withdrew {} lamports", amount);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    // Anchor finds the canonical bump here, so deposits always land at the
+    // canonical address
+    #[account(mut, seeds = [b"treasury", authority.key().as_ref()], bump)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    // ❌ VULNERABLE: No seeds constraint, so no canonical bump in ctx.bumps
+    /// CHECK: Signed for in the handler with the hardcoded bump
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("No PDA exists for these seeds and bump")]
+    PdaNotFound,
+}
+
+// ============================================================================
+// ATTACK DEMONSTRATION
+// ============================================================================
+//
+// Authority A, canonical bump 255 (about 1 in 2 keys):
+//   deposit   → ["treasury", A] bump 255            ✓
+//   withdraw  → ["treasury", A, 255] is a PDA        ✓  works by luck
+//
+// Authority B, canonical bump 253:
+//   deposit   → ["treasury", B] bump 253            ✓
+//   withdraw  → ["treasury", B, 255] is on the curve ✗  PdaNotFound
+//                                                      lamports stuck
+//
+// With a literal 254 instead, for a key where both 255 and 254 are off
+// the curve:
+//   ["treasury", C, 254] → valid, but not the address deposits go to
+//   The program now signs for a second "treasury" nobody validated.
+//
+// ============================================================================
+// SECURITY ANALYSIS: secure_wrong_bump.rs
+// ============================================================================
+//
+// The fix is to never guess the bump:
+//
+// 1. seeds = [...] plus a bare `bump` on the withdraw account makes Anchor
+//    run find_program_address and reject any other address
+// 2. The handler signs with ctx.bumps.treasury, the bump Anchor found
+// 3. Accounts with data can store that bump at init instead, as
+//    secure_pda does, and use `bump = account.bump` afterwards